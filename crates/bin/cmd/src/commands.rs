@@ -2,18 +2,119 @@
 //! # commands module
 //!
 
+pub mod archive_store;
 pub mod backup_target;
+pub mod cat_file;
+pub mod completion;
+pub mod config;
+pub mod daemon;
 pub mod delete_backup;
 pub mod delete_target;
+pub mod edit_target;
+pub mod export_target;
+pub mod find_file;
+pub mod hook;
+pub mod import_target;
 pub mod list_targets;
+#[cfg(target_os = "linux")]
+pub mod mount;
+pub mod pin_backup;
+pub mod prune;
 pub mod register_target;
+pub mod replica;
 pub mod restore_target;
+pub mod schedule;
 pub mod show_target;
+pub mod source;
+pub mod sqlite_storage;
+pub mod stats;
+pub mod tag_target;
+pub mod trash;
+pub mod watch_target;
 
+pub use archive_store::ArchiveStore;
 pub use backup_target::BackupTarget;
+pub use cat_file::CatFile;
+pub use completion::{Complete, Completions};
+pub use config::AppConfig;
+pub use daemon::Daemon;
 pub use delete_backup::DeleteBackup;
 pub use delete_target::DeleteTarget;
+pub use edit_target::EditTarget;
+pub use export_target::ExportTarget;
+pub use find_file::FindFile;
+pub use hook::HookTarget;
+pub use import_target::ImportTarget;
 pub use list_targets::ListTargets;
+#[cfg(target_os = "linux")]
+pub use mount::Mount;
+pub use pin_backup::{PinBackup, UnpinBackup};
+pub use prune::Prune;
 pub use register_target::RegisterTarget;
+pub use replica::Replica;
 pub use restore_target::RestoreTarget;
+pub use schedule::ScheduleTarget;
 pub use show_target::ShowTarget;
+pub use source::SourceTarget;
+pub use sqlite_storage::SqliteStorage;
+pub use stats::Stats;
+pub use tag_target::TagTarget;
+pub use trash::Trash;
+pub use watch_target::WatchTarget;
+
+//
+// Helpers shared by the commands.
+//
+use dirback::adapter::{ResolveTargetAdapter, ResolveTargetError, SelectBackupAdapter};
+use dirback::internal::TargetRepository;
+use dirback::usecase::dto::{BackupEntry, BackupSelector, BackupSelectorError, Target};
+use dirback_cmd::CmdError;
+use std::io::{BufRead, Write};
+
+/// Returns the target by its ID, name, a unique ID prefix or its path.
+///
+/// Returns `CmdError::NotFound`, or `CmdError::Usage` if several targets match.
+pub fn get_target<R: TargetRepository>(repo: &R, target_id: &str) -> anyhow::Result<Target> {
+    ResolveTargetAdapter::new(repo)
+        .execute(target_id)
+        .map_err(|e| match e.downcast::<ResolveTargetError>() {
+            Ok(e @ ResolveTargetError::NotFound(_)) => CmdError::NotFound(e.to_string()).into(),
+            Ok(e @ ResolveTargetError::Ambiguous { .. }) => CmdError::Usage(e.to_string()).into(),
+            Err(e) => e,
+        })
+}
+
+/// Returns the backup of the target selected by the selector, or `CmdError::NotFound`.
+pub fn get_backup<R: TargetRepository>(
+    repo: &R,
+    target: &Target,
+    selector: &BackupSelector,
+) -> anyhow::Result<BackupEntry> {
+    SelectBackupAdapter::new(repo)
+        .execute(&target.id, selector)
+        .map_err(|e| match e.downcast::<BackupSelectorError>() {
+            Ok(e) => CmdError::NotFound(e.to_string()).into(),
+            Err(e) => e,
+        })
+}
+
+/// Reads the answer of a `[yes/No]` prompt from the reader.
+///
+/// Returns `CmdError::Cancelled` unless the answer is "yes".
+pub fn confirm(reader: Box<dyn std::io::Read>) -> anyhow::Result<()> {
+    print!("[yes/No] > ");
+    std::io::stdout().flush()?;
+
+    let mut yesno = String::new();
+    std::io::BufReader::new(reader).read_line(&mut yesno)?;
+    if yesno.trim().to_lowercase() != "yes" {
+        return Err(CmdError::Cancelled.into());
+    }
+
+    Ok(())
+}
+
+/// Creates the reader of the confirmation answer.
+pub fn stdin_reader() -> Box<dyn std::io::Read> {
+    Box::new(std::io::stdin())
+}
@@ -0,0 +1,163 @@
+//!
+//! # ArchiveStore command
+//!
+//! Lists the archive stores, and sets the store of a target.
+//! Archive stores are registered in `{basedir}/stores.json`.
+//!
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::archive_store::{STORE_CONFIG_FILE_NAME, load_store_configs};
+use dirback::usecase::set_store::SetStoreUsecase;
+use dirback_cmd::say;
+
+/// The store name to keep backups in the data directory.
+const LOCAL_STORE_NAME: &str = "local";
+
+/// Manage the archive stores of the targets.
+#[derive(clap::Args)]
+pub struct ArchiveStore {
+    #[command(subcommand)]
+    command: StoreCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum StoreCommand {
+    /// List the registered archive stores.
+    List,
+
+    /// Set the store of the new backups ("local" for the data directory).
+    Set { target_id: String, store_id: String },
+}
+
+impl ArchiveStore {
+    fn list(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let configs = load_store_configs(&params.basedir)?;
+        if params.is_json() {
+            return params.print_json(&configs);
+        }
+
+        if configs.is_empty() {
+            say!(
+                params,
+                "No archive stores. Register them in {}.",
+                params.basedir.join(STORE_CONFIG_FILE_NAME).display()
+            );
+            return Ok(());
+        }
+
+        for (id, config) in configs {
+            say!(params, "{id}\t{}\t{}", config.kind(), config.location());
+        }
+
+        Ok(())
+    }
+
+    fn set(
+        &self,
+        params: &dirback_cmd::CmdParams,
+        target_id: &str,
+        store_id: &str,
+    ) -> anyhow::Result<()> {
+        let store_id = match store_id {
+            LOCAL_STORE_NAME => None,
+            id => Some(id),
+        };
+
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let target_id = &super::get_target(&repo, target_id)?.id;
+
+        let mut usecase = SetStoreUsecase::new(&mut repo);
+        let target = usecase.execute(target_id, store_id)?;
+
+        if params.is_json() {
+            return params.print_json(&target);
+        }
+        say!(
+            params,
+            "New backups of '{}' will be stored in: {}",
+            target.name,
+            target.store.as_deref().unwrap_or(LOCAL_STORE_NAME)
+        );
+
+        Ok(())
+    }
+}
+
+impl dirback_cmd::Command for ArchiveStore {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        match &self.command {
+            StoreCommand::List => self.list(params),
+            StoreCommand::Set {
+                target_id,
+                store_id,
+            } => self.set(params, target_id, store_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    fn register_store(basedir: &std::path::Path) {
+        let config = format!(
+            r#"{{ "nas": {{ "type": "local", "root": {:?} }} }}"#,
+            basedir.join("nas")
+        );
+        std::fs::write(basedir.join(STORE_CONFIG_FILE_NAME), config).unwrap();
+    }
+
+    #[test]
+    fn it_lists_stores() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let result = run::<ArchiveStore>(&CmdParams::new(&basedir), &["list"]);
+        assert!(result.is_ok());
+
+        register_store(&basedir);
+        let result = run::<ArchiveStore>(&CmdParams::new(&basedir), &["list"]);
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn it_sets_store_of_target() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        register_store(&basedir);
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+
+        let result = run::<ArchiveStore>(&CmdParams::new(&basedir), &["set", &target.id, "nas"]);
+        assert!(result.is_ok());
+        assert_eq!(repo.load(&target.id).unwrap().store.as_deref(), Some("nas"));
+
+        let result = run::<ArchiveStore>(&CmdParams::new(&basedir), &["set", &target.id, "local"]);
+        assert!(result.is_ok());
+        assert_eq!(repo.load(&target.id).unwrap().store, None);
+    }
+
+    #[test]
+    fn it_fails_with_unknown_store() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+
+        let result = run::<ArchiveStore>(&CmdParams::new(&basedir), &["set", &target.id, "nas"]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_fails_with_unknown_sub_command() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let result = run::<ArchiveStore>(&CmdParams::new(&basedir), &["unknown"]);
+        assert!(result.is_err());
+    }
+}
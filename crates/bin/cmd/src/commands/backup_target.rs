@@ -2,30 +2,182 @@
 //! # BackupTarget command
 //!
 
+use dirback::adapter::GetTargetAdapter;
 use dirback::infra::repository::file_storage::FileStorageTargetRepository;
 use dirback::infra::service::targz_backup_service::TargzBackupService;
 use dirback::usecase::backup::BackupUsecase;
+use dirback::usecase::backup_all::{BackupAllOptions, BackupAllUsecase};
+use dirback::usecase::dto::BackupSummary;
+use dirback_cmd::{detail, say};
 
-pub struct BackupTarget;
+/// Take a backup of the target, or of every target.
+#[derive(clap::Args)]
+pub struct BackupTarget {
+    /// The target to back up.
+    #[arg(required_unless_present_any = ["all", "tag"])]
+    target_id: Option<String>,
+
+    /// The note of the backup.
+    ///
+    /// With --all or --tag, every argument is the note.
+    note: Vec<String>,
+
+    /// Back up every target, continuing past failures.
+    #[arg(long)]
+    all: bool,
+
+    /// Back up every target with the tag ("all" for every target).
+    #[arg(long)]
+    tag: Option<String>,
+
+    /// Create up to N archives in parallel, with --all or --tag.
+    #[arg(long, value_name = "N", default_value_t = 1,
+          value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    jobs: usize,
+}
+
+impl BackupTarget {
+    fn note(&self) -> String {
+        let words = match (&self.target_id, self.all || self.tag.is_some()) {
+            (Some(first), true) => std::iter::once(first).chain(self.note.iter()).collect(),
+            _ => self.note.iter().collect::<Vec<_>>(),
+        };
+        words
+            .into_iter()
+            .map(String::as_str)
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
 
 impl dirback_cmd::Command for BackupTarget {
     fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
-        if params.args.is_empty() {
-            anyhow::bail!("Missing args: <target-id> [note]");
+        if self.all || self.tag.is_some() {
+            let options = BackupAllOptions {
+                tag: self.tag.clone(),
+                note: self.note(),
+                jobs: self.jobs,
+            };
+            return backup_all_targets(params, &options);
         }
 
-        let target_id = params.args[0].to_string();
-        let note = params.args[1..].join(" ");
+        let target_id = self.target_id.as_deref().unwrap_or_default();
+        backup_target(params, target_id, &self.note())
+    }
+}
+
+fn backup_target(
+    params: &dirback_cmd::CmdParams,
+    target_id: &str,
+    note: &str,
+) -> anyhow::Result<()> {
+    let mut repo = FileStorageTargetRepository::new(&params.basedir);
+    let target_id = &super::get_target(&repo, target_id)?.id;
+    let service = TargzBackupService::new()
+        .with_compression(params.config.compression.value)
+        .with_excludes(&params.config.excludes.value);
+    let hook_service = params.hook_service();
+
+    let mut usecase = BackupUsecase::new(&mut repo, &service).with_hooks(&hook_service);
+    let entry = usecase.execute(target_id, note)?;
 
-        let mut repo = FileStorageTargetRepository::new(&params.basedir);
-        let service = TargzBackupService::new();
+    say!(params, "Target({}) backup is complete.", target_id);
+    detail!(params, "Backup file: {}", entry.path.to_string_lossy());
 
-        let mut usecase = BackupUsecase::new(&mut repo, &service);
-        usecase.execute(&target_id, &note)?;
+    // Copy the new backup to the replica locations.
+    let has_replicas = GetTargetAdapter::new(&repo)
+        .execute(target_id)
+        .is_some_and(|t| !t.replicas.is_empty());
+    if has_replicas {
+        let target = super::replica::replicate(&params.basedir, target_id)?;
+        super::replica::print_summary(params, &target);
+    }
+
+    if params.is_json() {
+        let entry = GetTargetAdapter::new(&repo)
+            .execute(target_id)
+            .and_then(|t| t.backups.into_iter().find(|b| b.id == entry.id))
+            .unwrap_or(entry);
+        params.print_json(&entry)?;
+    }
 
-        println!("Target({}) backup is complete.", target_id);
+    Ok(())
+}
+
+/// Takes a backup of every target (or the targets with the tag), continuing past failures.
+fn backup_all_targets(
+    params: &dirback_cmd::CmdParams,
+    options: &BackupAllOptions,
+) -> anyhow::Result<()> {
+    let mut repo = FileStorageTargetRepository::new(&params.basedir);
+    let service = TargzBackupService::new()
+        .with_compression(params.config.compression.value)
+        .with_excludes(&params.config.excludes.value);
+    let hook_service = params.hook_service();
+    let summary = BackupAllUsecase::new(&mut repo, &service)
+        .with_hooks(&hook_service)
+        .execute(options)?;
+    if summary.results.is_empty() {
+        match &options.tag {
+            Some(tag) => anyhow::bail!("No targets with the tag '{tag}'."),
+            None => anyhow::bail!("No targets."),
+        }
+    }
+
+    // Copy the new backups to the replica locations.
+    for result in summary.results.iter().filter(|r| r.is_ok()) {
+        let has_replicas = GetTargetAdapter::new(&repo)
+            .execute(&result.target_id)
+            .is_some_and(|t| !t.replicas.is_empty());
+        if !has_replicas {
+            continue;
+        }
+        match super::replica::replicate(&params.basedir, &result.target_id) {
+            Ok(target) => super::replica::print_summary(params, &target),
+            Err(e) => eprintln!("Target({}) replication failed: {e}", result.target_id),
+        }
+    }
+
+    if params.is_json() {
+        params.print_json(&summary)?;
+    } else {
+        print_summary(params, &summary);
+    }
+
+    if summary.failed() > 0 {
+        anyhow::bail!(
+            "{} of {} backups failed.",
+            summary.failed(),
+            summary.results.len()
+        );
+    }
+
+    Ok(())
+}
 
-        Ok(())
+fn print_summary(params: &dirback_cmd::CmdParams, summary: &BackupSummary) {
+    say!(
+        params,
+        "* Backups ({} succeeded, {} failed)",
+        summary.succeeded(),
+        summary.failed()
+    );
+    say!(params, "id, name, backup-id, seconds, result");
+    for result in summary.results.iter() {
+        let backup_id = result
+            .backup
+            .as_ref()
+            .map(|b| format!("{:0>3}", b.id))
+            .unwrap_or_else(|| String::from("-"));
+        say!(
+            params,
+            "{}, {}, {}, {:.1}, {}",
+            result.target_id,
+            result.target_name,
+            backup_id,
+            result.duration_ms as f64 / 1000.0,
+            result.error.as_deref().unwrap_or("ok")
+        );
     }
 }
 
@@ -46,16 +198,9 @@ mod tests {
         let target_path = std::fs::canonicalize(".").unwrap();
         let target = repo.add("TestTarget", &target_path).unwrap();
 
-        // CmdParams
-        let args: Vec<String> = ["test", "backup", &target.id, "backup testing"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let params = CmdParams::build(&args, &basedir).unwrap();
-
         // Execute
-        let cmd = BackupTarget {};
-        let result = cmd.execute(&params);
+        let params = CmdParams::new(&basedir);
+        let result = run::<BackupTarget>(&params, &[&target.id, "backup", "testing"]);
         if let Err(ref e) = result {
             println!("{:?}", e);
         }
@@ -81,17 +226,75 @@ mod tests {
         assert_eq!(targz_files.len(), 1, "tar.gz file should be created.");
     }
 
+    #[test]
+    fn it_backs_up_tagged_targets() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path().join("data");
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let mut ids = Vec::new();
+        for name in ["a", "b", "c"] {
+            let src = temp.path().join(name);
+            std::fs::create_dir_all(&src).unwrap();
+            let mut target = repo.add(name, &src).unwrap();
+            if name != "b" {
+                target.add_tag("clients").unwrap();
+                repo.update(&target).unwrap();
+            }
+            ids.push(target.id);
+        }
+
+        let params = CmdParams::new(&basedir);
+        assert!(run::<BackupTarget>(&params, &["--tag", "clients", "nightly"]).is_ok());
+
+        let counts: Vec<usize> = ids
+            .iter()
+            .map(|id| repo.load(id).unwrap().backups.len())
+            .collect();
+        assert_eq!(counts, vec![1, 0, 1]);
+        assert_eq!(repo.load(&ids[0]).unwrap().backups[0].note, "nightly");
+    }
+
+    #[test]
+    fn it_backs_up_all_targets_past_failures() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path().join("data");
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let mut ids = Vec::new();
+        for name in ["a", "b", "c"] {
+            let src = temp.path().join(name);
+            std::fs::create_dir_all(&src).unwrap();
+            ids.push(repo.add(name, &src).unwrap().id);
+        }
+        // The source of "b" is gone.
+        std::fs::remove_dir_all(temp.path().join("b")).unwrap();
+
+        let params = CmdParams::new(&basedir).with_output(Output::Json);
+        let result = run::<BackupTarget>(&params, &["--all", "--jobs", "2", "weekly"]);
+        assert_eq!(result.unwrap_err().to_string(), "1 of 3 backups failed.");
+
+        let counts: Vec<usize> = ids
+            .iter()
+            .map(|id| repo.load(id).unwrap().backups.len())
+            .collect();
+        assert_eq!(counts, vec![1, 0, 1]);
+        assert_eq!(repo.load(&ids[2]).unwrap().backups[0].note, "weekly");
+    }
+
     #[test]
     fn it_fails_without_arguments() {
         let temp = mktemp::TempDir::new().unwrap();
         let basedir = temp.path();
 
-        let args = vec![String::from("test"), String::from("backup")];
-        let params = CmdParams::build(&args, &basedir).unwrap();
+        let params = CmdParams::new(&basedir);
+        for args in [vec![], vec!["--jobs", "2"], vec!["--all", "--jobs", "0"]] {
+            let result = run::<BackupTarget>(&params, &args);
+            assert!(result.is_err(), "{args:?}");
+        }
 
-        let cmd = BackupTarget {};
-        let result = cmd.execute(&params);
-        assert!(result.is_err());
+        let err = run::<BackupTarget>(&params, &["xxx"]).unwrap_err();
+        assert_eq!(exit_code_of(&err), exit_code::NOT_FOUND);
     }
 
     #[test]
@@ -104,16 +307,9 @@ mod tests {
         let target_path = std::fs::canonicalize(".").unwrap();
         let target = repo.add("TestTarget", &target_path).unwrap();
 
-        // CmdParams
-        let args: Vec<String> = ["test", "backup", &target.id]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let params = CmdParams::build(&args, &basedir).unwrap();
-
         // Execute
-        let cmd = BackupTarget {};
-        let result = cmd.execute(&params);
+        let params = CmdParams::new(&basedir).with_output(Output::Json);
+        let result = run::<BackupTarget>(&params, &[&target.id]);
         if let Err(ref e) = result {
             println!("{:?}", e);
         }
@@ -0,0 +1,121 @@
+//!
+//! # CatFile command
+//!
+//! Prints a file in a backup to the stdout, e.g. to compare it with the current file:
+//! `dirback cat app latest config/prod.yaml | diff - config/prod.yaml`
+//!
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::targz_backup_service::TargzBackupService;
+use dirback::usecase::cat_file::{CatFileError, CatFileUsecase};
+use dirback::usecase::dto::BackupSelector;
+use dirback_cmd::CmdError;
+use std::io::Write;
+use std::path::PathBuf;
+
+/// Print a file in a backup to the stdout, without restoring the backup.
+#[derive(clap::Args)]
+pub struct CatFile {
+    target_id: String,
+
+    /// The backup ID or selector, e.g. `latest~1` (see `dirback help`).
+    backup: BackupSelector,
+
+    /// The path of the file in the backup, e.g. `config/prod.yaml` (see `dirback find`).
+    path: PathBuf,
+}
+
+impl dirback_cmd::Command for CatFile {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let mut stdout = std::io::stdout().lock();
+        match self.write_to(params, &mut stdout) {
+            // The reader of the pipe has quit, e.g. `| head`.
+            Err(e) if is_broken_pipe(&e) => Ok(()),
+            result => result,
+        }
+    }
+}
+
+impl CatFile {
+    fn write_to(&self, params: &dirback_cmd::CmdParams, out: &mut dyn Write) -> anyhow::Result<()> {
+        let repo = FileStorageTargetRepository::new(&params.basedir);
+        let target = super::get_target(&repo, &self.target_id)?;
+        let entry = super::get_backup(&repo, &target, &self.backup)?;
+
+        let service = TargzBackupService::new();
+        CatFileUsecase::new(&repo, &service)
+            .execute(&target.id, entry.id, &self.path, out)
+            .map_err(|e| match e.downcast::<CatFileError>() {
+                Ok(e) => CmdError::NotFound(e.to_string()).into(),
+                Err(e) => e,
+            })?;
+        out.flush()?;
+
+        Ok(())
+    }
+}
+
+fn is_broken_pipe(e: &anyhow::Error) -> bool {
+    e.downcast_ref::<std::io::Error>()
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::BrokenPipe)
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback::usecase::backup::BackupUsecase;
+    use dirback_cmd::*;
+
+    fn cat(params: &CmdParams, args: &[&str]) -> anyhow::Result<Vec<u8>> {
+        let cmd: CatFile = parse_args(args)?;
+        let mut out = Vec::new();
+        cmd.write_to(params, &mut out)?;
+        Ok(out)
+    }
+
+    #[test]
+    fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let dir = temp.path().join("app");
+        std::fs::create_dir_all(dir.join("config")).unwrap();
+        std::fs::write(dir.join("config/prod.yaml"), "v1").unwrap();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("app", &dir).unwrap();
+        let service = TargzBackupService::new();
+        BackupUsecase::new(&mut repo, &service)
+            .execute(&target.id, "")
+            .unwrap();
+
+        let params = CmdParams::new(&basedir);
+        assert_eq!(
+            cat(&params, &["app", "latest", "config/prod.yaml"]).unwrap(),
+            b"v1"
+        );
+        assert_eq!(
+            cat(&params, &[&target.id, "1", "./config/prod.yaml"]).unwrap(),
+            b"v1"
+        );
+
+        for args in [
+            ["app", "latest", "config/dev.yaml"],
+            ["app", "2", "config/prod.yaml"],
+            ["unknown", "latest", "config/prod.yaml"],
+        ] {
+            let err = cat(&params, &args).unwrap_err();
+            assert_eq!(exit_code_of(&err), exit_code::NOT_FOUND);
+        }
+    }
+
+    #[test]
+    fn test_is_broken_pipe() {
+        let err = std::io::Error::from(std::io::ErrorKind::BrokenPipe);
+        assert!(is_broken_pipe(&err.into()));
+        assert!(!is_broken_pipe(&anyhow::anyhow!("error")));
+    }
+}
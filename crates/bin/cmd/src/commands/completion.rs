@@ -0,0 +1,404 @@
+//!
+//! # Completion commands
+//!
+//! `dirback completions <shell>` prints a completion script for the shell.
+//! The script calls the hidden `dirback __complete -- <words>` for the candidates,
+//! which walks the clap command tree for the subcommands and flags,
+//! and reads the repository for the target IDs and the backup IDs.
+//!
+
+use clap::CommandFactory;
+use dirback::adapter::ListTargetsAdapter;
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback_cmd::say;
+use std::path::{Path, PathBuf};
+
+/// Print the shell completion script.
+///
+/// e.g. `dirback completions bash > ~/.local/share/bash-completion/completions/dirback`
+#[derive(clap::Args)]
+pub struct Completions {
+    shell: Shell,
+}
+
+#[derive(Clone, Copy, clap::ValueEnum)]
+enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+}
+
+const BASH_SCRIPT: &str = r#"_dirback() {
+    local cur="${COMP_WORDS[COMP_CWORD]}"
+    local IFS=$'\n'
+    local candidates
+    candidates=$(dirback __complete -- "${COMP_WORDS[@]:0:COMP_CWORD+1}" 2>/dev/null | cut -f1)
+    COMPREPLY=($(compgen -W "$candidates" -- "$cur"))
+}
+complete -o default -F _dirback dirback
+"#;
+
+const ZSH_SCRIPT: &str = r#"#compdef dirback
+
+_dirback() {
+    local -a candidates
+    candidates=("${(@f)$(dirback __complete -- "${(@)words[1,CURRENT]}" 2>/dev/null | sed -e 's/:/\\:/g' -e 's/\t/:/')}")
+    if (( ${#candidates[@]} )) && [[ -n "${candidates[1]}" ]]; then
+        _describe 'dirback' candidates
+    else
+        _files
+    fi
+}
+
+compdef _dirback dirback
+"#;
+
+const FISH_SCRIPT: &str = r#"function __dirback_complete
+    dirback __complete -- (commandline -opc) (commandline -ct) 2>/dev/null
+end
+
+complete -c dirback -a '(__dirback_complete)'
+"#;
+
+impl dirback_cmd::Command for Completions {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let script = match self.shell {
+            Shell::Bash => BASH_SCRIPT,
+            Shell::Zsh => ZSH_SCRIPT,
+            Shell::Fish => FISH_SCRIPT,
+        };
+        say!(params, "{}", script.trim_end());
+        Ok(())
+    }
+}
+
+/// Print the completion candidates, one `value<TAB>description` per line.
+#[derive(clap::Args)]
+pub struct Complete {
+    /// The command line words, the last one is the word being completed.
+    #[arg(trailing_var_arg = true, allow_hyphen_values = true)]
+    words: Vec<String>,
+}
+
+impl dirback_cmd::Command for Complete {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        // Completions are printed as is, even with `--json` or `--quiet`.
+        for candidate in candidates(&params.basedir, &self.words) {
+            match candidate.description.is_empty() {
+                true => println!("{}", candidate.value),
+                false => println!("{}\t{}", candidate.value, candidate.description),
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Candidate {
+    value: String,
+    description: String,
+}
+
+impl Candidate {
+    fn new(value: impl Into<String>, description: impl ToString) -> Self {
+        Self {
+            value: value.into(),
+            description: description.to_string(),
+        }
+    }
+}
+
+/// What the word being completed is.
+enum Expect {
+    /// The value of the flag.
+    FlagValue(Box<clap::Arg>),
+
+    /// The positional argument at the index, or a subcommand.
+    Positional(usize),
+}
+
+/// Returns the candidates for the last word of `words` (`words[0]` is the program name).
+fn candidates(basedir: &Path, words: &[String]) -> Vec<Candidate> {
+    let Some((current, preceding)) = words.split_last() else {
+        return Vec::new();
+    };
+
+    let mut cmd = crate::Cli::command();
+    cmd.build();
+
+    let mut basedir = basedir.to_path_buf();
+    let mut positionals: Vec<&str> = Vec::new();
+    let mut expect = Expect::Positional(0);
+    for word in preceding.iter().skip(1) {
+        if let Expect::FlagValue(arg) = &expect {
+            if arg.get_id() == "datadir" {
+                basedir = PathBuf::from(word);
+            }
+            expect = Expect::Positional(positionals.len());
+            continue;
+        }
+
+        if word.starts_with('-') && word.len() > 1 {
+            if let Some(arg) = find_flag(&cmd, word) {
+                if arg.get_action().takes_values() && !word.contains('=') {
+                    expect = Expect::FlagValue(Box::new(arg.clone()));
+                }
+            }
+            continue;
+        }
+
+        match cmd.find_subcommand(word).filter(|_| positionals.is_empty()) {
+            Some(sub) => cmd = sub.clone(),
+            None => positionals.push(word),
+        }
+        expect = Expect::Positional(positionals.len());
+    }
+
+    let mut candidates = match expect {
+        Expect::FlagValue(arg) => possible_values(&arg),
+        Expect::Positional(_) if current.starts_with('-') => flags(&cmd),
+        Expect::Positional(index) if cmd.has_subcommands() && index == 0 => subcommands(&cmd),
+        Expect::Positional(index) => match positional(&cmd, index) {
+            Some(arg) => positional_values(&basedir, &cmd, &positionals, arg),
+            None => Vec::new(),
+        },
+    };
+    candidates.retain(|candidate| candidate.value.starts_with(current.as_str()));
+    candidates
+}
+
+fn find_flag<'a>(cmd: &'a clap::Command, word: &str) -> Option<&'a clap::Arg> {
+    let name = word.split('=').next().unwrap_or(word);
+    cmd.get_arguments().find(|arg| {
+        if let Some(long) = name.strip_prefix("--") {
+            arg.get_long() == Some(long)
+        } else {
+            let mut chars = name.chars().skip(1);
+            chars.next() == arg.get_short() && chars.next().is_none()
+        }
+    })
+}
+
+fn flags(cmd: &clap::Command) -> Vec<Candidate> {
+    cmd.get_arguments()
+        .filter(|arg| !arg.is_positional() && !arg.is_hide_set())
+        .flat_map(|arg| {
+            let help = arg
+                .get_help()
+                .map(|help| help.to_string())
+                .unwrap_or_default();
+            let long = arg
+                .get_long()
+                .map(|long| Candidate::new(format!("--{long}"), &help));
+            let short = arg
+                .get_short()
+                .map(|short| Candidate::new(format!("-{short}"), &help));
+            long.into_iter().chain(short)
+        })
+        .collect()
+}
+
+fn subcommands(cmd: &clap::Command) -> Vec<Candidate> {
+    cmd.get_subcommands()
+        .filter(|sub| !sub.is_hide_set())
+        .map(|sub| {
+            let about = sub
+                .get_about()
+                .map(|about| about.to_string())
+                .unwrap_or_default();
+            Candidate::new(sub.get_name(), about)
+        })
+        .collect()
+}
+
+/// Returns the positional argument at the index, or the last one if it takes several values.
+fn positional(cmd: &clap::Command, index: usize) -> Option<&clap::Arg> {
+    let args: Vec<&clap::Arg> = cmd.get_positionals().collect();
+    args.get(index).copied().or_else(|| {
+        args.last()
+            .copied()
+            .filter(|arg| arg.get_num_args().is_some_and(|n| n.max_values() > 1))
+    })
+}
+
+fn possible_values(arg: &clap::Arg) -> Vec<Candidate> {
+    arg.get_possible_values()
+        .into_iter()
+        .filter(|value| !value.is_hide_set())
+        .map(|value| {
+            let help = value
+                .get_help()
+                .map(|help| help.to_string())
+                .unwrap_or_default();
+            Candidate::new(value.get_name(), help)
+        })
+        .collect()
+}
+
+fn positional_values(
+    basedir: &Path,
+    cmd: &clap::Command,
+    positionals: &[&str],
+    arg: &clap::Arg,
+) -> Vec<Candidate> {
+    match arg.get_id().as_str() {
+        "target_id" => target_ids(basedir),
+        "backup" | "backups" | "backup_ids" => {
+            // The target is the preceding `target_id` argument.
+            let target_id = cmd
+                .get_positionals()
+                .position(|arg| arg.get_id() == "target_id")
+                .and_then(|index| positionals.get(index));
+            match target_id {
+                Some(target_id) => backup_ids(basedir, target_id, arg.get_id() != "backup_ids"),
+                None => Vec::new(),
+            }
+        }
+        _ => possible_values(arg),
+    }
+}
+
+fn target_ids(basedir: &Path) -> Vec<Candidate> {
+    let repo = FileStorageTargetRepository::new(basedir);
+    ListTargetsAdapter::new(&repo)
+        .execute()
+        .unwrap_or_default()
+        .into_iter()
+        .map(|target| Candidate::new(target.id, target.name))
+        .collect()
+}
+
+/// Returns the backup IDs of the target, newest first, and the keywords if `selectors`.
+fn backup_ids(basedir: &Path, target_id: &str, selectors: bool) -> Vec<Candidate> {
+    let repo = FileStorageTargetRepository::new(basedir);
+    let Ok(target) = super::get_target(&repo, target_id) else {
+        return Vec::new();
+    };
+
+    let mut candidates: Vec<Candidate> = target
+        .backups
+        .iter()
+        .rev()
+        .map(|entry| {
+            let time = entry.timestamp.to_rfc3339();
+            match entry.note.is_empty() {
+                true => Candidate::new(entry.id.to_string(), time),
+                false => Candidate::new(entry.id.to_string(), format!("{time} {}", entry.note)),
+            }
+        })
+        .collect();
+    if selectors && !candidates.is_empty() {
+        candidates.push(Candidate::new("latest", "the newest backup"));
+        candidates.push(Candidate::new("first", "the oldest backup"));
+    }
+    candidates
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    fn prepare_test_data(basedir: &Path) -> String {
+        let mut repo = FileStorageTargetRepository::new(basedir);
+        let mut target = repo.add("notes", Path::new(".")).unwrap();
+        let bkdir = repo.make_backup_dir_path(&target);
+        for _ in 1..=2 {
+            let entry = target.new_backup_entry(&bkdir, "tar.gz");
+            let _ = target.register_backup_entry(entry);
+        }
+        target.backups[0].note = String::from("initial");
+        repo.update(&target).unwrap().id
+    }
+
+    fn values(basedir: &Path, words: &[&str]) -> Vec<String> {
+        let words: Vec<String> = words.iter().map(|word| word.to_string()).collect();
+        candidates(basedir, &words)
+            .into_iter()
+            .map(|candidate| candidate.value)
+            .collect()
+    }
+
+    #[test]
+    fn it_prints_scripts() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let params = CmdParams::new(&temp.path());
+        for shell in ["bash", "zsh", "fish"] {
+            assert!(run::<Completions>(&params, &[shell]).is_ok());
+        }
+        assert!(run::<Completions>(&params, &["powershell"]).is_err());
+        assert!(run::<Complete>(&params, &["--", "dirback", "--j"]).is_ok());
+    }
+
+    #[test]
+    fn it_completes_subcommands_and_flags() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let got = values(&basedir, &["dirback", "re"]);
+        assert_eq!(got, ["register", "restore", "replica"]);
+        assert!(!values(&basedir, &["dirback", ""]).contains(&String::from("__complete")));
+
+        assert_eq!(values(&basedir, &["dirback", "delete", "--y"]), ["--yes"]);
+        assert_eq!(values(&basedir, &["dirback", "list", "--js"]), ["--json"]);
+        assert_eq!(values(&basedir, &["dirback", "tag", "a"]), ["add"]);
+        assert_eq!(values(&basedir, &["dirback", "completions", "z"]), ["zsh"]);
+    }
+
+    #[test]
+    fn it_completes_target_ids_with_names() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let target_id = prepare_test_data(&basedir);
+
+        let words: Vec<String> = ["dirback", "show", ""].map(String::from).to_vec();
+        assert_eq!(
+            candidates(&basedir, &words),
+            [Candidate::new(target_id.clone(), "notes")]
+        );
+
+        let got = values(&basedir, &["dirback", "-v", "tag", "add", ""]);
+        assert_eq!(got, [target_id.clone()]);
+        assert!(values(&basedir, &["dirback", "show", "x"]).is_empty());
+    }
+
+    #[test]
+    fn it_completes_backup_ids_with_timestamps_and_notes() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let target_id = prepare_test_data(&basedir);
+
+        let words: Vec<String> = ["dirback", "restore", "notes", ""]
+            .map(String::from)
+            .to_vec();
+        let got = candidates(&basedir, &words);
+        assert_eq!(
+            got.iter().map(|c| c.value.as_str()).collect::<Vec<&str>>(),
+            ["2", "1", "latest", "first"]
+        );
+        assert!(got[1].description.ends_with(" initial"));
+
+        let got = values(&basedir, &["dirback", "pin", &target_id, "1", ""]);
+        assert_eq!(got, ["2", "1", "latest", "first"]);
+        let got = values(&basedir, &["dirback", "export", &target_id, "out.tar", ""]);
+        assert_eq!(got, ["2", "1"]);
+        assert!(values(&basedir, &["dirback", "restore", "unknown", ""]).is_empty());
+    }
+
+    #[test]
+    fn it_uses_datadir_of_the_command_line() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let target_id = prepare_test_data(&basedir);
+
+        let other = mktemp::TempDir::new().unwrap();
+        let datadir = basedir.to_string_lossy().to_string();
+        let got = values(
+            &other.path(),
+            &["dirback", "--datadir", &datadir, "show", ""],
+        );
+        assert_eq!(got, [target_id]);
+        assert!(values(&other.path(), &["dirback", "show", ""]).is_empty());
+    }
+}
@@ -0,0 +1,132 @@
+//!
+//! # Config command
+//!
+//! Prints the effective config and where each value comes from.
+//!
+
+use dirback::infra::app_path;
+use dirback::infra::config::{CONFIG_FILE_NAME, Setting};
+use dirback_cmd::say;
+
+/// Show the effective config.
+#[derive(clap::Args)]
+pub struct AppConfig {
+    #[command(subcommand)]
+    command: Option<ConfigCommand>,
+}
+
+#[derive(clap::Subcommand)]
+enum ConfigCommand {
+    /// Print the effective config with the source of each value (default).
+    Show,
+}
+
+impl dirback_cmd::Command for AppConfig {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        match self.command {
+            None | Some(ConfigCommand::Show) => show(params),
+        }
+    }
+}
+
+fn show(params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+    let config = &params.config;
+    if params.is_json() {
+        return params.print_json(config);
+    }
+
+    match &config.file {
+        Some(file) => say!(params, "Config file : {}", file.display()),
+        None => {
+            let default = app_path::config_dir()
+                .map(|dir| dir.join(CONFIG_FILE_NAME).display().to_string())
+                .unwrap_or_default();
+            say!(params, "Config file : none ({default} not found)");
+        }
+    }
+    say!(params);
+
+    let datadir = config.datadir.value.display().to_string();
+    let retention = &config.retention.value;
+    let retention = match (retention.keep_last, retention.keep_days) {
+        (None, None) => String::from("none"),
+        (keep_last, keep_days) => [
+            keep_last.map(|n| format!("keep_last = {n}")),
+            keep_days.map(|d| format!("keep_days = {d}")),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<String>>()
+        .join(", "),
+    };
+    let excludes = match config.excludes.value.as_slice() {
+        [] => String::from("none"),
+        patterns => patterns
+            .iter()
+            .map(|p| p.to_string())
+            .collect::<Vec<String>>()
+            .join(", "),
+    };
+
+    say!(params, "{}", line("datadir", &datadir, &config.datadir));
+    say!(
+        params,
+        "{}",
+        line("log_level", &config.log_level.value, &config.log_level)
+    );
+    say!(
+        params,
+        "{}",
+        line(
+            "compression",
+            &config.compression.value,
+            &config.compression
+        )
+    );
+    say!(
+        params,
+        "{}",
+        line("retention", &retention, &config.retention)
+    );
+    say!(params, "{}", line("excludes", &excludes, &config.excludes));
+
+    Ok(())
+}
+
+fn line<T>(name: &str, value: &dyn std::fmt::Display, setting: &Setting<T>) -> String {
+    format!("{name:<12}= {value}  ({})", setting.source)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::infra::config::{Config, ConfigFlags, ConfigSource, LogLevel};
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let file = basedir.join(CONFIG_FILE_NAME);
+        let text = "compression = 9\nexcludes = [\"*.log\"]\n[retention]\nkeep_last = 3\n";
+        std::fs::write(&file, text).unwrap();
+
+        let flags = ConfigFlags {
+            config: Some(file),
+            datadir: Some(basedir.clone()),
+            log_level: Some(LogLevel::Debug),
+        };
+        // Loaded without the environment variables of the host.
+        let config = Config::load_with(&flags, |_| None, None).unwrap();
+        assert_eq!(config.compression.value, 9);
+        assert_eq!(config.log_level.source, ConfigSource::Flag("--log-level"));
+
+        for output in [Output::Normal, Output::Json] {
+            let params = CmdParams::new(&basedir)
+                .with_output(output)
+                .with_config(config.clone());
+            assert!(run::<AppConfig>(&params, &[]).is_ok());
+            assert!(run::<AppConfig>(&params, &["show"]).is_ok());
+        }
+    }
+}
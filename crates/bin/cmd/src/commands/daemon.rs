@@ -0,0 +1,143 @@
+//!
+//! # Daemon command
+//!
+//! Runs the scheduled backups until stopped.
+//!
+//! The targets are reloaded on each wake up, so schedule changes take effect
+//! without restarting the daemon. The daemon wakes up at least every minute,
+//! so runs missed while the machine was asleep are caught up soon after resume.
+//!
+
+use dirback::adapter::ListSchedulesAdapter;
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::targz_backup_service::TargzBackupService;
+use dirback::usecase::dto::{ScheduledRun, Timestamp};
+use dirback::usecase::run_scheduled::RunScheduledUsecase;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::{error, info};
+
+/// The longest sleep between checks.
+const MAX_SLEEP: Duration = Duration::from_secs(60);
+
+/// Run the scheduled backups until stopped.
+#[derive(clap::Args)]
+pub struct Daemon {
+    /// Run the due backups once and exit, e.g. from cron.
+    #[arg(long)]
+    once: bool,
+
+    /// The log file, `{datadir}/dirback.log` by default.
+    #[arg(long, value_name = "PATH")]
+    logfile: Option<PathBuf>,
+}
+
+impl dirback_cmd::Command for Daemon {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let logfile = self
+            .logfile
+            .clone()
+            .unwrap_or_else(|| params.basedir.join("dirback.log"));
+        let _guard = crate::logs::setup_logger(&logfile, params.config.log_level.value)?;
+
+        info!("Start Dirback daemon");
+        info!("Dirback base dir: {}", params.basedir.display());
+        info!("Log file: {}", logfile.display());
+
+        let service = TargzBackupService::new()
+            .with_compression(params.config.compression.value)
+            .with_excludes(&params.config.excludes.value);
+        let hook_service = params.hook_service();
+        loop {
+            let mut repo = FileStorageTargetRepository::new(&params.basedir);
+            let now = Timestamp::now();
+
+            let mut usecase =
+                RunScheduledUsecase::new(&mut repo, &service).with_hooks(&hook_service);
+            match usecase.execute(&now) {
+                Ok(runs) => runs.iter().for_each(log_run),
+                Err(e) => error!("Failed to run the scheduled backups: {e}"),
+            }
+
+            if self.once {
+                return Ok(());
+            }
+
+            let next = ListSchedulesAdapter::new(&repo)
+                .execute(&Timestamp::now())
+                .unwrap_or_else(|e| {
+                    error!("Failed to load the schedules: {e}");
+                    Vec::new()
+                })
+                .into_iter()
+                .next();
+            let sleep = match next {
+                Some((target, next)) => {
+                    info!("Next run: '{}' at {}", target.name, next.to_rfc3339());
+                    (*next - *Timestamp::now())
+                        .to_std()
+                        .unwrap_or(Duration::ZERO)
+                        .clamp(Duration::from_secs(1), MAX_SLEEP)
+                }
+                None => MAX_SLEEP,
+            };
+            std::thread::sleep(sleep);
+        }
+    }
+}
+
+fn log_run(run: &ScheduledRun) {
+    if let Some(backup) = &run.backup {
+        info!(
+            "Target({}) '{}': backup {:0>3} is complete.",
+            run.target_id, run.target_name, backup.id
+        );
+    }
+    if !run.pruned.is_empty() {
+        let ids: Vec<String> = run.pruned.iter().map(|b| format!("{:0>3}", b.id)).collect();
+        info!(
+            "Target({}) '{}': pruned {}",
+            run.target_id,
+            run.target_name,
+            ids.join(" ")
+        );
+    }
+    if let Some(e) = &run.error {
+        error!("Target({}) '{}': {e}", run.target_id, run.target_name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_runs_due_backups_once() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path().join("data");
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let mut target = repo.add("TestTarget", &src).unwrap();
+        target.schedule = Some("every 1d".parse().unwrap());
+        repo.update(&target).unwrap();
+        let manual = repo.add("Manual", &src).unwrap();
+
+        let logfile = temp.path().join("logs").join("daemon.log");
+        let params = CmdParams::new(&basedir);
+        let args = ["--once", "--logfile", &logfile.to_string_lossy()];
+        assert!(run::<Daemon>(&params, &args).is_ok());
+
+        assert_eq!(repo.load(&target.id).unwrap().backups.len(), 1);
+        assert!(repo.load(&manual.id).unwrap().backups.is_empty());
+        assert!(logfile.exists());
+    }
+
+    #[test]
+    fn it_returns_err_with_unknown_option() {
+        assert!(parse_args::<Daemon>(&["--forever"]).is_err());
+    }
+}
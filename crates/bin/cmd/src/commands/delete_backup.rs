@@ -2,82 +2,65 @@
 //! # DeleteBackup command
 //!
 
-use anyhow::Context;
-use dirback::adapter::GetTargetAdapter;
 use dirback::infra::repository::file_storage::FileStorageTargetRepository;
 use dirback::usecase::delete_backup::DeleteBackupUsecase;
+use dirback::usecase::dto::{BackupSelector, TRASH_EXPIRY_DAYS};
+use dirback_cmd::{CmdError, detail, say};
 
-use std::io::BufRead;
-use std::io::Write;
-
+/// Delete a backup of the target.
+#[derive(clap::Args)]
 pub struct DeleteBackup {
-    create_reader: fn() -> Box<dyn std::io::Read>,
-}
+    target_id: String,
 
-impl DeleteBackup {
-    pub fn new() -> Self {
-        Self {
-            create_reader: || Box::new(std::io::stdin()),
-        }
-    }
+    /// The backup ID or selector, e.g. `latest~1` (see `dirback help`).
+    backup: BackupSelector,
+
+    /// Delete without the confirmation (required with --json).
+    #[arg(short, long)]
+    yes: bool,
+
+    #[arg(skip = super::stdin_reader as fn() -> Box<dyn std::io::Read>)]
+    create_reader: fn() -> Box<dyn std::io::Read>,
 }
 
 impl dirback_cmd::Command for DeleteBackup {
     fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
-        if params.args.len() < 2 {
-            anyhow::bail!("Missing args: <target-id> <backup-id>");
+        if params.is_json() && !self.yes {
+            return Err(CmdError::Usage(String::from("--json requires --yes.")).into());
         }
 
-        let target_id = params.args[0].to_string();
-        let backup_id = params.args[1].to_string();
-
-        let backup_id = backup_id
-            .parse::<u32>()
-            .context(format!("Invalid backup ID ('{backup_id}')."))?;
-
         let mut repo = FileStorageTargetRepository::new(&params.basedir);
-        let target = GetTargetAdapter::new(&repo)
-            .execute(&target_id)
-            .context(format!("Target not found ('{target_id}')"))?;
-
-        let entry = target
-            .backups
-            .iter()
-            .find(|be| be.id == backup_id)
-            .context(format!("Backup not found ('{backup_id}')."))?;
-
-        println!("* Target: {}", target.name);
-        println!("ID    : {}", target.id);
-        println!("Path  : {}", target.path.to_string_lossy());
-        println!();
-        println!("* Backup: {:0>3}", entry.id);
-        println!("Backup date: {}", entry.timestamp.to_rfc3339());
-        println!("Backup file: {}", entry.path.to_string_lossy());
-        println!("Note       : {}", entry.note);
-        println!();
-        println!("##### Delete confirmation #####");
-        println!("Do you want to delete the Backup {:0>3}?", entry.id);
-        println!("This action cannnot be undone.");
-        print!("[yes/No] > ");
-
-        std::io::stdout().flush().unwrap();
-        let mut yesno = String::new();
-        let reader = (self.create_reader)();
-        let mut buf_reader = std::io::BufReader::new(reader);
-        buf_reader
-            .read_line(&mut yesno)
-            .expect("Error: Failed to read line.");
-        let yesno = yesno.trim().to_lowercase();
-        if yesno != "yes" {
-            println!("Cancelled.");
-            return Ok(());
+        let target = super::get_target(&repo, &self.target_id)?;
+        let entry = super::get_backup(&repo, &target, &self.backup)?;
+        if entry.pinned {
+            anyhow::bail!("The backup[{:0>3}] is pinned. Unpin it first.", entry.id);
+        }
+
+        if !self.yes {
+            say!(params, "* Target: {}", target.name);
+            say!(params, "ID    : {}", target.id);
+            say!(params, "Path  : {}", target.path.to_string_lossy());
+            say!(params);
+            say!(params, "* Backup: {:0>3}", entry.id);
+            say!(params, "Backup date: {}", entry.timestamp.to_rfc3339());
+            say!(params, "Backup file: {}", entry.path.to_string_lossy());
+            say!(params, "Note       : {}", entry.note);
+            say!(params);
+            println!("##### Delete confirmation #####");
+            println!("Do you want to delete the Backup {:0>3}?", entry.id);
+            println!("It is moved to the trash, and can be restored for {TRASH_EXPIRY_DAYS} days.");
+            super::confirm((self.create_reader)())?;
         }
 
         // Delete the backup entry.
         let mut usecase = DeleteBackupUsecase::new(&mut repo);
-        let de = usecase.execute(&target.id, entry.id)?;
+        usecase.execute(&target.id, entry.id)?;
 
-        println!("The backup[{:0>3}] has been deleted.", de.id);
+        if params.is_json() {
+            return params.print_json(&entry);
+        }
+        say!(params, "The backup[{:0>3}] has been deleted.", entry.id);
+        detail!(params, "Backup file: {}", entry.path.to_string_lossy());
         Ok(())
     }
 }
@@ -121,19 +104,12 @@ mod tests {
         let del_target_bk_path = target.backups[1].path.clone();
         let before_backup_count = target.backups.len();
 
-        // CmdParams
-        let args: Vec<String> = ["test", "delete", &target.id, "2"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let params = CmdParams::build(&args, &basedir).unwrap();
-
         // Execute
-        let cmd = DeleteBackup {
-            create_reader: || {
-                let data = "yes";
-                Box::new(std::io::Cursor::new(data))
-            },
+        let params = CmdParams::new(&basedir);
+        let mut cmd: DeleteBackup = parse_args(&[&target.id, "2"]).unwrap();
+        cmd.create_reader = || {
+            let data = "yes";
+            Box::new(std::io::Cursor::new(data))
         };
 
         let result = cmd.execute(&params);
@@ -163,26 +139,19 @@ mod tests {
         let del_target_bk_path = target.backups[1].path.clone();
         let before_backup_count = target.backups.len();
 
-        // CmdParams
-        let args: Vec<String> = ["test", "delete", &target.id, "2"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let params = CmdParams::build(&args, &basedir).unwrap();
-
         // Execute
-        let cmd = DeleteBackup {
-            create_reader: || {
-                let data = "no";
-                Box::new(std::io::Cursor::new(data))
-            },
+        let params = CmdParams::new(&basedir);
+        let mut cmd: DeleteBackup = parse_args(&[&target.id, "2"]).unwrap();
+        cmd.create_reader = || {
+            let data = "no";
+            Box::new(std::io::Cursor::new(data))
         };
 
         let result = cmd.execute(&params);
         if let Err(ref e) = result {
             println!("{:?}", e);
         }
-        assert!(result.is_ok());
+        assert_eq!(exit_code_of(&result.unwrap_err()), exit_code::CANCELLED);
         assert!(
             del_target_bk_path.exists(),
             "The backup file should not be deleted."
@@ -193,27 +162,38 @@ mod tests {
     }
 
     #[test]
-    fn it_returns_err_when_non_existent_target_id() {
+    fn it_returns_err_when_backup_is_pinned() {
         let temp = mktemp::TempDir::new().unwrap();
         let basedir = temp.path();
 
-        // CmdParams
-        let args: Vec<String> = ["test", "delete", "xxxx-xxxxx-xxx", "1"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let params = CmdParams::build(&args, &basedir).unwrap();
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target_id = prepare_test_data(&temp);
+        let mut target = repo.load(&target_id).unwrap();
+        target.set_pinned(2, true).unwrap();
+        repo.update(&target).unwrap();
+
+        let params = CmdParams::new(&basedir);
+        let result = run::<DeleteBackup>(&params, &[&target.id, "2", "--yes"]);
+        assert!(result.is_err());
+        assert!(target.backups[1].path.exists());
+        assert_eq!(repo.load(&target.id).unwrap().backups.len(), 3);
+    }
+
+    #[test]
+    fn it_returns_err_when_non_existent_target_id() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
 
         // Execute
-        let cmd = DeleteBackup {
-            create_reader: || {
-                let data = "yes";
-                Box::new(std::io::Cursor::new(data))
-            },
+        let params = CmdParams::new(&basedir);
+        let mut cmd: DeleteBackup = parse_args(&["xxxx-xxxxx-xxx", "1"]).unwrap();
+        cmd.create_reader = || {
+            let data = "yes";
+            Box::new(std::io::Cursor::new(data))
         };
 
         let result = cmd.execute(&params);
-        assert!(result.is_err());
+        assert_eq!(exit_code_of(&result.unwrap_err()), exit_code::NOT_FOUND);
     }
 
     #[test]
@@ -226,22 +206,32 @@ mod tests {
         let target_id = prepare_test_data(&temp);
         let target = repo.load(&target_id).unwrap();
 
-        // CmdParams
-        let args: Vec<String> = ["test", "delete", &target.id, "10"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let params = CmdParams::build(&args, &basedir).unwrap();
-
         // Execute
-        let cmd = DeleteBackup {
-            create_reader: || {
-                let data = "yes";
-                Box::new(std::io::Cursor::new(data))
-            },
+        let params = CmdParams::new(&basedir);
+        let mut cmd: DeleteBackup = parse_args(&[&target.id, "10"]).unwrap();
+        cmd.create_reader = || {
+            let data = "yes";
+            Box::new(std::io::Cursor::new(data))
         };
 
         let result = cmd.execute(&params);
         assert!(result.is_err());
     }
+
+    #[test]
+    fn it_requires_yes_with_json() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let target_id = prepare_test_data(&temp);
+        let repo = FileStorageTargetRepository::new(&basedir);
+
+        let params = CmdParams::new(&basedir).with_output(Output::Json);
+        let err = run::<DeleteBackup>(&params, &[&target_id, "2"]).unwrap_err();
+        assert_eq!(exit_code_of(&err), exit_code::USAGE);
+        assert_eq!(repo.load(&target_id).unwrap().backups.len(), 3);
+
+        let result = run::<DeleteBackup>(&params, &[&target_id, "2", "-y"]);
+        assert!(result.is_ok());
+        assert_eq!(repo.load(&target_id).unwrap().backups.len(), 2);
+    }
 }
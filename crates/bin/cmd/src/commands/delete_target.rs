@@ -2,81 +2,82 @@
 //! # DeleteTarget command
 //!
 
-use anyhow::Context;
-use dirback::adapter::GetTargetAdapter;
 use dirback::infra::repository::file_storage::FileStorageTargetRepository;
 use dirback::usecase::delete_target::DeleteTargetUsecase;
+use dirback::usecase::dto::TRASH_EXPIRY_DAYS;
+use dirback_cmd::{CmdError, say};
 
-use std::io::BufRead;
-use std::io::Write;
-
+/// Delete the target and its backups.
+#[derive(clap::Args)]
 pub struct DeleteTarget {
-    create_reader: fn() -> Box<dyn std::io::Read>,
-}
+    target_id: String,
 
-impl DeleteTarget {
-    pub fn new() -> Self {
-        Self {
-            create_reader: || Box::new(std::io::stdin()),
-        }
-    }
+    /// Delete the pinned backups too.
+    #[arg(long)]
+    force: bool,
+
+    /// Delete without the confirmation (required with --json).
+    #[arg(short, long)]
+    yes: bool,
+
+    #[arg(skip = super::stdin_reader as fn() -> Box<dyn std::io::Read>)]
+    create_reader: fn() -> Box<dyn std::io::Read>,
 }
 
 impl dirback_cmd::Command for DeleteTarget {
     fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
-        if params.args.is_empty() {
-            anyhow::bail!("Missing args: <target-id>");
+        if params.is_json() && !self.yes {
+            return Err(CmdError::Usage(String::from("--json requires --yes.")).into());
         }
 
-        let target_id = params.args[0].to_string();
-
         let mut repo = FileStorageTargetRepository::new(&params.basedir);
-        let target = GetTargetAdapter::new(&repo)
-            .execute(&target_id)
-            .context(format!("Target not found ('{target_id}')"))?;
-
-        println!("* Target: {}", target.name);
-        println!("ID    : {}", target.id);
-        println!("Path  : {}", target.path.to_string_lossy());
-        println!();
-
-        if target.backups.is_empty() {
-            println!("No backups yet.");
-        } else {
-            println!("* Backups ({})", target.backups.len());
-            for entry in target.backups.iter() {
-                print!("- {}: {}", entry.id, entry.timestamp.to_rfc3339());
-                if !entry.note.is_empty() {
-                    print!(" - {}", entry.note);
+        let target = super::get_target(&repo, &self.target_id)?;
+
+        let pinned = target.backups.iter().filter(|b| b.pinned).count();
+        if pinned > 0 && !self.force {
+            anyhow::bail!(
+                "The target '{}' has {pinned} pinned backup(s). Use --force to delete them too.",
+                target.name
+            );
+        }
+
+        if !self.yes {
+            say!(params, "* Target: {}", target.name);
+            say!(params, "ID    : {}", target.id);
+            say!(params, "Path  : {}", target.path.to_string_lossy());
+            say!(params);
+
+            if target.backups.is_empty() {
+                say!(params, "No backups yet.");
+            } else {
+                say!(params, "* Backups ({})", target.backups.len());
+                for entry in target.backups.iter() {
+                    let mut line = format!("- {}: {}", entry.id, entry.timestamp.to_rfc3339());
+                    if entry.pinned {
+                        line.push_str(" (pinned)");
+                    }
+                    if !entry.note.is_empty() {
+                        line.push_str(&format!(" - {}", entry.note));
+                    }
+                    say!(params, "{line}");
                 }
-                println!();
+                say!(params);
             }
-            println!();
-        }
 
-        println!("##### Delete confirmation #####");
-        println!("Do you want to delete the Target '{}'?", target.name);
-        println!("This action cannnot be undone.");
-        print!("[yes/No] > ");
-
-        std::io::stdout().flush().unwrap();
-        let mut yesno = String::new();
-        let reader = (self.create_reader)();
-        let mut buf_reader = std::io::BufReader::new(reader);
-        buf_reader
-            .read_line(&mut yesno)
-            .expect("Error: Failed to read line.");
-        let yesno = yesno.trim().to_lowercase();
-        if yesno != "yes" {
-            println!("Cancelled.");
-            return Ok(());
+            println!("##### Delete confirmation #####");
+            println!("Do you want to delete the Target '{}'?", target.name);
+            println!("It is moved to the trash, and can be restored for {TRASH_EXPIRY_DAYS} days.");
+            super::confirm((self.create_reader)())?;
         }
 
         // Delete the target.
-        let mut usecase = DeleteTargetUsecase::new(&mut repo);
-        let dt = usecase.execute(&target.id)?;
+        let mut usecase = DeleteTargetUsecase::new(&mut repo).allow_pinned(self.force);
+        usecase.execute(&target.id)?;
 
-        println!("The target '{}' has been deleted.", dt.name);
+        if params.is_json() {
+            return params.print_json(&target);
+        }
+        say!(params, "The target '{}' has been deleted.", target.name);
         Ok(())
     }
 }
@@ -115,19 +116,12 @@ mod tests {
         let del_target = repo.load(&ids[1]).unwrap();
         let del_target_dir = basedir.join("targets").join(&del_target.id);
 
-        // CmdParams
-        let args: Vec<String> = ["test", "delete-target", &del_target.id]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let params = CmdParams::build(&args, &basedir).unwrap();
-
         // Execute
-        let cmd = DeleteTarget {
-            create_reader: || {
-                let data = "yes";
-                Box::new(std::io::Cursor::new(data))
-            },
+        let params = CmdParams::new(&basedir);
+        let mut cmd: DeleteTarget = parse_args(&[&del_target.id]).unwrap();
+        cmd.create_reader = || {
+            let data = "yes";
+            Box::new(std::io::Cursor::new(data))
         };
 
         let result = cmd.execute(&params);
@@ -157,26 +151,19 @@ mod tests {
         let del_target = repo.load(&ids[1]).unwrap();
         let del_target_dir = basedir.join("targets").join(&del_target.id);
 
-        // CmdParams
-        let args: Vec<String> = ["test", "delete-target", &del_target.id]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let params = CmdParams::build(&args, &basedir).unwrap();
-
         // Execute
-        let cmd = DeleteTarget {
-            create_reader: || {
-                let data = "no";
-                Box::new(std::io::Cursor::new(data))
-            },
+        let params = CmdParams::new(&basedir);
+        let mut cmd: DeleteTarget = parse_args(&[&del_target.id]).unwrap();
+        cmd.create_reader = || {
+            let data = "no";
+            Box::new(std::io::Cursor::new(data))
         };
 
         let result = cmd.execute(&params);
         if let Err(ref e) = result {
             println!("{:?}", e);
         }
-        assert!(result.is_ok());
+        assert_eq!(exit_code_of(&result.unwrap_err()), exit_code::CANCELLED);
         assert!(
             del_target_dir.exists(),
             "The target info directory should not be deleted."
@@ -187,26 +174,42 @@ mod tests {
     }
 
     #[test]
-    fn it_returns_err_when_non_existent_target_id() {
+    fn it_requires_force_for_pinned_backups() {
         let temp = mktemp::TempDir::new().unwrap();
         let basedir = temp.path();
 
-        // CmdParams
-        let args: Vec<String> = ["test", "delete-target", "xxxx-xxxx-xxxx"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let params = CmdParams::build(&args, &basedir).unwrap();
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let ids = prepare_test_data(&temp);
+        let mut target = repo.load(&ids[0]).unwrap();
+        let entry = target.new_backup_entry(&repo.make_backup_dir_path(&target), "tar.gz");
+        target.register_backup_entry(entry).unwrap();
+        target.set_pinned(1, true).unwrap();
+        repo.update(&target).unwrap();
+
+        let params = CmdParams::new(&basedir);
+        for (args, deleted) in [
+            (vec![target.id.as_str(), "--yes"], false),
+            (vec![target.id.as_str(), "--yes", "--force"], true),
+        ] {
+            assert_eq!(run::<DeleteTarget>(&params, &args).is_ok(), deleted);
+            assert_eq!(repo.load(&target.id).is_none(), deleted);
+        }
+    }
+
+    #[test]
+    fn it_returns_err_when_non_existent_target_id() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
 
         // Execute
-        let cmd = DeleteTarget {
-            create_reader: || {
-                let data = "yes";
-                Box::new(std::io::Cursor::new(data))
-            },
+        let params = CmdParams::new(&basedir);
+        let mut cmd: DeleteTarget = parse_args(&["xxxx-xxxx-xxxx"]).unwrap();
+        cmd.create_reader = || {
+            let data = "yes";
+            Box::new(std::io::Cursor::new(data))
         };
 
         let result = cmd.execute(&params);
-        assert!(result.is_err());
+        assert_eq!(exit_code_of(&result.unwrap_err()), exit_code::NOT_FOUND);
     }
 }
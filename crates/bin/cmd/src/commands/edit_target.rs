@@ -0,0 +1,144 @@
+//!
+//! # EditTarget command
+//!
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::targz_backup_service::TargzBackupService;
+use dirback::usecase::edit_target::{EditTargetUsecase, TargetEdit};
+use dirback_cmd::{CmdError, say};
+use std::path::PathBuf;
+
+/// Edit the name, path or description of the target, or the notes of its backups.
+#[derive(clap::Args)]
+#[command(group = clap::ArgGroup::new("edit").required(true).multiple(true))]
+pub struct EditTarget {
+    target_id: String,
+
+    #[arg(long, group = "edit")]
+    name: Option<String>,
+
+    #[arg(long, group = "edit")]
+    path: Option<PathBuf>,
+
+    #[arg(long, group = "edit")]
+    description: Option<String>,
+
+    /// Replace the note of the backup.
+    #[arg(long, group = "edit", num_args = 2, value_names = ["BACKUP_ID", "NOTE"])]
+    note: Vec<String>,
+
+    /// Skip the check that the new path is the same directory tree.
+    #[arg(long)]
+    force: bool,
+
+    /// Allow the new path inside another target, or containing another target.
+    #[arg(long)]
+    allow_overlap: bool,
+}
+
+impl EditTarget {
+    fn target_edit(&self) -> anyhow::Result<TargetEdit> {
+        let mut edit = TargetEdit {
+            name: self.name.clone(),
+            path: self.path.clone(),
+            description: self.description.clone(),
+            force: self.force,
+            allow_overlap: self.allow_overlap,
+            ..TargetEdit::default()
+        };
+
+        for pair in self.note.chunks(2) {
+            let backup_id = pair[0]
+                .parse::<u32>()
+                .map_err(|_| CmdError::Usage(format!("Invalid backup ID ('{}').", pair[0])))?;
+            edit.notes.push((backup_id, pair[1].clone()));
+        }
+
+        Ok(edit)
+    }
+}
+
+impl dirback_cmd::Command for EditTarget {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let edit = self.target_edit()?;
+
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let target_id = &super::get_target(&repo, &self.target_id)?.id;
+
+        let service = TargzBackupService::new();
+        let mut usecase = EditTargetUsecase::new(&mut repo, &service);
+        let target = usecase.execute(target_id, &edit)?;
+
+        if params.is_json() {
+            return params.print_json(&target);
+        }
+        say!(params, "Target '{}' has been updated.", target.name);
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path().join("data");
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &src).unwrap();
+
+        let result = run::<EditTarget>(
+            &CmdParams::new(&basedir),
+            &[&target.id, "--name", "Renamed", "--description", "memo"],
+        );
+        assert!(result.is_ok());
+
+        let target = repo.load(&target.id).unwrap();
+        assert_eq!(target.name, "Renamed");
+        assert_eq!(target.description, "memo");
+    }
+
+    #[test]
+    fn it_rejects_data_dir_even_if_forced() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path().join("data");
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &src).unwrap();
+
+        let basedir_str = basedir.to_string_lossy();
+        let result = run::<EditTarget>(
+            &CmdParams::new(&basedir),
+            &[
+                &target.id,
+                "--path",
+                &basedir_str,
+                "--force",
+                "--allow-overlap",
+            ],
+        );
+        assert!(result.is_err());
+        assert_eq!(repo.load(&target.id).unwrap().path, src);
+    }
+
+    #[test]
+    fn it_fails_without_changes() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let result = run::<EditTarget>(&CmdParams::new(&basedir), &["xxx"]);
+        assert!(result.is_err());
+
+        let result = run::<EditTarget>(&CmdParams::new(&basedir), &["xxx", "--note", "1"]);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,86 @@
+//!
+//! # ExportTarget command
+//!
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::targz_bundle_service::TargzBundleService;
+use dirback::usecase::export_target::ExportTargetUsecase;
+use dirback_cmd::say;
+use std::path::PathBuf;
+
+/// Export the target and its backups to a bundle file.
+#[derive(clap::Args)]
+pub struct ExportTarget {
+    target_id: String,
+    file: PathBuf,
+
+    /// The backups to export, every backup by default.
+    backup_ids: Vec<u32>,
+}
+
+impl dirback_cmd::Command for ExportTarget {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let dest = &self.file;
+        if dest.exists() {
+            anyhow::bail!("File already exists: '{}'", dest.display());
+        }
+
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let target_id = &super::get_target(&repo, &self.target_id)?.id;
+
+        let backup_ids = (!self.backup_ids.is_empty()).then_some(self.backup_ids.as_slice());
+        let service = TargzBundleService::new();
+        let mut usecase = ExportTargetUsecase::new(&mut repo, &service);
+        let target = usecase.execute(target_id, backup_ids, dest)?;
+
+        if params.is_json() {
+            return params.print_json(&target);
+        }
+        say!(
+            params,
+            "Target '{}' has been exported with {} backup(s): {}",
+            target.name,
+            target.backups.len(),
+            dest.display()
+        );
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path().join("data");
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &src).unwrap();
+
+        let bundle = temp.path().join("bundle.tar.gz");
+        let bundle_str = bundle.to_string_lossy();
+        let result = run::<ExportTarget>(&CmdParams::new(&basedir), &[&target.id, &bundle_str]);
+        assert!(result.is_ok());
+        assert!(bundle.exists());
+
+        // Never overwrites the existing file.
+        let result = run::<ExportTarget>(&CmdParams::new(&basedir), &[&target.id, &bundle_str]);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_fails_with_invalid_backup_id() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let result = run::<ExportTarget>(&CmdParams::new(&basedir), &["xxx", "out.tar.gz", "a"]);
+        assert!(result.is_err());
+    }
+}
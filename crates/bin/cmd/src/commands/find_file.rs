@@ -0,0 +1,122 @@
+//!
+//! # FindFile command
+//!
+//! Finds a file in every backup of the target, to see when it changed.
+//!
+
+use dirback::adapter::format_size;
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::targz_backup_service::TargzBackupService;
+use dirback::usecase::dto::{FilePattern, FileVersion};
+use dirback::usecase::find_file::FindFileUsecase;
+use dirback_cmd::{detail, say};
+
+/// Find the files matching a path or a glob pattern in every backup of the target.
+#[derive(clap::Args)]
+pub struct FindFile {
+    target_id: String,
+
+    /// The path in the backup, e.g. `config/prod.yaml`, or a glob, e.g. `config/*.yaml`.
+    /// A pattern without `/` matches the file name in any directory.
+    pattern: FilePattern,
+}
+
+impl dirback_cmd::Command for FindFile {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let repo = FileStorageTargetRepository::new(&params.basedir);
+        let target = super::get_target(&repo, &self.target_id)?;
+
+        let service = TargzBackupService::new();
+        let report = FindFileUsecase::new(&repo, &service).execute(&target.id, &self.pattern)?;
+        if params.is_json() {
+            return params.print_json(&report);
+        }
+
+        say!(
+            params,
+            "* Find '{}' in {} ({}): {} files in {} backups",
+            report.pattern,
+            target.name,
+            target.id,
+            report.versions.len(),
+            report.searched
+        );
+        let mut path = None;
+        for version in report.versions.iter() {
+            if path != Some(&version.path) {
+                path = Some(&version.path);
+                say!(params, "{}", version.path.to_string_lossy());
+                say!(params, "  id, timestamp, size, mtime, sha256, change");
+            }
+            print_version(params, version);
+        }
+        for id in report.unreadable.iter() {
+            say!(
+                params,
+                "Skipped the backup {id:0>3}: the backup file cannot be read."
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn print_version(params: &dirback_cmd::CmdParams, version: &FileVersion) {
+    let mut line = format!(
+        "  {:0>3}, {}, {}, {}, {:.12}, {}",
+        version.backup_id,
+        version.timestamp.to_rfc3339(),
+        format_size(version.size),
+        version
+            .mtime
+            .as_ref()
+            .map(|mtime| mtime.to_rfc3339())
+            .unwrap_or_else(|| String::from("-")),
+        version.sha256,
+        if version.changed { "changed" } else { "same" }
+    );
+    if !version.note.is_empty() {
+        line.push_str(&format!(" # {}", version.note));
+    }
+    say!(params, "{line}");
+    detail!(params, "       sha256: {}", version.sha256);
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback::usecase::backup::BackupUsecase;
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let dir = temp.path().join("app");
+        std::fs::create_dir_all(dir.join("config")).unwrap();
+        std::fs::write(dir.join("config/prod.yaml"), "v1").unwrap();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("app", &dir).unwrap();
+        let service = TargzBackupService::new();
+        BackupUsecase::new(&mut repo, &service)
+            .execute(&target.id, "")
+            .unwrap();
+
+        for output in [Output::Normal, Output::Json] {
+            let params = CmdParams::new(&basedir).with_output(output);
+            assert!(run::<FindFile>(&params, &["app", "config/prod.yaml"]).is_ok());
+            assert!(run::<FindFile>(&params, &[&target.id, "*.yaml"]).is_ok());
+        }
+
+        let params = CmdParams::new(&basedir);
+        let err = run::<FindFile>(&params, &["unknown", "prod.yaml"]).unwrap_err();
+        assert_eq!(exit_code_of(&err), exit_code::NOT_FOUND);
+        let err = run::<FindFile>(&params, &["app", "config/[a"]).unwrap_err();
+        assert_eq!(exit_code_of(&err), exit_code::USAGE);
+    }
+}
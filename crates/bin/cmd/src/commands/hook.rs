@@ -0,0 +1,202 @@
+//!
+//! # Hook command
+//!
+//! Manages the commands run around the backups and restores of a target.
+//!
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::usecase::dto::{HookEvent, Hooks, Target};
+use dirback::usecase::set_hooks::SetHooksUsecase;
+use dirback_cmd::say;
+
+/// Manage the commands run around the backups and restores of a target.
+#[derive(clap::Args)]
+pub struct HookTarget {
+    #[command(subcommand)]
+    command: HookCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum HookCommand {
+    /// Set the shell command of the hook.
+    ///
+    /// HOOK is pre-backup, post-backup, pre-restore, post-restore or on-failure.
+    /// A failing pre-backup / pre-restore hook aborts the operation.
+    Set {
+        target_id: String,
+        hook: HookEvent,
+
+        #[arg(required = true, trailing_var_arg = true, allow_hyphen_values = true)]
+        command: Vec<String>,
+    },
+
+    /// Remove the hook, or every hook if not given.
+    Unset {
+        target_id: String,
+        hook: Option<HookEvent>,
+    },
+
+    /// Kill the hook commands after SECS (default: 300).
+    Timeout {
+        target_id: String,
+
+        #[arg(value_parser = clap::builder::RangedU64ValueParser::<u64>::new().range(1..))]
+        secs: u64,
+    },
+}
+
+impl HookTarget {
+    /// Loads the target, changes its hooks by `f`, and saves them.
+    fn update<F>(
+        &self,
+        params: &dirback_cmd::CmdParams,
+        target_id: &str,
+        f: F,
+    ) -> anyhow::Result<Target>
+    where
+        F: FnOnce(&mut Hooks),
+    {
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let target = super::get_target(&repo, target_id)?;
+
+        let mut hooks = target.hooks;
+        f(&mut hooks);
+        let target = SetHooksUsecase::new(&mut repo).execute(&target.id, hooks)?;
+
+        if params.is_json() {
+            params.print_json(&target)?;
+        }
+        Ok(target)
+    }
+
+    fn set(
+        &self,
+        params: &dirback_cmd::CmdParams,
+        target_id: &str,
+        event: HookEvent,
+        command: &[String],
+    ) -> anyhow::Result<()> {
+        let command = command.join(" ");
+        let target = self.update(params, target_id, |hooks| {
+            hooks.set_command(event, Some(&command))
+        })?;
+        say!(params, "Hook '{event}' of '{}' has been set.", target.name);
+
+        Ok(())
+    }
+
+    fn unset(
+        &self,
+        params: &dirback_cmd::CmdParams,
+        target_id: &str,
+        event: Option<HookEvent>,
+    ) -> anyhow::Result<()> {
+        let target = self.update(params, target_id, |hooks| match event {
+            Some(event) => hooks.set_command(event, None),
+            None => *hooks = Hooks::default(),
+        })?;
+        match event {
+            Some(event) => say!(
+                params,
+                "Hook '{event}' of '{}' has been removed.",
+                target.name
+            ),
+            None => say!(params, "Hooks of '{}' have been removed.", target.name),
+        }
+
+        Ok(())
+    }
+
+    fn timeout(
+        &self,
+        params: &dirback_cmd::CmdParams,
+        target_id: &str,
+        secs: u64,
+    ) -> anyhow::Result<()> {
+        let target = self.update(params, target_id, |hooks| hooks.timeout_secs = Some(secs))?;
+        say!(params, "Hook timeout of '{}': {secs} seconds", target.name);
+
+        Ok(())
+    }
+}
+
+impl dirback_cmd::Command for HookTarget {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        match &self.command {
+            HookCommand::Set {
+                target_id,
+                hook,
+                command,
+            } => self.set(params, target_id, *hook, command),
+            HookCommand::Unset { target_id, hook } => self.unset(params, target_id, *hook),
+            HookCommand::Timeout { target_id, secs } => self.timeout(params, target_id, *secs),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_sets_and_unsets_hooks() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &basedir).unwrap();
+
+        let result = run::<HookTarget>(
+            &CmdParams::new(&basedir),
+            &["set", &target.id, "pre-backup", "systemctl", "stop", "db"],
+        );
+        assert!(result.is_ok());
+        let result = run::<HookTarget>(
+            &CmdParams::new(&basedir),
+            &["set", &target.id, "post-backup", "true"],
+        );
+        assert!(result.is_ok());
+        let result = run::<HookTarget>(&CmdParams::new(&basedir), &["timeout", &target.id, "30"]);
+        assert!(result.is_ok());
+
+        let hooks = repo.load(&target.id).unwrap().hooks;
+        assert_eq!(
+            hooks.command(HookEvent::PreBackup),
+            Some("systemctl stop db")
+        );
+        assert_eq!(hooks.timeout_secs, Some(30));
+
+        let result = run::<HookTarget>(
+            &CmdParams::new(&basedir),
+            &["unset", &target.id, "pre-backup"],
+        );
+        assert!(result.is_ok());
+        let hooks = repo.load(&target.id).unwrap().hooks;
+        assert_eq!(hooks.command(HookEvent::PreBackup), None);
+        assert_eq!(hooks.command(HookEvent::PostBackup), Some("true"));
+
+        let result = run::<HookTarget>(&CmdParams::new(&basedir), &["unset", &target.id]);
+        assert!(result.is_ok());
+        assert!(repo.load(&target.id).unwrap().hooks.is_empty());
+    }
+
+    #[test]
+    fn it_returns_err_if_args_are_invalid() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &basedir).unwrap();
+
+        for args in [
+            vec![],
+            vec!["set", target.id.as_str(), "pre-backup"],
+            vec!["set", target.id.as_str(), "before-backup", "true"],
+            vec!["set", "xxx", "pre-backup", "true"],
+            vec!["timeout", target.id.as_str(), "0"],
+        ] {
+            let result = run::<HookTarget>(&CmdParams::new(&basedir), &args);
+            assert!(result.is_err(), "{args:?}");
+        }
+    }
+}
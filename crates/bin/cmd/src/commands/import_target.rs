@@ -0,0 +1,96 @@
+//!
+//! # ImportTarget command
+//!
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::targz_bundle_service::TargzBundleService;
+use dirback::usecase::import_target::{ImportOptions, ImportTargetUsecase};
+use dirback_cmd::say;
+use std::path::PathBuf;
+
+/// Import a target from a bundle file.
+#[derive(clap::Args)]
+pub struct ImportTarget {
+    file: PathBuf,
+
+    /// Replace the path of the target, e.g. on another machine.
+    #[arg(long, value_name = "NEW_PATH")]
+    path: Option<PathBuf>,
+}
+
+impl dirback_cmd::Command for ImportTarget {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let options = ImportOptions {
+            path: self.path.as_deref().map(std::path::absolute).transpose()?,
+        };
+
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let service = TargzBundleService::new();
+        let mut usecase = ImportTargetUsecase::new(&mut repo, &service);
+        let target = usecase.execute(&self.file, &options)?;
+
+        if params.is_json() {
+            return params.print_json(&target);
+        }
+        say!(params, "Target '{}' has been imported.", target.name);
+        say!(params, "ID     : {}", target.id);
+        say!(params, "Path   : {}", target.path.to_string_lossy());
+        say!(params, "Backups: {}", target.backups.len());
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::ExportTarget;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_imports_exported_target() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path().join("data");
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &src).unwrap();
+
+        let bundle = temp.path().join("bundle.tar.gz");
+        let bundle_str = bundle.to_string_lossy();
+        run::<ExportTarget>(&CmdParams::new(&basedir), &[&target.id, &bundle_str]).unwrap();
+
+        let other = temp.path().join("other");
+        let result = run::<ImportTarget>(&CmdParams::new(&other), &[&bundle_str]);
+        assert!(result.is_ok());
+        assert!(
+            FileStorageTargetRepository::new(&other)
+                .load(&target.id)
+                .is_some()
+        );
+
+        // Same path in the same data directory conflicts.
+        let result = run::<ImportTarget>(&CmdParams::new(&basedir), &[&bundle_str]);
+        assert!(result.is_err());
+
+        let new_path = temp.path().join("moved");
+        let new_path = new_path.to_string_lossy();
+        let result = run::<ImportTarget>(
+            &CmdParams::new(&basedir),
+            &[&bundle_str, "--path", &new_path],
+        );
+        assert!(result.is_ok());
+        assert_eq!(repo.load_all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn it_fails_without_path_value() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let result = run::<ImportTarget>(&CmdParams::new(&basedir), &["x", "--path"]);
+        assert!(result.is_err());
+    }
+}
@@ -4,28 +4,67 @@
 
 use dirback::adapter::ListTargetsAdapter;
 use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback_cmd::say;
 
-pub struct ListTargets;
+/// Print the target list.
+#[derive(clap::Args)]
+pub struct ListTargets {
+    /// Only the targets with the tag.
+    #[arg(long)]
+    tag: Option<String>,
+}
 
 impl dirback_cmd::Command for ListTargets {
     fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
         let repo = FileStorageTargetRepository::new(&params.basedir);
 
         let list_targets = ListTargetsAdapter::new(&repo);
-        let targets = list_targets.execute()?;
+        let targets = match &self.tag {
+            None => list_targets.execute()?,
+            Some(tag) => list_targets.execute_by_tag(tag)?,
+        };
+
+        if params.is_json() {
+            return params.print_json(&targets);
+        }
 
-        println!("* Targets ({})", targets.len());
-        println!("id, name, path, backup-count");
+        say!(params, "* Targets ({})", targets.len());
+        say!(params, "id, name, path, backup-count, tags");
         for target in targets {
-            println!(
-                "{}, {}, {}, {}",
+            say!(
+                params,
+                "{}, {}, {}, {}, {}",
                 target.id,
                 target.name,
                 target.path.to_string_lossy(),
-                target.backups.len()
+                target.backups.len(),
+                target.tags.join(" ")
             );
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let _ = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+
+        for output in [Output::Normal, Output::Json] {
+            let params = CmdParams::new(&basedir).with_output(output);
+            assert!(run::<ListTargets>(&params, &[]).is_ok());
+            assert!(run::<ListTargets>(&params, &["--tag", "work"]).is_ok());
+        }
+        assert!(run::<ListTargets>(&CmdParams::new(&basedir), &["--tag"]).is_err());
+    }
+}
@@ -0,0 +1,667 @@
+//!
+//! # Mount command
+//!
+//! Mounts the backups of a target as a read-only file system with FUSE (Linux only).
+//!
+//! ```text
+//! {mountpoint}
+//! ├── 0001_20250123T120000Z   ... a backup, by its ID and timestamp
+//! │   └── config
+//! │       └── prod.yaml
+//! └── 0002_20250124T120000Z
+//!     └── ...
+//! ```
+//!
+//! The index of a backup is read when its directory is first accessed,
+//! and a file is extracted into a temporary file when it is opened,
+//! so that a large file is not held in memory.
+//!
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::targz_backup_service::TargzBackupService;
+use dirback::internal::TargetRepository;
+use dirback::usecase::browse_backups::BrowseBackupsUsecase;
+use dirback::usecase::dto::{BackupEntry, IndexEntry, IndexEntryKind, Timestamp};
+use dirback_cmd::say;
+use fuser::{
+    FileAttr, FileType, Filesystem, MountOption, ReplyAttr, ReplyData, ReplyDirectory, ReplyEntry,
+    ReplyOpen, Request,
+};
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::{OsStr, OsString};
+use std::io::Write;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{FileExt, MetadataExt};
+use std::path::{Component, Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+/// Mount the backups of the target as read-only directories (Linux, FUSE).
+#[derive(clap::Args)]
+pub struct Mount {
+    target_id: String,
+
+    /// An empty directory to mount the backups on.
+    mountpoint: PathBuf,
+}
+
+impl dirback_cmd::Command for Mount {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let repo = FileStorageTargetRepository::new(&params.basedir);
+        let target = super::get_target(&repo, &self.target_id)?;
+        if !self.mountpoint.is_dir() {
+            anyhow::bail!("'{}' is not a directory.", self.mountpoint.display());
+        }
+
+        let service = TargzBackupService::new();
+        let browse = BrowseBackupsUsecase::open(&repo, &service, &target.id)?;
+        let fs = BackupFs::new(browse, std::fs::metadata(&self.mountpoint)?)?;
+        let options = [
+            MountOption::RO,
+            MountOption::NoExec,
+            MountOption::FSName(format!("dirback:{}", target.name)),
+            MountOption::Subtype(String::from("dirback")),
+        ];
+
+        say!(
+            params,
+            "Mounted {} backups of '{}' ({}) on '{}'.",
+            target.backups.len(),
+            target.name,
+            target.id,
+            self.mountpoint.display()
+        );
+        say!(
+            params,
+            "Unmount with `umount {}` (`fusermount -u` as a normal user) to stop.",
+            self.mountpoint.display()
+        );
+        fuser::mount2(fs, &self.mountpoint, &options)?;
+        say!(params, "Unmounted.");
+
+        Ok(())
+    }
+}
+
+//
+// File system
+//
+
+/// The time the kernel may cache the attributes. The backups do not change.
+const TTL: Duration = Duration::from_secs(60);
+
+const ROOT_INO: u64 = fuser::FUSE_ROOT_ID;
+
+struct BackupFs<'a, R: TargetRepository> {
+    browse: BrowseBackupsUsecase<'a, R, TargzBackupService>,
+    tree: Tree,
+
+    /// The contents of the opened files, by the file handle.
+    handles: HashMap<u64, std::fs::File>,
+    next_handle: u64,
+
+    /// The directory of the extracted files, removed when unmounted.
+    spool: mktemp::TempDir,
+
+    /// The owner of the files, the owner of the mountpoint.
+    uid: u32,
+    gid: u32,
+}
+
+impl<'a, R: TargetRepository> BackupFs<'a, R> {
+    fn new(
+        browse: BrowseBackupsUsecase<'a, R, TargzBackupService>,
+        mountpoint: std::fs::Metadata,
+    ) -> anyhow::Result<Self> {
+        let tree = Tree::new(&browse.backups());
+        Ok(Self {
+            browse,
+            tree,
+            handles: HashMap::new(),
+            next_handle: 1,
+            spool: mktemp::TempDir::new()?,
+            uid: mountpoint.uid(),
+            gid: mountpoint.gid(),
+        })
+    }
+
+    /// Extracts the file of the backup into a temporary file.
+    ///
+    /// The temporary file is unlinked at once, it is removed when the handle is closed.
+    fn extract(&mut self, backup_id: u32, path: &Path) -> anyhow::Result<Option<std::fs::File>> {
+        let spool_path = self.spool.path().join(self.next_handle.to_string());
+        let file = std::fs::File::options()
+            .read(true)
+            .write(true)
+            .create_new(true)
+            .open(&spool_path)?;
+        std::fs::remove_file(&spool_path)?;
+
+        let mut writer = std::io::BufWriter::new(&file);
+        let found = self.browse.read_file(backup_id, path, &mut writer)?;
+        writer.flush()?;
+        drop(writer);
+
+        Ok(found.map(|_| file))
+    }
+
+    /// Reads the index of the backup if the inode is a backup directory not read yet.
+    fn load(&mut self, ino: u64) -> Result<(), libc::c_int> {
+        let Some(NodeKind::Backup {
+            backup_id,
+            loaded: false,
+        }) = self.tree.node(ino).map(|node| &node.kind)
+        else {
+            return Ok(());
+        };
+
+        let backup_id = *backup_id;
+        match self.browse.index(backup_id) {
+            Ok(index) => {
+                self.tree.add_index(ino, index);
+                Ok(())
+            }
+            Err(e) => {
+                eprintln!("Failed to read the backup {backup_id}: {e}");
+                Err(libc::EIO)
+            }
+        }
+    }
+
+    fn attr(&self, ino: u64) -> Option<FileAttr> {
+        self.tree.attr(ino, self.uid, self.gid)
+    }
+}
+
+impl<R: TargetRepository> Filesystem for BackupFs<'_, R> {
+    fn lookup(&mut self, _req: &Request<'_>, parent: u64, name: &OsStr, reply: ReplyEntry) {
+        if let Err(e) = self.load(parent) {
+            return reply.error(e);
+        }
+        match self
+            .tree
+            .lookup(parent, name)
+            .and_then(|ino| self.attr(ino))
+        {
+            Some(attr) => reply.entry(&TTL, &attr, 0),
+            None => reply.error(libc::ENOENT),
+        }
+    }
+
+    fn getattr(&mut self, _req: &Request<'_>, ino: u64, _fh: Option<u64>, reply: ReplyAttr) {
+        match self.attr(ino) {
+            Some(attr) => reply.attr(&TTL, &attr),
+            None => reply.error(libc::ENOENT),
+        }
+    }
+
+    fn readlink(&mut self, _req: &Request<'_>, ino: u64, reply: ReplyData) {
+        match self.tree.node(ino).map(|node| &node.kind) {
+            Some(NodeKind::Symlink(target)) => reply.data(target.as_os_str().as_bytes()),
+            _ => reply.error(libc::EINVAL),
+        }
+    }
+
+    fn open(&mut self, _req: &Request<'_>, ino: u64, flags: i32, reply: ReplyOpen) {
+        if flags & libc::O_ACCMODE != libc::O_RDONLY {
+            return reply.error(libc::EROFS);
+        }
+        let Some(NodeKind::File { backup_id, path }) = self.tree.node(ino).map(|node| &node.kind)
+        else {
+            return reply.error(libc::EISDIR);
+        };
+        let (backup_id, path) = (*backup_id, path.clone());
+
+        match self.extract(backup_id, &path) {
+            Ok(Some(file)) => {
+                let fh = self.next_handle;
+                self.next_handle += 1;
+                self.handles.insert(fh, file);
+                reply.opened(fh, 0);
+            }
+            Ok(None) => reply.error(libc::ENOENT),
+            Err(e) => {
+                eprintln!("Failed to read '{}': {e}", path.display());
+                reply.error(libc::EIO);
+            }
+        }
+    }
+
+    fn read(
+        &mut self,
+        _req: &Request<'_>,
+        _ino: u64,
+        fh: u64,
+        offset: i64,
+        size: u32,
+        _flags: i32,
+        _lock_owner: Option<u64>,
+        reply: ReplyData,
+    ) {
+        let Some(file) = self.handles.get(&fh) else {
+            return reply.error(libc::EBADF);
+        };
+
+        // Reads up to `size` bytes, fewer only at the end of the file.
+        let mut buf = vec![0; size as usize];
+        let mut len = 0;
+        while len < buf.len() {
+            match file.read_at(&mut buf[len..], offset.max(0) as u64 + len as u64) {
+                Ok(0) => break,
+                Ok(n) => len += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => {}
+                Err(e) => {
+                    eprintln!("Failed to read the extracted file: {e}");
+                    return reply.error(libc::EIO);
+                }
+            }
+        }
+        reply.data(&buf[..len]);
+    }
+
+    fn release(
+        &mut self,
+        _req: &Request<'_>,
+        _ino: u64,
+        fh: u64,
+        _flags: i32,
+        _lock_owner: Option<u64>,
+        _flush: bool,
+        reply: fuser::ReplyEmpty,
+    ) {
+        self.handles.remove(&fh);
+        reply.ok();
+    }
+
+    fn readdir(
+        &mut self,
+        _req: &Request<'_>,
+        ino: u64,
+        _fh: u64,
+        offset: i64,
+        mut reply: ReplyDirectory,
+    ) {
+        if let Err(e) = self.load(ino) {
+            return reply.error(e);
+        }
+        let Some(node) = self.tree.node(ino) else {
+            return reply.error(libc::ENOENT);
+        };
+
+        let dots = [
+            (ino, FileType::Directory, OsStr::new(".")),
+            (node.parent, FileType::Directory, OsStr::new("..")),
+        ];
+        let children = node.children.iter().filter_map(|(name, child)| {
+            let kind = self.tree.node(*child)?.file_type();
+            Some((*child, kind, name.as_os_str()))
+        });
+        for (i, (ino, kind, name)) in dots
+            .into_iter()
+            .chain(children)
+            .enumerate()
+            .skip(offset as usize)
+        {
+            // The offset of an entry is the offset of the next one.
+            if reply.add(ino, (i + 1) as i64, kind, name) {
+                break;
+            }
+        }
+        reply.ok();
+    }
+}
+
+//
+// Inodes
+//
+
+#[derive(Debug, PartialEq)]
+enum NodeKind {
+    Root,
+
+    /// The directory of a backup, whose index is read on the first access.
+    Backup {
+        backup_id: u32,
+        loaded: bool,
+    },
+    Dir,
+    File {
+        backup_id: u32,
+        path: PathBuf,
+    },
+    Symlink(PathBuf),
+}
+
+#[derive(Debug)]
+struct Node {
+    parent: u64,
+    kind: NodeKind,
+    size: u64,
+    mtime: SystemTime,
+    perm: u16,
+    children: BTreeMap<OsString, u64>,
+}
+
+impl Node {
+    fn file_type(&self) -> FileType {
+        match self.kind {
+            NodeKind::File { .. } => FileType::RegularFile,
+            NodeKind::Symlink(_) => FileType::Symlink,
+            _ => FileType::Directory,
+        }
+    }
+}
+
+/// The inodes of the mounted backups. The inode number is the index + 1.
+struct Tree {
+    nodes: Vec<Node>,
+}
+
+impl Tree {
+    /// Makes the root directory with a directory for each backup.
+    fn new(backups: &[BackupEntry]) -> Self {
+        let mtime = backups
+            .last()
+            .map(|entry| to_system_time(&entry.timestamp))
+            .unwrap_or(SystemTime::UNIX_EPOCH);
+        let mut tree = Self {
+            nodes: vec![Node {
+                parent: ROOT_INO,
+                kind: NodeKind::Root,
+                size: 0,
+                mtime,
+                perm: 0o555,
+                children: BTreeMap::new(),
+            }],
+        };
+
+        for entry in backups {
+            let name = format!("{:0>4}_{}", entry.id, entry.timestamp.fmt());
+            let kind = NodeKind::Backup {
+                backup_id: entry.id,
+                loaded: false,
+            };
+            let ino = tree.push(ROOT_INO, kind, to_system_time(&entry.timestamp));
+            tree.nodes[0].children.insert(name.into(), ino);
+        }
+        tree
+    }
+
+    fn node(&self, ino: u64) -> Option<&Node> {
+        self.nodes.get((ino as usize).checked_sub(1)?)
+    }
+
+    fn lookup(&self, parent: u64, name: &OsStr) -> Option<u64> {
+        self.node(parent)?.children.get(name).copied()
+    }
+
+    fn push(&mut self, parent: u64, kind: NodeKind, mtime: SystemTime) -> u64 {
+        self.nodes.push(Node {
+            parent,
+            kind,
+            size: 0,
+            mtime,
+            perm: 0o555,
+            children: BTreeMap::new(),
+        });
+        self.nodes.len() as u64
+    }
+
+    /// Adds the entries of the index under the backup directory.
+    ///
+    /// The parent directories missing in the index are made with the time of the backup.
+    fn add_index(&mut self, backup_ino: u64, index: &[IndexEntry]) {
+        let Some(backup) = self.nodes.get_mut(backup_ino as usize - 1) else {
+            return;
+        };
+        let NodeKind::Backup { backup_id, loaded } = &mut backup.kind else {
+            return;
+        };
+        *loaded = true;
+        let (backup_id, backup_mtime) = (*backup_id, backup.mtime);
+
+        for entry in index {
+            let names: Vec<&OsStr> = entry
+                .path
+                .components()
+                .filter_map(|c| match c {
+                    Component::Normal(name) => Some(name),
+                    _ => None,
+                })
+                .collect();
+            let Some((name, dirs)) = names.split_last() else {
+                continue;
+            };
+
+            let mut parent = backup_ino;
+            for dir in dirs {
+                parent = match self.lookup(parent, dir) {
+                    Some(ino) => ino,
+                    None => self.add_child(parent, dir, NodeKind::Dir, backup_mtime),
+                };
+            }
+
+            let kind = match &entry.kind {
+                IndexEntryKind::File => NodeKind::File {
+                    backup_id,
+                    path: entry.path.clone(),
+                },
+                IndexEntryKind::Dir => NodeKind::Dir,
+                IndexEntryKind::Symlink(target) => NodeKind::Symlink(target.clone()),
+            };
+            let mtime = entry
+                .mtime
+                .as_ref()
+                .map(to_system_time)
+                .unwrap_or(backup_mtime);
+
+            // A directory may have been made as a parent of an earlier entry.
+            let ino = match self.lookup(parent, name) {
+                Some(ino) if kind == NodeKind::Dir => ino,
+                _ => self.add_child(parent, name, kind, mtime),
+            };
+            let node = &mut self.nodes[ino as usize - 1];
+            node.size = entry.size;
+            node.mtime = mtime;
+            // Read-only, without the write bits.
+            node.perm = (entry.mode & 0o7555) as u16;
+        }
+    }
+
+    fn add_child(&mut self, parent: u64, name: &OsStr, kind: NodeKind, mtime: SystemTime) -> u64 {
+        let ino = self.push(parent, kind, mtime);
+        self.nodes[parent as usize - 1]
+            .children
+            .insert(name.to_os_string(), ino);
+        ino
+    }
+
+    fn attr(&self, ino: u64, uid: u32, gid: u32) -> Option<FileAttr> {
+        let node = self.node(ino)?;
+        let kind = node.file_type();
+        Some(FileAttr {
+            ino,
+            size: node.size,
+            blocks: node.size.div_ceil(512),
+            atime: node.mtime,
+            mtime: node.mtime,
+            ctime: node.mtime,
+            crtime: node.mtime,
+            kind,
+            perm: node.perm,
+            nlink: if kind == FileType::Directory { 2 } else { 1 },
+            uid,
+            gid,
+            rdev: 0,
+            blksize: 512,
+            flags: 0,
+        })
+    }
+}
+
+fn to_system_time(ts: &Timestamp) -> SystemTime {
+    SystemTime::from(**ts)
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::usecase::backup::BackupUsecase;
+    use dirback::usecase::dto::TargetKind;
+
+    fn index_entry(path: &str, kind: IndexEntryKind, size: u64) -> IndexEntry {
+        IndexEntry {
+            path: PathBuf::from(path),
+            kind,
+            size,
+            mtime: None,
+            mode: 0o644,
+        }
+    }
+
+    fn backup_entry(id: u32) -> BackupEntry {
+        BackupEntry {
+            id,
+            path: PathBuf::from(format!("backups/{id}.tar.gz")),
+            timestamp: Timestamp::now(),
+            note: String::new(),
+            store: None,
+            checksum: None,
+            replicas: Vec::new(),
+            sources: Vec::new(),
+            kind: TargetKind::Directory,
+            pinned: false,
+            stats: None,
+        }
+    }
+
+    #[test]
+    fn it_makes_backup_directories() {
+        let backups = [backup_entry(1), backup_entry(2)];
+        let tree = Tree::new(&backups);
+
+        let root = tree.node(ROOT_INO).unwrap();
+        assert_eq!(root.children.len(), 2);
+        let name = format!("0002_{}", backups[1].timestamp.fmt());
+        let ino = tree.lookup(ROOT_INO, OsStr::new(&name)).unwrap();
+        let kind = &tree.node(ino).unwrap().kind;
+        assert_eq!(
+            kind,
+            &NodeKind::Backup {
+                backup_id: 2,
+                loaded: false
+            }
+        );
+        assert_eq!(tree.attr(ino, 0, 0).unwrap().kind, FileType::Directory);
+        assert!(tree.node(0).is_none());
+        assert!(tree.node(100).is_none());
+    }
+
+    #[test]
+    fn it_adds_index_with_missing_parents() {
+        let mut tree = Tree::new(&[backup_entry(1)]);
+        let backup_ino = *tree
+            .node(ROOT_INO)
+            .unwrap()
+            .children
+            .values()
+            .next()
+            .unwrap();
+
+        let index = [
+            index_entry("config/env/prod.yaml", IndexEntryKind::File, 2),
+            index_entry("config", IndexEntryKind::Dir, 0),
+            index_entry("link", IndexEntryKind::Symlink("config".into()), 0),
+        ];
+        tree.add_index(backup_ino, &index);
+
+        let config = tree.lookup(backup_ino, OsStr::new("config")).unwrap();
+        let env = tree.lookup(config, OsStr::new("env")).unwrap();
+        let prod = tree.lookup(env, OsStr::new("prod.yaml")).unwrap();
+        assert_eq!(
+            tree.node(prod).unwrap().kind,
+            NodeKind::File {
+                backup_id: 1,
+                path: PathBuf::from("config/env/prod.yaml")
+            }
+        );
+        assert_eq!(tree.node(prod).unwrap().parent, env);
+        assert_eq!(tree.node(config).unwrap().children.len(), 1);
+
+        let attr = tree.attr(prod, 1000, 1000).unwrap();
+        assert_eq!(attr.size, 2);
+        assert_eq!(attr.perm, 0o444, "The write bits are dropped.");
+        assert_eq!(attr.uid, 1000);
+
+        let link = tree.lookup(backup_ino, OsStr::new("link")).unwrap();
+        assert_eq!(tree.attr(link, 0, 0).unwrap().kind, FileType::Symlink);
+        assert!(matches!(
+            tree.node(backup_ino).unwrap().kind,
+            NodeKind::Backup { loaded: true, .. }
+        ));
+    }
+
+    /// Mounts a backup, reads a file with the normal file API, and unmounts it.
+    ///
+    /// Requires FUSE and the permission to mount and `umount` (e.g. root),
+    /// skipped if `/dev/fuse` cannot be opened.
+    #[test]
+    fn it_works_with_fuse() {
+        let fuse = std::fs::File::options()
+            .read(true)
+            .write(true)
+            .open("/dev/fuse");
+        if fuse.is_err() {
+            eprintln!("Skipped: /dev/fuse is not available.");
+            return;
+        }
+
+        let temp = mktemp::TempDir::new().unwrap();
+        let dir = temp.path().join("app");
+        std::fs::create_dir_all(dir.join("config")).unwrap();
+        std::fs::write(dir.join("config/prod.yaml"), "v1").unwrap();
+        // Larger than a FUSE read, to be read by offset.
+        let large = "0123456789abcdef".repeat(64 * 1024);
+        std::fs::write(dir.join("large.txt"), &large).unwrap();
+        let mountpoint = temp.path().join("mnt");
+        std::fs::create_dir_all(&mountpoint).unwrap();
+
+        let mut repo = FileStorageTargetRepository::new(&temp.path().join("data"));
+        let target = repo.add("app", &dir).unwrap();
+        let service = TargzBackupService::new();
+        let entry = BackupUsecase::new(&mut repo, &service)
+            .execute(&target.id, "")
+            .unwrap();
+
+        let browse = BrowseBackupsUsecase::open(&repo, &service, &target.id).unwrap();
+        let fs = BackupFs::new(browse, std::fs::metadata(&mountpoint).unwrap()).unwrap();
+        std::thread::scope(|scope| {
+            let mounted = scope.spawn(|| fuser::mount2(fs, &mountpoint, &[MountOption::RO]));
+
+            let backup_dir = mountpoint.join(format!("0001_{}", entry.timestamp.fmt()));
+            let file = backup_dir.join("config/prod.yaml");
+            let mut content = None;
+            for _ in 0..50 {
+                if let Ok(text) = std::fs::read_to_string(&file) {
+                    content = Some(text);
+                    break;
+                }
+                std::thread::sleep(Duration::from_millis(100));
+            }
+            let write = std::fs::write(&file, "v2");
+            let large_content = std::fs::read_to_string(backup_dir.join("large.txt"));
+
+            std::process::Command::new("umount")
+                .arg(&mountpoint)
+                .status()
+                .unwrap();
+            mounted.join().unwrap().unwrap();
+
+            assert_eq!(content.as_deref(), Some("v1"));
+            assert_eq!(large_content.unwrap(), large);
+            assert!(write.is_err(), "The mount should be read-only.");
+        });
+    }
+}
@@ -0,0 +1,135 @@
+//!
+//! # PinBackup command
+//!
+//! Pins backups to protect them from pruning and deletion, or unpins them.
+//!
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::usecase::dto::BackupSelector;
+use dirback::usecase::pin_backup::PinBackupUsecase;
+use dirback_cmd::say;
+
+#[derive(clap::Args)]
+pub struct PinArgs {
+    target_id: String,
+
+    /// The backup IDs or selectors, e.g. `latest~1` (see `dirback help`).
+    #[arg(required = true, value_name = "BACKUP")]
+    backups: Vec<BackupSelector>,
+}
+
+/// Pin backups to protect them from pruning and deletion.
+#[derive(clap::Args)]
+pub struct PinBackup {
+    #[command(flatten)]
+    args: PinArgs,
+}
+
+/// Unpin backups.
+#[derive(clap::Args)]
+pub struct UnpinBackup {
+    #[command(flatten)]
+    args: PinArgs,
+}
+
+impl dirback_cmd::Command for PinBackup {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        set_pinned(params, &self.args, true)
+    }
+}
+
+impl dirback_cmd::Command for UnpinBackup {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        set_pinned(params, &self.args, false)
+    }
+}
+
+fn set_pinned(params: &dirback_cmd::CmdParams, args: &PinArgs, pinned: bool) -> anyhow::Result<()> {
+    let mut repo = FileStorageTargetRepository::new(&params.basedir);
+    let target = super::get_target(&repo, &args.target_id)?;
+    let mut backup_ids = Vec::new();
+    for selector in args.backups.iter() {
+        backup_ids.push(super::get_backup(&repo, &target, selector)?.id);
+    }
+
+    let mut entries = Vec::new();
+    for backup_id in backup_ids {
+        let entry = PinBackupUsecase::new(&mut repo).execute(&target.id, backup_id, pinned)?;
+        match entry.pinned {
+            true => say!(params, "The backup[{:0>3}] has been pinned.", entry.id),
+            false => say!(params, "The backup[{:0>3}] has been unpinned.", entry.id),
+        }
+        entries.push(entry);
+    }
+
+    if params.is_json() {
+        params.print_json(&entries)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    fn prepare_test_data(basedir: &std::path::Path) -> String {
+        let mut repo = FileStorageTargetRepository::new(basedir);
+        let mut target = repo.add("TestTarget", basedir).unwrap();
+        for _ in 1..=3 {
+            let entry = target.new_backup_entry(&repo.make_backup_dir_path(&target), "tar.gz");
+            target.register_backup_entry(entry).unwrap();
+        }
+        repo.update(&target).unwrap();
+        target.id
+    }
+
+    #[test]
+    fn it_pins_and_unpins_backups() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let target_id = prepare_test_data(&basedir);
+        let repo = FileStorageTargetRepository::new(&basedir);
+
+        let params = CmdParams::new(&basedir);
+        assert!(run::<PinBackup>(&params, &[&target_id, "1", "3"]).is_ok());
+        assert_eq!(
+            repo.load(&target_id).unwrap().pinned_backup_ids(),
+            vec![1, 3]
+        );
+
+        assert!(run::<UnpinBackup>(&params, &[&target_id, "3"]).is_ok());
+        assert_eq!(repo.load(&target_id).unwrap().pinned_backup_ids(), vec![1]);
+    }
+
+    #[test]
+    fn it_returns_err_if_args_are_invalid() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let target_id = prepare_test_data(&basedir);
+
+        let params = CmdParams::new(&basedir);
+        for (args, code) in [
+            (vec![target_id.as_str()], exit_code::USAGE),
+            (vec![target_id.as_str(), "latest~x"], exit_code::USAGE),
+            (
+                vec![target_id.as_str(), "no such note"],
+                exit_code::NOT_FOUND,
+            ),
+            (vec![target_id.as_str(), "1", "4"], exit_code::NOT_FOUND),
+            (vec!["xxx", "1"], exit_code::NOT_FOUND),
+        ] {
+            let err = run::<PinBackup>(&params, &args).unwrap_err();
+            assert_eq!(exit_code_of(&err), code, "{args:?}");
+        }
+
+        let repo = FileStorageTargetRepository::new(&basedir);
+        assert!(
+            repo.load(&target_id)
+                .unwrap()
+                .pinned_backup_ids()
+                .is_empty()
+        );
+    }
+}
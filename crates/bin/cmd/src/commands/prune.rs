@@ -0,0 +1,255 @@
+//!
+//! # Prune command
+//!
+//! Deletes old backups by the retention policy.
+//!
+
+use dirback::adapter::ListTargetsAdapter;
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::usecase::dto::{RetentionPolicy, Target};
+use dirback::usecase::prune::PruneUsecase;
+use dirback_cmd::{CmdError, say};
+use std::collections::BTreeMap;
+
+/// Delete old backups by the retention policy.
+///
+/// Without --keep-last and --keep-days, the retention of each target is used,
+/// or `[retention]` in the config file if the target has none.
+#[derive(clap::Args)]
+#[command(group = clap::ArgGroup::new("targets").required(true))]
+pub struct Prune {
+    #[arg(group = "targets")]
+    target_id: Option<String>,
+
+    /// Prune every target with the tag ("all" for every target).
+    #[arg(long, group = "targets")]
+    tag: Option<String>,
+
+    /// Keep the latest N backups.
+    #[arg(long, value_name = "N")]
+    keep_last: Option<usize>,
+
+    /// Keep the backups taken within D days.
+    #[arg(long, value_name = "D")]
+    keep_days: Option<u32>,
+
+    /// Show the backups to be deleted without deleting them.
+    #[arg(long)]
+    dry_run: bool,
+}
+
+impl Prune {
+    /// Returns the retention policy of the target: the flags, the target's, or the config's.
+    fn policy(&self, params: &dirback_cmd::CmdParams, target: &Target) -> RetentionPolicy {
+        let policy = RetentionPolicy {
+            keep_last: self.keep_last,
+            keep_days: self.keep_days,
+        };
+        if !policy.is_empty() {
+            return policy;
+        }
+        if !target.retention.is_empty() {
+            return target.retention.clone();
+        }
+        params.config.retention.value.clone()
+    }
+}
+
+impl dirback_cmd::Command for Prune {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let targets = match (&self.target_id, &self.tag) {
+            (Some(target_id), _) => vec![super::get_target(&repo, target_id)?],
+            (None, Some(tag)) => ListTargetsAdapter::new(&repo).execute_by_tag(tag)?,
+            (None, None) => unreachable!(),
+        };
+        if targets.iter().all(|t| self.policy(params, t).is_empty()) {
+            let message = "No retention: use --keep-last, --keep-days, `schedule set` with the retention options \
+                 or `[retention]` in the config file.";
+            return Err(CmdError::Usage(message.to_string()).into());
+        }
+
+        let verb = if self.dry_run {
+            "Would delete"
+        } else {
+            "Deleted"
+        };
+        let mut results = BTreeMap::new();
+        let mut failed = 0;
+        for target in targets.iter() {
+            let policy = self.policy(params, target);
+            if policy.is_empty() {
+                say!(params, "Target({}): Skipped, no retention.", target.id);
+                continue;
+            }
+
+            let mut usecase = PruneUsecase::new(&mut repo);
+            let pruned = match usecase.execute(&target.id, &policy, self.dry_run) {
+                Ok(pruned) => pruned,
+                Err(e) => {
+                    // Continue with the other targets, like `backup --all`.
+                    eprintln!("Target({}) prune failed: {e}", target.id);
+                    failed += 1;
+                    continue;
+                }
+            };
+
+            say!(
+                params,
+                "Target({}): {verb} {} backup(s).",
+                target.id,
+                pruned.len()
+            );
+            for entry in pruned.iter() {
+                say!(
+                    params,
+                    "  {:0>3}: {}",
+                    entry.id,
+                    entry.timestamp.to_rfc3339()
+                );
+            }
+            results.insert(target.id.clone(), pruned);
+        }
+
+        if params.is_json() {
+            params.print_json(&results)?;
+        }
+        if failed > 0 {
+            anyhow::bail!("{failed} of {} targets failed to prune.", targets.len());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    fn add_target_with_backups(repo: &mut FileStorageTargetRepository, tag: &str) -> String {
+        let mut target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+        target.add_tag(tag).unwrap();
+        let bkdir = repo.make_backup_dir_path(&target);
+        std::fs::create_dir_all(&bkdir).unwrap();
+        for _ in 0..3 {
+            let entry = target.new_backup_entry(&bkdir, "tar.gz");
+            std::fs::File::create(&entry.path).unwrap();
+            target.register_backup_entry(entry).unwrap();
+        }
+        repo.update(&target).unwrap();
+        target.id
+    }
+
+    #[test]
+    fn it_prunes_tagged_targets() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let a = add_target_with_backups(&mut repo, "clients");
+        let b = add_target_with_backups(&mut repo, "games");
+
+        let result = run::<Prune>(
+            &CmdParams::new(&basedir),
+            &["--tag", "clients", "--keep-last", "1", "--dry-run"],
+        );
+        assert!(result.is_ok());
+        assert_eq!(repo.load(&a).unwrap().backups.len(), 3);
+
+        let result = run::<Prune>(
+            &CmdParams::new(&basedir),
+            &["--tag", "all", "--keep-last", "1"],
+        );
+        assert!(result.is_ok());
+        assert_eq!(repo.load(&a).unwrap().backups.len(), 1);
+        assert_eq!(repo.load(&b).unwrap().backups.len(), 1);
+    }
+
+    #[test]
+    fn it_uses_retention_of_each_target() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let a = add_target_with_backups(&mut repo, "clients");
+        let b = add_target_with_backups(&mut repo, "clients");
+        let mut target = repo.load(&a).unwrap();
+        target.retention.keep_last = Some(2);
+        repo.update(&target).unwrap();
+
+        // The target without a retention is skipped.
+        assert!(run::<Prune>(&CmdParams::new(&basedir), &["--tag", "clients"]).is_ok());
+        assert_eq!(repo.load(&a).unwrap().backups.len(), 2);
+        assert_eq!(repo.load(&b).unwrap().backups.len(), 3);
+
+        // The target's retention takes precedence over the config.
+        let mut config = dirback::infra::config::Config::default();
+        config.retention.value.keep_last = Some(1);
+        let params = CmdParams::new(&basedir).with_config(config);
+        assert!(run::<Prune>(&params, &["--tag", "clients"]).is_ok());
+        assert_eq!(repo.load(&a).unwrap().backups.len(), 2);
+        assert_eq!(repo.load(&b).unwrap().backups.len(), 1);
+    }
+
+    #[test]
+    fn it_continues_past_failed_targets() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let a = add_target_with_backups(&mut repo, "clients");
+        let b = add_target_with_backups(&mut repo, "clients");
+
+        // The oldest backup file of a target is lost, so it can't be moved to the trash.
+        let target = repo.load(&a).unwrap();
+        std::fs::remove_file(&target.backups[0].path).unwrap();
+
+        let result = run::<Prune>(
+            &CmdParams::new(&basedir),
+            &["--tag", "clients", "--keep-last", "1"],
+        );
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "1 of 2 targets failed to prune."
+        );
+        assert_eq!(repo.load(&b).unwrap().backups.len(), 1);
+    }
+
+    #[test]
+    fn it_fails_without_retention() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let result = run::<Prune>(&CmdParams::new(&basedir), &["xxx"]);
+        assert!(result.is_err());
+
+        let result = run::<Prune>(&CmdParams::new(&basedir), &["xxx", "--keep-days", "7"]);
+        assert_eq!(exit_code_of(&result.unwrap_err()), exit_code::NOT_FOUND);
+
+        let result = run::<Prune>(&CmdParams::new(&basedir), &["--keep-last", "1"]);
+        assert!(result.is_err());
+
+        let result = run::<Prune>(&CmdParams::new(&basedir), &["--tag", "all"]);
+        assert_eq!(exit_code_of(&result.unwrap_err()), exit_code::USAGE);
+    }
+
+    #[test]
+    fn it_uses_retention_of_config() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let a = add_target_with_backups(&mut repo, "clients");
+
+        let mut config = dirback::infra::config::Config::default();
+        config.retention.value.keep_last = Some(2);
+        let params = CmdParams::new(&basedir).with_config(config);
+        assert!(run::<Prune>(&params, &[&a]).is_ok());
+        assert_eq!(repo.load(&a).unwrap().backups.len(), 2);
+
+        // The options take precedence.
+        assert!(run::<Prune>(&params, &[&a, "--keep-last", "1"]).is_ok());
+        assert_eq!(repo.load(&a).unwrap().backups.len(), 1);
+    }
+}
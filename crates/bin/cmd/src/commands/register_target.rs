@@ -4,33 +4,39 @@
 
 use dirback::infra::repository::file_storage::FileStorageTargetRepository;
 use dirback::usecase::register_target::RegisterTargetUsecase;
+use dirback_cmd::say;
+use std::path::PathBuf;
 
-pub struct RegisterTarget;
+/// Register a new target, a directory or a single file.
+#[derive(clap::Args)]
+pub struct RegisterTarget {
+    name: String,
+    path: PathBuf,
+
+    /// Allow the path inside another target, or containing another target.
+    #[arg(long)]
+    allow_overlap: bool,
+}
 
 impl dirback_cmd::Command for RegisterTarget {
     fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
-        if params.args.len() < 2 {
-            anyhow::bail!("Missing args: <name> <path>");
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let mut usecase = RegisterTargetUsecase::new(&mut repo);
+        if self.allow_overlap {
+            usecase = usecase.allow_overlap();
         }
 
-        let name = params.args[0].to_string();
-        let path = std::path::PathBuf::from(params.args[1].to_string());
+        let target = usecase.execute(&self.name, &self.path)?;
 
-        if !path.exists() {
-            anyhow::bail!("Target path is invalid: '{}'", path.to_string_lossy());
+        if params.is_json() {
+            return params.print_json(&target);
         }
 
-        let path = std::fs::canonicalize(&path)?;
-
-        let mut repo = FileStorageTargetRepository::new(&params.basedir);
-        let mut usecase = RegisterTargetUsecase::new(&mut repo);
-
-        let target = usecase.execute(&name, &path)?;
-
-        println!("A new target has been registered!");
-        println!("ID  : {}", target.id);
-        println!("Name: {}", target.name);
-        println!("Path: {}", target.path.to_string_lossy());
+        say!(params, "A new target has been registered!");
+        say!(params, "ID  : {}", target.id);
+        say!(params, "Name: {}", target.name);
+        say!(params, "Path: {}", target.path.to_string_lossy());
+        say!(params, "Kind: {}", target.kind);
 
         Ok(())
     }
@@ -47,19 +53,50 @@ mod tests {
     fn it_works() {
         let temp = mktemp::TempDir::new().unwrap();
         let basedir = temp.path();
-        let args: Vec<String> = ["test", "register", "test-target", "."]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-
-        let params = CmdParams::build(&args, &basedir).unwrap();
-
-        let cmd = RegisterTarget {};
-        let result = cmd.execute(&params);
+        let params = CmdParams::new(&basedir);
+        let result = run::<RegisterTarget>(&params, &["test-target", "."]);
         assert!(result.is_ok());
 
         let repo = FileStorageTargetRepository::new(&basedir);
         let targets = repo.load_all().unwrap();
         assert_eq!(targets.len(), 1);
     }
+
+    #[test]
+    fn it_rejects_duplicate_and_nested_paths() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path().join("data");
+        let app = temp.path().join("app");
+        std::fs::create_dir_all(app.join("src")).unwrap();
+
+        let app_str = app.to_string_lossy();
+        let src_str = app.join("src").to_string_lossy().to_string();
+        let params = CmdParams::new(&basedir);
+        assert!(run::<RegisterTarget>(&params, &["app", &app_str]).is_ok());
+        assert!(run::<RegisterTarget>(&params, &["again", &app_str]).is_err());
+        assert!(run::<RegisterTarget>(&params, &["src", &src_str]).is_err());
+        assert!(run::<RegisterTarget>(&params, &["src", &src_str, "--allow-overlap"]).is_ok());
+
+        let data_str = basedir.to_string_lossy();
+        assert!(run::<RegisterTarget>(&params, &["data", &data_str]).is_err());
+
+        let repo = FileStorageTargetRepository::new(&basedir);
+        assert_eq!(repo.load_all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn it_registers_file() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path().join("data");
+        let file = temp.path().join("app.db");
+        std::fs::write(&file, "data").unwrap();
+
+        let file_str = file.to_string_lossy();
+        let params = CmdParams::new(&basedir).with_output(Output::Json);
+        assert!(run::<RegisterTarget>(&params, &["database", &file_str]).is_ok());
+
+        let repo = FileStorageTargetRepository::new(&basedir);
+        let targets = repo.load_all().unwrap();
+        assert_eq!(targets[0].kind, dirback::usecase::dto::TargetKind::File);
+    }
 }
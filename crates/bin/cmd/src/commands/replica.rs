@@ -0,0 +1,209 @@
+//!
+//! # Replica command
+//!
+//! Manages the replica locations of a target, and copies backups to them.
+//!
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::file_replica_service::FileReplicaService;
+use dirback::usecase::add_replica::AddReplicaUsecase;
+use dirback::usecase::dto::{ReplicaState, Target};
+use dirback::usecase::remove_replica::RemoveReplicaUsecase;
+use dirback::usecase::replicate::ReplicateUsecase;
+use dirback_cmd::say;
+use std::path::{Path, PathBuf};
+
+/// Manage the replica locations of a target.
+#[derive(clap::Args)]
+pub struct Replica {
+    #[command(subcommand)]
+    command: ReplicaCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum ReplicaCommand {
+    /// Add a replica location (e.g. a NAS mount).
+    Add { target_id: String, path: PathBuf },
+
+    /// Remove a replica location, leaving its backups as they are.
+    Remove { target_id: String, path: PathBuf },
+
+    /// Copy the backups to the replica locations.
+    Sync { target_id: String },
+}
+
+impl Replica {
+    fn add(
+        &self,
+        params: &dirback_cmd::CmdParams,
+        target_id: &str,
+        path: &Path,
+    ) -> anyhow::Result<()> {
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let target_id = &super::get_target(&repo, target_id)?.id;
+
+        std::fs::create_dir_all(path)?;
+        let path = std::fs::canonicalize(path)?;
+
+        let mut usecase = AddReplicaUsecase::new(&mut repo);
+        let target = usecase.execute(target_id, &path)?;
+
+        if params.is_json() {
+            return params.print_json(&target);
+        }
+        say!(
+            params,
+            "Replica location has been added: {}",
+            path.display()
+        );
+        say!(
+            params,
+            "Run `dirback replica sync {}` to copy backups.",
+            target.id
+        );
+
+        Ok(())
+    }
+
+    fn remove(
+        &self,
+        params: &dirback_cmd::CmdParams,
+        target_id: &str,
+        path: &Path,
+    ) -> anyhow::Result<()> {
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let target_id = &super::get_target(&repo, target_id)?.id;
+
+        // The location may be already unmounted, so it is not canonicalized.
+        let mut usecase = RemoveReplicaUsecase::new(&mut repo);
+        let target = usecase.execute(target_id, path)?;
+
+        if params.is_json() {
+            return params.print_json(&target);
+        }
+        say!(
+            params,
+            "Replica location has been removed: {}",
+            path.display()
+        );
+        say!(params, "Backups in the location are left as they are.");
+
+        Ok(())
+    }
+
+    fn sync(&self, params: &dirback_cmd::CmdParams, target_id: &str) -> anyhow::Result<()> {
+        let repo = FileStorageTargetRepository::new(&params.basedir);
+        let target_id = &super::get_target(&repo, target_id)?.id;
+
+        let target = replicate(&params.basedir, target_id)?;
+        if params.is_json() {
+            return params.print_json(&target);
+        }
+        print_summary(params, &target);
+
+        Ok(())
+    }
+}
+
+impl dirback_cmd::Command for Replica {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        match &self.command {
+            ReplicaCommand::Add { target_id, path } => self.add(params, target_id, path),
+            ReplicaCommand::Remove { target_id, path } => self.remove(params, target_id, path),
+            ReplicaCommand::Sync { target_id } => self.sync(params, target_id),
+        }
+    }
+}
+
+/// Copies the backups of the target to its replica locations.
+pub fn replicate(basedir: &Path, target_id: &str) -> anyhow::Result<Target> {
+    let mut repo = FileStorageTargetRepository::new(basedir);
+    let service = FileReplicaService::new();
+    let mut usecase = ReplicateUsecase::new(&mut repo, &service);
+    usecase.execute(target_id)
+}
+
+/// Prints the number of synced backups of each replica location, and the failures.
+pub fn print_summary(params: &dirback_cmd::CmdParams, target: &Target) {
+    for replica in target.replicas.iter() {
+        let statuses = target.backups.iter().filter_map(|b| {
+            b.replicas
+                .iter()
+                .find(|r| r.replica == *replica)
+                .map(|r| (b.id, r))
+        });
+
+        let mut synced = 0;
+        let mut failures = Vec::new();
+        for (backup_id, status) in statuses {
+            match status.state {
+                ReplicaState::Synced => synced += 1,
+                ReplicaState::Failed => failures.push((backup_id, status.message.clone())),
+            }
+        }
+
+        say!(
+            params,
+            "{}: {synced}/{} synced",
+            replica.display(),
+            target.backups.len()
+        );
+        for (backup_id, message) in failures {
+            say!(params, "  {backup_id:0>3}: {message}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_adds_syncs_and_removes_replica() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path().join("data");
+        let replica = temp.path().join("nas");
+
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &src).unwrap();
+
+        let replica_str = replica.to_string_lossy().to_string();
+        let params = CmdParams::new(&basedir);
+        let result = run::<Replica>(&params, &["add", &target.id, &replica_str]);
+        assert!(result.is_ok());
+        assert!(replica.exists(), "it should create the location.");
+
+        let result = run::<Replica>(&params, &["sync", &target.id]);
+        assert!(result.is_ok());
+        assert!(
+            FileStorageTargetRepository::new(&replica)
+                .load(&target.id)
+                .is_some()
+        );
+
+        let replica_str = std::fs::canonicalize(&replica).unwrap();
+        let replica_str = replica_str.to_string_lossy();
+        let result = run::<Replica>(&params, &["remove", &target.id, &replica_str]);
+        assert!(result.is_ok());
+        assert!(repo.load(&target.id).unwrap().replicas.is_empty());
+    }
+
+    #[test]
+    fn it_fails_with_unknown_sub_command() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let params = CmdParams::new(&basedir);
+        for args in [vec![], vec!["unknown"], vec!["add", "xxx"]] {
+            let result = run::<Replica>(&params, &args);
+            assert!(result.is_err(), "{args:?}");
+        }
+
+        let err = run::<Replica>(&params, &["sync", "xxx"]).unwrap_err();
+        assert_eq!(exit_code_of(&err), exit_code::NOT_FOUND);
+    }
+}
@@ -2,40 +2,61 @@
 //! # RestoreTarget command
 //!
 
-use anyhow::Context;
 use dirback::infra::repository::file_storage::FileStorageTargetRepository;
 use dirback::infra::service::targz_backup_service::TargzBackupService;
+use dirback::usecase::dto::BackupSelector;
 use dirback::usecase::restore::RestoreUsecase;
+use dirback_cmd::{detail, say};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
 
-pub struct RestoreTarget;
+/// Restore the target from a backup.
+#[derive(clap::Args)]
+pub struct RestoreTarget {
+    target_id: String,
 
-impl dirback_cmd::Command for RestoreTarget {
-    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
-        if params.args.len() < 2 {
-            anyhow::bail!("Missing args: <target-id> <backup-id>");
-        }
+    /// The backup ID or selector, e.g. `latest~1` (see `dirback help`).
+    backup: BackupSelector,
 
-        let target_id = params.args[0].to_string();
-        let backup_id = params.args[1].to_string();
+    /// Restore the source NAME to PATH instead of its own location.
+    #[arg(long = "to", value_name = "NAME=PATH", value_parser = parse_location)]
+    to: Vec<(String, PathBuf)>,
+}
 
-        let backup_id = backup_id
-            .parse::<u32>()
-            .context(format!("Invalid Backup ID ('{backup_id}')."))?;
+impl dirback_cmd::Command for RestoreTarget {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let target = super::get_target(&repo, &self.target_id)?;
+        let entry = super::get_backup(&repo, &target, &self.backup)?;
 
-        println!("Target ID = {target_id}");
-        println!("Backup ID = {backup_id}");
+        say!(params, "Target ID = {}", target.id);
+        say!(params, "Backup ID = {}", entry.id);
+        detail!(params, "Backup file: {}", entry.path.to_string_lossy());
 
-        let mut repo = FileStorageTargetRepository::new(&params.basedir);
         let service = TargzBackupService::new();
+        let hook_service = params.hook_service();
+        let locations: BTreeMap<String, PathBuf> = self.to.iter().cloned().collect();
 
-        let mut usecase = RestoreUsecase::new(&mut repo, &service);
-        usecase.execute(&target_id, backup_id)?;
+        let mut usecase = RestoreUsecase::new(&mut repo, &service).with_hooks(&hook_service);
+        usecase.execute_remapped(&target.id, entry.id, &locations)?;
 
-        println!("Restore completed!");
+        if params.is_json() {
+            return params.print_json(&entry);
+        }
+        say!(params, "Restore completed!");
         Ok(())
     }
 }
 
+/// Parses `NAME=PATH` into the restore location of the source.
+fn parse_location(value: &str) -> Result<(String, PathBuf), String> {
+    value
+        .split_once('=')
+        .filter(|(name, path)| !name.is_empty() && !path.is_empty())
+        .map(|(name, path)| (name.to_string(), PathBuf::from(path)))
+        .ok_or_else(|| format!("Invalid value: '{value}' (expected NAME=PATH)"))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -91,16 +112,9 @@ mod tests {
         let _ = std::fs::create_dir(&target_path);
         assert!(!target_path.join("foo.txt").exists());
 
-        // CmdParams for Restore
-        let args: Vec<String> = ["test", "restore", &target.id, "1"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let params = CmdParams::build(&args, &base_path).unwrap();
-
         // Restore
-        let cmd = RestoreTarget {};
-        let result = cmd.execute(&params);
+        let params = CmdParams::new(&base_path);
+        let result = run::<RestoreTarget>(&params, &[&target.id, "1"]);
         if let Err(ref e) = result {
             println!("{:?}", e);
         }
@@ -150,16 +164,9 @@ mod tests {
         let _ = write!(file, "modified");
         drop(file);
 
-        // CmdParams for Restore
-        let args: Vec<String> = ["test", "restore", &target.id, "1"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let params = CmdParams::build(&args, &base_path).unwrap();
-
         // Restore
-        let cmd = RestoreTarget {};
-        let result = cmd.execute(&params);
+        let params = CmdParams::new(&base_path);
+        let result = run::<RestoreTarget>(&params, &[&target.id, "1"]);
         if let Err(ref e) = result {
             println!("{:?}", e);
         }
@@ -203,16 +210,9 @@ mod tests {
         let _ = write!(file, "super fresh!");
         drop(file);
 
-        // CmdParams for Restore
-        let args: Vec<String> = ["test", "restore", &target.id, "1"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let params = CmdParams::build(&args, &base_path).unwrap();
-
         // Restore
-        let cmd = RestoreTarget {};
-        let result = cmd.execute(&params);
+        let params = CmdParams::new(&base_path);
+        let result = run::<RestoreTarget>(&params, &[&target.id, "1"]);
         if let Err(ref e) = result {
             println!("{:?}", e);
         }
@@ -229,47 +229,63 @@ mod tests {
     }
 
     #[test]
-    fn it_returns_err_if_missing_args() {
+    fn it_restores_sources_to_other_locations() {
         let temp = mktemp::TempDir::new().unwrap();
-        let basedir = temp.path();
+        let base_path = temp.path().join("dirback");
+        let target_path = prepare_test_files(&temp);
+        let config_path = temp.path().join("config");
+        std::fs::create_dir_all(&config_path).unwrap();
+        std::fs::write(config_path.join("app.toml"), "v1").unwrap();
 
-        {
-            // Without the target-id.
-            let args: Vec<String> = ["test", "restore"].iter().map(|s| s.to_string()).collect();
-            let params = CmdParams::build(&args, &basedir).unwrap();
+        let mut repo = FileStorageTargetRepository::new(&base_path);
+        let mut target = repo.add("TestTarget", &target_path).unwrap();
+        target.add_source("config", &config_path).unwrap();
+        repo.update(&target).unwrap();
 
-            let cmd = RestoreTarget {};
-            let result = cmd.execute(&params);
-            assert!(result.is_err(), "it should be fail without target-id.");
-        }
+        let bk_service = TargzBackupService::new();
+        let mut bk_usecase = BackupUsecase::new(&mut repo, &bk_service);
+        bk_usecase.execute(&target.id, "first backup").unwrap();
+
+        let restored = temp.path().join("restored-config");
+        let to = format!("config={}", restored.display());
+        let params = CmdParams::new(&base_path).with_output(Output::Json);
+        let result = run::<RestoreTarget>(&params, &[&target.id, "1", "--to", &to]);
+        assert!(result.is_ok());
+        assert!(restored.join("app.toml").exists());
 
-        {
-            // Without the backup-id.
-            let args: Vec<String> = ["test", "restore", "target-id"]
-                .iter()
-                .map(|s| s.to_string())
-                .collect();
-            let params = CmdParams::build(&args, &basedir).unwrap();
-
-            let cmd = RestoreTarget {};
-            let result = cmd.execute(&params);
-            assert!(result.is_err(), "it should be fail without backup-id.");
+        for to in ["config", "=path", "cache=/tmp/cache"] {
+            let result = run::<RestoreTarget>(&params, &[&target.id, "1", "--to", to]);
+            assert!(result.is_err(), "{to}");
         }
     }
 
+    #[test]
+    fn it_returns_err_if_missing_args() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let params = CmdParams::new(&basedir);
+
+        let result = run::<RestoreTarget>(&params, &[]);
+        assert!(result.is_err(), "it should be fail without target-id.");
+
+        let result = run::<RestoreTarget>(&params, &["target-id"]);
+        assert!(result.is_err(), "it should be fail without backup-id.");
+    }
+
     #[test]
     fn it_returns_err_if_invalid_backup_id_passed() {
         let temp = mktemp::TempDir::new().unwrap();
         let basedir = temp.path();
+        let params = CmdParams::new(&basedir);
 
-        let args: Vec<String> = ["test", "restore", "non-existing-id", "invalid-backup-id"]
-            .iter()
-            .map(|s| s.to_string())
-            .collect();
-        let params = CmdParams::build(&args, &basedir).unwrap();
+        let err = run::<RestoreTarget>(&params, &["non-existing-id", "latest~x"]).unwrap_err();
+        assert_eq!(exit_code_of(&err), exit_code::USAGE);
 
-        let cmd = RestoreTarget {};
-        let result = cmd.execute(&params);
-        assert!(result.is_err());
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &basedir).unwrap();
+        for args in [["non-existing-id", "1"], [target.id.as_str(), "1"]] {
+            let err = run::<RestoreTarget>(&params, &args).unwrap_err();
+            assert_eq!(exit_code_of(&err), exit_code::NOT_FOUND, "{args:?}");
+        }
     }
 }
@@ -0,0 +1,216 @@
+//!
+//! # Schedule command
+//!
+//! Manages the schedules of the backups taken by the daemon.
+//!
+
+use dirback::adapter::ListSchedulesAdapter;
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::usecase::dto::{RetentionPolicy, Schedule, Target, Timestamp};
+use dirback::usecase::set_schedule::SetScheduleUsecase;
+use dirback_cmd::say;
+use serde::Serialize;
+
+/// Manage the schedules of the backups taken by the daemon.
+#[derive(clap::Args)]
+pub struct ScheduleTarget {
+    #[command(subcommand)]
+    command: Option<ScheduleCommand>,
+}
+
+#[derive(clap::Subcommand)]
+enum ScheduleCommand {
+    /// List the schedules with their next runs (default).
+    List,
+
+    /// Set the schedule, e.g. "every 6h", "daily 03:00" or a cron expression.
+    Set {
+        target_id: String,
+
+        #[arg(required = true)]
+        schedule: Vec<String>,
+
+        /// Keep the latest N backups after each scheduled backup.
+        #[arg(long, value_name = "N")]
+        keep_last: Option<usize>,
+
+        /// Keep the backups taken within D days after each scheduled backup.
+        #[arg(long, value_name = "D")]
+        keep_days: Option<u32>,
+    },
+
+    /// Clear the schedule.
+    Clear { target_id: String },
+}
+
+/// A schedule printed with `--json`.
+#[derive(Serialize)]
+struct ScheduleEntry {
+    target: Target,
+    next_run: Timestamp,
+}
+
+impl ScheduleTarget {
+    fn set(
+        &self,
+        params: &dirback_cmd::CmdParams,
+        target_id: &str,
+        schedule: &[String],
+        retention: RetentionPolicy,
+    ) -> anyhow::Result<()> {
+        // `every 6h` may be given as two args.
+        let schedule = Schedule::parse(&schedule.join(" "))?;
+
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let target_id = &super::get_target(&repo, target_id)?.id;
+
+        let mut usecase = SetScheduleUsecase::new(&mut repo);
+        let target = usecase.execute(target_id, Some(schedule), retention)?;
+
+        if params.is_json() {
+            return params.print_json(&target);
+        }
+        say!(
+            params,
+            "Schedule of '{}': {}",
+            target.name,
+            target.schedule.map(|s| s.to_string()).unwrap_or_default()
+        );
+
+        Ok(())
+    }
+
+    fn clear(&self, params: &dirback_cmd::CmdParams, target_id: &str) -> anyhow::Result<()> {
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let target_id = &super::get_target(&repo, target_id)?.id;
+
+        let mut usecase = SetScheduleUsecase::new(&mut repo);
+        let target = usecase.execute(target_id, None, RetentionPolicy::default())?;
+
+        if params.is_json() {
+            return params.print_json(&target);
+        }
+        say!(params, "Schedule of '{}' has been cleared.", target.name);
+
+        Ok(())
+    }
+
+    fn list(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let repo = FileStorageTargetRepository::new(&params.basedir);
+        let now = Timestamp::now();
+        let schedules = ListSchedulesAdapter::new(&repo).execute(&now)?;
+
+        if params.is_json() {
+            let entries: Vec<ScheduleEntry> = schedules
+                .into_iter()
+                .map(|(target, next_run)| ScheduleEntry { target, next_run })
+                .collect();
+            return params.print_json(&entries);
+        }
+
+        say!(params, "* Schedules ({})", schedules.len());
+        say!(params, "id, name, schedule, retention, next-run");
+        for (target, next) in schedules {
+            let next = if *next <= *now {
+                String::from("due")
+            } else {
+                next.to_rfc3339()
+            };
+            say!(
+                params,
+                "{}, {}, {}, {}, {}",
+                target.id,
+                target.name,
+                target.schedule.map(|s| s.to_string()).unwrap_or_default(),
+                format_retention(&target.retention),
+                next
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn format_retention(retention: &RetentionPolicy) -> String {
+    let mut rules = Vec::new();
+    if let Some(n) = retention.keep_last {
+        rules.push(format!("keep-last {n}"));
+    }
+    if let Some(d) = retention.keep_days {
+        rules.push(format!("keep-days {d}"));
+    }
+
+    if rules.is_empty() {
+        String::from("keep all")
+    } else {
+        rules.join(" ")
+    }
+}
+
+impl dirback_cmd::Command for ScheduleTarget {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        match &self.command {
+            None | Some(ScheduleCommand::List) => self.list(params),
+            Some(ScheduleCommand::Set {
+                target_id,
+                schedule,
+                keep_last,
+                keep_days,
+            }) => {
+                let retention = RetentionPolicy {
+                    keep_last: *keep_last,
+                    keep_days: *keep_days,
+                };
+                self.set(params, target_id, schedule, retention)
+            }
+            Some(ScheduleCommand::Clear { target_id }) => self.clear(params, target_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_sets_and_clears_schedule() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &basedir).unwrap();
+
+        let params = CmdParams::new(&basedir);
+        let args = ["set", &target.id, "every", "6h", "--keep-last", "5"];
+        assert!(run::<ScheduleTarget>(&params, &args).is_ok());
+
+        let loaded = repo.load(&target.id).unwrap();
+        assert_eq!(loaded.schedule.unwrap().to_string(), "every 6h");
+        assert_eq!(loaded.retention.keep_last, Some(5));
+
+        assert!(run::<ScheduleTarget>(&params, &[]).is_ok());
+        let json = params.with_output(Output::Json);
+        assert!(run::<ScheduleTarget>(&json, &["list"]).is_ok());
+
+        assert!(run::<ScheduleTarget>(&json, &["clear", &target.id]).is_ok());
+        assert!(repo.load(&target.id).unwrap().schedule.is_none());
+    }
+
+    #[test]
+    fn it_returns_err_if_schedule_is_invalid() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &basedir).unwrap();
+
+        for args in [
+            vec!["set", target.id.as_str()],
+            vec!["set", target.id.as_str(), "every 6x"],
+            vec!["set", target.id.as_str(), "* * *"],
+        ] {
+            let result = run::<ScheduleTarget>(&CmdParams::new(&basedir), &args);
+            assert!(result.is_err(), "{args:?}");
+        }
+    }
+}
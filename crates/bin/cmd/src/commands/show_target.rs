@@ -2,49 +2,149 @@
 //! # ShowTarget command
 //!
 
-use dirback::adapter::GetTargetAdapter;
+use dirback::adapter::format_size;
 use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::usecase::dto::{BackupEntry, BackupSelector, HookEvent, ReplicaState};
+use dirback_cmd::{detail, say};
 
-pub struct ShowTarget;
+/// Show the target information, or a backup of the target.
+#[derive(clap::Args)]
+pub struct ShowTarget {
+    target_id: String,
+
+    /// Show the backup instead, by its ID or selector, e.g. `latest~1` (see `dirback help`).
+    backup: Option<BackupSelector>,
+}
 
 impl dirback_cmd::Command for ShowTarget {
     fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
-        if params.args.is_empty() {
-            anyhow::bail!("Missing args: <target_id>");
+        let repo = FileStorageTargetRepository::new(&params.basedir);
+        let target = super::get_target(&repo, &self.target_id)?;
+
+        if let Some(selector) = &self.backup {
+            let entry = super::get_backup(&repo, &target, selector)?;
+            if params.is_json() {
+                return params.print_json(&entry);
+            }
+            say!(params, "* Target: {} ({})", target.name, target.id);
+            say!(params);
+            print_backup_details(params, &entry);
+            return Ok(());
         }
 
-        let target_id = params.args[0].to_string();
+        if params.is_json() {
+            return params.print_json(&target);
+        }
 
-        let repo = FileStorageTargetRepository::new(&params.basedir);
-        let adapter = GetTargetAdapter::new(&repo);
-
-        if let Some(target) = adapter.execute(&target_id) {
-            println!("* Target Information");
-            println!("ID            : {}", target.id);
-            println!("Name          : {}", target.name);
-            println!("Path          : {}", target.path.to_string_lossy());
-            println!("Backup count  : {}", target.backups.len());
-
-            if !target.backups.is_empty() {
-                println!("\n* Backups");
-                for entry in target.backups {
-                    print!("{:0>3}: {}", entry.id, entry.timestamp.to_rfc3339());
-
-                    if !entry.note.is_empty() {
-                        println!(" # {}", entry.note);
-                    } else {
-                        println!();
-                    }
+        say!(params, "* Target Information");
+        say!(params, "ID            : {}", target.id);
+        say!(params, "Name          : {}", target.name);
+        say!(params, "Path          : {}", target.path.to_string_lossy());
+        say!(params, "Kind          : {}", target.kind);
+        for source in target.sources.iter() {
+            say!(
+                params,
+                "Source        : {}: {}",
+                source.name,
+                source.path.to_string_lossy()
+            );
+        }
+        if !target.description.is_empty() {
+            say!(params, "Description   : {}", target.description);
+        }
+        say!(
+            params,
+            "Store         : {}",
+            target.store.as_deref().unwrap_or("local")
+        );
+        for replica in target.replicas.iter() {
+            say!(params, "Replica       : {}", replica.to_string_lossy());
+        }
+        for event in HookEvent::ALL {
+            if let Some(command) = target.hooks.command(event) {
+                say!(params, "Hook          : {event}: {command}");
+            }
+        }
+        say!(params, "Backup count  : {}", target.backups.len());
+
+        if !target.backups.is_empty() {
+            say!(params, "\n* Backups");
+            for entry in target.backups {
+                let mut line = format!("{:0>3}: {}", entry.id, entry.timestamp.to_rfc3339());
+                if let Some(store) = &entry.store {
+                    line.push_str(&format!(" [{store}]"));
                 }
+                if entry.pinned {
+                    line.push_str(" (pinned)");
+                }
+                if !entry.note.is_empty() {
+                    line.push_str(&format!(" # {}", entry.note));
+                }
+                say!(params, "{line}");
+                detail!(params, "     file: {}", entry.path.to_string_lossy());
+                print_replica_statuses(params, &entry, "     ");
             }
-        } else {
-            println!("Target is none (id={target_id}).");
         }
 
         Ok(())
     }
 }
 
+fn print_backup_details(params: &dirback_cmd::CmdParams, entry: &BackupEntry) {
+    say!(params, "* Backup: {:0>3}", entry.id);
+    say!(params, "Backup date: {}", entry.timestamp.to_rfc3339());
+    say!(params, "Backup file: {}", entry.path.to_string_lossy());
+    say!(params, "Note       : {}", entry.note);
+    say!(
+        params,
+        "Store      : {}",
+        entry.store.as_deref().unwrap_or("local")
+    );
+    say!(
+        params,
+        "Pinned     : {}",
+        if entry.pinned { "yes" } else { "no" }
+    );
+    if let Some(stats) = &entry.stats {
+        say!(
+            params,
+            "Size       : {} ({} uncompressed, {} files)",
+            format_size(stats.size),
+            format_size(stats.uncompressed_size),
+            stats.files
+        );
+    }
+    if let Some(checksum) = &entry.checksum {
+        detail!(params, "Checksum   : {checksum}");
+    }
+    for source in entry.sources.iter() {
+        detail!(
+            params,
+            "Source     : {}: {}",
+            source.name,
+            source.path.to_string_lossy()
+        );
+    }
+    if !entry.replicas.is_empty() {
+        say!(params, "Replicas   :");
+        print_replica_statuses(params, entry, "  ");
+    }
+}
+
+fn print_replica_statuses(params: &dirback_cmd::CmdParams, entry: &BackupEntry, indent: &str) {
+    for status in entry.replicas.iter() {
+        let state = match status.state {
+            ReplicaState::Synced => "synced",
+            ReplicaState::Failed => "failed",
+        };
+        let mut line = format!("{indent}- {state}: {}", status.replica.to_string_lossy());
+        if !status.message.is_empty() {
+            line.push_str(&format!(" ({})", status.message));
+        }
+        say!(params, "{line}");
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -59,15 +159,73 @@ mod tests {
 
         // Test target
         let mut repo = FileStorageTargetRepository::new(&basedir);
-        let _ = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+        let target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
 
-        // CmdParams
-        let args: Vec<String> = ["test", "show", ""].iter().map(|s| s.to_string()).collect();
+        for output in [Output::Normal, Output::Verbose, Output::Json] {
+            let params = CmdParams::new(&basedir).with_output(output);
+            assert!(run::<ShowTarget>(&params, &[&target.id]).is_ok());
+        }
+    }
+
+    #[test]
+    fn it_returns_not_found() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let params = CmdParams::new(&temp.path());
 
-        let params = CmdParams::build(&args, &basedir).unwrap();
+        let err = run::<ShowTarget>(&params, &["xxx"]).unwrap_err();
+        assert_eq!(exit_code_of(&err), exit_code::NOT_FOUND);
+        assert!(run::<ShowTarget>(&params, &[]).is_err());
+    }
+
+    #[test]
+    fn it_resolves_target_by_name_prefix_and_path() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let src = std::fs::canonicalize(&basedir).unwrap().join("src");
+        std::fs::create_dir_all(src.join("sub")).unwrap();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &src).unwrap();
+
+        let params = CmdParams::new(&basedir);
+        let sub = src.join("sub").to_string_lossy().to_string();
+        for query in ["TestTarget", &target.id[..8], &sub] {
+            assert!(run::<ShowTarget>(&params, &[query]).is_ok(), "{query}");
+        }
+
+        let _ = repo.add("TestTarget", &basedir).unwrap();
+        let err = run::<ShowTarget>(&params, &["TestTarget"]).unwrap_err();
+        assert_eq!(exit_code_of(&err), exit_code::USAGE);
+    }
+
+    #[test]
+    fn it_shows_backup_by_selector() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let mut target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+        let bkdir = repo.make_backup_dir_path(&target);
+        for _ in 1..=2 {
+            let entry = target.new_backup_entry(&bkdir, "tar.gz");
+            let _ = target.register_backup_entry(entry);
+        }
+        repo.update(&target).unwrap();
+
+        for output in [Output::Normal, Output::Verbose, Output::Json] {
+            let params = CmdParams::new(&basedir).with_output(output);
+            for selector in ["1", "latest", "latest~1", "first"] {
+                assert!(
+                    run::<ShowTarget>(&params, &[&target.id, selector]).is_ok(),
+                    "{selector}"
+                );
+            }
+        }
 
-        let cmd = ShowTarget {};
-        let result = cmd.execute(&params);
-        assert!(result.is_ok());
+        let params = CmdParams::new(&basedir);
+        let err = run::<ShowTarget>(&params, &[&target.id, "latest~2"]).unwrap_err();
+        assert_eq!(exit_code_of(&err), exit_code::NOT_FOUND);
+        let err = run::<ShowTarget>(&params, &[&target.id, "latest~x"]).unwrap_err();
+        assert_eq!(exit_code_of(&err), exit_code::USAGE);
     }
 }
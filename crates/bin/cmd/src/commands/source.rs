@@ -0,0 +1,184 @@
+//!
+//! # Source command
+//!
+//! Manages the additional source directories of a target.
+//!
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::usecase::add_source::AddSourceUsecase;
+use dirback::usecase::dto::MAIN_SOURCE_NAME;
+use dirback::usecase::remove_source::RemoveSourceUsecase;
+use dirback_cmd::say;
+use std::path::{Path, PathBuf};
+
+/// Manage the additional source directories of a target.
+#[derive(clap::Args)]
+pub struct SourceTarget {
+    #[command(subcommand)]
+    command: SourceCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum SourceCommand {
+    /// Add a source directory, archived under its name.
+    Add {
+        target_id: String,
+        name: String,
+        path: PathBuf,
+
+        /// Allow the path inside a target, or containing a target.
+        #[arg(long)]
+        allow_overlap: bool,
+    },
+
+    /// Remove a source; existing backups can still restore it.
+    Remove { target_id: String, name: String },
+
+    /// List the sources of the target.
+    List { target_id: String },
+}
+
+impl SourceTarget {
+    fn add(
+        &self,
+        params: &dirback_cmd::CmdParams,
+        target_id: &str,
+        name: &str,
+        path: &Path,
+        allow_overlap: bool,
+    ) -> anyhow::Result<()> {
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let target_id = &super::get_target(&repo, target_id)?.id;
+
+        let mut usecase = AddSourceUsecase::new(&mut repo);
+        if allow_overlap {
+            usecase = usecase.allow_overlap();
+        }
+        let target = usecase.execute(target_id, name, path)?;
+
+        if params.is_json() {
+            return params.print_json(&target);
+        }
+        let source = target.sources.iter().find(|s| s.name == name);
+        let path = source.map_or(path, |s| s.path.as_path());
+        say!(params, "Source '{name}' has been added: {}", path.display());
+        say!(
+            params,
+            "Backups of '{}' archive each source under its name.",
+            target.name
+        );
+
+        Ok(())
+    }
+
+    fn remove(
+        &self,
+        params: &dirback_cmd::CmdParams,
+        target_id: &str,
+        name: &str,
+    ) -> anyhow::Result<()> {
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let target_id = &super::get_target(&repo, target_id)?.id;
+
+        let mut usecase = RemoveSourceUsecase::new(&mut repo);
+        let target = usecase.execute(target_id, name)?;
+
+        if params.is_json() {
+            return params.print_json(&target);
+        }
+        say!(params, "Source '{name}' has been removed.");
+        say!(params, "Existing backups can still restore it.");
+
+        Ok(())
+    }
+
+    fn list(&self, params: &dirback_cmd::CmdParams, target_id: &str) -> anyhow::Result<()> {
+        let repo = FileStorageTargetRepository::new(&params.basedir);
+        let target = super::get_target(&repo, target_id)?;
+
+        if params.is_json() {
+            return params.print_json(&target.sources);
+        }
+        say!(params, "* Sources ({})", target.sources.len() + 1);
+        say!(params, "{MAIN_SOURCE_NAME}: {}", target.path.display());
+        for source in target.sources.iter() {
+            say!(params, "{}: {}", source.name, source.path.display());
+        }
+
+        Ok(())
+    }
+}
+
+impl dirback_cmd::Command for SourceTarget {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        match &self.command {
+            SourceCommand::Add {
+                target_id,
+                name,
+                path,
+                allow_overlap,
+            } => self.add(params, target_id, name, path, *allow_overlap),
+            SourceCommand::Remove { target_id, name } => self.remove(params, target_id, name),
+            SourceCommand::List { target_id } => self.list(params, target_id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_adds_and_removes_sources() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path().join("data");
+        let src = temp.path().join("src");
+        let config = temp.path().join("config");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::create_dir_all(&config).unwrap();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &src).unwrap();
+
+        let config_str = config.to_string_lossy();
+        let result = run::<SourceTarget>(
+            &CmdParams::new(&basedir),
+            &["add", &target.id, "config", &config_str],
+        );
+        assert!(result.is_ok());
+
+        let loaded = repo.load(&target.id).unwrap();
+        assert_eq!(loaded.sources.len(), 1);
+        assert_eq!(loaded.sources[0].name, "config");
+
+        assert!(run::<SourceTarget>(&CmdParams::new(&basedir), &["list", &target.id]).is_ok());
+
+        let result =
+            run::<SourceTarget>(&CmdParams::new(&basedir), &["remove", &target.id, "config"]);
+        assert!(result.is_ok());
+        assert!(repo.load(&target.id).unwrap().sources.is_empty());
+    }
+
+    #[test]
+    fn it_returns_err_if_args_are_invalid() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &basedir).unwrap();
+        let dir = basedir.to_string_lossy();
+
+        for args in [
+            vec![],
+            vec!["add", target.id.as_str(), "config"],
+            vec!["add", target.id.as_str(), "main", &dir],
+            vec!["add", target.id.as_str(), "config", "not-exists"],
+            vec!["add", target.id.as_str(), "config", &dir, "--allow-overlap"],
+            vec!["remove", target.id.as_str(), "config"],
+        ] {
+            let result = run::<SourceTarget>(&CmdParams::new(&basedir), &args);
+            assert!(result.is_err(), "{args:?}");
+        }
+    }
+}
@@ -0,0 +1,261 @@
+//!
+//! # SqliteStorage command
+//!
+//! Migrates target informations between the FileStorage and the SQLite database,
+//! and searches the targets in the database.
+//!
+//! The other commands use the FileStorage, so the database is refreshed from it before a search.
+//!
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::repository::sqlite::{SqliteTargetRepository, TargetQuery};
+use dirback::usecase::dto::{Target, Timestamp};
+use dirback::usecase::transfer_targets::TransferTargetsUsecase;
+use dirback_cmd::say;
+
+/// Migrate the targets between the data directory and the SQLite database, or search them.
+#[derive(clap::Args)]
+pub struct SqliteStorage {
+    #[command(subcommand)]
+    command: SqliteCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum SqliteCommand {
+    /// Copy the targets from the data directory into the database.
+    Import,
+
+    /// Copy the targets from the database into the data directory.
+    Export,
+
+    /// Search the targets, refreshing the database from the data directory first.
+    Query {
+        /// The target name contains the text.
+        #[arg(long)]
+        name: Option<String>,
+
+        /// The target has the tag.
+        #[arg(long)]
+        tag: Option<String>,
+
+        /// Only the backups taken at or after the time (YYYYMMDDThhmmssZ).
+        #[arg(long, value_parser = parse_timestamp)]
+        since: Option<Timestamp>,
+
+        /// Only the backups taken at or before the time (YYYYMMDDThhmmssZ).
+        #[arg(long, value_parser = parse_timestamp)]
+        until: Option<Timestamp>,
+
+        /// Only the backups whose archive is at least this many bytes.
+        #[arg(long, value_name = "BYTES")]
+        min_size: Option<u64>,
+
+        /// Only the backups whose archive is at most this many bytes.
+        #[arg(long, value_name = "BYTES")]
+        max_size: Option<u64>,
+    },
+}
+
+fn parse_timestamp(s: &str) -> Result<Timestamp, String> {
+    Timestamp::from_fmt_str(s).map_err(|_| format!("Invalid time: '{s}' (e.g. '20250123T120000Z')"))
+}
+
+impl dirback_cmd::Command for SqliteStorage {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let mut file_repo = FileStorageTargetRepository::new(&params.basedir);
+        let mut sqlite_repo = SqliteTargetRepository::build(&params.basedir)?;
+
+        let targets = match &self.command {
+            SqliteCommand::Import => {
+                TransferTargetsUsecase::new(&file_repo, &mut sqlite_repo).execute()?
+            }
+            SqliteCommand::Export => {
+                TransferTargetsUsecase::new(&sqlite_repo, &mut file_repo).execute()?
+            }
+            SqliteCommand::Query {
+                name,
+                tag,
+                since,
+                until,
+                min_size,
+                max_size,
+            } => {
+                let query = TargetQuery {
+                    name: name.clone(),
+                    tag: tag.clone(),
+                    since: since.clone(),
+                    until: until.clone(),
+                    min_size: *min_size,
+                    max_size: *max_size,
+                };
+                sqlite_repo.refresh(&file_repo)?;
+                let targets: Vec<Target> = sqlite_repo
+                    .query(&query)?
+                    .into_iter()
+                    .map(Target::from)
+                    .collect();
+                return print_query_result(params, &targets);
+            }
+        };
+
+        if params.is_json() {
+            return params.print_json(&targets);
+        }
+        say!(params, "{} targets have been transferred.", targets.len());
+        for target in targets {
+            say!(params, "- {}: {}", target.id, target.name);
+        }
+
+        Ok(())
+    }
+}
+
+fn print_query_result(params: &dirback_cmd::CmdParams, targets: &[Target]) -> anyhow::Result<()> {
+    if params.is_json() {
+        return params.print_json(&targets);
+    }
+
+    say!(params, "* Targets ({})", targets.len());
+    for target in targets {
+        say!(
+            params,
+            "{}, {}, {}",
+            target.id,
+            target.name,
+            target.tags.join(" ")
+        );
+        for backup in target.backups.iter() {
+            say!(
+                params,
+                "  - {:0>3}, {}, {}",
+                backup.id,
+                backup.timestamp.to_rfc3339(),
+                backup
+                    .stats
+                    .as_ref()
+                    .map(|stats| format!("{} bytes", stats.size))
+                    .unwrap_or_else(|| String::from("-"))
+            );
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_imports_and_exports() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        // Test targets
+        let mut file_repo = FileStorageTargetRepository::new(&basedir);
+        let target = file_repo
+            .add("TestTarget", std::path::Path::new("."))
+            .unwrap();
+
+        // Import
+        let result = run::<SqliteStorage>(&CmdParams::new(&basedir), &["import"]);
+        assert!(result.is_ok());
+
+        let mut sqlite_repo = SqliteTargetRepository::build(&basedir).unwrap();
+        assert_eq!(sqlite_repo.load(&target.id), Some(target.clone()));
+
+        // Export
+        let mut renamed = target.clone();
+        renamed.name = String::from("Renamed");
+        let _ = sqlite_repo.update(&renamed);
+        let _ = sqlite_repo.add("NewTarget", std::path::Path::new("."));
+        drop(sqlite_repo);
+
+        let result = run::<SqliteStorage>(&CmdParams::new(&basedir), &["export"]);
+        assert!(result.is_ok());
+
+        let targets = file_repo.load_all().unwrap();
+        assert_eq!(targets.len(), 2);
+        assert_eq!(file_repo.load(&target.id), Some(renamed));
+    }
+
+    #[test]
+    fn it_queries_targets() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut file_repo = FileStorageTargetRepository::new(&basedir);
+        let mut target = file_repo
+            .add("TestTarget", std::path::Path::new("."))
+            .unwrap();
+        target.tags = vec![String::from("work")];
+        file_repo.update(&target).unwrap();
+
+        for output in [Output::Normal, Output::Json] {
+            let params = CmdParams::new(&basedir).with_output(output);
+            let args = [
+                "query",
+                "--tag",
+                "work",
+                "--since",
+                "20250101T000000Z",
+                "--until",
+                "20251231T235959Z",
+                "--min-size",
+                "1024",
+            ];
+            assert!(run::<SqliteStorage>(&params, &args).is_ok());
+        }
+
+        let params = CmdParams::new(&basedir);
+        assert!(run::<SqliteStorage>(&params, &["query", "--since", "yesterday"]).is_err());
+    }
+
+    #[test]
+    fn it_refreshes_database_before_query() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut file_repo = FileStorageTargetRepository::new(&basedir);
+        let mut target = file_repo
+            .add("TestTarget", std::path::Path::new("."))
+            .unwrap();
+        let deleted = file_repo
+            .add("Deleted", std::path::Path::new("deleted"))
+            .unwrap();
+        assert!(run::<SqliteStorage>(&CmdParams::new(&basedir), &["import"]).is_ok());
+
+        // Changes after the import are seen by the query.
+        target.name = String::from("Renamed");
+        file_repo.update(&target).unwrap();
+        file_repo.delete_target(&deleted.id).unwrap();
+        let added = file_repo
+            .add("Added", std::path::Path::new("added"))
+            .unwrap();
+
+        assert!(run::<SqliteStorage>(&CmdParams::new(&basedir), &["query"]).is_ok());
+
+        let sqlite_repo = SqliteTargetRepository::build(&basedir).unwrap();
+        let mut names: Vec<String> = sqlite_repo
+            .query(&TargetQuery::default())
+            .unwrap()
+            .into_iter()
+            .map(|t| t.name)
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["Added", "Renamed"]);
+        assert!(sqlite_repo.load(&added.id).is_some());
+        assert!(sqlite_repo.load(&deleted.id).is_none());
+    }
+
+    #[test]
+    fn it_fails_with_unknown_sub_command() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let result = run::<SqliteStorage>(&CmdParams::new(&basedir), &["unknown"]);
+        assert!(result.is_err());
+    }
+}
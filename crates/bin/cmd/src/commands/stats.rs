@@ -0,0 +1,155 @@
+//!
+//! # Stats command
+//!
+//! Prints the sizes of the backups, the compression ratio, the growth over time,
+//! and the disk usage of the data directory.
+//!
+
+use dirback::adapter::{StatsAdapter, format_growth, format_size};
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::usecase::dto::StatsReport;
+use dirback_cmd::say;
+
+/// Show the disk usage of the targets, or of each backup of the target.
+#[derive(clap::Args)]
+pub struct Stats {
+    target_id: Option<String>,
+}
+
+impl dirback_cmd::Command for Stats {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let repo = FileStorageTargetRepository::new(&params.basedir);
+        let target_id = match &self.target_id {
+            Some(query) => Some(super::get_target(&repo, query)?.id),
+            None => None,
+        };
+
+        let report = StatsAdapter::new(&repo)
+            .with_datadir(&params.basedir)
+            .execute(target_id.as_deref())?;
+        if params.is_json() {
+            return params.print_json(&report);
+        }
+
+        match target_id {
+            Some(_) => print_backups(params, &report),
+            None => print_targets(params, &report),
+        }
+        say!(params);
+        say!(
+            params,
+            "Total: {} backups, {} ({} uncompressed)",
+            report.backup_count,
+            format_size(report.size),
+            format_size(report.uncompressed_size)
+        );
+        if let Some(size) = report.datadir_size {
+            say!(
+                params,
+                "Data directory: {} ({})",
+                format_size(size),
+                params.basedir.display()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+fn print_targets(params: &dirback_cmd::CmdParams, report: &StatsReport) {
+    say!(params, "* Stats ({} targets)", report.targets.len());
+    say!(params, "id, name, backups, size, uncompressed, ratio");
+    for target in report.targets.iter() {
+        say!(
+            params,
+            "{}, {}, {}, {}, {}, {}",
+            target.id,
+            target.name,
+            target.backups.len(),
+            format_size(target.size),
+            format_size(target.uncompressed_size),
+            format_ratio(target.ratio)
+        );
+    }
+}
+
+fn print_backups(params: &dirback_cmd::CmdParams, report: &StatsReport) {
+    let Some(target) = report.targets.first() else {
+        return;
+    };
+
+    say!(params, "* Stats: {} ({})", target.name, target.id);
+    say!(
+        params,
+        "id, timestamp, size, uncompressed, ratio, files, seconds, growth"
+    );
+    for backup in target.backups.iter() {
+        say!(
+            params,
+            "{:0>3}, {}, {}, {}, {}, {}, {}, {}",
+            backup.id,
+            backup.timestamp.to_rfc3339(),
+            backup.size.map(format_size).unwrap_or_else(unknown),
+            backup
+                .uncompressed_size
+                .map(format_size)
+                .unwrap_or_else(unknown),
+            format_ratio(backup.ratio),
+            backup.files.map(|n| n.to_string()).unwrap_or_else(unknown),
+            backup
+                .duration_ms
+                .map(|ms| format!("{:.1}", ms as f64 / 1000.0))
+                .unwrap_or_else(unknown),
+            backup.growth.map(format_growth).unwrap_or_else(unknown)
+        );
+    }
+    if target.unknown > 0 {
+        say!(
+            params,
+            "({} backups made before the sizes were recorded are shown as '-'.)",
+            target.unknown
+        );
+    }
+}
+
+fn unknown() -> String {
+    String::from("-")
+}
+
+/// Formats the bytes with a binary unit, e.g. `1.5 MiB`.
+fn format_ratio(ratio: Option<f64>) -> String {
+    ratio
+        .map(|ratio| format!("{:.1}%", ratio * 100.0))
+        .unwrap_or_else(unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let mut target = repo.add("notes", std::path::Path::new(".")).unwrap();
+        let bkdir = repo.make_backup_dir_path(&target);
+        let entry = target.new_backup_entry(&bkdir, "tar.gz");
+        target.register_backup_entry(entry).unwrap();
+        let target_id = repo.update(&target).unwrap().id;
+
+        for output in [Output::Normal, Output::Json] {
+            let params = CmdParams::new(&basedir).with_output(output);
+            assert!(run::<Stats>(&params, &[]).is_ok());
+            assert!(run::<Stats>(&params, &[&target_id]).is_ok());
+            assert!(run::<Stats>(&params, &["notes"]).is_ok());
+        }
+
+        let params = CmdParams::new(&basedir);
+        let err = run::<Stats>(&params, &["unknown"]).unwrap_err();
+        assert_eq!(exit_code_of(&err), exit_code::NOT_FOUND);
+    }
+}
@@ -0,0 +1,140 @@
+//!
+//! # TagTarget command
+//!
+//! Manages the tags of targets.
+//!
+
+use dirback::adapter::ListTargetsAdapter;
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::usecase::tag_target::TagTargetUsecase;
+use dirback_cmd::say;
+use std::collections::BTreeMap;
+
+/// Manage the tags of targets.
+#[derive(clap::Args)]
+pub struct TagTarget {
+    #[command(subcommand)]
+    command: TagCommand,
+}
+
+#[derive(clap::Subcommand)]
+enum TagCommand {
+    /// Add tags to the target.
+    Add {
+        target_id: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    /// Remove tags from the target.
+    Remove {
+        target_id: String,
+        #[arg(required = true)]
+        tags: Vec<String>,
+    },
+
+    /// List the tags with the number of targets.
+    List,
+}
+
+impl TagTarget {
+    fn update(
+        &self,
+        params: &dirback_cmd::CmdParams,
+        target_id: &str,
+        add: &[String],
+        remove: &[String],
+    ) -> anyhow::Result<()> {
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let target_id = &super::get_target(&repo, target_id)?.id;
+
+        let mut usecase = TagTargetUsecase::new(&mut repo);
+        let target = usecase.execute(target_id, add, remove)?;
+
+        if params.is_json() {
+            return params.print_json(&target);
+        }
+        say!(
+            params,
+            "Tags of '{}': {}",
+            target.name,
+            target.tags.join(" ")
+        );
+
+        Ok(())
+    }
+
+    fn list(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let repo = FileStorageTargetRepository::new(&params.basedir);
+        let targets = ListTargetsAdapter::new(&repo).execute()?;
+
+        let mut counts = BTreeMap::<String, usize>::new();
+        for tag in targets.iter().flat_map(|t| t.tags.iter()) {
+            *counts.entry(tag.clone()).or_default() += 1;
+        }
+
+        if params.is_json() {
+            return params.print_json(&counts);
+        }
+        say!(params, "* Tags ({})", counts.len());
+        for (tag, count) in counts {
+            say!(params, "{tag} ({count})");
+        }
+
+        Ok(())
+    }
+}
+
+impl dirback_cmd::Command for TagTarget {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        match &self.command {
+            TagCommand::Add { target_id, tags } => self.update(params, target_id, tags, &[]),
+            TagCommand::Remove { target_id, tags } => self.update(params, target_id, &[], tags),
+            TagCommand::List => self.list(params),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_adds_and_removes_tags() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+
+        let result = run::<TagTarget>(
+            &CmdParams::new(&basedir),
+            &["add", &target.id, "clients", "dotfiles"],
+        );
+        assert!(result.is_ok());
+        assert_eq!(repo.load(&target.id).unwrap().tags, ["clients", "dotfiles"]);
+
+        let result = run::<TagTarget>(
+            &CmdParams::new(&basedir),
+            &["remove", &target.id, "clients"],
+        );
+        assert!(result.is_ok());
+        assert_eq!(repo.load(&target.id).unwrap().tags, ["dotfiles"]);
+
+        assert!(run::<TagTarget>(&CmdParams::new(&basedir), &["list"]).is_ok());
+    }
+
+    #[test]
+    fn it_fails_with_invalid_tag() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+
+        let result = run::<TagTarget>(&CmdParams::new(&basedir), &["add", &target.id, "all"]);
+        assert!(result.is_err());
+    }
+}
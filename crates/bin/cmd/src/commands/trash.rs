@@ -0,0 +1,175 @@
+//!
+//! # Trash command
+//!
+//! Lists, restores or empties the deleted targets and backups.
+//!
+
+use dirback::adapter::ListTrashAdapter;
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::usecase::dto::TrashItem;
+use dirback::usecase::empty_trash::EmptyTrashUsecase;
+use dirback::usecase::restore_trash::RestoreTrashUsecase;
+use dirback_cmd::{CmdError, say};
+
+/// List, restore or empty the deleted targets and backups.
+#[derive(clap::Args)]
+pub struct Trash {
+    #[command(subcommand)]
+    command: Option<TrashCommand>,
+}
+
+#[derive(clap::Subcommand)]
+enum TrashCommand {
+    /// List the deleted targets and backups (default).
+    List,
+
+    /// Restore the deleted target or backup.
+    Restore { trash_id: u32 },
+
+    /// Remove the items permanently.
+    Empty {
+        /// Remove only the expired items.
+        #[arg(long)]
+        expired: bool,
+    },
+}
+
+fn describe(item: &TrashItem) -> String {
+    match &item.backup {
+        Some(entry) => format!(
+            "backup {:0>3} of '{}' ({})",
+            entry.id,
+            item.target_name,
+            entry.timestamp.to_rfc3339()
+        ),
+        None => format!("target '{}' ({})", item.target_name, item.target_id),
+    }
+}
+
+impl Trash {
+    fn list(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let repo = FileStorageTargetRepository::new(&params.basedir);
+        let items = ListTrashAdapter::new(&repo).execute()?;
+
+        if params.is_json() {
+            return params.print_json(&items);
+        }
+        if items.is_empty() {
+            say!(params, "The trash is empty.");
+            return Ok(());
+        }
+
+        say!(params, "* Trash ({})", items.len());
+        for item in items.iter() {
+            say!(
+                params,
+                "- {}: {} deleted at {}, expires at {}",
+                item.id,
+                describe(item),
+                item.deleted_at.to_rfc3339(),
+                item.expires_at.to_rfc3339()
+            );
+        }
+
+        Ok(())
+    }
+
+    fn restore(&self, params: &dirback_cmd::CmdParams, trash_id: u32) -> anyhow::Result<()> {
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let items = ListTrashAdapter::new(&repo).execute()?;
+        if !items.iter().any(|item| item.id == trash_id) {
+            return Err(CmdError::NotFound(format!("Trash item not found ('{trash_id}').")).into());
+        }
+        let item = RestoreTrashUsecase::new(&mut repo).execute(trash_id)?;
+
+        if params.is_json() {
+            return params.print_json(&item);
+        }
+        say!(params, "The {} has been restored.", describe(&item));
+        Ok(())
+    }
+
+    fn empty(&self, params: &dirback_cmd::CmdParams, expired_only: bool) -> anyhow::Result<()> {
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let items = EmptyTrashUsecase::new(&mut repo).execute(expired_only)?;
+
+        if params.is_json() {
+            return params.print_json(&items);
+        }
+        for item in items.iter() {
+            say!(params, "Removed: {}", describe(item));
+        }
+        say!(
+            params,
+            "{} item(s) have been removed permanently.",
+            items.len()
+        );
+        Ok(())
+    }
+}
+
+impl dirback_cmd::Command for Trash {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        match &self.command {
+            None | Some(TrashCommand::List) => self.list(params),
+            Some(TrashCommand::Restore { trash_id }) => self.restore(params, *trash_id),
+            Some(TrashCommand::Empty { expired }) => self.empty(params, *expired),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::{TargetRepository, TrashRepository};
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_restores_deleted_target() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &basedir).unwrap();
+        repo.delete_target(&target.id).unwrap();
+
+        assert!(run::<Trash>(&CmdParams::new(&basedir), &[]).is_ok());
+        assert!(run::<Trash>(&CmdParams::new(&basedir), &["restore", "1"]).is_ok());
+        assert!(repo.load(&target.id).is_some());
+        assert!(repo.list_trash().unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_empties_trash() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", &basedir).unwrap();
+        repo.delete_target(&target.id).unwrap();
+
+        let params = CmdParams::new(&basedir);
+        assert!(run::<Trash>(&params, &["empty", "--expired"]).is_ok());
+        assert_eq!(repo.list_trash().unwrap().len(), 1);
+
+        assert!(run::<Trash>(&CmdParams::new(&basedir), &["empty"]).is_ok());
+        assert!(repo.list_trash().unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_returns_err_if_args_are_invalid() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        for args in [
+            vec!["restore"],
+            vec!["restore", "x"],
+            vec!["restore", "1"],
+            vec!["empty", "--all"],
+            vec!["unknown"],
+        ] {
+            let result = run::<Trash>(&CmdParams::new(&basedir), &args);
+            assert!(result.is_err(), "{args:?}");
+        }
+    }
+}
@@ -0,0 +1,128 @@
+//!
+//! # WatchTarget command
+//!
+//! Takes a backup of the target shortly after its files change, until stopped.
+//!
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::notify_watch_service::NotifyWatchService;
+use dirback::infra::service::targz_backup_service::TargzBackupService;
+use dirback::usecase::watch::{WatchOptions, WatchUsecase};
+use dirback_cmd::{detail, say};
+use std::path::Path;
+use std::time::Duration;
+
+/// Take a backup shortly after the files of the target change, until stopped.
+#[derive(clap::Args)]
+pub struct WatchTarget {
+    target_id: String,
+
+    /// Take a backup after SECS without changes (default: 10).
+    #[arg(long, value_name = "SECS")]
+    quiet_period: Option<u64>,
+
+    /// Take a backup at least SECS after the previous one (default: 300).
+    #[arg(long, value_name = "SECS")]
+    min_interval: Option<u64>,
+}
+
+impl WatchTarget {
+    fn options(&self) -> WatchOptions {
+        let mut options = WatchOptions::default();
+        if let Some(secs) = self.quiet_period {
+            options.quiet = Duration::from_secs(secs);
+        }
+        if let Some(secs) = self.min_interval {
+            options.min_interval = Duration::from_secs(secs);
+        }
+        options
+    }
+}
+
+impl dirback_cmd::Command for WatchTarget {
+    fn execute(&self, params: &dirback_cmd::CmdParams) -> anyhow::Result<()> {
+        let mut options = self.options();
+        options.excludes = params.config.excludes.value.clone();
+
+        let mut repo = FileStorageTargetRepository::new(&params.basedir);
+        let target = super::get_target(&repo, &self.target_id)?;
+        let has_replicas = !target.replicas.is_empty();
+
+        let paths: Vec<&Path> = std::iter::once(target.path.as_path())
+            .chain(target.sources.iter().map(|s| s.path.as_path()))
+            .collect();
+        let mut watcher = NotifyWatchService::build(&paths)?
+            .on_error(|e| eprintln!("Watch error (still watching): {e}"));
+        let service = TargzBackupService::new()
+            .with_compression(params.config.compression.value)
+            .with_excludes(&params.config.excludes.value);
+        let hook_service = params.hook_service();
+
+        say!(
+            params,
+            "Watching '{}' ({}). Press Ctrl-C to stop.",
+            target.name,
+            target.path.display()
+        );
+
+        let mut usecase =
+            WatchUsecase::new(&mut repo, &service, &mut watcher).with_hooks(&hook_service);
+        usecase.execute(&target.id, &options, |result| {
+            match result {
+                Ok(entry) => {
+                    say!(params, "Backup {:0>3}: {}", entry.id, entry.note);
+                    detail!(params, "Backup file: {}", entry.path.to_string_lossy());
+
+                    // Copy the new backup to the replica locations.
+                    if has_replicas {
+                        match super::replica::replicate(&params.basedir, &target.id) {
+                            Ok(target) => super::replica::print_summary(params, &target),
+                            Err(e) => eprintln!("Replication failed: {e}"),
+                        }
+                    }
+                }
+                Err(e) => eprintln!("Backup failed: {e}"),
+            }
+            true
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback_cmd::*;
+
+    #[test]
+    fn it_parses_options() {
+        let cmd: WatchTarget =
+            parse_args(&["xxx", "--quiet-period", "3", "--min-interval", "60"]).unwrap();
+        assert_eq!(cmd.target_id, "xxx");
+
+        let options = cmd.options();
+        assert_eq!(options.quiet, Duration::from_secs(3));
+        assert_eq!(options.min_interval, Duration::from_secs(60));
+
+        let cmd: WatchTarget = parse_args(&["xxx"]).unwrap();
+        assert_eq!(cmd.options().quiet, WatchOptions::default().quiet);
+    }
+
+    #[test]
+    fn it_returns_err_with_invalid_args() {
+        for a in [
+            vec![],
+            vec!["xxx", "--quiet-period"],
+            vec!["xxx", "--quiet-period", "x"],
+            vec!["a", "b"],
+        ] {
+            assert!(parse_args::<WatchTarget>(&a).is_err(), "{a:?}");
+        }
+    }
+
+    #[test]
+    fn it_returns_err_if_target_not_found() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let err = run::<WatchTarget>(&CmdParams::new(&temp.path()), &["xxx"]).unwrap_err();
+        assert_eq!(exit_code_of(&err), exit_code::NOT_FOUND);
+    }
+}
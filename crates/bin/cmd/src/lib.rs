@@ -2,29 +2,176 @@
 //! # dirback cmd lib
 //!
 
-use std::collections::HashMap;
+use dirback::infra::config::Config;
+use dirback::infra::service::shell_hook_service::ShellHookService;
+use serde::Serialize;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 //-----------------------------------------------------------------------------
-//  Args
+//  Params
 //-----------------------------------------------------------------------------
+/// How the commands print their results.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum Output {
+    #[default]
+    Normal,
+
+    /// Prints nothing but errors.
+    Quiet,
+
+    /// Prints the details, e.g. backup file paths and the causes of errors.
+    Verbose,
+
+    /// Prints the result as JSON, built from the `usecase::dto` types.
+    Json,
+}
+
+/// The global options of the commands.
 pub struct CmdParams {
-    pub command: String,
-    pub args: Vec<String>,
     pub basedir: PathBuf,
+    pub output: Output,
+
+    /// The effective config, e.g. the compression level of new backups.
+    pub config: Config,
 }
 
 impl CmdParams {
-    pub fn build(args: &[String], basedir: &Path) -> anyhow::Result<Self> {
-        if args.len() == 1 {
-            anyhow::bail!("No command specified.");
+    pub fn new(basedir: &Path) -> Self {
+        Self {
+            basedir: basedir.to_path_buf(),
+            output: Output::default(),
+            config: Config::default(),
         }
+    }
 
-        Ok(Self {
-            command: args[1].to_string(),
-            args: args[2..].to_vec(),
-            basedir: basedir.to_path_buf(),
-        })
+    pub fn with_config(mut self, config: Config) -> Self {
+        self.config = config;
+        self
+    }
+
+    pub fn with_output(mut self, output: Output) -> Self {
+        self.output = output;
+        self
+    }
+
+    pub fn is_json(&self) -> bool {
+        self.output == Output::Json
+    }
+
+    /// Returns the hook service, which keeps the stdout of the hooks out of quiet or JSON output.
+    pub fn hook_service(&self) -> ShellHookService {
+        match self.output {
+            Output::Normal | Output::Verbose => ShellHookService::new(),
+            Output::Quiet => ShellHookService::quiet(),
+            Output::Json => ShellHookService::to_stderr(),
+        }
+    }
+
+    /// Prints the message line, unless the output is quiet or JSON.
+    pub fn say(&self, args: std::fmt::Arguments) {
+        if matches!(self.output, Output::Normal | Output::Verbose) {
+            println!("{args}");
+        }
+    }
+
+    /// Prints the message line only if the output is verbose.
+    pub fn detail(&self, args: std::fmt::Arguments) {
+        if self.output == Output::Verbose {
+            println!("{args}");
+        }
+    }
+
+    /// Prints the value as JSON.
+    pub fn print_json<T: Serialize>(&self, value: &T) -> anyhow::Result<()> {
+        let mut stdout = std::io::stdout().lock();
+        serde_json::to_writer_pretty(&mut stdout, value)?;
+        writeln!(stdout)?;
+        Ok(())
+    }
+}
+
+/// Prints a message line with `CmdParams::say`.
+#[macro_export]
+macro_rules! say {
+    ($params:expr) => {
+        $params.say(format_args!(""))
+    };
+    ($params:expr, $($arg:tt)*) => {
+        $params.say(format_args!($($arg)*))
+    };
+}
+
+/// Prints a message line with `CmdParams::detail`.
+#[macro_export]
+macro_rules! detail {
+    ($params:expr, $($arg:tt)*) => {
+        $params.detail(format_args!($($arg)*))
+    };
+}
+
+//-----------------------------------------------------------------------------
+//  Errors
+//-----------------------------------------------------------------------------
+/// Exit codes of the dirback command.
+pub mod exit_code {
+    pub const SUCCESS: i32 = 0;
+
+    /// The command failed.
+    pub const FAILURE: i32 = 1;
+
+    /// The arguments are invalid.
+    pub const USAGE: i32 = 2;
+
+    /// The target or the backup is not found.
+    pub const NOT_FOUND: i32 = 3;
+
+    /// The confirmation was declined.
+    pub const CANCELLED: i32 = 4;
+}
+
+/// Errors with their own exit codes, other errors exit with `exit_code::FAILURE`.
+#[derive(Debug, thiserror::Error)]
+pub enum CmdError {
+    #[error("{0}")]
+    Usage(String),
+
+    #[error("{0}")]
+    NotFound(String),
+
+    #[error("Cancelled.")]
+    Cancelled,
+}
+
+impl CmdError {
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Self::Usage(_) => exit_code::USAGE,
+            Self::NotFound(_) => exit_code::NOT_FOUND,
+            Self::Cancelled => exit_code::CANCELLED,
+        }
+    }
+}
+
+/// Returns the exit code for the error.
+pub fn exit_code_of(err: &anyhow::Error) -> i32 {
+    err.downcast_ref::<CmdError>()
+        .map_or(exit_code::FAILURE, CmdError::exit_code)
+}
+
+/// The error printed with `--json`.
+#[derive(Debug, Serialize)]
+pub struct ErrorOutput {
+    pub code: i32,
+    pub message: String,
+}
+
+impl ErrorOutput {
+    pub fn new(err: &anyhow::Error) -> Self {
+        Self {
+            code: exit_code_of(err),
+            message: err.to_string(),
+        }
     }
 }
 
@@ -35,26 +182,63 @@ pub trait Command {
     fn execute(&self, params: &CmdParams) -> anyhow::Result<()>;
 }
 
-#[derive(Default)]
-pub struct CommandInvoker {
-    commands: HashMap<String, Box<dyn Command>>,
+/// Parses the arguments of a command, without the command name.
+pub fn parse_args<T: clap::Args>(args: &[&str]) -> Result<T, clap::Error> {
+    let cmd = T::augment_args(clap::Command::new("dirback"));
+    let matches =
+        cmd.try_get_matches_from(std::iter::once("dirback").chain(args.iter().copied()))?;
+    T::from_arg_matches(&matches)
 }
 
-impl CommandInvoker {
-    pub fn new() -> Self {
-        Self {
-            commands: HashMap::new(),
-        }
+/// Parses the arguments and executes the command, e.g. in tests.
+///
+/// Invalid arguments are returned as `CmdError::Usage`.
+pub fn run<T: clap::Args + Command>(params: &CmdParams, args: &[&str]) -> anyhow::Result<()> {
+    let cmd: T = parse_args(args).map_err(|e| CmdError::Usage(e.to_string()))?;
+    cmd.execute(params)
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_code_of() {
+        let err = anyhow::Error::new(CmdError::NotFound(String::from("xxx")));
+        assert_eq!(exit_code_of(&err), exit_code::NOT_FOUND);
+
+        let err = anyhow::Error::new(CmdError::Cancelled);
+        assert_eq!(exit_code_of(&err), exit_code::CANCELLED);
+
+        let err = anyhow::anyhow!("failed");
+        assert_eq!(exit_code_of(&err), exit_code::FAILURE);
     }
 
-    pub fn register(&mut self, name: &str, command: Box<dyn Command>) {
-        self.commands.insert(name.to_string(), command);
+    #[test]
+    fn test_error_output() {
+        let err = anyhow::Error::new(CmdError::Usage(String::from("Missing args")));
+        let json = serde_json::to_value(ErrorOutput::new(&err)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({ "code": 2, "message": "Missing args" })
+        );
     }
 
-    pub fn execute(&self, params: &CmdParams) -> anyhow::Result<()> {
-        match self.commands.get(&params.command) {
-            Some(cmd) => cmd.execute(params),
-            None => anyhow::bail!("Unknown command: '{}'", params.command),
+    #[test]
+    fn test_parse_args() {
+        #[derive(clap::Args)]
+        struct TestArgs {
+            name: String,
+            #[arg(long)]
+            force: bool,
         }
+
+        let args: TestArgs = parse_args(&["test", "--force"]).unwrap();
+        assert_eq!(args.name, "test");
+        assert!(args.force);
+        assert!(parse_args::<TestArgs>(&[]).is_err());
     }
 }
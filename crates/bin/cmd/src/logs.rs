@@ -0,0 +1,32 @@
+//!
+//! # logs
+//!
+
+use dirback::infra::config::LogLevel;
+use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::filter::LevelFilter;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+/// setup logger.
+///
+/// Logs are written to the log file and the stderr.
+pub fn setup_logger(logfilepath: &std::path::Path, level: LogLevel) -> anyhow::Result<WorkerGuard> {
+    let logdir = logfilepath.parent().unwrap();
+    if !logdir.exists() {
+        std::fs::create_dir_all(logdir)?;
+    }
+
+    let logfile = logfilepath.file_name().unwrap();
+
+    let fa = tracing_appender::rolling::never(logdir, logfile);
+    let (non_blocking, guard) = tracing_appender::non_blocking(fa);
+
+    let filter: LevelFilter = level.to_string().parse()?;
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(tracing_subscriber::fmt::Layer::default().with_writer(non_blocking))
+        .with(tracing_subscriber::fmt::Layer::default().with_writer(std::io::stderr))
+        .try_init()?;
+
+    Ok(guard)
+}
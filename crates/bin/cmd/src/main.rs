@@ -2,93 +2,192 @@
 //! # cmd - dirback commands
 //!
 
-use dirback::infra::app_path;
+use clap::{Parser, Subcommand};
+use dirback::infra::config::{Config, ConfigFlags, LogLevel};
+use dirback_cmd::{CmdError, CmdParams, Command, ErrorOutput, Output};
+use std::path::PathBuf;
 
 mod commands;
+mod logs;
 
-fn usage() {
-    let s = r#"Usage: dirback <command> [args...]
+const AFTER_HELP: &str = r#"Targets:
+    TARGET_ID is the target ID, the target name, a unique prefix of the ID,
+    or a path in the target ("." for the target containing the current directory).
+
+Backups:
+    BACKUP is the backup ID, "latest", "latest~N" (the Nth before the latest),
+    "first", a date/time for the newest backup at or before it
+    (e.g. 2025-01-23, "2025-01-23 12:00", 2025-01-23T12:00:00Z),
+    or "note:TEXT" (or any other text) for the newest backup with the text in its note.
 
 Path to store backups:
-    if DIRBACK_STORE_DIR is set, use it's value.
-    if not set, use default directory.
+    --datadir if given, else DIRBACK_STORE_DIR if set,
+    else `datadir` in the config file, else the default directory.
 
     Linux: ~/.local/share/dirback
     Windows: TODO
 
-Commands:
-    help, -h
-        Print help.
-
-    list
-        Print target list.
-
-    register <TARGET_PATH>
-        Register new target.
-
-    show <TARGET_ID>
-        Show target information.
-
-    backup <TARGET_ID> [NOTE]
-        Take a backup of the target.
-
-    restore <TARGET_ID> <BACKUP_ID>
-        Restore from the specified backup.
-
-    delete <TARGET_ID> <BACKUP_ID>
-        Delete the backup.
-        This action cannnot be undone.
+Config file:
+    --config if given, else DIRBACK_CONFIG if set, else the default file.
+    `dirback config show` prints the effective config.
+
+    Linux: ~/.config/dirback/config.toml
+
+Exit codes:
+    0  Success
+    1  The command failed
+    2  The arguments are invalid
+    3  The target or the backup is not found
+    4  The confirmation was declined"#;
+
+/// Backup directories into tar.gz archives.
+#[derive(Parser)]
+#[command(name = "dirback", bin_name = "dirback", version, after_help = AFTER_HELP)]
+struct Cli {
+    /// The data directory to store the targets and backups.
+    #[arg(long, global = true, value_name = "PATH")]
+    datadir: Option<PathBuf>,
+
+    /// The config file to use instead of the default one.
+    #[arg(long, global = true, value_name = "PATH")]
+    config: Option<PathBuf>,
+
+    /// The log level of the daemon (error, warn, info, debug or trace).
+    #[arg(long, global = true, value_name = "LEVEL")]
+    log_level: Option<LogLevel>,
+
+    /// Print the result as JSON, and errors as {"code", "message"}.
+    #[arg(long, global = true)]
+    json: bool,
+
+    /// Print nothing but errors.
+    #[arg(short, long, global = true, conflicts_with = "verbose")]
+    quiet: bool,
+
+    /// Print the details, e.g. backup file paths and the causes of errors.
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    #[command(subcommand)]
+    command: Commands,
+}
 
-    delete-target <TARGET_ID>
-        Delete the target.
-        The target's backups will also be deleted.
-        This action cannnot be undone.
-"#;
+#[derive(Subcommand)]
+enum Commands {
+    List(commands::ListTargets),
+    Register(commands::RegisterTarget),
+    Show(commands::ShowTarget),
+    Find(commands::FindFile),
+    Cat(commands::CatFile),
+    #[cfg(target_os = "linux")]
+    Mount(commands::Mount),
+    Edit(commands::EditTarget),
+    Backup(commands::BackupTarget),
+    Prune(commands::Prune),
+    Tag(commands::TagTarget),
+    Restore(commands::RestoreTarget),
+    Delete(commands::DeleteBackup),
+    DeleteTarget(commands::DeleteTarget),
+    Trash(commands::Trash),
+    Pin(commands::PinBackup),
+    Unpin(commands::UnpinBackup),
+    Export(commands::ExportTarget),
+    Import(commands::ImportTarget),
+    Sqlite(commands::SqliteStorage),
+    Store(commands::ArchiveStore),
+    Replica(commands::Replica),
+    Schedule(commands::ScheduleTarget),
+    Daemon(commands::Daemon),
+    Watch(commands::WatchTarget),
+    Hook(commands::HookTarget),
+    Source(commands::SourceTarget),
+    Stats(commands::Stats),
+    Config(commands::AppConfig),
+    Completions(commands::Completions),
+    #[command(name = "__complete", hide = true)]
+    Complete(commands::Complete),
+}
 
-    println!("{s}");
+impl Commands {
+    fn command(&self) -> &dyn Command {
+        match self {
+            Self::List(cmd) => cmd,
+            Self::Register(cmd) => cmd,
+            Self::Show(cmd) => cmd,
+            Self::Find(cmd) => cmd,
+            Self::Cat(cmd) => cmd,
+            #[cfg(target_os = "linux")]
+            Self::Mount(cmd) => cmd,
+            Self::Edit(cmd) => cmd,
+            Self::Backup(cmd) => cmd,
+            Self::Prune(cmd) => cmd,
+            Self::Tag(cmd) => cmd,
+            Self::Restore(cmd) => cmd,
+            Self::Delete(cmd) => cmd,
+            Self::DeleteTarget(cmd) => cmd,
+            Self::Trash(cmd) => cmd,
+            Self::Pin(cmd) => cmd,
+            Self::Unpin(cmd) => cmd,
+            Self::Export(cmd) => cmd,
+            Self::Import(cmd) => cmd,
+            Self::Sqlite(cmd) => cmd,
+            Self::Store(cmd) => cmd,
+            Self::Replica(cmd) => cmd,
+            Self::Schedule(cmd) => cmd,
+            Self::Daemon(cmd) => cmd,
+            Self::Watch(cmd) => cmd,
+            Self::Hook(cmd) => cmd,
+            Self::Source(cmd) => cmd,
+            Self::Stats(cmd) => cmd,
+            Self::Config(cmd) => cmd,
+            Self::Completions(cmd) => cmd,
+            Self::Complete(cmd) => cmd,
+        }
+    }
 }
 
-fn get_basedir_path() -> anyhow::Result<std::path::PathBuf> {
-    // If DIRBACK_STORE_DIR is set, use it's value.
-    // If not set, use app_path::data_dir.
-    let basedir = std::env::var("DIRBACK_STORE_DIR")
-        .ok()
-        .map(std::path::PathBuf::from)
-        .or_else(app_path::data_dir)
-        .ok_or_else(|| anyhow::anyhow!("Failed to get path to directory for application data."))?;
+impl Cli {
+    fn output(&self) -> Output {
+        if self.json {
+            Output::Json
+        } else if self.quiet {
+            Output::Quiet
+        } else if self.verbose {
+            Output::Verbose
+        } else {
+            Output::Normal
+        }
+    }
+}
 
-    Ok(basedir)
+fn run(cli: &Cli) -> anyhow::Result<()> {
+    let flags = ConfigFlags {
+        config: cli.config.clone(),
+        datadir: cli.datadir.clone(),
+        log_level: cli.log_level,
+    };
+    let config = Config::load(&flags)?;
+
+    let params = CmdParams::new(&config.datadir.value)
+        .with_output(cli.output())
+        .with_config(config);
+    cli.command.command().execute(&params)
 }
 
 fn main() {
-    let args: Vec<String> = std::env::args().collect();
-    let basedir = get_basedir_path().unwrap_or_else(|e| {
-        eprintln!("Error: {e}");
-        std::process::exit(1);
-    });
-
-    let params = dirback_cmd::CmdParams::build(&args, &basedir).unwrap_or_else(|e| {
-        eprintln!("Error: {e}");
-        usage();
-        std::process::exit(1);
-    });
-
-    if params.command == "-h" || params.command == "help" {
-        usage();
-        std::process::exit(0);
-    }
-
-    let mut invoker = dirback_cmd::CommandInvoker::new();
-    invoker.register("list", Box::new(commands::ListTargets));
-    invoker.register("register", Box::new(commands::RegisterTarget));
-    invoker.register("show", Box::new(commands::ShowTarget));
-    invoker.register("backup", Box::new(commands::BackupTarget));
-    invoker.register("restore", Box::new(commands::RestoreTarget));
-    invoker.register("delete", Box::new(commands::DeleteBackup::new()));
-    invoker.register("delete-target", Box::new(commands::DeleteTarget::new()));
-
-    if let Err(e) = invoker.execute(&params) {
-        eprintln!("Error: {e}");
-        std::process::exit(1);
+    let cli = Cli::parse();
+
+    if let Err(e) = run(&cli) {
+        match cli.output() {
+            Output::Json => {
+                let output = serde_json::to_string_pretty(&ErrorOutput::new(&e))
+                    .unwrap_or_else(|_| e.to_string());
+                println!("{output}");
+            }
+            _ if matches!(e.downcast_ref(), Some(CmdError::Cancelled)) => println!("{e}"),
+            Output::Verbose => eprintln!("Error: {e:?}"),
+            _ => eprintln!("Error: {e}"),
+        }
+        std::process::exit(dirback_cmd::exit_code_of(&e));
     }
 }
@@ -0,0 +1,53 @@
+//!
+//! Runs the dirback binary, checking that the JSON output is kept machine-readable.
+//!
+
+#![cfg(unix)]
+
+use std::path::Path;
+use std::process::{Command, Output};
+
+/// Runs the binary with the data directory and an empty config file.
+fn dirback(temp: &Path, args: &[&str]) -> Output {
+    let config = temp.join("config.toml");
+    std::fs::write(&config, "").unwrap();
+
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_dirback_cmd"));
+    for (name, _) in std::env::vars().filter(|(name, _)| name.starts_with("DIRBACK_")) {
+        cmd.env_remove(name);
+    }
+    cmd.arg("--datadir")
+        .arg(temp.join("data"))
+        .arg("--config")
+        .arg(&config)
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+#[test]
+fn it_keeps_hook_stdout_out_of_json() {
+    let temp = mktemp::TempDir::new().unwrap();
+    let src = temp.path().join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(src.join("file.txt"), "hello").unwrap();
+
+    let output = dirback(&temp.path(), &["register", "src", src.to_str().unwrap()]);
+    assert!(output.status.success());
+    for hook in ["pre-backup", "post-backup"] {
+        let command = format!("echo {hook} hook");
+        let output = dirback(&temp.path(), &["hook", "set", "src", hook, &command]);
+        assert!(output.status.success());
+    }
+
+    let output = dirback(&temp.path(), &["--json", "backup", "src", "note"]);
+    assert!(output.status.success());
+    let entry: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(entry["note"], "note");
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("pre-backup hook"), "{stderr}");
+
+    let output = dirback(&temp.path(), &["--quiet", "backup", "src"]);
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
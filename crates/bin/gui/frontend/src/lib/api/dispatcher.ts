@@ -11,8 +11,28 @@ export type Command =
   | { type: "BackupTarget"; payload: { target_id: string; note: string } }
   | { type: "DeleteBackup"; payload: { target_id: string; backup_id: number } }
   | { type: "DeleteTarget"; payload: { target_id: string } }
+  | {
+      type: "EditBackupNote";
+      payload: { target_id: string; backup_id: number; note: string };
+    }
+  | {
+      type: "EditTarget";
+      payload: {
+        target_id: string;
+        name?: string;
+        path?: string;
+        description?: string;
+        force?: boolean;
+      };
+    }
+  | { type: "FindBackup"; payload: { target_id: string; selector: string } }
+  | { type: "GetStats"; payload: { target_id?: string } }
   | { type: "GetTarget"; payload: { target_id: string } }
-  | { type: "ListTargets"; payload: {} }
+  | { type: "ListTargets"; payload: { tag?: string } }
+  | {
+      type: "PinBackup";
+      payload: { target_id: string; backup_id: number; pinned: boolean };
+    }
   | { type: "RegisterTarget"; payload: { name: string; path: string } }
   | {
       type: "RestoreTarget";
@@ -0,0 +1,21 @@
+/**
+ *  API: Edit the note of a backup
+ */
+
+import { dispatch } from "./dispatcher";
+import type { Target } from "$lib/types/target";
+
+export async function editBackupNote(
+  target_id: string,
+  backup_id: number,
+  note: string,
+): Promise<Target> {
+  return await dispatch({
+    type: "EditBackupNote",
+    payload: {
+      target_id,
+      backup_id,
+      note,
+    },
+  });
+}
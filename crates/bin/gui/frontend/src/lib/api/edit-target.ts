@@ -0,0 +1,24 @@
+/**
+ *  API: Edit target
+ */
+
+import { dispatch } from "./dispatcher";
+import type { Target } from "$lib/types/target";
+
+export async function editTarget(
+  target_id: string,
+  changes: {
+    name?: string;
+    path?: string;
+    description?: string;
+    force?: boolean;
+  },
+): Promise<Target> {
+  return await dispatch({
+    type: "EditTarget",
+    payload: {
+      target_id,
+      ...changes,
+    },
+  });
+}
@@ -0,0 +1,22 @@
+/**
+ *  API: Find a backup by a selector
+ *
+ *  The selector is the backup ID, `latest`, `latest~N`, `first`,
+ *  a date/time (the newest backup at or before it) or a note text.
+ */
+
+import { dispatch } from "./dispatcher";
+import type { BackupEntry } from "$lib/types/backup-entry";
+
+export async function findBackup(
+  target_id: string,
+  selector: string,
+): Promise<BackupEntry> {
+  return await dispatch({
+    type: "FindBackup",
+    payload: {
+      target_id,
+      selector,
+    },
+  });
+}
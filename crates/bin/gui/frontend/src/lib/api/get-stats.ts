@@ -0,0 +1,13 @@
+/**
+ *  API: Get the disk usage stats of the targets, or of a target
+ */
+
+import { dispatch } from "./dispatcher";
+import type { StatsReport } from "$lib/types/stats";
+
+export async function getStats(target_id?: string): Promise<StatsReport> {
+  return await dispatch({
+    type: "GetStats",
+    payload: { target_id },
+  });
+}
@@ -5,9 +5,9 @@
 import { dispatch } from "./dispatcher";
 import type { Target } from "$lib/types/target";
 
-export async function listTargets(): Promise<Target[]> {
+export async function listTargets(tag?: string): Promise<Target[]> {
   return await dispatch({
     type: "ListTargets",
-    payload: {},
+    payload: { tag },
   });
 }
@@ -3,6 +3,7 @@
  */
 import type { Target } from "$lib/types/target";
 import type { BackupEntry } from "$lib/types/backup-entry";
+import type { BackupStats } from "$lib/types/backup-stats";
 
 const DIRBACK_BASE_PATH = `/tmp/dirback/.data/`;
 
@@ -29,6 +30,16 @@ function makeTimestamps(msec: number): [string, string] {
   return [`${y}${m}${d}T${hh}${mm}${ss}Z`, t.toISOString()];
 }
 
+function makeMockStats(): BackupStats {
+  const uncompressed_size = randInt(1, 500) * 1024 * 1024;
+  return {
+    size: Math.floor(uncompressed_size * (randInt(20, 90) / 100)),
+    uncompressed_size,
+    files: randInt(10, 5000),
+    duration_ms: randInt(100, 60000),
+  };
+}
+
 export function generateNewMockBackup(
   target: Target,
   note: string,
@@ -44,6 +55,13 @@ export function generateNewMockBackup(
     path: `${DIRBACK_BASE_PATH}/targets/${target.id}/backups/${idx}_${ts[0]}.tar.gz`,
     timestamp: ts[1],
     note: note,
+    store: target.store,
+    checksum: null,
+    replicas: [],
+    sources: [],
+    kind: target.kind,
+    pinned: false,
+    stats: makeMockStats(),
   };
 }
 
@@ -79,11 +97,39 @@ export function generateMockTargets(): Target[] {
         path: `${DIRBACK_BASE_PATH}/targets/${id}/backups/${bkIdx}_${ts[0]}.tar.gz`,
         timestamp: ts[1],
         note: `The ${name}'s backup ${bkId}.`,
+        store: null,
+        checksum: null,
+        replicas: [],
+        sources: [],
+        kind: "directory",
+        pinned: false,
+        stats: k === 0 ? null : makeMockStats(),
       });
     }
 
     // Add targets
-    targets.push({ id, name, path, backups });
+    targets.push({
+      id,
+      name,
+      path,
+      kind: "directory",
+      sources: [],
+      backups,
+      store: null,
+      replicas: [],
+      description: "",
+      tags: i % 2 === 0 ? ["clients"] : [],
+      schedule: null,
+      retention: { keep_last: null, keep_days: null },
+      hooks: {
+        pre_backup: null,
+        post_backup: null,
+        pre_restore: null,
+        post_restore: null,
+        on_failure: null,
+        timeout_secs: null,
+      },
+    });
   }
 
   return targets;
@@ -4,6 +4,7 @@
 import type { Command } from "./../dispatcher";
 import type { Target } from "$lib/types/target";
 import type { BackupEntry } from "$lib/types/backup-entry";
+import type { StatsReport, TargetStats } from "$lib/types/stats";
 import { generateMockTargets, generateNewMockBackup } from "./data";
 
 const mockTargets: Target[] = generateMockTargets();
@@ -11,7 +12,7 @@ const mockTargets: Target[] = generateMockTargets();
 export async function mockDispatch<T>(cmd: Command): Promise<T> {
   switch (cmd.type) {
     case "ListTargets":
-      return mockTargets as T;
+      return listTargets(cmd.payload.tag) as T;
 
     case "GetTarget":
       return getTarget(cmd.payload.target_id) as T;
@@ -28,6 +29,29 @@ export async function mockDispatch<T>(cmd: Command): Promise<T> {
     case "RegisterTarget":
       return registerTarget(cmd.payload.name, cmd.payload.path) as T;
 
+    case "EditTarget":
+      return editTarget(cmd.payload) as T;
+
+    case "EditBackupNote":
+      return editBackupNote(
+        cmd.payload.target_id,
+        cmd.payload.backup_id,
+        cmd.payload.note,
+      ) as T;
+
+    case "PinBackup":
+      return pinBackup(
+        cmd.payload.target_id,
+        cmd.payload.backup_id,
+        cmd.payload.pinned,
+      ) as T;
+
+    case "FindBackup":
+      return findBackup(cmd.payload.target_id, cmd.payload.selector) as T;
+
+    case "GetStats":
+      return getStats(cmd.payload.target_id) as T;
+
     case "RestoreTarget":
       restoreTarget(cmd.payload.target_id, cmd.payload.backup_id);
       return null as T;
@@ -45,6 +69,13 @@ function findMockTarget(target_id: string): Target | null {
   return target;
 }
 
+function listTargets(tag?: string): Target[] {
+  if (tag === undefined || tag === "all") {
+    return mockTargets;
+  }
+  return mockTargets.filter((t) => t.tags.includes(tag));
+}
+
 function getTarget(target_id: string): Target | null {
   return findMockTarget(target_id);
 }
@@ -72,6 +103,10 @@ function deleteBackup(target_id: string, backup_id: number): BackupEntry {
     throw new Error(`Backup not found: '${backup_id}'`);
   }
 
+  if (target.backups[idx].pinned) {
+    throw new Error(`Backup ${backup_id} is pinned. Unpin it before deleting.`);
+  }
+
   const deleted = target.backups.splice(idx, 1);
   return deleted[0];
 }
@@ -95,15 +130,200 @@ function registerTarget(name: string, path: string): Target {
     throw new Error(`Invalid path.`);
   }
 
+  const registered = mockTargets.find((t) => t.path === path);
+  if (registered) {
+    throw new Error(
+      `'${path}' is already registered as the target '${registered.name}' (${registered.id}).`,
+    );
+  }
+
   const backups: BackupEntry[] = [];
   const id = crypto.randomUUID();
 
-  const target: Target = { id, name, path, backups };
+  const target: Target = {
+    id,
+    name,
+    path,
+    kind: "directory",
+    sources: [],
+    backups,
+    store: null,
+    replicas: [],
+    description: "",
+    tags: [],
+    schedule: null,
+    retention: { keep_last: null, keep_days: null },
+    hooks: {
+      pre_backup: null,
+      post_backup: null,
+      pre_restore: null,
+      post_restore: null,
+      on_failure: null,
+      timeout_secs: null,
+    },
+  };
   mockTargets.push(target);
 
   return target;
 }
 
+function editTarget(payload: {
+  target_id: string;
+  name?: string;
+  path?: string;
+  description?: string;
+}): Target {
+  const target = findMockTarget(payload.target_id);
+  if (target === null) {
+    throw new Error(`Target not found: '${payload.target_id}'`);
+  }
+
+  if (payload.name !== undefined) {
+    if (payload.name === "") {
+      throw new Error(`Invalid name.`);
+    }
+    target.name = payload.name;
+  }
+  if (payload.path !== undefined) {
+    if (payload.path === "") {
+      throw new Error(`Invalid path.`);
+    }
+    target.path = payload.path;
+  }
+  if (payload.description !== undefined) {
+    target.description = payload.description;
+  }
+
+  return target;
+}
+
+function editBackupNote(
+  target_id: string,
+  backup_id: number,
+  note: string,
+): Target {
+  const target = findMockTarget(target_id);
+  if (target === null) {
+    throw new Error(`Target not found: '${target_id}'`);
+  }
+
+  const backup = target.backups.find((be) => be.id === backup_id);
+  if (backup === undefined) {
+    throw new Error(`Backup not found: '${backup_id}'`);
+  }
+  backup.note = note;
+
+  return target;
+}
+
+function pinBackup(
+  target_id: string,
+  backup_id: number,
+  pinned: boolean,
+): BackupEntry {
+  const target = findMockTarget(target_id);
+  if (target === null) {
+    throw new Error(`Target not found: '${target_id}'`);
+  }
+
+  const backup = target.backups.find((be) => be.id === backup_id);
+  if (backup === undefined) {
+    throw new Error(`Backup not found: '${backup_id}'`);
+  }
+  backup.pinned = pinned;
+
+  return backup;
+}
+
+// Supports the IDs, `latest`, `latest~N`, `first` and note substrings.
+function findBackup(target_id: string, selector: string): BackupEntry {
+  const target = findMockTarget(target_id);
+  if (target === null) {
+    throw new Error(`Target not found: '${target_id}'`);
+  }
+
+  const backups = target.backups;
+  const s = selector.trim();
+  let backup: BackupEntry | undefined;
+  if (/^\d+$/.test(s)) {
+    backup = backups.find((be) => be.id === Number(s));
+  } else if (s === "first") {
+    backup = backups[0];
+  } else if (/^latest(~\d+)?$/.test(s)) {
+    const n = Number(s.split("~")[1] ?? 0);
+    backup = backups[backups.length - 1 - n];
+  } else {
+    const text = s.replace(/^note:/, "").toLowerCase();
+    backup = [...backups]
+      .reverse()
+      .find((be) => be.note.toLowerCase().includes(text));
+  }
+
+  if (backup === undefined) {
+    throw new Error(`Backup not found ('${selector}').`);
+  }
+  return backup;
+}
+
+function getStats(target_id?: string): StatsReport {
+  let targets = mockTargets;
+  if (target_id !== undefined) {
+    const target = findMockTarget(target_id);
+    if (target === null) {
+      throw new Error(`Target not found ('${target_id}').`);
+    }
+    targets = [target];
+  }
+
+  const stats: TargetStats[] = targets.map((target) => {
+    let previous: number | null = null;
+    const backups = target.backups.map((be) => {
+      const s = be.stats;
+      const growth =
+        s === null || previous === null
+          ? null
+          : s.uncompressed_size - previous;
+      if (s !== null) {
+        previous = s.uncompressed_size;
+      }
+      return {
+        id: be.id,
+        timestamp: be.timestamp,
+        note: be.note,
+        size: s?.size ?? null,
+        uncompressed_size: s?.uncompressed_size ?? null,
+        files: s?.files ?? null,
+        duration_ms: s?.duration_ms ?? null,
+        ratio: s && s.uncompressed_size > 0 ? s.size / s.uncompressed_size : null,
+        growth,
+      };
+    });
+    const size = backups.reduce((sum, b) => sum + (b.size ?? 0), 0);
+    const uncompressed_size = backups.reduce(
+      (sum, b) => sum + (b.uncompressed_size ?? 0),
+      0,
+    );
+    return {
+      id: target.id,
+      name: target.name,
+      size,
+      uncompressed_size,
+      ratio: uncompressed_size > 0 ? size / uncompressed_size : null,
+      unknown: backups.filter((b) => b.size === null).length,
+      backups,
+    };
+  });
+
+  const size = stats.reduce((sum, t) => sum + t.size, 0);
+  return {
+    targets: stats,
+    backup_count: stats.reduce((sum, t) => sum + t.backups.length, 0),
+    size,
+    uncompressed_size: stats.reduce((sum, t) => sum + t.uncompressed_size, 0),
+    datadir_size: size + 64 * 1024,
+  };
+}
+
 function restoreTarget(target_id: string, backup_id: number) {
   const target = findMockTarget(target_id);
   if (target === null) {
@@ -0,0 +1,21 @@
+/**
+ *  API: Pin or unpin a backup
+ */
+
+import { dispatch } from "./dispatcher";
+import type { BackupEntry } from "$lib/types/backup-entry";
+
+export async function pinBackup(
+  target_id: string,
+  backup_id: number,
+  pinned: boolean,
+): Promise<BackupEntry> {
+  return await dispatch({
+    type: "PinBackup",
+    payload: {
+      target_id,
+      backup_id,
+      pinned,
+    },
+  });
+}
@@ -4,6 +4,10 @@
  * Rust: crates/lib/dirback/src/usecase/dto/backup_entry.rs
  */
 
+import type { BackupStats } from "./backup-stats";
+import type { ReplicaStatus } from "./replica-status";
+import type { Source } from "./source";
+import type { TargetKind } from "./target-kind";
 import type { Timestamp } from "./timestamp";
 
 export interface BackupEntry {
@@ -11,4 +15,15 @@ export interface BackupEntry {
   path: string;
   timestamp: Timestamp;
   note: string;
+  store: string | null;
+  checksum: string | null;
+  replicas: ReplicaStatus[];
+  /** The archived directories, empty if only the target path is archived. */
+  sources: Source[];
+  /** The kind of the archived target path. */
+  kind: TargetKind;
+  /** Pinned backups are never pruned nor deleted. */
+  pinned: boolean;
+  /** The sizes of the backup, `null` for the backups made before they were recorded. */
+  stats: BackupStats | null;
 }
@@ -0,0 +1,15 @@
+/**
+ * BackupStats Type
+ *
+ * Rust: crates/lib/dirback/src/domain/model/backup_stats.rs
+ */
+
+export interface BackupStats {
+  /** The size of the archive file in bytes. */
+  size: number;
+  /** The total size of the archived files in bytes. */
+  uncompressed_size: number;
+  /** The number of the archived files, without directories. */
+  files: number;
+  duration_ms: number;
+}
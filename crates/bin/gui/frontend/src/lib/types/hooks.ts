@@ -0,0 +1,15 @@
+/**
+ * Hooks Type
+ *
+ * Rust: crates/lib/dirback/src/domain/model/hooks.rs
+ */
+
+export interface Hooks {
+  pre_backup: string | null;
+  post_backup: string | null;
+  pre_restore: string | null;
+  post_restore: string | null;
+  on_failure: string | null;
+  /** The timeout of each command in seconds, null for the default (300). */
+  timeout_secs: number | null;
+}
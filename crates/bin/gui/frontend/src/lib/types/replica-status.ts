@@ -0,0 +1,17 @@
+/**
+ * ReplicaStatus Type
+ *
+ * Rust: crates/lib/dirback/src/usecase/dto/replica_status.rs
+ */
+
+import type { Timestamp } from "./timestamp";
+
+export type ReplicaState = "synced" | "failed";
+
+export interface ReplicaStatus {
+  replica: string;
+  path: string;
+  state: ReplicaState;
+  timestamp: Timestamp;
+  message: string;
+}
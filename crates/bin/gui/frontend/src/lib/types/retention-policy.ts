@@ -0,0 +1,10 @@
+/**
+ * RetentionPolicy Type
+ *
+ * Rust: crates/lib/dirback/src/domain/model/retention_policy.rs
+ */
+
+export interface RetentionPolicy {
+  keep_last: number | null;
+  keep_days: number | null;
+}
@@ -0,0 +1,11 @@
+/**
+ * Source Type
+ *
+ * Rust: crates/lib/dirback/src/domain/model/source.rs
+ */
+
+export interface Source {
+  /** The prefix of the directory in the archives. */
+  name: string;
+  path: string;
+}
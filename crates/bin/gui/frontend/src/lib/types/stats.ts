@@ -0,0 +1,42 @@
+/**
+ * Stats Types
+ *
+ * Rust: crates/lib/dirback/src/usecase/dto/stats.rs
+ */
+
+import type { Timestamp } from "./timestamp";
+
+/** The sizes of a backup, `null` if unknown (e.g. made before they were recorded). */
+export interface BackupUsage {
+  id: number;
+  timestamp: Timestamp;
+  note: string;
+  size: number | null;
+  uncompressed_size: number | null;
+  files: number | null;
+  duration_ms: number | null;
+  /** The archive size divided by the uncompressed size. */
+  ratio: number | null;
+  /** The change of the uncompressed size from the previous backup. */
+  growth: number | null;
+}
+
+export interface TargetStats {
+  id: string;
+  name: string;
+  size: number;
+  uncompressed_size: number;
+  ratio: number | null;
+  /** The number of the backups whose archive size is unknown. */
+  unknown: number;
+  backups: BackupUsage[];
+}
+
+export interface StatsReport {
+  targets: TargetStats[];
+  backup_count: number;
+  size: number;
+  uncompressed_size: number;
+  /** The disk usage of the whole data directory. */
+  datadir_size: number | null;
+}
@@ -0,0 +1,7 @@
+/**
+ * TargetKind Type
+ *
+ * Rust: crates/lib/dirback/src/domain/model/target_kind.rs
+ */
+
+export type TargetKind = "directory" | "file";
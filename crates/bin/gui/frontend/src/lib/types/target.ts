@@ -5,10 +5,25 @@
  */
 
 import type { BackupEntry } from "./backup-entry";
+import type { Hooks } from "./hooks";
+import type { RetentionPolicy } from "./retention-policy";
+import type { Source } from "./source";
+import type { TargetKind } from "./target-kind";
 
 export interface Target {
   id: string;
   name: string;
   path: string;
+  kind: TargetKind;
+  /** Additional directories archived with the path. */
+  sources: Source[];
   backups: BackupEntry[];
+  store: string | null;
+  replicas: string[];
+  description: string;
+  tags: string[];
+  /** An interval ("every 6h") or a cron expression, null for manual backups only. */
+  schedule: string | null;
+  retention: RetentionPolicy;
+  hooks: Hooks;
 }
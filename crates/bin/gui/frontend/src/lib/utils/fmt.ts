@@ -21,3 +21,27 @@ export function fmtDateTime(timestamp: string): string {
 
   return `${year}/${mon}/${day} ${h}:${m}:${s}`;
 }
+
+/** Formats the bytes with a binary unit, e.g. `1.5 MiB`. */
+export function fmtSize(bytes: number | null): string {
+  if (bytes === null) {
+    return "-";
+  }
+  if (bytes < 1024) {
+    return `${bytes} B`;
+  }
+
+  const units = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+  let size = bytes / 1024;
+  let unit = 0;
+  while (size >= 1024 && unit < units.length - 1) {
+    size /= 1024;
+    unit += 1;
+  }
+  return `${size.toFixed(1)} ${units[unit]}`;
+}
+
+/** Formats the ratio as a percentage, e.g. `45.7%`. */
+export function fmtRatio(ratio: number | null): string {
+  return ratio === null ? "-" : `${(ratio * 100).toFixed(1)}%`;
+}
@@ -6,8 +6,13 @@ pub mod backup_target;
 pub mod command;
 pub mod delete_backup;
 pub mod delete_target;
+pub mod edit_backup_note;
+pub mod edit_target;
+pub mod find_backup;
+pub mod get_stats;
 pub mod get_target;
 pub mod list_targets;
+pub mod pin_backup;
 pub mod register_target;
 pub mod restore_target;
 
@@ -16,8 +21,13 @@ pub use command::Command;
 pub use command::NoPayload;
 pub use delete_backup::DeleteBackup;
 pub use delete_target::DeleteTarget;
+pub use edit_backup_note::EditBackupNote;
+pub use edit_target::EditTarget;
+pub use find_backup::FindBackup;
+pub use get_stats::GetStats;
 pub use get_target::GetTarget;
 pub use list_targets::ListTargets;
+pub use pin_backup::PinBackup;
 pub use register_target::RegisterTarget;
 pub use restore_target::RestoreTarget;
 
@@ -30,10 +40,15 @@ use serde::Deserialize;
 #[serde(tag = "type", content = "payload")]
 pub enum CommandType {
     GetTarget(get_target::GetTargetPayload),
-    ListTargets(NoPayload),
+    ListTargets(list_targets::ListTargetsPayload),
     RegisterTarget(register_target::RegisterTargetPayload),
     DeleteTarget(delete_target::DeleteTargetPayload),
     BackupTarget(backup_target::BackupTargetPayload),
     DeleteBackup(delete_backup::DeleteBackupPayload),
     RestoreTarget(restore_target::RestoreTargetPayload),
+    EditTarget(edit_target::EditTargetPayload),
+    EditBackupNote(edit_backup_note::EditBackupNotePayload),
+    PinBackup(pin_backup::PinBackupPayload),
+    FindBackup(find_backup::FindBackupPayload),
+    GetStats(get_stats::GetStatsPayload),
 }
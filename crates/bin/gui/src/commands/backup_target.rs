@@ -4,10 +4,12 @@
 use crate::commands::Command;
 
 use dirback::adapter::GetTargetAdapter;
+use dirback::infra::config::DEFAULT_COMPRESSION;
 use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::shell_hook_service::ShellHookService;
 use dirback::infra::service::targz_backup_service::TargzBackupService;
 use dirback::usecase::backup::BackupUsecase;
-use dirback::usecase::dto::Target;
+use dirback::usecase::dto::{FilePattern, Target};
 use serde::Deserialize;
 
 #[derive(Debug, Deserialize)]
@@ -16,7 +18,22 @@ pub struct BackupTargetPayload {
     pub note: String,
 }
 
-pub struct BackupTarget;
+pub struct BackupTarget {
+    /// Compression level of the new backup.
+    pub compression: u32,
+
+    /// The paths left out of the new backup.
+    pub excludes: Vec<FilePattern>,
+}
+
+impl Default for BackupTarget {
+    fn default() -> Self {
+        Self {
+            compression: DEFAULT_COMPRESSION,
+            excludes: Vec::new(),
+        }
+    }
+}
 
 impl Command for BackupTarget {
     type Payload = BackupTargetPayload;
@@ -28,8 +45,11 @@ impl Command for BackupTarget {
         payload: Self::Payload,
     ) -> anyhow::Result<Self::Output> {
         let mut repo = FileStorageTargetRepository::new(datadir);
-        let service = TargzBackupService::new();
-        let mut usecase = BackupUsecase::new(&mut repo, &service);
+        let service = TargzBackupService::new()
+            .with_compression(self.compression)
+            .with_excludes(&self.excludes);
+        let hook_service = ShellHookService::new();
+        let mut usecase = BackupUsecase::new(&mut repo, &service).with_hooks(&hook_service);
         usecase.execute(&payload.target_id, &payload.note)?;
 
         let adapter = GetTargetAdapter::new(&repo);
@@ -53,7 +73,7 @@ mod tests {
         let target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
 
         // Command
-        let cmd = BackupTarget;
+        let cmd = BackupTarget::default();
         let payload = BackupTargetPayload {
             target_id: target.id.clone(),
             note: String::from("Test backup!"),
@@ -81,7 +101,7 @@ mod tests {
         let basedir = temp.path();
 
         // Command
-        let cmd = BackupTarget;
+        let cmd = BackupTarget::default();
         let payload = BackupTargetPayload {
             target_id: String::from("xxxxx-xxxxx-xxxxx"),
             note: String::from("Test backup!"),
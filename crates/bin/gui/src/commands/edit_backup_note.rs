@@ -0,0 +1,89 @@
+//!
+//! # EditBackupNote command
+//!
+
+use crate::commands::Command;
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::targz_backup_service::TargzBackupService;
+use dirback::usecase::dto::Target;
+use dirback::usecase::edit_target::{EditTargetUsecase, TargetEdit};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct EditBackupNotePayload {
+    pub target_id: String,
+    pub backup_id: u32,
+    pub note: String,
+}
+
+pub struct EditBackupNote;
+
+impl Command for EditBackupNote {
+    type Payload = EditBackupNotePayload;
+    type Output = Target;
+
+    fn execute(
+        &self,
+        datadir: &std::path::Path,
+        payload: Self::Payload,
+    ) -> anyhow::Result<Self::Output> {
+        let edit = TargetEdit {
+            notes: vec![(payload.backup_id, payload.note)],
+            ..Default::default()
+        };
+
+        let mut repo = FileStorageTargetRepository::new(datadir);
+        let service = TargzBackupService::new();
+        let mut usecase = EditTargetUsecase::new(&mut repo, &service);
+        usecase.execute(&payload.target_id, &edit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+
+    #[test]
+    fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let mut target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+        let bkdir = repo.make_backup_dir_path(&target);
+        let entry = target.new_backup_entry(&bkdir, "tar.gz");
+        let _ = target.register_backup_entry(entry);
+        let target = repo.update(&target).unwrap();
+
+        let cmd = EditBackupNote;
+        let payload = EditBackupNotePayload {
+            target_id: target.id.clone(),
+            backup_id: 1,
+            note: String::from("edited"),
+        };
+
+        let got = cmd.execute(&basedir, payload).unwrap();
+        assert_eq!(got.backups[0].note, "edited");
+    }
+
+    #[test]
+    fn it_returns_err_if_backup_not_found() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+
+        let cmd = EditBackupNote;
+        let payload = EditBackupNotePayload {
+            target_id: target.id.clone(),
+            backup_id: 1,
+            note: String::from("edited"),
+        };
+
+        let result = cmd.execute(&basedir, payload);
+        assert!(result.is_err());
+    }
+}
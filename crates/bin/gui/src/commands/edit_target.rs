@@ -0,0 +1,94 @@
+//!
+//! # EditTarget command
+//!
+
+use crate::commands::Command;
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::targz_backup_service::TargzBackupService;
+use dirback::usecase::dto::Target;
+use dirback::usecase::edit_target::{EditTargetUsecase, TargetEdit};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct EditTargetPayload {
+    pub target_id: String,
+    pub name: Option<String>,
+    pub path: Option<std::path::PathBuf>,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub force: bool,
+}
+
+pub struct EditTarget;
+
+impl Command for EditTarget {
+    type Payload = EditTargetPayload;
+    type Output = Target;
+
+    fn execute(
+        &self,
+        datadir: &std::path::Path,
+        payload: Self::Payload,
+    ) -> anyhow::Result<Self::Output> {
+        let edit = TargetEdit {
+            name: payload.name,
+            path: payload.path,
+            description: payload.description,
+            force: payload.force,
+            ..Default::default()
+        };
+
+        let mut repo = FileStorageTargetRepository::new(datadir);
+        let service = TargzBackupService::new();
+        let mut usecase = EditTargetUsecase::new(&mut repo, &service);
+        usecase.execute(&payload.target_id, &edit)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+
+    #[test]
+    fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+
+        let cmd = EditTarget;
+        let payload = EditTargetPayload {
+            target_id: target.id.clone(),
+            name: Some(String::from("Renamed")),
+            path: None,
+            description: Some(String::from("memo")),
+            force: false,
+        };
+
+        let got = cmd.execute(&basedir, payload).unwrap();
+        assert_eq!(got.name, "Renamed");
+        assert_eq!(got.description, "memo");
+        assert_eq!(got.path, target.path);
+    }
+
+    #[test]
+    fn it_returns_err_if_target_not_found() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let cmd = EditTarget;
+        let payload = EditTargetPayload {
+            target_id: String::from("xxxxx-xxxxx-xxxxx"),
+            name: Some(String::from("Renamed")),
+            path: None,
+            description: None,
+            force: false,
+        };
+
+        let result = cmd.execute(&basedir, payload);
+        assert!(result.is_err());
+    }
+}
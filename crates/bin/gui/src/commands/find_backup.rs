@@ -0,0 +1,85 @@
+//!
+//! # FindBackup command
+//!
+//! Finds a backup by a selector, e.g. `latest~1` or `2025-01-23`.
+//!
+
+use crate::commands::Command;
+
+use dirback::adapter::SelectBackupAdapter;
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::usecase::dto::{BackupEntry, BackupSelector};
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct FindBackupPayload {
+    pub target_id: String,
+    pub selector: String,
+}
+
+pub struct FindBackup;
+
+impl Command for FindBackup {
+    type Payload = FindBackupPayload;
+    type Output = BackupEntry;
+
+    fn execute(
+        &self,
+        datadir: &std::path::Path,
+        payload: Self::Payload,
+    ) -> anyhow::Result<Self::Output> {
+        let repo = FileStorageTargetRepository::new(datadir);
+        let selector = BackupSelector::parse(&payload.selector)?;
+        SelectBackupAdapter::new(&repo).execute(&payload.target_id, &selector)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+
+    fn prepare_test_data(basedir: &std::path::Path) -> String {
+        let mut repo = FileStorageTargetRepository::new(basedir);
+        let mut target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+        let bkdir = repo.make_backup_dir_path(&target);
+        for _ in 1..=3 {
+            let entry = target.new_backup_entry(&bkdir, "tar.gz");
+            let _ = target.register_backup_entry(entry);
+        }
+        repo.update(&target).unwrap().id
+    }
+
+    #[test]
+    fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let target_id = prepare_test_data(&basedir);
+
+        let cmd = FindBackup;
+        for (selector, id) in [("latest", 3), ("latest~2", 1), ("first", 1), ("2", 2)] {
+            let payload = FindBackupPayload {
+                target_id: target_id.clone(),
+                selector: selector.to_string(),
+            };
+            let got = cmd.execute(&basedir, payload).unwrap();
+            assert_eq!(got.id, id, "{selector}");
+        }
+    }
+
+    #[test]
+    fn it_returns_err_if_invalid_or_not_found() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+        let target_id = prepare_test_data(&basedir);
+
+        let cmd = FindBackup;
+        for selector in ["", "latest~x", "latest~3", "no such note"] {
+            let payload = FindBackupPayload {
+                target_id: target_id.clone(),
+                selector: selector.to_string(),
+            };
+            assert!(cmd.execute(&basedir, payload).is_err(), "{selector}");
+        }
+    }
+}
@@ -0,0 +1,77 @@
+//!
+//! # GetStats command
+//!
+//! Returns the disk usage of the targets and the data directory.
+//!
+
+use crate::commands::Command;
+
+use dirback::adapter::StatsAdapter;
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::usecase::dto::StatsReport;
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct GetStatsPayload {
+    /// Only the target, or every target if `None`.
+    #[serde(default)]
+    pub target_id: Option<String>,
+}
+
+pub struct GetStats;
+
+impl Command for GetStats {
+    type Payload = GetStatsPayload;
+    type Output = StatsReport;
+
+    fn execute(
+        &self,
+        datadir: &std::path::Path,
+        payload: Self::Payload,
+    ) -> anyhow::Result<Self::Output> {
+        let repo = FileStorageTargetRepository::new(datadir);
+        StatsAdapter::new(&repo)
+            .with_datadir(datadir)
+            .execute(payload.target_id.as_deref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+
+    #[test]
+    fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let mut target = repo.add("TestTarget1", std::path::Path::new(".")).unwrap();
+        let bkdir = repo.make_backup_dir_path(&target);
+        let entry = target.new_backup_entry(&bkdir, "tar.gz");
+        std::fs::write(&entry.path, "0123456789").unwrap();
+        target.register_backup_entry(entry).unwrap();
+        repo.update(&target).unwrap();
+        let _ = repo.add("TestTarget2", std::path::Path::new(".")).unwrap();
+
+        let cmd = GetStats;
+        let got = cmd.execute(&basedir, GetStatsPayload::default()).unwrap();
+        assert_eq!(got.targets.len(), 2);
+        assert_eq!(got.backup_count, 1);
+        assert_eq!(got.size, 10);
+        assert!(got.datadir_size.unwrap() >= 10);
+
+        let payload = GetStatsPayload {
+            target_id: Some(target.id.clone()),
+        };
+        let got = cmd.execute(&basedir, payload).unwrap();
+        assert_eq!(got.targets.len(), 1);
+        assert_eq!(got.targets[0].backups[0].size, Some(10));
+
+        let payload = GetStatsPayload {
+            target_id: Some(String::from("unknown")),
+        };
+        assert!(cmd.execute(&basedir, payload).is_err());
+    }
+}
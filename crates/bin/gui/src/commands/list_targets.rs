@@ -2,26 +2,37 @@
 //! # ListTargets command
 //!
 
-use crate::commands::{Command, NoPayload};
+use crate::commands::Command;
 
 use dirback::adapter::ListTargetsAdapter;
 use dirback::infra::repository::file_storage::FileStorageTargetRepository;
 use dirback::usecase::dto::Target;
+use serde::Deserialize;
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ListTargetsPayload {
+    /// Only the targets with the tag. `all` or `None` lists every target.
+    #[serde(default)]
+    pub tag: Option<String>,
+}
 
 pub struct ListTargets;
 
 impl Command for ListTargets {
-    type Payload = NoPayload;
+    type Payload = ListTargetsPayload;
     type Output = Vec<Target>;
 
     fn execute(
         &self,
         datadir: &std::path::Path,
-        _payload: Self::Payload,
+        payload: Self::Payload,
     ) -> anyhow::Result<Self::Output> {
         let repo = FileStorageTargetRepository::new(datadir);
         let adapter = ListTargetsAdapter::new(&repo);
-        let targets = adapter.execute()?;
+        let targets = match &payload.tag {
+            Some(tag) => adapter.execute_by_tag(tag)?,
+            None => adapter.execute()?,
+        };
         Ok(targets)
     }
 }
@@ -45,10 +56,31 @@ mod tests {
         // Command
         let cmd = ListTargets;
 
-        let result = cmd.execute(&basedir, NoPayload);
+        let result = cmd.execute(&basedir, ListTargetsPayload::default());
         assert!(result.is_ok());
 
         let got = result.unwrap();
         assert_eq!(got.len(), 2);
     }
+
+    #[test]
+    fn it_filters_by_tag() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        // Test target
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let mut target = repo.add("TestTarget1", std::path::Path::new(".")).unwrap();
+        target.add_tag("clients").unwrap();
+        repo.update(&target).unwrap();
+        let _ = repo.add("TestTarget2", std::path::Path::new(".")).unwrap();
+
+        // Command
+        let cmd = ListTargets;
+        let payload: ListTargetsPayload = serde_json::from_str(r#"{ "tag": "clients" }"#).unwrap();
+
+        let got = cmd.execute(&basedir, payload).unwrap();
+        assert_eq!(got.len(), 1);
+        assert_eq!(got[0].id, target.id);
+    }
 }
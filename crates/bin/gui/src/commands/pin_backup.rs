@@ -0,0 +1,85 @@
+//!
+//! # PinBackup command
+//!
+
+use crate::commands::Command;
+
+use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::usecase::dto::BackupEntry;
+use dirback::usecase::pin_backup::PinBackupUsecase;
+use serde::Deserialize;
+
+#[derive(Debug, Deserialize)]
+pub struct PinBackupPayload {
+    pub target_id: String,
+    pub backup_id: u32,
+    pub pinned: bool,
+}
+
+pub struct PinBackup;
+
+impl Command for PinBackup {
+    type Payload = PinBackupPayload;
+    type Output = BackupEntry;
+
+    fn execute(
+        &self,
+        datadir: &std::path::Path,
+        payload: Self::Payload,
+    ) -> anyhow::Result<Self::Output> {
+        let mut repo = FileStorageTargetRepository::new(datadir);
+        let mut usecase = PinBackupUsecase::new(&mut repo);
+        usecase.execute(&payload.target_id, payload.backup_id, payload.pinned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use dirback::internal::TargetRepository;
+
+    #[test]
+    fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let mut target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+        let bkdir = repo.make_backup_dir_path(&target);
+        let entry = target.new_backup_entry(&bkdir, "tar.gz");
+        let _ = target.register_backup_entry(entry);
+        let target = repo.update(&target).unwrap();
+
+        let cmd = PinBackup;
+        let payload = PinBackupPayload {
+            target_id: target.id.clone(),
+            backup_id: 1,
+            pinned: true,
+        };
+
+        let got = cmd.execute(&basedir, payload).unwrap();
+        assert!(got.pinned);
+
+        let target = repo.load(&target.id).unwrap();
+        assert!(target.backups[0].pinned);
+    }
+
+    #[test]
+    fn it_returns_err_if_backup_not_found() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let mut repo = FileStorageTargetRepository::new(&basedir);
+        let target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+
+        let cmd = PinBackup;
+        let payload = PinBackupPayload {
+            target_id: target.id.clone(),
+            backup_id: 1,
+            pinned: true,
+        };
+
+        let result = cmd.execute(&basedir, payload);
+        assert!(result.is_err());
+    }
+}
@@ -26,17 +26,6 @@ impl Command for RegisterTarget {
         datadir: &std::path::Path,
         payload: Self::Payload,
     ) -> anyhow::Result<Self::Output> {
-        if payload.name.is_empty() {
-            anyhow::bail!("Error: name is empty.");
-        }
-
-        if !payload.path.exists() {
-            anyhow::bail!(
-                "Error: target path is invalid: '{}'",
-                payload.path.display()
-            );
-        }
-
         let mut repo = FileStorageTargetRepository::new(datadir);
         let mut usecase = RegisterTargetUsecase::new(&mut repo);
 
@@ -66,7 +55,37 @@ mod tests {
 
         let got = result.unwrap();
         assert_eq!(got.name, "Test Target");
-        assert_eq!(got.path, std::path::PathBuf::from("."));
+        assert_eq!(got.path, std::fs::canonicalize(".").unwrap());
+    }
+
+    #[test]
+    fn it_returns_err_when_already_registered() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let cmd = RegisterTarget;
+        for (name, expected) in [("Test Target", true), ("Test Target 2", false)] {
+            let payload = RegisterTargetPayload {
+                name: String::from(name),
+                path: std::path::PathBuf::from("."),
+            };
+            assert_eq!(cmd.execute(&basedir, payload).is_ok(), expected);
+        }
+    }
+
+    #[test]
+    fn it_returns_err_when_data_dir() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let basedir = temp.path();
+
+        let cmd = RegisterTarget;
+        let payload = RegisterTargetPayload {
+            name: String::from("Test Target"),
+            path: basedir.clone(),
+        };
+
+        let result = cmd.execute(&basedir, payload);
+        assert!(result.is_err());
     }
 
     #[test]
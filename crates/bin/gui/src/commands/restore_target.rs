@@ -5,6 +5,7 @@
 use crate::commands::Command;
 
 use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::shell_hook_service::ShellHookService;
 use dirback::infra::service::targz_backup_service::TargzBackupService;
 use dirback::usecase::restore::RestoreUsecase;
 use serde::Deserialize;
@@ -28,8 +29,9 @@ impl Command for RestoreTarget {
     ) -> anyhow::Result<Self::Output> {
         let mut repo = FileStorageTargetRepository::new(datadir);
         let service = TargzBackupService::new();
+        let hook_service = ShellHookService::new();
 
-        let mut usecase = RestoreUsecase::new(&mut repo, &service);
+        let mut usecase = RestoreUsecase::new(&mut repo, &service).with_hooks(&hook_service);
         usecase.execute(&payload.target_id, payload.backup_id)?;
 
         Ok(())
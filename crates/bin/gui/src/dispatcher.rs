@@ -5,28 +5,53 @@
 use crate::commands::BackupTarget;
 use crate::commands::DeleteBackup;
 use crate::commands::DeleteTarget;
+use crate::commands::EditBackupNote;
+use crate::commands::EditTarget;
+use crate::commands::FindBackup;
+use crate::commands::GetStats;
 use crate::commands::GetTarget;
 use crate::commands::ListTargets;
+use crate::commands::PinBackup;
 use crate::commands::RegisterTarget;
 use crate::commands::RestoreTarget;
-use crate::commands::{Command, CommandType, NoPayload};
+use crate::commands::{Command, CommandType};
+use dirback::infra::config::DEFAULT_COMPRESSION;
+use dirback::usecase::dto::FilePattern;
 
 pub struct Dispatcher {
     pub datadir: std::path::PathBuf,
+
+    /// Compression level of new backups.
+    pub compression: u32,
+
+    /// The paths left out of new backups.
+    pub excludes: Vec<FilePattern>,
 }
 
 impl Dispatcher {
     pub fn new(datadir: &std::path::Path) -> Self {
         Self {
             datadir: datadir.to_path_buf(),
+            compression: DEFAULT_COMPRESSION,
+            excludes: Vec::new(),
         }
     }
 
+    pub fn with_compression(mut self, level: u32) -> Self {
+        self.compression = level;
+        self
+    }
+
+    pub fn with_excludes(mut self, excludes: &[FilePattern]) -> Self {
+        self.excludes = excludes.to_vec();
+        self
+    }
+
     pub fn dispatch(&self, cmd: CommandType) -> anyhow::Result<serde_json::Value> {
         match cmd {
-            CommandType::ListTargets(_) => {
+            CommandType::ListTargets(payload) => {
                 let cmd = ListTargets;
-                let result = cmd.execute(&self.datadir, NoPayload)?;
+                let result = cmd.execute(&self.datadir, payload)?;
                 Ok(serde_json::json!(result))
             }
 
@@ -49,7 +74,10 @@ impl Dispatcher {
             }
 
             CommandType::BackupTarget(payload) => {
-                let cmd = BackupTarget;
+                let cmd = BackupTarget {
+                    compression: self.compression,
+                    excludes: self.excludes.clone(),
+                };
                 let result = cmd.execute(&self.datadir, payload)?;
                 Ok(serde_json::json!(result))
             }
@@ -65,6 +93,36 @@ impl Dispatcher {
                 cmd.execute(&self.datadir, payload)?;
                 Ok(serde_json::json!(()))
             }
+
+            CommandType::EditTarget(payload) => {
+                let cmd = EditTarget;
+                let result = cmd.execute(&self.datadir, payload)?;
+                Ok(serde_json::json!(result))
+            }
+
+            CommandType::EditBackupNote(payload) => {
+                let cmd = EditBackupNote;
+                let result = cmd.execute(&self.datadir, payload)?;
+                Ok(serde_json::json!(result))
+            }
+
+            CommandType::PinBackup(payload) => {
+                let cmd = PinBackup;
+                let result = cmd.execute(&self.datadir, payload)?;
+                Ok(serde_json::json!(result))
+            }
+
+            CommandType::FindBackup(payload) => {
+                let cmd = FindBackup;
+                let result = cmd.execute(&self.datadir, payload)?;
+                Ok(serde_json::json!(result))
+            }
+
+            CommandType::GetStats(payload) => {
+                let cmd = GetStats;
+                let result = cmd.execute(&self.datadir, payload)?;
+                Ok(serde_json::json!(result))
+            }
         }
     }
 }
@@ -8,6 +8,8 @@ mod dispatcher;
 use crate::commands::CommandType;
 use crate::dispatcher::Dispatcher;
 
+use dirback::infra::config::Config;
+use dirback::usecase::dto::FilePattern;
 use tauri::Manager;
 
 /// # AppState
@@ -16,6 +18,8 @@ use tauri::Manager;
 #[derive(Default)]
 struct AppState {
     pub datadir: std::path::PathBuf,
+    pub compression: u32,
+    pub excludes: Vec<FilePattern>,
 }
 
 #[tauri::command]
@@ -23,7 +27,9 @@ async fn command_dispatcher(
     state: tauri::State<'_, AppState>,
     cmd: CommandType,
 ) -> Result<serde_json::Value, String> {
-    let dispatcher = Dispatcher::new(&state.datadir);
+    let dispatcher = Dispatcher::new(&state.datadir)
+        .with_compression(state.compression)
+        .with_excludes(&state.excludes);
 
     match dispatcher.dispatch(cmd) {
         Ok(result) => Ok(result),
@@ -31,12 +37,18 @@ async fn command_dispatcher(
     }
 }
 
-pub fn run(datadir: &std::path::Path) -> anyhow::Result<()> {
-    let datadir = datadir.to_path_buf();
+pub fn run(config: &Config) -> anyhow::Result<()> {
+    let datadir = config.datadir.value.clone();
+    let compression = config.compression.value;
+    let excludes = config.excludes.value.clone();
 
     tauri::Builder::default()
-        .setup(|app| {
-            app.manage(AppState { datadir });
+        .setup(move |app| {
+            app.manage(AppState {
+                datadir,
+                compression,
+                excludes,
+            });
             Ok(())
         })
         .plugin(tauri_plugin_dialog::init())
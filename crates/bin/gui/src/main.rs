@@ -19,16 +19,17 @@ fn main() {
         }
     };
 
-    let _guard = logs::setup_logger(&opts.logfile).unwrap_or_else(|e| {
-        eprintln!("Error: {e}");
-        std::process::exit(1);
-    });
+    let _guard =
+        logs::setup_logger(&opts.logfile, opts.config.log_level.value).unwrap_or_else(|e| {
+            eprintln!("Error: {e}");
+            std::process::exit(1);
+        });
 
     info!("Start Dirback GUI");
-    info!("Dirback base dir: {}", opts.datadir.display());
+    info!("Dirback base dir: {}", opts.config.datadir.value.display());
     info!("Log file: {}", opts.logfile.display());
 
-    if let Err(ref e) = dirback_gui_lib::run(&opts.datadir) {
+    if let Err(ref e) = dirback_gui_lib::run(&opts.config) {
         eprintln!("Error: {e}");
         std::process::exit(1);
     }
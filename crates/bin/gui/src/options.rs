@@ -1,37 +1,24 @@
 //!
-//! # CLI arguments options
+//! # Options
+//!
+//! The GUI has no command line flags, it uses the config file and the environment variables.
 //!
 
-use dirback::infra::app_path;
+use dirback::infra::config::{Config, ConfigFlags};
 
 pub struct Options {
-    /// Sets a custom dirback data directory.
-    pub datadir: std::path::PathBuf,
+    /// The effective config.
+    pub config: Config,
 
-    /// Sets a custom log file path.
-    /// The default path is `{datadir}/dirback.log`.
+    /// The log file path, `{datadir}/dirback.log`.
     pub logfile: std::path::PathBuf,
 }
 
 impl Options {
     pub fn build() -> anyhow::Result<Self> {
-        let basedir = get_basedir_path()?;
+        let config = Config::load(&ConfigFlags::default())?;
+        let logfile = config.datadir.value.join("dirback.log");
 
-        Ok(Self {
-            datadir: basedir.clone(),
-            logfile: basedir.join("dirback.log"),
-        })
+        Ok(Self { config, logfile })
     }
 }
-
-fn get_basedir_path() -> anyhow::Result<std::path::PathBuf> {
-    // If DIRBACK_STORE_DIR is set, use it's value.
-    // If not set, use app_path::data_dir.
-    let basedir = std::env::var("DIRBACK_STORE_DIR")
-        .ok()
-        .map(std::path::PathBuf::from)
-        .or_else(app_path::data_dir)
-        .ok_or_else(|| anyhow::anyhow!("Failed to get path to directory for application data."))?;
-
-    Ok(basedir)
-}
@@ -2,13 +2,17 @@
 //! # Dirback TUI Application
 //!
 
-use dirback::adapter::ListTargetsAdapter;
+use dirback::adapter::{ListTargetsAdapter, SelectBackupAdapter, StatsAdapter};
+use dirback::infra::config::DEFAULT_COMPRESSION;
 use dirback::infra::repository::file_storage::FileStorageTargetRepository;
+use dirback::infra::service::shell_hook_service::ShellHookService;
 use dirback::infra::service::targz_backup_service::TargzBackupService;
 use dirback::usecase::backup::BackupUsecase;
 use dirback::usecase::delete_backup::DeleteBackupUsecase;
 use dirback::usecase::delete_target::DeleteTargetUsecase;
-use dirback::usecase::dto::Target;
+use dirback::usecase::dto::{BackupSelector, FilePattern, StatsReport, Target};
+use dirback::usecase::edit_target::{EditTargetUsecase, TargetEdit};
+use dirback::usecase::pin_backup::PinBackupUsecase;
 use dirback::usecase::register_target::RegisterTargetUsecase;
 use dirback::usecase::restore::RestoreUsecase;
 
@@ -16,6 +20,7 @@ use dirback::usecase::restore::RestoreUsecase;
 pub enum Panel {
     TargetList,
     TargetInfo,
+    Stats,
 }
 
 #[derive(Debug, PartialEq)]
@@ -25,6 +30,10 @@ pub enum Popup {
     TakeBackup,
     DeleteBackup,
     Restore,
+    EditTarget,
+    EditNote,
+    FilterTag,
+    FindBackup,
 }
 
 #[derive(Debug, PartialEq)]
@@ -38,8 +47,18 @@ pub enum Status {
 /// Manages the application state and handles each events.
 pub struct App {
     repo: FileStorageTargetRepository,
+    basedir: std::path::PathBuf,
+
+    /// Compression level of new backups.
+    compression: u32,
+
+    /// The paths left out of new backups.
+    excludes: Vec<FilePattern>,
+
     pub targets: Vec<Target>,
     pub current_target: Option<Target>,
+    pub tag_filter: Option<String>,
+    pub stats: Option<StatsReport>,
 
     // UI Info
     pub current_panel: Panel,
@@ -61,10 +80,15 @@ impl App {
     pub fn new(basedir: &std::path::Path) -> Self {
         Self {
             repo: FileStorageTargetRepository::new(basedir),
+            basedir: basedir.to_path_buf(),
+            compression: DEFAULT_COMPRESSION,
+            excludes: Vec::new(),
 
             // Targets
             targets: Vec::new(),
             current_target: None,
+            tag_filter: None,
+            stats: None,
 
             // UI
             current_panel: Panel::TargetList,
@@ -82,12 +106,26 @@ impl App {
         }
     }
 
+    pub fn with_compression(mut self, level: u32) -> Self {
+        self.compression = level;
+        self
+    }
+
+    pub fn with_excludes(mut self, excludes: &[FilePattern]) -> Self {
+        self.excludes = excludes.to_vec();
+        self
+    }
+
     //-------------------------------------------------------------------------
     // Dirback
     //-------------------------------------------------------------------------
     pub fn fetch_targets(&mut self) {
         let list_targets = ListTargetsAdapter::new(&self.repo);
-        match list_targets.execute() {
+        let result = match &self.tag_filter {
+            Some(tag) => list_targets.execute_by_tag(tag),
+            None => list_targets.execute(),
+        };
+        match result {
             Ok(targets) => {
                 self.targets = targets.clone();
                 self.set_status(Status::Info, &format!("{} targets loaded.", targets.len()));
@@ -99,13 +137,8 @@ impl App {
     }
 
     pub fn register_target(&mut self, name: &str, path: &std::path::Path) -> anyhow::Result<()> {
-        if !path.exists() {
-            anyhow::bail!("Target path is invalid: '{}'", path.to_string_lossy());
-        }
-
-        let path = std::fs::canonicalize(path)?;
         let mut usecase = RegisterTargetUsecase::new(&mut self.repo);
-        let _ = usecase.execute(name, &path)?;
+        let _ = usecase.execute(name, path)?;
 
         self.fetch_targets();
         self.set_status(Status::Info, &format!("New target '{}' registered!", name));
@@ -139,8 +172,11 @@ impl App {
         let target = self.current_target.as_ref().unwrap().clone();
         let note = note.to_string();
 
-        let service = TargzBackupService::new();
-        let mut usecase = BackupUsecase::new(&mut self.repo, &service);
+        let service = TargzBackupService::new()
+            .with_compression(self.compression)
+            .with_excludes(&self.excludes);
+        let hook_service = ShellHookService::quiet();
+        let mut usecase = BackupUsecase::new(&mut self.repo, &service).with_hooks(&hook_service);
         usecase.execute(&target.id, &note)?;
 
         // Update current-target
@@ -186,6 +222,40 @@ impl App {
         Ok(())
     }
 
+    pub fn toggle_pin_current_backup(&mut self) -> anyhow::Result<()> {
+        if self.current_target.is_none() {
+            anyhow::bail!("Target is none.");
+        }
+
+        let target = self.current_target.as_ref().unwrap().clone();
+        let entry = target.backups.get(self.cursor_backup);
+        if entry.is_none() {
+            anyhow::bail!("Backup is none.");
+        }
+        let entry = entry.unwrap();
+
+        let mut usecase = PinBackupUsecase::new(&mut self.repo);
+        let pinned_entry = usecase.execute(&target.id, entry.id, !entry.pinned)?;
+
+        // Update current target
+        self.fetch_targets();
+        if let Some(target) = self.targets.iter().find(|t| t.id == target.id) {
+            self.current_target = Some(target.clone());
+        }
+
+        let action = if pinned_entry.pinned {
+            "pinned"
+        } else {
+            "unpinned"
+        };
+        self.set_status(
+            Status::Info,
+            &format!("Backup[{:0>3}] has been {action}.", pinned_entry.id),
+        );
+
+        Ok(())
+    }
+
     pub fn restore_target_with_current_backup(&mut self) -> anyhow::Result<()> {
         if self.current_target.is_none() {
             anyhow::bail!("Target is none.");
@@ -200,7 +270,8 @@ impl App {
 
         // Restore
         let service = TargzBackupService::new();
-        let mut usecase = RestoreUsecase::new(&mut self.repo, &service);
+        let hook_service = ShellHookService::quiet();
+        let mut usecase = RestoreUsecase::new(&mut self.repo, &service).with_hooks(&hook_service);
 
         usecase.execute(&target.id, entry.id)?;
 
@@ -214,6 +285,109 @@ impl App {
         Ok(())
     }
 
+    pub fn edit_current_target(
+        &mut self,
+        name: &str,
+        path: &std::path::Path,
+        description: &str,
+    ) -> anyhow::Result<()> {
+        if self.current_target.is_none() {
+            anyhow::bail!("Target is none.");
+        }
+
+        let target = self.current_target.as_ref().unwrap().clone();
+        if !path.exists() {
+            anyhow::bail!("Target path is invalid: '{}'", path.to_string_lossy());
+        }
+
+        let edit = TargetEdit {
+            name: Some(name.to_string()),
+            path: Some(path.to_path_buf()),
+            description: Some(description.to_string()),
+            ..Default::default()
+        };
+        let service = TargzBackupService::new();
+        let mut usecase = EditTargetUsecase::new(&mut self.repo, &service);
+        let edited = usecase.execute(&target.id, &edit)?;
+
+        // Update current target
+        self.fetch_targets();
+        self.current_target = Some(edited.clone());
+        self.set_status(
+            Status::Info,
+            &format!("Target '{}' has been updated.", edited.name),
+        );
+
+        Ok(())
+    }
+
+    pub fn edit_current_backup_note(&mut self, note: &str) -> anyhow::Result<()> {
+        if self.current_target.is_none() {
+            anyhow::bail!("Target is none.");
+        }
+
+        let target = self.current_target.as_ref().unwrap().clone();
+        let entry = target.backups.get(self.cursor_backup);
+        if entry.is_none() {
+            anyhow::bail!("Backup is none.");
+        }
+        let entry = entry.unwrap();
+
+        let edit = TargetEdit {
+            notes: vec![(entry.id, note.to_string())],
+            ..Default::default()
+        };
+        let service = TargzBackupService::new();
+        let mut usecase = EditTargetUsecase::new(&mut self.repo, &service);
+        let edited = usecase.execute(&target.id, &edit)?;
+
+        // Update current target
+        self.fetch_targets();
+        self.current_target = Some(edited);
+        self.set_status(
+            Status::Info,
+            &format!("Note of Backup[{:0>3}] has been updated.", entry.id),
+        );
+
+        Ok(())
+    }
+
+    /// Moves the backup cursor to the backup selected by the selector, e.g. `latest~1`.
+    pub fn find_backup(&mut self, selector: &str) -> anyhow::Result<()> {
+        let Some(target) = self.current_target.as_ref() else {
+            anyhow::bail!("Target is none.");
+        };
+
+        let selector = BackupSelector::parse(selector)?;
+        let entry = SelectBackupAdapter::new(&self.repo).execute(&target.id, &selector)?;
+        if let Some(index) = target.backups.iter().position(|b| b.id == entry.id) {
+            self.cursor_backup = index;
+        }
+        self.set_status(
+            Status::Info,
+            &format!("Backup[{:0>3}] is selected.", entry.id),
+        );
+
+        Ok(())
+    }
+
+    /// Loads the disk usage of every target and the data directory.
+    pub fn fetch_stats(&mut self) -> anyhow::Result<()> {
+        let report = StatsAdapter::new(&self.repo)
+            .with_datadir(&self.basedir)
+            .execute(None)?;
+        self.stats = Some(report);
+        Ok(())
+    }
+
+    /// Shows only the targets with the tag. An empty tag shows every target.
+    pub fn set_tag_filter(&mut self, tag: &str) {
+        let tag = tag.trim();
+        self.tag_filter = (!tag.is_empty()).then(|| tag.to_string());
+        self.cursor_target = 0;
+        self.fetch_targets();
+    }
+
     //-------------------------------------------------------------------------
     // Panel
     //-------------------------------------------------------------------------
@@ -231,6 +405,7 @@ impl App {
                 self.current_panel = Panel::TargetList;
                 self.current_target = None;
                 self.cursor_backup = 0;
+                self.stats = None;
             }
             Panel::TargetInfo => {
                 if self.targets.is_empty() {
@@ -246,6 +421,14 @@ impl App {
                     None => return false,
                 }
             }
+            Panel::Stats => {
+                if let Err(e) = self.fetch_stats() {
+                    self.set_status(Status::Error, &format!("Failed to load stats: {e}"));
+                    return false;
+                }
+                self.current_target = None;
+                self.current_panel = Panel::Stats;
+            }
         }
 
         true
@@ -297,9 +480,44 @@ impl App {
             }
         }
 
+        // Edit popups start with the current values.
+        match popup {
+            Popup::EditTarget => {
+                let Some(target) = self.current_target.as_ref() else {
+                    return false;
+                };
+                self.popup_input_buf = vec![
+                    target.name.clone(),
+                    target.path.display().to_string(),
+                    target.description.clone(),
+                ];
+            }
+            Popup::EditNote => {
+                let Some(entry) = self
+                    .current_target
+                    .as_ref()
+                    .and_then(|t| t.backups.get(self.cursor_backup))
+                else {
+                    return false;
+                };
+                self.popup_input_buf = vec![entry.note.clone()];
+            }
+            Popup::FilterTag => {
+                self.popup_input_buf = vec![self.tag_filter.clone().unwrap_or_default()];
+            }
+            Popup::FindBackup => {
+                if self.current_target.is_none() {
+                    return false;
+                }
+                self.popup_input_buf = vec![String::new()];
+            }
+            _ => {
+                self.popup_input_buf.push(String::new());
+                self.popup_input_buf.push(String::new());
+            }
+        }
+
         self.current_popup = Some(popup);
-        self.popup_input_buf.push(String::new());
-        self.popup_input_buf.push(String::new());
         true
     }
 }
@@ -384,13 +602,29 @@ mod tests {
             assert_eq!(app.targets.len(), 3);
 
             let name = "RegisterTest";
-            let path = std::path::Path::new(".");
+            // Outside the data directory of the app.
+            let target = mktemp::TempDir::new().unwrap();
+            let path = target.path();
 
-            let result = app.register_target(name, path);
+            let result = app.register_target(name, &path);
             assert!(result.is_ok());
             assert_eq!(app.targets.len(), 4);
         }
 
+        #[test]
+        fn it_fails_when_already_registered() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut app = make_app(&temp);
+
+            let _ = add_test_targets(&mut app);
+            app.fetch_targets();
+
+            let result = app.register_target("RegisterTest", std::path::Path::new("."));
+            let err = result.unwrap_err();
+            assert!(err.to_string().contains("already registered"), "{err}");
+            assert_eq!(app.targets.len(), 3);
+        }
+
         #[test]
         fn it_fails_when_invalid_path() {
             let temp = mktemp::TempDir::new().unwrap();
@@ -472,6 +706,30 @@ mod tests {
         }
     }
 
+    mod pin_backup {
+        use super::*;
+
+        #[test]
+        fn it_toggles_pin_and_protects_backup() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut app = make_app(&temp);
+
+            let ids = add_test_targets(&mut app);
+            app.fetch_targets();
+            let target = add_test_backups(&mut app, &ids[1]);
+            app.current_target = Some(target.clone());
+            app.cursor_backup = 1;
+
+            assert!(app.toggle_pin_current_backup().is_ok());
+            assert!(app.current_target.as_ref().unwrap().backups[1].pinned);
+            assert!(app.delete_current_backup().is_err());
+
+            assert!(app.toggle_pin_current_backup().is_ok());
+            assert!(!app.current_target.as_ref().unwrap().backups[1].pinned);
+            assert!(app.delete_current_backup().is_ok());
+        }
+    }
+
     mod restore {
         use super::*;
 
@@ -532,6 +790,117 @@ mod tests {
         }
     }
 
+    mod edit_current_target {
+        use super::*;
+
+        #[test]
+        fn it_works() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut app = make_app(&temp);
+
+            // The test targets share a path, which cannot be given to another target.
+            let src = mktemp::TempDir::new().unwrap();
+            let path = src.path();
+            let id = app.repo.add("TestTarget", &path).unwrap().id;
+            app.fetch_targets();
+            app.current_target = app.targets.iter().find(|t| t.id == id).cloned();
+
+            let result = app.edit_current_target("Renamed", &path, "memo");
+            assert!(result.is_ok());
+
+            let target = app.current_target.as_ref().unwrap();
+            assert_eq!(target.name, "Renamed");
+            assert_eq!(target.description, "memo");
+        }
+
+        #[test]
+        fn it_edits_backup_note() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut app = make_app(&temp);
+
+            let ids = add_test_targets(&mut app);
+            app.fetch_targets();
+            let target = add_test_backups(&mut app, &ids[1]);
+            app.current_target = Some(target);
+            app.cursor_backup = 1;
+
+            let result = app.edit_current_backup_note("edited");
+            assert!(result.is_ok());
+            assert_eq!(
+                app.current_target.as_ref().unwrap().backups[1].note,
+                "edited"
+            );
+        }
+
+        #[test]
+        fn it_fails_when_current_target_not_set() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut app = make_app(&temp);
+
+            let result = app.edit_current_target("Renamed", std::path::Path::new("."), "");
+            assert!(result.is_err());
+            assert!(app.edit_current_backup_note("edited").is_err());
+        }
+    }
+
+    mod tag_filter {
+        use super::*;
+
+        #[test]
+        fn it_works() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut app = make_app(&temp);
+
+            let ids = add_test_targets(&mut app);
+            let mut target = app.repo.load(&ids[1]).unwrap();
+            target.add_tag("games").unwrap();
+            app.repo.update(&target).unwrap();
+
+            app.cursor_target = 2;
+            app.set_tag_filter("games");
+            assert_eq!(app.targets.len(), 1);
+            assert_eq!(app.targets[0].id, ids[1]);
+            assert_eq!(app.cursor_target, 0);
+
+            app.set_tag_filter(" ");
+            assert_eq!(app.tag_filter, None);
+            assert_eq!(app.targets.len(), 3);
+        }
+    }
+
+    mod find_backup {
+        use super::*;
+
+        #[test]
+        fn it_works() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut app = make_app(&temp);
+
+            let ids = add_test_targets(&mut app);
+            let target = add_test_backups(&mut app, &ids[0]);
+            app.fetch_targets();
+            app.current_target = app.targets.iter().find(|t| t.id == target.id).cloned();
+
+            app.find_backup("latest").unwrap();
+            assert_eq!(app.cursor_backup, 2);
+            app.find_backup("first").unwrap();
+            assert_eq!(app.cursor_backup, 0);
+            app.find_backup("latest~1").unwrap();
+            assert_eq!(app.cursor_backup, 1);
+
+            assert!(app.find_backup("latest~3").is_err());
+            assert!(app.find_backup("latest~x").is_err());
+            assert_eq!(app.cursor_backup, 1);
+        }
+
+        #[test]
+        fn it_returns_err_if_target_is_none() {
+            let mut app = make_dummy_app();
+            assert!(app.find_backup("latest").is_err());
+            assert!(!app.show_popup(Popup::FindBackup));
+        }
+    }
+
     mod quit {
         use super::*;
 
@@ -567,7 +936,16 @@ mod tests {
                 id: String::from("xxx-xxx-xxx"),
                 name: String::from("Test Target"),
                 path: std::path::PathBuf::from("."),
+                kind: Default::default(),
+                sources: Vec::new(),
                 backups: Vec::new(),
+                store: None,
+                replicas: Vec::new(),
+                description: String::new(),
+                tags: Vec::new(),
+                schedule: None,
+                retention: Default::default(),
+                hooks: Default::default(),
             });
             app.cursor_target = 10;
             app.cursor_backup = 10;
@@ -580,6 +958,25 @@ mod tests {
             );
             assert_eq!(app.cursor_backup, 0, "cursor_backup should be reset");
         }
+
+        #[test]
+        fn to_stats_panel() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut app = make_app(&temp);
+            let ids = add_test_targets(&mut app);
+            add_test_backups(&mut app, &ids[0]);
+            app.fetch_targets();
+
+            assert!(app.switch_panel(Panel::Stats));
+            assert_eq!(app.current_panel, Panel::Stats);
+            let stats = app.stats.as_ref().unwrap();
+            assert_eq!(stats.targets.len(), 3);
+            assert_eq!(stats.backup_count, 3);
+            assert!(stats.datadir_size.is_some());
+
+            app.switch_panel(Panel::TargetList);
+            assert!(app.stats.is_none());
+        }
     }
 
     mod status {
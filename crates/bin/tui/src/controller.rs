@@ -14,12 +14,17 @@ pub fn handle_key_events(app: &mut app::App, key: KeyEvent) {
             Some(app::Popup::TakeBackup) => in_take_backup_popup(app, key),
             Some(app::Popup::DeleteBackup) => in_delete_backup_popup(app, key),
             Some(app::Popup::Restore) => in_restore_popup(app, key),
+            Some(app::Popup::EditTarget) => in_edit_target_popup(app, key),
+            Some(app::Popup::EditNote) => in_edit_note_popup(app, key),
+            Some(app::Popup::FilterTag) => in_filter_tag_popup(app, key),
+            Some(app::Popup::FindBackup) => in_find_backup_popup(app, key),
             _ => {}
         }
     } else {
         match app.current_panel {
             app::Panel::TargetList => in_target_list_panel(app, key),
             app::Panel::TargetInfo => in_target_info_panel(app, key),
+            app::Panel::Stats => in_stats_panel(app, key),
         }
     }
 }
@@ -44,6 +49,12 @@ fn in_target_list_panel(app: &mut app::App, key: KeyEvent) {
         KeyCode::Char('d') => {
             app.show_popup(app::Popup::DeleteTarget);
         }
+        KeyCode::Char('t') => {
+            app.show_popup(app::Popup::FilterTag);
+        }
+        KeyCode::Char('s') => {
+            app.switch_panel(app::Panel::Stats);
+        }
         KeyCode::Esc | KeyCode::Char('q') => {
             app.quit();
         }
@@ -51,6 +62,20 @@ fn in_target_list_panel(app: &mut app::App, key: KeyEvent) {
     }
 }
 
+fn in_stats_panel(app: &mut app::App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Char('r') => {
+            if let Err(e) = app.fetch_stats() {
+                app.set_status(app::Status::Error, &format!("Failed to load stats: {e}"));
+            }
+        }
+        KeyCode::Esc | KeyCode::Backspace | KeyCode::Char('q') => {
+            app.switch_panel(app::Panel::TargetList);
+        }
+        _ => {}
+    }
+}
+
 fn in_target_info_panel(app: &mut app::App, key: KeyEvent) {
     match key.code {
         KeyCode::Up | KeyCode::Char('k') => {
@@ -65,6 +90,20 @@ fn in_target_info_panel(app: &mut app::App, key: KeyEvent) {
         KeyCode::Char('d') => {
             app.show_popup(app::Popup::DeleteBackup);
         }
+        KeyCode::Char('e') => {
+            app.show_popup(app::Popup::EditTarget);
+        }
+        KeyCode::Char('m') => {
+            app.show_popup(app::Popup::EditNote);
+        }
+        KeyCode::Char('/') => {
+            app.show_popup(app::Popup::FindBackup);
+        }
+        KeyCode::Char('p') => {
+            if let Err(e) = app.toggle_pin_current_backup() {
+                app.set_status(app::Status::Error, &e.to_string());
+            }
+        }
         KeyCode::Enter => {
             app.show_popup(app::Popup::Restore);
         }
@@ -116,17 +155,8 @@ fn in_register_target_popup(app: &mut app::App, key: KeyEvent) {
                 return;
             }
 
-            // Check path.
+            // Submit, the path is checked by the usecase.
             let path = std::path::PathBuf::from(path);
-            if !path.exists() {
-                app.popup_errors
-                    .push(String::from("Target path is invalid!"));
-            }
-            if !app.popup_errors.is_empty() {
-                return;
-            }
-
-            // Submit
             match app.register_target(&name, &path) {
                 Ok(()) => app.hide_popup(),
                 Err(e) => app.popup_errors.push(e.to_string()),
@@ -240,3 +270,143 @@ fn in_restore_popup(app: &mut app::App, key: KeyEvent) {
         _ => {}
     }
 }
+
+fn in_edit_target_popup(app: &mut app::App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => {
+            app.hide_popup();
+        }
+        KeyCode::Tab => {
+            app.popup_edit_index = (app.popup_edit_index + 1) % app.popup_input_buf.len();
+        }
+        KeyCode::Char(ch) => {
+            if let Some(buf) = app.popup_input_buf.get_mut(app.popup_edit_index) {
+                buf.push(ch);
+            }
+        }
+        KeyCode::Backspace => {
+            if let Some(buf) = app.popup_input_buf.get_mut(app.popup_edit_index) {
+                buf.pop();
+            }
+        }
+        KeyCode::Enter => {
+            app.popup_errors.clear();
+            let name = app
+                .popup_input_buf
+                .first()
+                .unwrap_or(&String::new())
+                .clone();
+            let path = app.popup_input_buf.get(1).unwrap_or(&String::new()).clone();
+            let description = app.popup_input_buf.get(2).unwrap_or(&String::new()).clone();
+
+            // Check input.
+            if name.is_empty() {
+                app.popup_errors.push(String::from("Name is empty!"));
+            }
+            if path.is_empty() {
+                app.popup_errors.push(String::from("Target path is empty!"));
+            }
+            if !app.popup_errors.is_empty() {
+                return;
+            }
+
+            // Submit
+            let path = std::path::PathBuf::from(path);
+            match app.edit_current_target(&name, &path, &description) {
+                Ok(()) => app.hide_popup(),
+                Err(e) => app.popup_errors.push(e.to_string()),
+            }
+        }
+        _ => {}
+    }
+}
+
+fn in_edit_note_popup(app: &mut app::App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => {
+            app.hide_popup();
+        }
+        KeyCode::Char(ch) => {
+            if let Some(buf) = app.popup_input_buf.get_mut(0) {
+                buf.push(ch);
+            }
+        }
+        KeyCode::Backspace => {
+            if let Some(buf) = app.popup_input_buf.get_mut(0) {
+                buf.pop();
+            }
+        }
+        KeyCode::Enter => {
+            app.popup_errors.clear();
+            let note = app
+                .popup_input_buf
+                .first()
+                .unwrap_or(&String::new())
+                .clone();
+            match app.edit_current_backup_note(&note) {
+                Ok(()) => app.hide_popup(),
+                Err(e) => app.popup_errors.push(e.to_string()),
+            }
+        }
+        _ => {}
+    }
+}
+
+fn in_filter_tag_popup(app: &mut app::App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => {
+            app.hide_popup();
+        }
+        KeyCode::Char(ch) => {
+            if let Some(buf) = app.popup_input_buf.get_mut(0) {
+                buf.push(ch);
+            }
+        }
+        KeyCode::Backspace => {
+            if let Some(buf) = app.popup_input_buf.get_mut(0) {
+                buf.pop();
+            }
+        }
+        KeyCode::Enter => {
+            let tag = app
+                .popup_input_buf
+                .first()
+                .unwrap_or(&String::new())
+                .clone();
+            app.set_tag_filter(&tag);
+            app.hide_popup();
+        }
+        _ => {}
+    }
+}
+
+fn in_find_backup_popup(app: &mut app::App, key: KeyEvent) {
+    match key.code {
+        KeyCode::Esc => {
+            app.hide_popup();
+        }
+        KeyCode::Char(ch) => {
+            if let Some(buf) = app.popup_input_buf.get_mut(0) {
+                buf.push(ch);
+            }
+        }
+        KeyCode::Backspace => {
+            if let Some(buf) = app.popup_input_buf.get_mut(0) {
+                buf.pop();
+            }
+        }
+        KeyCode::Enter => {
+            app.popup_errors.clear();
+            let selector = app
+                .popup_input_buf
+                .first()
+                .unwrap_or(&String::new())
+                .clone();
+            match app.find_backup(&selector) {
+                Ok(()) => app.hide_popup(),
+                Err(e) => app.popup_errors.push(e.to_string()),
+            }
+        }
+        _ => {}
+    }
+}
@@ -2,18 +2,21 @@
 //! # DirbackTUI lib
 //!
 
+use dirback::infra::config::Config;
 use ratatui::crossterm::event::{self, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 
 mod app;
 mod controller;
 mod view;
 
-pub fn run(basedir: &std::path::Path) -> anyhow::Result<()> {
+pub fn run(config: &Config) -> anyhow::Result<()> {
     // Setup terminal.
     let mut terminal = ratatui::init();
 
     // Application loop.
-    let mut app = app::App::new(basedir);
+    let mut app = app::App::new(&config.datadir.value)
+        .with_compression(config.compression.value)
+        .with_excludes(&config.excludes.value);
     app.fetch_targets();
 
     let mut view = view::View::default();
@@ -2,12 +2,14 @@
 //! # logs
 //!
 
+use dirback::infra::config::LogLevel;
 use tracing_appender::non_blocking::WorkerGuard;
+use tracing_subscriber::filter::LevelFilter;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 /// setup logger.
 ///
-pub fn setup_logger(logfilepath: &std::path::Path) -> anyhow::Result<WorkerGuard> {
+pub fn setup_logger(logfilepath: &std::path::Path, level: LogLevel) -> anyhow::Result<WorkerGuard> {
     let logdir = logfilepath.parent().unwrap();
     if !logdir.exists() {
         std::fs::create_dir_all(logdir)?;
@@ -18,7 +20,9 @@ pub fn setup_logger(logfilepath: &std::path::Path) -> anyhow::Result<WorkerGuard
     let fa = tracing_appender::rolling::never(logdir, logfile);
     let (non_blocking, guard) = tracing_appender::non_blocking(fa);
 
+    let filter: LevelFilter = level.to_string().parse()?;
     tracing_subscriber::registry()
+        .with(filter)
         .with(tracing_subscriber::fmt::Layer::default().with_writer(non_blocking))
         .init();
 
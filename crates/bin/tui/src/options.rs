@@ -3,6 +3,7 @@
 //!
 
 use clap::Parser;
+use dirback::infra::config::{ConfigFlags, LogLevel};
 
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
@@ -15,6 +16,24 @@ pub struct Options {
     /// The default path is `{datadir}/dirback.log`.
     #[arg(short, long, value_name = "FILE")]
     pub logfile: Option<std::path::PathBuf>,
+
+    /// Sets a custom config file.
+    #[arg(short, long, value_name = "FILE")]
+    pub config: Option<std::path::PathBuf>,
+
+    /// Sets the log level (error, warn, info, debug or trace).
+    #[arg(long, value_name = "LEVEL")]
+    pub log_level: Option<LogLevel>,
+}
+
+impl Options {
+    pub fn config_flags(&self) -> ConfigFlags {
+        ConfigFlags {
+            config: self.config.clone(),
+            datadir: self.datadir.clone(),
+            log_level: self.log_level,
+        }
+    }
 }
 
 pub fn parse_args() -> Options {
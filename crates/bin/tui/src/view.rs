@@ -3,6 +3,7 @@
 //!
 
 use crate::app;
+use dirback::adapter::format_size;
 use dirback::usecase::dto::Target;
 
 use ratatui::{
@@ -31,7 +32,7 @@ impl View {
                 Constraint::Length(3),
                 Constraint::Min(10),
                 Constraint::Length(status_bar_len),
-                Constraint::Length(9),
+                Constraint::Length(13),
             ])
             .split(frame.area());
 
@@ -49,6 +50,9 @@ impl View {
             app::Panel::TargetInfo => {
                 render_target_info_panel(frame, self, app, chunks[1]);
             }
+            app::Panel::Stats => {
+                render_stats_panel(frame, app, chunks[1]);
+            }
         }
 
         // State.
@@ -68,6 +72,10 @@ impl View {
             Some(app::Popup::TakeBackup) => render_take_backup_popup(frame, app),
             Some(app::Popup::DeleteBackup) => render_delete_backup_popup(frame, app),
             Some(app::Popup::Restore) => render_restore_popup(frame, app),
+            Some(app::Popup::EditTarget) => render_edit_target_popup(frame, app),
+            Some(app::Popup::EditNote) => render_edit_note_popup(frame, app),
+            Some(app::Popup::FilterTag) => render_filter_tag_popup(frame, app),
+            Some(app::Popup::FindBackup) => render_find_backup_popup(frame, app),
             None => {}
         }
     }
@@ -78,9 +86,16 @@ impl View {
 //-----------------------------------------------------------------------------
 fn make_target_list_panel<'a>(ui: &'a mut View, app: &'a app::App, chunk: Rect) -> List<'a> {
     let mut title = String::from(" Targets ");
+    if let Some(tag) = &app.tag_filter {
+        title.push_str(&format!("(tag: {tag}) "));
+    }
     let mut list_items = Vec::<ListItem>::new();
 
-    if app.targets.is_empty() {
+    if let (true, Some(tag)) = (app.targets.is_empty(), &app.tag_filter) {
+        list_items.push(ListItem::new(Line::from(vec![Span::raw(format!(
+            "No targets with the tag '{tag}'."
+        ))])));
+    } else if app.targets.is_empty() {
         list_items.push(ListItem::new(Line::from(vec![Span::raw(
             "No targets registered yet.",
         )])));
@@ -244,7 +259,11 @@ fn make_target_info_panel(target: &Target) -> Paragraph {
             Span::raw("      : "),
             Span::from(target.id.clone()),
         ]),
-        Line::from(vec![Span::styled("Target", key_style), Span::raw("  : ")]),
+        Line::from(vec![
+            Span::styled("Target", key_style),
+            Span::raw("  : "),
+            Span::from(target.kind.to_string()),
+        ]),
         Line::from(vec![
             Span::raw("    "),
             Span::from(target.path.display().to_string()),
@@ -254,9 +273,15 @@ fn make_target_info_panel(target: &Target) -> Paragraph {
             Span::raw(" : "),
             Span::from(format!("{}", target.backups.len())),
         ]),
+        Line::from(vec![
+            Span::styled("Tags", key_style),
+            Span::raw("    : "),
+            Span::from(target.tags.join(" ")),
+        ]),
+        Line::from(vec![Span::from(target.description.clone())]),
     ];
 
-    Paragraph::new(lines).block(block)
+    Paragraph::new(lines).block(block).wrap(Wrap { trim: true })
 }
 
 fn make_backup_list_panel<'a>(
@@ -299,8 +324,16 @@ fn make_backup_list_panel<'a>(
                 String::from("   ")
             };
 
+            // Pinned backups are protected from prune and delete.
+            let pin = if entry.pinned {
+                Span::styled("[P] ", Style::default().fg(Color::Yellow))
+            } else {
+                Span::raw("    ")
+            };
+
             list_items.push(ListItem::new(Line::from(vec![
                 Span::from(cursor),
+                pin,
                 Span::from(format!("{:0>3}", entry.id)),
                 Span::raw(" - "),
                 Span::from(entry.timestamp.to_rfc3339()),
@@ -339,7 +372,7 @@ fn make_backup_info_panel<'a>(app: &'a app::App, target: &Target) -> Paragraph<'
         .fg(Color::Yellow)
         .add_modifier(Modifier::BOLD);
 
-    let lines = vec![
+    let mut lines = vec![
         Line::from(vec![
             Span::styled("ID", key_style),
             Span::raw("          : "),
@@ -354,17 +387,116 @@ fn make_backup_info_panel<'a>(app: &'a app::App, target: &Target) -> Paragraph<'
             Span::styled("Backup File", key_style),
             Span::raw(" : "),
         ]),
-        Line::from(Span::from(entry.path.display().to_string())),
+        Line::from(Span::from(match &entry.store {
+            Some(store) => format!("{store}:{}", entry.path.display()),
+            None => entry.path.display().to_string(),
+        })),
         Line::from(vec![
             Span::styled("Note ", key_style),
             Span::raw("       : "),
         ]),
         Line::from(vec![Span::from(entry.note.clone())]),
     ];
+    if let Some(stats) = &entry.stats {
+        lines.push(Line::from(vec![
+            Span::styled("Size", key_style),
+            Span::raw("        : "),
+            Span::from(format!(
+                "{} ({} uncompressed, {} files)",
+                format_size(stats.size),
+                format_size(stats.uncompressed_size),
+                stats.files
+            )),
+        ]));
+    }
 
     Paragraph::new(lines).block(block).wrap(Wrap { trim: true })
 }
 
+//-----------------------------------------------------------------------------
+//  Main panel - Stats panel.
+//-----------------------------------------------------------------------------
+fn render_stats_panel(frame: &mut Frame, app: &app::App, chunk: Rect) {
+    let Some(report) = app.stats.as_ref() else {
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .style(Style::default());
+        let warn = Paragraph::new("Stats are not loaded.").block(block);
+        frame.render_widget(warn, chunk);
+        return;
+    };
+
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .constraints([Constraint::Min(3), Constraint::Length(4)])
+        .split(chunk);
+
+    // Top: the sizes of each target, the largest first.
+    let mut targets: Vec<_> = report.targets.iter().collect();
+    targets.sort_by(|a, b| b.size.cmp(&a.size));
+
+    let name_width = targets.iter().map(|t| t.name.len()).max().unwrap_or(0) + 3;
+    let header_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let mut list_items = vec![ListItem::new(Line::from(Span::styled(
+        format!(
+            "   {:<name_width$}{:>12}{:>14}{:>8}{:>9}",
+            "Name", "Size", "Uncompressed", "Ratio", "Backups"
+        ),
+        header_style,
+    )))];
+    for target in targets {
+        let ratio = target
+            .ratio
+            .map(|r| format!("{:.1}%", r * 100.0))
+            .unwrap_or_else(|| String::from("-"));
+        list_items.push(ListItem::new(Line::from(format!(
+            "   {:<name_width$}{:>12}{:>14}{:>8}{:>9}",
+            target.name,
+            format_size(target.size),
+            format_size(target.uncompressed_size),
+            ratio,
+            target.backups.len()
+        ))));
+    }
+    let block = Block::default()
+        .title(format!(" Stats [{} targets] ", report.targets.len()))
+        .borders(Borders::ALL)
+        .style(Style::default());
+    frame.render_widget(List::new(list_items).block(block), chunks[0]);
+
+    // Bottom: the totals.
+    let key_style = Style::default()
+        .fg(Color::Yellow)
+        .add_modifier(Modifier::BOLD);
+    let datadir = report
+        .datadir_size
+        .map(format_size)
+        .unwrap_or_else(|| String::from("-"));
+    let lines = vec![
+        Line::from(vec![
+            Span::styled("Total", key_style),
+            Span::raw("          : "),
+            Span::from(format!(
+                "{} backups, {} ({} uncompressed)",
+                report.backup_count,
+                format_size(report.size),
+                format_size(report.uncompressed_size)
+            )),
+        ]),
+        Line::from(vec![
+            Span::styled("Data directory", key_style),
+            Span::raw(" : "),
+            Span::from(datadir),
+        ]),
+    ];
+    let block = Block::default()
+        .borders(Borders::ALL)
+        .style(Style::default());
+    frame.render_widget(Paragraph::new(lines).block(block), chunks[1]);
+}
+
 //-----------------------------------------------------------------------------
 //  Header / Footer
 //-----------------------------------------------------------------------------
@@ -396,6 +528,8 @@ fn make_footer_panel(app: &app::App) -> Paragraph {
                 ("  Register new target", vec!["r"]),
                 ("  Select a target", vec!["ArrowKeys", "k", "j", "Enter"]),
                 ("  Delete a target", vec!["d"]),
+                ("  Filter by tag", vec!["t"]),
+                ("  Disk usage stats", vec!["s"]),
             ]));
         }
         app::Panel::TargetInfo => {
@@ -407,6 +541,20 @@ fn make_footer_panel(app: &app::App) -> Paragraph {
                 ("  Take a new backup", vec!["n", "b"]),
                 ("  Select a backup", vec!["ArrowKeys", "k", "j", "Enter"]),
                 ("  Delete a backup", vec!["d"]),
+                ("  Edit the target", vec!["e"]),
+                ("  Edit the note of a backup", vec!["m"]),
+                ("  Pin / unpin a backup", vec!["p"]),
+                ("  Find a backup (e.g. latest~1, 2025-01-23)", vec!["/"]),
+                ("  Back to the target list", vec!["Esc", "BackSpace", "q"]),
+            ]));
+        }
+        app::Panel::Stats => {
+            lines.push(Line::from(vec![
+                Span::styled("Disk usage stats", title_style),
+                Span::raw(":"),
+            ]));
+            lines.append(&mut manual_lines(&vec![
+                ("  Reload", vec!["r"]),
                 ("  Back to the target list", vec!["Esc", "BackSpace", "q"]),
             ]));
         }
@@ -537,7 +685,7 @@ fn render_delete_target_popup(frame: &mut Frame, app: &app::App) {
     let header = Paragraph::new(vec![
         Line::from(vec![
             Span::styled("Warning", Style::default().fg(Color::Red)),
-            Span::raw(": This action cannot be undone!"),
+            Span::raw(": It is moved to the trash (see `dirback trash`)."),
         ]),
         Line::from("Confirm the target name to continue."),
         Line::from(vec![
@@ -661,7 +809,7 @@ fn render_delete_backup_popup(frame: &mut Frame, app: &app::App) {
     let desc = Paragraph::new(vec![
         Line::from(vec![
             Span::styled("Warning", Style::default().fg(Color::Red)),
-            Span::raw(": This action cannot be undone!"),
+            Span::raw(": It is moved to the trash (see `dirback trash`)."),
         ]),
         Line::raw(""),
         Line::from(format!(
@@ -740,9 +888,211 @@ fn render_restore_popup(frame: &mut Frame, app: &app::App) {
     frame.render_widget(footer, chunk_footer);
 }
 
+fn render_edit_target_popup(frame: &mut Frame, app: &app::App) {
+    // Render popup base
+    let popup = popup_area(75, 60, frame.area());
+    let popup_block = Block::bordered()
+        .title(" Edit target ")
+        .style(Style::default().bg(Color::DarkGray));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(popup_block, popup);
+
+    // Layout
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(1), // spacer
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(3),
+            Constraint::Length(1), // spacer
+            Constraint::Min(3),
+        ])
+        .split(popup);
+    let chunk_footer = chunks[5];
+
+    // Inputs
+    let titles = [" Name ", " Target path ", " Description "];
+    let active_style = Style::default().bg(Color::LightYellow).fg(Color::Black);
+    for (i, title) in titles.iter().enumerate() {
+        let mut block = Block::bordered().title(*title);
+        if i == app.popup_edit_index {
+            block = block.style(active_style);
+        }
+        let value = app.popup_input_buf.get(i).cloned().unwrap_or_default();
+        frame.render_widget(Paragraph::new(value).block(block), chunks[i + 1]);
+    }
+
+    // Footer
+    let mut lines = vec![];
+    if !app.popup_errors.is_empty() {
+        let err_style = Style::default().fg(Color::Red);
+        for err in app.popup_errors.iter() {
+            lines.push(Line::styled(err.clone(), err_style));
+        }
+        lines.push(Line::raw(""));
+    }
+    lines.append(&mut manual_lines(&vec![
+        ("Switch input field", vec!["TAB"]),
+        ("Cancel", vec!["Esc"]),
+        ("Submit", vec!["Enter"]),
+    ]));
+    let footer = Paragraph::new(lines).wrap(Wrap { trim: true });
+    frame.render_widget(footer, chunk_footer);
+}
+
+fn render_edit_note_popup(frame: &mut Frame, app: &app::App) {
+    // Render popup base
+    let popup = popup_area(75, 50, frame.area());
+    let entry_id = app
+        .current_target
+        .as_ref()
+        .and_then(|t| t.backups.get(app.cursor_backup))
+        .map_or(0, |e| e.id);
+    let popup_block = Block::bordered()
+        .title(format!(" Edit the note of backup {entry_id:0>3} "))
+        .style(Style::default().bg(Color::DarkGray));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(popup_block, popup);
+
+    // Layout
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(1), // spacer
+            Constraint::Length(3),
+            Constraint::Length(1), // spacer
+            Constraint::Min(3),
+        ])
+        .split(popup);
+    let chunk_note = chunks[1];
+    let chunk_footer = chunks[3];
+
+    // Input
+    let edit_note = app
+        .popup_input_buf
+        .first()
+        .unwrap_or(&String::new())
+        .clone();
+    let block = Block::bordered()
+        .title(" Note ")
+        .style(Style::default().bg(Color::LightYellow).fg(Color::Black));
+    frame.render_widget(Paragraph::new(edit_note).block(block), chunk_note);
+
+    // Footer
+    let mut lines = vec![];
+    if !app.popup_errors.is_empty() {
+        let err_style = Style::default().fg(Color::Red);
+        for err in app.popup_errors.iter() {
+            lines.push(Line::styled(err.clone(), err_style));
+        }
+        lines.push(Line::raw(""));
+    }
+    lines.append(&mut manual_lines(&vec![
+        ("Cancel", vec!["Esc"]),
+        ("Submit", vec!["Enter"]),
+    ]));
+    let footer = Paragraph::new(lines);
+    frame.render_widget(footer, chunk_footer);
+}
+
+fn render_filter_tag_popup(frame: &mut Frame, app: &app::App) {
+    // Render popup base
+    let popup = popup_area(75, 50, frame.area());
+    let popup_block = Block::bordered()
+        .title(" Filter targets by tag ")
+        .style(Style::default().bg(Color::DarkGray));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(popup_block, popup);
+
+    // Layout
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(1), // spacer
+            Constraint::Length(3),
+            Constraint::Length(1), // spacer
+            Constraint::Min(3),
+        ])
+        .split(popup);
+    let chunk_tag = chunks[1];
+    let chunk_footer = chunks[3];
+
+    // Input
+    let edit_tag = app
+        .popup_input_buf
+        .first()
+        .unwrap_or(&String::new())
+        .clone();
+    let block = Block::bordered()
+        .title(" Tag (empty to show all) ")
+        .style(Style::default().bg(Color::LightYellow).fg(Color::Black));
+    frame.render_widget(Paragraph::new(edit_tag).block(block), chunk_tag);
+
+    // Footer
+    let lines = manual_lines(&vec![("Cancel", vec!["Esc"]), ("Apply", vec!["Enter"])]);
+    let footer = Paragraph::new(lines);
+    frame.render_widget(footer, chunk_footer);
+}
+
+fn render_find_backup_popup(frame: &mut Frame, app: &app::App) {
+    // Render popup base
+    let popup = popup_area(75, 50, frame.area());
+    let popup_block = Block::bordered()
+        .title(" Find a backup ")
+        .style(Style::default().bg(Color::DarkGray));
+    frame.render_widget(Clear, popup);
+    frame.render_widget(popup_block, popup);
+
+    // Layout
+    let chunks = Layout::default()
+        .direction(Direction::Vertical)
+        .margin(1)
+        .constraints([
+            Constraint::Length(1), // spacer
+            Constraint::Length(3),
+            Constraint::Length(1), // spacer
+            Constraint::Min(3),
+        ])
+        .split(popup);
+    let chunk_selector = chunks[1];
+    let chunk_footer = chunks[3];
+
+    // Input
+    let selector = app
+        .popup_input_buf
+        .first()
+        .unwrap_or(&String::new())
+        .clone();
+    let block = Block::bordered()
+        .title(" ID, latest, latest~N, first, date/time or note ")
+        .style(Style::default().bg(Color::LightYellow).fg(Color::Black));
+    frame.render_widget(Paragraph::new(selector).block(block), chunk_selector);
+
+    // Footer
+    let mut lines = vec![];
+    if !app.popup_errors.is_empty() {
+        let err_style = Style::default().fg(Color::Red);
+        for err in app.popup_errors.iter() {
+            lines.push(Line::styled(err.clone(), err_style));
+        }
+        lines.push(Line::raw(""));
+    }
+    lines.append(&mut manual_lines(&vec![
+        ("Cancel", vec!["Esc"]),
+        ("Find", vec!["Enter"]),
+    ]));
+    let footer = Paragraph::new(lines);
+    frame.render_widget(footer, chunk_footer);
+}
+
 //-----------------------------------------------------------------------------
 // Helpers
 //-----------------------------------------------------------------------------
+/// Formats the bytes with a binary unit, e.g. `1.5 MiB`.
 fn manual_lines<'a>(manuals: &Vec<(&str, Vec<&str>)>) -> Vec<Line<'a>> {
     // Style
     let key_style = Style::default().fg(Color::Yellow);
@@ -3,7 +3,17 @@
 //!
 
 pub mod get_target;
+pub mod list_schedules;
 pub mod list_targets;
+pub mod list_trash;
+pub mod resolve_target;
+pub mod select_backup;
+pub mod stats;
 
 pub use get_target::GetTargetAdapter;
+pub use list_schedules::ListSchedulesAdapter;
 pub use list_targets::ListTargetsAdapter;
+pub use list_trash::ListTrashAdapter;
+pub use resolve_target::{ResolveTargetAdapter, ResolveTargetError};
+pub use select_backup::SelectBackupAdapter;
+pub use stats::{StatsAdapter, format_growth, format_size};
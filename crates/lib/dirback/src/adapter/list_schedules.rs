@@ -0,0 +1,63 @@
+//!
+//! # Adapter: ListSchedules
+//!
+
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::{Target, Timestamp};
+
+pub struct ListSchedulesAdapter<'a, R: TargetRepository> {
+    repo: &'a R,
+}
+
+impl<'a, R: TargetRepository> ListSchedulesAdapter<'a, R> {
+    pub fn new(repo: &'a R) -> Self {
+        Self { repo }
+    }
+
+    /// Returns the scheduled targets and their next run times, the earliest first.
+    ///
+    /// A next run before `now` is a missed run, which is due at once.
+    pub fn execute(&self, now: &Timestamp) -> anyhow::Result<Vec<(Target, Timestamp)>> {
+        let mut schedules: Vec<(Target, Timestamp)> = self
+            .repo
+            .load_all()?
+            .into_iter()
+            .filter_map(|t| {
+                let next = t.next_run(now)?;
+                Some((Target::from(t), next))
+            })
+            .collect();
+        schedules.sort_by_key(|(_, next)| **next);
+
+        Ok(schedules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::schedule::Schedule;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use std::path::Path;
+
+    #[test]
+    fn it_returns_scheduled_targets_by_next_run() {
+        let mut repo = InMemoryTargetRepository::new();
+        let now = Timestamp::from_fmt_str("20250101T000000Z").unwrap();
+
+        repo.add("manual", Path::new("manual")).unwrap();
+        for (name, schedule) in [("daily", "every 1d"), ("hourly", "every 1h")] {
+            let mut target = repo.add(name, Path::new(name)).unwrap();
+            target.schedule = Some(Schedule::parse(schedule).unwrap());
+            let mut entry = target.new_backup_entry(Path::new("backups"), "tar.gz");
+            entry.timestamp = now.clone();
+            target.register_backup_entry(entry).unwrap();
+            repo.update(&target).unwrap();
+        }
+
+        let schedules = ListSchedulesAdapter::new(&repo).execute(&now).unwrap();
+        let names: Vec<&str> = schedules.iter().map(|(t, _)| t.name.as_str()).collect();
+        assert_eq!(names, vec!["hourly", "daily"]);
+        assert_eq!(schedules[0].1.fmt(), "20250101T010000Z");
+    }
+}
@@ -19,6 +19,17 @@ impl<'a, R: TargetRepository> ListTargetsAdapter<'a, R> {
 
         Ok(targets.into_iter().map(Target::from).collect())
     }
+
+    /// Returns the targets which have the tag. `all` returns every target.
+    pub fn execute_by_tag(&self, tag: &str) -> anyhow::Result<Vec<Target>> {
+        let targets = self.repo.load_all()?;
+
+        Ok(targets
+            .into_iter()
+            .filter(|t| t.matches_tag(tag))
+            .map(Target::from)
+            .collect())
+    }
 }
 
 #[cfg(test)]
@@ -42,4 +53,23 @@ mod tests {
         let targets = targets.unwrap();
         assert_eq!(targets.len(), 3);
     }
+
+    #[test]
+    fn it_filters_by_tag() {
+        let mut repo = InMemoryTargetRepository::new();
+        for i in 1..=3 {
+            let name = format!("Test Target {i}");
+            let path = std::path::PathBuf::from(format!("target{i}"));
+            let mut target = repo.add(&name, &path).unwrap();
+            if i != 2 {
+                target.add_tag("clients").unwrap();
+                repo.update(&target).unwrap();
+            }
+        }
+
+        let adapter = ListTargetsAdapter::new(&repo);
+        assert_eq!(adapter.execute_by_tag("clients").unwrap().len(), 2);
+        assert_eq!(adapter.execute_by_tag("games").unwrap().len(), 0);
+        assert_eq!(adapter.execute_by_tag("all").unwrap().len(), 3);
+    }
 }
@@ -0,0 +1,48 @@
+//!
+//! # Adapter: ListTrash
+//!
+
+use crate::domain::repository::trash::TrashRepository;
+use crate::usecase::dto::TrashItem;
+
+pub struct ListTrashAdapter<'a, R: TrashRepository> {
+    repo: &'a R,
+}
+
+impl<'a, R: TrashRepository> ListTrashAdapter<'a, R> {
+    pub fn new(repo: &'a R) -> Self {
+        Self { repo }
+    }
+
+    /// Returns the items in the trash, the oldest first.
+    pub fn execute(&self) -> anyhow::Result<Vec<TrashItem>> {
+        let items = self.repo.list_trash()?;
+        Ok(items.into_iter().map(TrashItem::from).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repository::targets::TargetRepository;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use std::path::Path;
+
+    #[test]
+    fn it_returns_deleted_items() {
+        let mut repo = InMemoryTargetRepository::new();
+        let mut target = repo.add("TestTarget", Path::new("target")).unwrap();
+        let entry = target.new_backup_entry(Path::new("backups"), "tar.gz");
+        target.register_backup_entry(entry).unwrap();
+        repo.update(&target).unwrap();
+
+        repo.delete_backup(&target.id, 1).unwrap();
+        repo.delete_target(&target.id).unwrap();
+
+        let items = ListTrashAdapter::new(&repo).execute().unwrap();
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].backup.as_ref().map(|b| b.id), Some(1));
+        assert!(items[1].backup.is_none());
+        assert_eq!(items[1].target_name, "TestTarget");
+    }
+}
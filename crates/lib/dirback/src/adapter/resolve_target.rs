@@ -0,0 +1,228 @@
+//!
+//! # Adapter: ResolveTarget
+//!
+//! Finds the target referred by the user, in this order:
+//!
+//! 1. The exact target ID.
+//! 2. The exact target name.
+//! 3. A unique prefix of the target ID.
+//! 4. A path: the target (or one of its sources) at the path, or containing it,
+//!    e.g. `.` for the target containing the current directory.
+//!
+
+use crate::domain::model::target::Target as TargetModel;
+use crate::domain::model::target_kind::TargetKind;
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::Target;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum ResolveTargetError {
+    #[error("Target not found ('{0}').")]
+    NotFound(String),
+
+    #[error("'{query}' matches several targets: {}. Use the target ID instead.", .candidates.join(", "))]
+    Ambiguous {
+        query: String,
+
+        /// `name (id)` of the matched targets.
+        candidates: Vec<String>,
+    },
+}
+
+pub struct ResolveTargetAdapter<'a, R: TargetRepository> {
+    repo: &'a R,
+}
+
+impl<'a, R: TargetRepository> ResolveTargetAdapter<'a, R> {
+    pub fn new(repo: &'a R) -> Self {
+        Self { repo }
+    }
+
+    /// Resolves the query, with relative paths from the current directory.
+    pub fn execute(&self, query: &str) -> anyhow::Result<Target> {
+        let cwd = std::env::current_dir()?;
+        self.execute_in(query, &cwd)
+    }
+
+    /// Resolves the query, with relative paths from `cwd`.
+    pub fn execute_in(&self, query: &str, cwd: &Path) -> anyhow::Result<Target> {
+        if query.is_empty() {
+            return Err(ResolveTargetError::NotFound(query.to_string()).into());
+        }
+        if let Some(target) = self.repo.load(query) {
+            return Ok(target.into());
+        }
+
+        let targets = self.repo.load_all()?;
+
+        let by_name: Vec<&TargetModel> = targets.iter().filter(|t| t.name == query).collect();
+        if let Some(target) = pick(query, by_name)? {
+            return Ok(target.clone().into());
+        }
+
+        let by_prefix: Vec<&TargetModel> =
+            targets.iter().filter(|t| t.id.starts_with(query)).collect();
+        if let Some(target) = pick(query, by_prefix)? {
+            return Ok(target.clone().into());
+        }
+
+        if let Some(path) = to_path(query, cwd) {
+            let by_path = find_by_path(&targets, &path);
+            if let Some(target) = pick(query, by_path)? {
+                return Ok(target.clone().into());
+            }
+        }
+
+        Err(ResolveTargetError::NotFound(query.to_string()).into())
+    }
+}
+
+/// Returns the only target, or `Ambiguous` if there are several.
+fn pick<'t>(
+    query: &str,
+    targets: Vec<&'t TargetModel>,
+) -> Result<Option<&'t TargetModel>, ResolveTargetError> {
+    match targets.len() {
+        0 => Ok(None),
+        1 => Ok(Some(targets[0])),
+        _ => Err(ResolveTargetError::Ambiguous {
+            query: query.to_string(),
+            candidates: targets
+                .iter()
+                .map(|t| format!("{} ({})", t.name, t.id))
+                .collect(),
+        }),
+    }
+}
+
+/// Returns the absolute path, if the query looks like a path or exists.
+fn to_path(query: &str, cwd: &Path) -> Option<PathBuf> {
+    let path = cwd.join(query);
+    let looks_like_path =
+        query.contains(['/', std::path::MAIN_SEPARATOR]) || query.starts_with('.');
+    if !looks_like_path && !path.exists() {
+        return None;
+    }
+
+    // The target paths are canonicalized on register.
+    Some(std::fs::canonicalize(&path).unwrap_or(path))
+}
+
+/// Returns the targets at the path, or else the innermost targets containing it.
+fn find_by_path<'t>(targets: &'t [TargetModel], path: &Path) -> Vec<&'t TargetModel> {
+    let depth = |target: &TargetModel| -> Option<usize> {
+        std::iter::once(&target.path)
+            .chain(target.sources.iter().map(|source| &source.path))
+            .filter(|dir| match target.kind {
+                TargetKind::Directory => path.starts_with(dir),
+                TargetKind::File => path == *dir,
+            })
+            .map(|dir| dir.components().count())
+            .max()
+    };
+
+    let depths: Vec<(&TargetModel, usize)> = targets
+        .iter()
+        .filter_map(|t| depth(t).map(|d| (t, d)))
+        .collect();
+    let Some(deepest) = depths.iter().map(|(_, d)| *d).max() else {
+        return Vec::new();
+    };
+
+    depths
+        .into_iter()
+        .filter(|(_, d)| *d == deepest)
+        .map(|(t, _)| t)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+
+    fn resolve(repo: &InMemoryTargetRepository, query: &str) -> anyhow::Result<Target> {
+        ResolveTargetAdapter::new(repo).execute_in(query, Path::new("/home/user"))
+    }
+
+    fn error_of(result: anyhow::Result<Target>) -> ResolveTargetError {
+        result
+            .unwrap_err()
+            .downcast::<ResolveTargetError>()
+            .unwrap()
+    }
+
+    #[test]
+    fn it_resolves_id_name_and_prefix() {
+        let mut repo = InMemoryTargetRepository::new();
+        let a = repo.add("notes", Path::new("/home/user/notes")).unwrap();
+        let b = repo.add("games", Path::new("/home/user/games")).unwrap();
+
+        assert_eq!(resolve(&repo, &a.id).unwrap().id, a.id);
+        assert_eq!(resolve(&repo, "games").unwrap().id, b.id);
+
+        let prefix = &b.id[..8];
+        assert_eq!(resolve(&repo, prefix).unwrap().id, b.id);
+    }
+
+    #[test]
+    fn it_resolves_path() {
+        let mut repo = InMemoryTargetRepository::new();
+        let home = repo.add("home", Path::new("/home/user")).unwrap();
+        let notes = repo.add("notes", Path::new("/home/user/notes")).unwrap();
+        let mut db = repo.add("db", Path::new("/var/lib/app.db")).unwrap();
+        db.kind = TargetKind::File;
+        repo.update(&db).unwrap();
+
+        assert_eq!(resolve(&repo, "/home/user/notes").unwrap().id, notes.id);
+        assert_eq!(resolve(&repo, "./notes/2025").unwrap().id, notes.id);
+        assert_eq!(resolve(&repo, ".").unwrap().id, home.id);
+        assert_eq!(resolve(&repo, "/var/lib/app.db").unwrap().id, db.id);
+
+        let err = error_of(resolve(&repo, "/var/lib/app.db/x"));
+        assert_eq!(
+            err,
+            ResolveTargetError::NotFound(String::from("/var/lib/app.db/x"))
+        );
+    }
+
+    #[test]
+    fn it_resolves_path_in_sources() {
+        let mut repo = InMemoryTargetRepository::new();
+        let mut target = repo.add("app", Path::new("/home/user/app")).unwrap();
+        target
+            .add_source("config", Path::new("/home/user/.config/app"))
+            .unwrap();
+        repo.update(&target).unwrap();
+
+        let found = resolve(&repo, "/home/user/.config/app/settings.toml").unwrap();
+        assert_eq!(found.id, target.id);
+    }
+
+    #[test]
+    fn it_returns_err_if_ambiguous() {
+        let mut repo = InMemoryTargetRepository::new();
+        let _ = repo.add("notes", Path::new("/home/user/notes")).unwrap();
+        let _ = repo
+            .add("notes", Path::new("/home/user/work/notes"))
+            .unwrap();
+
+        let err = error_of(resolve(&repo, "notes"));
+        assert!(matches!(
+            err,
+            ResolveTargetError::Ambiguous { ref candidates, .. } if candidates.len() == 2
+        ));
+    }
+
+    #[test]
+    fn it_returns_err_if_not_found() {
+        let mut repo = InMemoryTargetRepository::new();
+        let _ = repo.add("notes", Path::new("/home/user/notes")).unwrap();
+
+        for query in ["", "xxx", "/tmp/other"] {
+            let err = error_of(resolve(&repo, query));
+            assert_eq!(err, ResolveTargetError::NotFound(query.to_string()));
+        }
+    }
+}
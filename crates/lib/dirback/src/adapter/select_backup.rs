@@ -0,0 +1,99 @@
+//!
+//! # Adapter: SelectBackup
+//!
+//! Finds the backup of a target referred by a `BackupSelector`,
+//! e.g. `latest~2` or `2025-01-23`.
+//!
+
+use crate::domain::model::backup_selector::{BackupSelector, BackupSelectorError};
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::BackupEntry;
+
+pub struct SelectBackupAdapter<'a, R: TargetRepository> {
+    repo: &'a R,
+}
+
+impl<'a, R: TargetRepository> SelectBackupAdapter<'a, R> {
+    pub fn new(repo: &'a R) -> Self {
+        Self { repo }
+    }
+
+    pub fn execute(
+        &self,
+        target_id: &str,
+        selector: &BackupSelector,
+    ) -> anyhow::Result<BackupEntry> {
+        let target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target not found ('{target_id}')."))?;
+
+        selector
+            .select(&target.backups)
+            .map(|entry| entry.clone().into())
+            .ok_or_else(|| BackupSelectorError::NotFound(selector.to_string()).into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::backup_entry::BackupEntry as BackupEntryModel;
+    use crate::domain::model::timestamp::Timestamp;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use std::path::Path;
+
+    fn make_repo() -> (InMemoryTargetRepository, String) {
+        let mut repo = InMemoryTargetRepository::new();
+        let mut target = repo.add("notes", Path::new("/home/user/notes")).unwrap();
+        for (id, time, note) in [
+            (1, "20250101T090000Z", "initial"),
+            (2, "20250102T090000Z", "weekly"),
+            (3, "20250103T090000Z", ""),
+        ] {
+            let ts = Timestamp::from_fmt_str(time).unwrap();
+            let entry = BackupEntryModel::new(id, Path::new("backup.tar.gz"), ts, note);
+            target.backups.push(entry);
+        }
+        repo.update(&target).unwrap();
+        (repo, target.id)
+    }
+
+    #[test]
+    fn it_works() {
+        let (repo, target_id) = make_repo();
+        let adapter = SelectBackupAdapter::new(&repo);
+
+        for (selector, id) in [
+            ("latest", 3),
+            ("latest~1", 2),
+            ("first", 1),
+            ("2025-01-02T12:00:00Z", 2),
+            ("week", 2),
+        ] {
+            let selector = BackupSelector::parse(selector).unwrap();
+            let entry = adapter.execute(&target_id, &selector).unwrap();
+            assert_eq!(entry.id, id, "{selector}");
+        }
+    }
+
+    #[test]
+    fn it_returns_err_if_not_found() {
+        let (repo, target_id) = make_repo();
+        let adapter = SelectBackupAdapter::new(&repo);
+
+        let selector = BackupSelector::parse("latest~3").unwrap();
+        let err = adapter.execute(&target_id, &selector).unwrap_err();
+        assert_eq!(
+            err.downcast::<BackupSelectorError>().unwrap(),
+            BackupSelectorError::NotFound(String::from("latest~3"))
+        );
+
+        let selector = BackupSelector::parse("latest").unwrap();
+        assert!(
+            adapter
+                .execute("non-existing-target-id", &selector)
+                .is_err()
+        );
+    }
+}
@@ -0,0 +1,236 @@
+//!
+//! # Adapter: Stats
+//!
+//! Reports the sizes of the backups of each target,
+//! the compression ratio, the growth over time, and the disk usage of the data directory.
+//!
+
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::model::target::Target;
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::{BackupUsage, StatsReport, TargetStats};
+use std::path::{Path, PathBuf};
+
+pub struct StatsAdapter<'a, R: TargetRepository> {
+    repo: &'a R,
+    datadir: Option<PathBuf>,
+}
+
+impl<'a, R: TargetRepository> StatsAdapter<'a, R> {
+    pub fn new(repo: &'a R) -> Self {
+        Self {
+            repo,
+            datadir: None,
+        }
+    }
+
+    /// Measures the disk usage of the data directory too.
+    pub fn with_datadir(mut self, datadir: &Path) -> Self {
+        self.datadir = Some(datadir.to_path_buf());
+        self
+    }
+
+    /// Returns the stats of the target, or every target if `None`.
+    pub fn execute(&self, target_id: Option<&str>) -> anyhow::Result<StatsReport> {
+        let targets = match target_id {
+            None => self.repo.load_all()?,
+            Some(target_id) => vec![
+                self.repo
+                    .load(target_id)
+                    .ok_or_else(|| anyhow::anyhow!("Target not found ('{target_id}')."))?,
+            ],
+        };
+
+        let targets: Vec<TargetStats> = targets.iter().map(target_stats).collect();
+        let datadir_size = match &self.datadir {
+            Some(datadir) => Some(dir_size(datadir)?),
+            None => None,
+        };
+
+        Ok(StatsReport {
+            backup_count: targets.iter().map(|t| t.backups.len()).sum(),
+            size: targets.iter().map(|t| t.size).sum(),
+            uncompressed_size: targets.iter().map(|t| t.uncompressed_size).sum(),
+            targets,
+            datadir_size,
+        })
+    }
+}
+
+fn target_stats(target: &Target) -> TargetStats {
+    let mut backups: Vec<BackupUsage> = target.backups.iter().map(backup_usage).collect();
+    backups.sort_by_key(|b| b.id);
+
+    let mut previous: Option<u64> = None;
+    for backup in backups.iter_mut() {
+        if let Some(size) = backup.uncompressed_size {
+            backup.growth = previous.map(|prev| size as i64 - prev as i64);
+            previous = Some(size);
+        }
+    }
+
+    // The ratio is of the backups with both sizes recorded.
+    let (recorded_size, uncompressed_size) = target
+        .backups
+        .iter()
+        .filter_map(|b| b.stats.as_ref())
+        .fold((0, 0), |(size, uncompressed), stats| {
+            (size + stats.size, uncompressed + stats.uncompressed_size)
+        });
+
+    TargetStats {
+        id: target.id.clone(),
+        name: target.name.clone(),
+        size: backups.iter().filter_map(|b| b.size).sum(),
+        uncompressed_size,
+        ratio: (uncompressed_size > 0).then(|| recorded_size as f64 / uncompressed_size as f64),
+        unknown: backups.iter().filter(|b| b.size.is_none()).count(),
+        backups,
+    }
+}
+
+fn backup_usage(entry: &BackupEntry) -> BackupUsage {
+    let stats = entry.stats.as_ref();
+
+    // The size of an old backup in the data directory can still be read from the file.
+    let size = stats.map(|s| s.size).or_else(|| match entry.store {
+        None => std::fs::metadata(&entry.path).ok().map(|m| m.len()),
+        Some(_) => None,
+    });
+
+    BackupUsage {
+        id: entry.id,
+        timestamp: entry.timestamp.clone(),
+        note: entry.note.clone(),
+        size,
+        uncompressed_size: stats.map(|s| s.uncompressed_size),
+        files: stats.map(|s| s.files),
+        duration_ms: stats.map(|s| s.duration_ms),
+        ratio: stats.and_then(|s| s.ratio()),
+        growth: None,
+    }
+}
+
+/// Formats the size in bytes with a binary unit, e.g. `1.5 KiB`.
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 5] = ["KiB", "MiB", "GiB", "TiB", "PiB"];
+    if bytes < 1024 {
+        return format!("{bytes} B");
+    }
+
+    let mut size = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    format!("{size:.1} {}", UNITS[unit])
+}
+
+/// Formats the growth in bytes with its sign, e.g. `+1.5 KiB`.
+pub fn format_growth(growth: i64) -> String {
+    let sign = if growth < 0 { "-" } else { "+" };
+    format!("{sign}{}", format_size(growth.unsigned_abs()))
+}
+
+/// Returns the total size of the files under the directory, not following symlinks.
+fn dir_size(dir: &Path) -> anyhow::Result<u64> {
+    if !dir.exists() {
+        return Ok(0);
+    }
+
+    let mut size = 0;
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let metadata = entry.path().symlink_metadata()?;
+        if metadata.is_dir() {
+            size += dir_size(&entry.path())?;
+        } else {
+            size += metadata.len();
+        }
+    }
+    Ok(size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::backup_stats::BackupStats;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+
+    fn stats(size: u64, uncompressed_size: u64) -> Option<BackupStats> {
+        Some(BackupStats {
+            size,
+            uncompressed_size,
+            files: 3,
+            duration_ms: 100,
+        })
+    }
+
+    fn make_repo(backup_dir: &Path) -> (InMemoryTargetRepository, String) {
+        let mut repo = InMemoryTargetRepository::new();
+        let mut target = repo.add("notes", Path::new("notes")).unwrap();
+        for stats in [None, stats(30, 100), None, stats(50, 150)] {
+            let mut entry = target.new_backup_entry(backup_dir, "tar.gz");
+            entry.stats = stats;
+            target.register_backup_entry(entry).unwrap();
+        }
+        repo.update(&target).unwrap();
+        (repo, target.id)
+    }
+
+    #[test]
+    fn it_reports_sizes_ratio_and_growth() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (repo, target_id) = make_repo(&temp.path());
+
+        // The size of the first backup is read from its file.
+        let target = repo.load(&target_id).unwrap();
+        std::fs::write(&target.backups[0].path, "0123456789").unwrap();
+
+        let report = StatsAdapter::new(&repo).execute(None).unwrap();
+        assert_eq!(report.backup_count, 4);
+        assert_eq!(report.datadir_size, None);
+
+        let target = &report.targets[0];
+        assert_eq!(target.size, 90);
+        assert_eq!(target.uncompressed_size, 250);
+        assert_eq!(target.ratio, Some(80.0 / 250.0));
+        assert_eq!(target.unknown, 1);
+
+        let sizes: Vec<Option<u64>> = target.backups.iter().map(|b| b.size).collect();
+        assert_eq!(sizes, [Some(10), Some(30), None, Some(50)]);
+        let growth: Vec<Option<i64>> = target.backups.iter().map(|b| b.growth).collect();
+        assert_eq!(growth, [None, None, None, Some(50)]);
+        assert_eq!(target.backups[1].ratio, Some(0.3));
+        assert_eq!(report.size, 90);
+    }
+
+    #[test]
+    fn it_measures_datadir() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (repo, target_id) = make_repo(&temp.path().join("missing"));
+        std::fs::create_dir_all(temp.path().join("sub")).unwrap();
+        std::fs::write(temp.path().join("a.txt"), "12345").unwrap();
+        std::fs::write(temp.path().join("sub/b.txt"), "123").unwrap();
+
+        let report = StatsAdapter::new(&repo)
+            .with_datadir(&temp.path())
+            .execute(Some(&target_id))
+            .unwrap();
+        assert_eq!(report.datadir_size, Some(8));
+        assert_eq!(report.targets.len(), 1);
+
+        assert!(StatsAdapter::new(&repo).execute(Some("unknown")).is_err());
+    }
+
+    #[test]
+    fn it_formats_sizes() {
+        assert_eq!(format_size(0), "0 B");
+        assert_eq!(format_size(1023), "1023 B");
+        assert_eq!(format_size(1536), "1.5 KiB");
+        assert_eq!(format_size(5 * 1024 * 1024), "5.0 MiB");
+        assert_eq!(format_growth(-2048), "-2.0 KiB");
+        assert_eq!(format_growth(0), "+0 B");
+    }
+}
@@ -2,9 +2,24 @@
 //! # model module
 //!
 
+pub mod archived_file;
 pub mod backup_entry;
+pub mod backup_selector;
+pub mod backup_stats;
+pub mod bundle;
+pub mod change_set;
+pub mod file_pattern;
+pub mod hooks;
+pub mod index_entry;
+pub mod replica_status;
+pub mod retention_policy;
+pub mod schedule;
+pub mod source;
 pub mod target;
+pub mod target_kind;
 pub mod timestamp;
+pub mod trash_item;
 
 pub use backup_entry::BackupEntry;
+pub use replica_status::ReplicaStatus;
 pub use target::Target;
@@ -0,0 +1,24 @@
+//!
+//! # ArchivedFile
+//!
+//! A file in a backup, found by a `FilePattern`.
+//!
+
+use crate::domain::model::timestamp::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ArchivedFile {
+    /// The relative path in the backup.
+    pub path: PathBuf,
+
+    /// The size of the file in bytes.
+    pub size: u64,
+
+    /// The modification time of the file, `None` if not recorded in the backup.
+    pub mtime: Option<Timestamp>,
+
+    /// The SHA-256 hash of the content, in hex.
+    pub sha256: String,
+}
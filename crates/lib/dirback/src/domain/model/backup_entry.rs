@@ -7,6 +7,10 @@
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
 
+use crate::domain::model::backup_stats::BackupStats;
+use crate::domain::model::replica_status::ReplicaStatus;
+use crate::domain::model::source::Source;
+use crate::domain::model::target_kind::TargetKind;
 use crate::domain::model::timestamp::Timestamp;
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
@@ -15,6 +19,35 @@ pub struct BackupEntry {
     pub path: PathBuf,
     pub timestamp: Timestamp,
     pub note: String,
+
+    /// ID of the archive store that holds the backup file.
+    ///
+    /// `None` means the `path` is a local file (in the data directory),
+    /// otherwise the `path` is the key of the archive in the store.
+    pub store: Option<String>,
+
+    /// SHA-256 of the backup file, calculated when it is first replicated.
+    pub checksum: Option<String>,
+
+    /// Replication status of each replica location.
+    pub replicas: Vec<ReplicaStatus>,
+
+    /// The directories archived under their names,
+    /// empty if the archive contains the target path only.
+    #[serde(default)]
+    pub sources: Vec<Source>,
+
+    /// The kind of the archived target path, restored as the same kind.
+    #[serde(default)]
+    pub kind: TargetKind,
+
+    /// A pinned backup is never pruned, nor deleted until it is unpinned.
+    #[serde(default)]
+    pub pinned: bool,
+
+    /// The sizes of the backup, `None` for the backups made before they were recorded.
+    #[serde(default)]
+    pub stats: Option<BackupStats>,
 }
 
 impl BackupEntry {
@@ -24,6 +57,13 @@ impl BackupEntry {
             path: path.to_path_buf(),
             timestamp,
             note: note.to_string(),
+            store: None,
+            checksum: None,
+            replicas: Vec::new(),
+            sources: Vec::new(),
+            kind: TargetKind::Directory,
+            pinned: false,
+            stats: None,
         }
     }
 
@@ -36,6 +76,23 @@ impl BackupEntry {
     pub fn generate_backup_filename(id: u32, timestamp: &Timestamp, ext: &str) -> String {
         format!("{:0>4}_{}.{}", id, timestamp.fmt(), ext).to_string()
     }
+
+    /// Returns the status of the replica location.
+    pub fn replica_status(&self, replica: &Path) -> Option<&ReplicaStatus> {
+        self.replicas.iter().find(|r| r.replica == replica)
+    }
+
+    /// Sets the status of the replica location, replacing the previous one.
+    pub fn set_replica_status(&mut self, status: ReplicaStatus) {
+        match self
+            .replicas
+            .iter_mut()
+            .find(|r| r.replica == status.replica)
+        {
+            Some(current) => *current = status,
+            None => self.replicas.push(status),
+        }
+    }
 }
 
 //-----------------------------------------------------------------------------
@@ -72,6 +129,22 @@ mod tests {
         assert!(result.ends_with(ext));
     }
 
+    #[test]
+    fn test_set_replica_status() {
+        let ts = Timestamp::from_fmt_str("20250123T123456Z").unwrap();
+        let mut entry = BackupEntry::new(1, Path::new("1.tar.gz"), ts, "");
+        let nas = Path::new("/mnt/nas");
+
+        entry.set_replica_status(ReplicaStatus::failed(nas, Path::new(""), "error"));
+        assert!(!entry.replica_status(nas).unwrap().is_synced());
+
+        entry.set_replica_status(ReplicaStatus::synced(nas, Path::new("/mnt/nas/1.tar.gz")));
+        assert_eq!(entry.replicas.len(), 1, "it should replace the status.");
+        assert!(entry.replica_status(nas).unwrap().is_synced());
+
+        assert!(entry.replica_status(Path::new("/mnt/usb")).is_none());
+    }
+
     #[test]
     fn it_serializable() {
         let id = 23;
@@ -0,0 +1,313 @@
+//!
+//! # BackupSelector
+//!
+//! Refers to a backup of a target without knowing its ID.
+//!
+//! A selector is written as a string:
+//! - `<id>` ... the backup ID, e.g. `3`
+//! - `latest`, `latest~N` ... the newest backup, or the Nth one before it
+//! - `first` ... the oldest backup
+//! - an ISO date/time ... the newest backup at or before the time,
+//!   e.g. `2025-01-23T12:00:00Z`, `2025-01-23 12:00` (local time),
+//!   `2025-01-23` (the end of the day, local time)
+//! - `note:<text>` or any other string ... the newest backup whose note contains
+//!   the text, ignoring case
+//!
+
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::model::timestamp::Timestamp;
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum BackupSelectorError {
+    #[error("Empty backup selector.")]
+    Empty,
+
+    #[error("Invalid backup selector: '{0}' (e.g. 'latest~2')")]
+    InvalidOffset(String),
+
+    #[error("Invalid time in backup selector: '{0}'")]
+    InvalidTime(String),
+
+    #[error("Backup not found ('{0}').")]
+    NotFound(String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum BackupSelector {
+    /// The backup with the ID.
+    Id(u32),
+
+    /// The newest backup, skipping the given number of backups.
+    Latest(usize),
+
+    /// The oldest backup.
+    First,
+
+    /// The newest backup taken at or before the time.
+    AtOrBefore(Timestamp),
+
+    /// The newest backup whose note contains the text, ignoring case.
+    Note(String),
+}
+
+const LATEST_KEYWORD: &str = "latest";
+const FIRST_KEYWORD: &str = "first";
+const NOTE_PREFIX: &str = "note:";
+
+/// Formats of the date and time in the local time zone.
+const LOCAL_DATETIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+];
+
+impl BackupSelector {
+    pub fn parse(s: &str) -> Result<Self, BackupSelectorError> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(BackupSelectorError::Empty);
+        }
+
+        if let Ok(id) = s.parse::<u32>() {
+            return Ok(Self::Id(id));
+        }
+        if s == FIRST_KEYWORD {
+            return Ok(Self::First);
+        }
+        if let Some(rest) = s.strip_prefix(LATEST_KEYWORD) {
+            return match rest.strip_prefix('~') {
+                None if rest.is_empty() => Ok(Self::Latest(0)),
+                Some(n) => n
+                    .parse()
+                    .map(Self::Latest)
+                    .map_err(|_| BackupSelectorError::InvalidOffset(s.to_string())),
+                None => Err(BackupSelectorError::InvalidOffset(s.to_string())),
+            };
+        }
+        if let Some(text) = s.strip_prefix(NOTE_PREFIX) {
+            return match text.trim() {
+                "" => Err(BackupSelectorError::Empty),
+                text => Ok(Self::Note(text.to_string())),
+            };
+        }
+        if let Some(time) = parse_time(s)? {
+            return Ok(Self::AtOrBefore(time));
+        }
+
+        Ok(Self::Note(s.to_string()))
+    }
+
+    /// Returns the selected backup from the backups sorted by ID.
+    pub fn select<'a>(&self, backups: &'a [BackupEntry]) -> Option<&'a BackupEntry> {
+        match self {
+            Self::Id(id) => backups.iter().find(|entry| entry.id == *id),
+            Self::Latest(n) => backups.iter().rev().nth(*n),
+            Self::First => backups.first(),
+            Self::AtOrBefore(time) => backups
+                .iter()
+                .filter(|entry| *entry.timestamp <= **time)
+                .max_by_key(|entry| (*entry.timestamp, entry.id)),
+            Self::Note(text) => {
+                let text = text.to_lowercase();
+                backups
+                    .iter()
+                    .rev()
+                    .find(|entry| entry.note.to_lowercase().contains(&text))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for BackupSelector {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Id(id) => write!(f, "{id}"),
+            Self::Latest(0) => write!(f, "{LATEST_KEYWORD}"),
+            Self::Latest(n) => write!(f, "{LATEST_KEYWORD}~{n}"),
+            Self::First => write!(f, "{FIRST_KEYWORD}"),
+            Self::AtOrBefore(time) => write!(f, "{}", time.format("%Y-%m-%dT%H:%M:%SZ")),
+            Self::Note(text) => write!(f, "{NOTE_PREFIX}{text}"),
+        }
+    }
+}
+
+impl FromStr for BackupSelector {
+    type Err = BackupSelectorError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+/// Parses the date/time, or returns `None` if it doesn't look like one.
+fn parse_time(s: &str) -> Result<Option<Timestamp>, BackupSelectorError> {
+    if let Ok(time) = DateTime::parse_from_rfc3339(s) {
+        return Ok(Some(Timestamp::from_utc(&time.to_utc())));
+    }
+
+    let naive = LOCAL_DATETIME_FORMATS
+        .iter()
+        .find_map(|fmt| NaiveDateTime::parse_from_str(s, fmt).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(23, 59, 59))
+        });
+    let Some(naive) = naive else {
+        return Ok(None);
+    };
+
+    // On a DST transition, the later of the ambiguous times is used.
+    let local = Local
+        .from_local_datetime(&naive)
+        .latest()
+        .ok_or_else(|| BackupSelectorError::InvalidTime(s.to_string()))?;
+    Ok(Some(Timestamp::from_utc(&local.to_utc())))
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    fn ts(s: &str) -> Timestamp {
+        Timestamp::from_fmt_str(s).unwrap()
+    }
+
+    fn make_backups() -> Vec<BackupEntry> {
+        [
+            (1, "20250101T090000Z", "Before the update"),
+            (2, "20250102T090000Z", ""),
+            (3, "20250103T090000Z", "before cleanup"),
+            (4, "20250104T090000Z", "weekly"),
+        ]
+        .into_iter()
+        .map(|(id, time, note)| BackupEntry::new(id, Path::new("backup.tar.gz"), ts(time), note))
+        .collect()
+    }
+
+    fn select(s: &str) -> Option<u32> {
+        let backups = make_backups();
+        let selector = BackupSelector::parse(s).unwrap();
+        selector.select(&backups).map(|entry| entry.id)
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn it_parses_keywords() {
+            assert_eq!(BackupSelector::parse("3"), Ok(BackupSelector::Id(3)));
+            assert_eq!(
+                BackupSelector::parse("latest"),
+                Ok(BackupSelector::Latest(0))
+            );
+            assert_eq!(
+                BackupSelector::parse("latest~2"),
+                Ok(BackupSelector::Latest(2))
+            );
+            assert_eq!(BackupSelector::parse(" first "), Ok(BackupSelector::First));
+            assert_eq!(
+                BackupSelector::parse("note:weekly"),
+                Ok(BackupSelector::Note(String::from("weekly")))
+            );
+            assert_eq!(
+                BackupSelector::parse("weekly"),
+                Ok(BackupSelector::Note(String::from("weekly")))
+            );
+        }
+
+        #[test]
+        fn it_parses_times() {
+            assert_eq!(
+                BackupSelector::parse("2025-01-23T12:34:56Z"),
+                Ok(BackupSelector::AtOrBefore(ts("20250123T123456Z")))
+            );
+
+            let local = Local.with_ymd_and_hms(2025, 1, 23, 12, 34, 0).unwrap();
+            let expected = BackupSelector::AtOrBefore(Timestamp::from_utc(&local.to_utc()));
+            assert_eq!(
+                BackupSelector::parse("2025-01-23 12:34"),
+                Ok(expected.clone())
+            );
+            assert_eq!(BackupSelector::parse("2025-01-23T12:34"), Ok(expected));
+
+            let local = Local.with_ymd_and_hms(2025, 1, 23, 23, 59, 59).unwrap();
+            let expected = BackupSelector::AtOrBefore(Timestamp::from_utc(&local.to_utc()));
+            assert_eq!(BackupSelector::parse("2025-01-23"), Ok(expected));
+        }
+
+        #[test]
+        fn it_rejects_invalid_selectors() {
+            assert_eq!(BackupSelector::parse(" "), Err(BackupSelectorError::Empty));
+            assert_eq!(
+                BackupSelector::parse("note:"),
+                Err(BackupSelectorError::Empty)
+            );
+            for s in ["latest~", "latest~x", "latest-1", "latest~-1"] {
+                assert_eq!(
+                    BackupSelector::parse(s),
+                    Err(BackupSelectorError::InvalidOffset(s.to_string()))
+                );
+            }
+        }
+
+        #[test]
+        fn it_displays_parsable_strings() {
+            for s in [
+                "3",
+                "latest",
+                "latest~2",
+                "first",
+                "2025-01-23T12:34:56Z",
+                "note:weekly",
+            ] {
+                let selector = BackupSelector::parse(s).unwrap();
+                assert_eq!(selector.to_string(), s);
+                assert_eq!(BackupSelector::parse(&selector.to_string()), Ok(selector));
+            }
+        }
+    }
+
+    mod select {
+        use super::*;
+
+        #[test]
+        fn it_selects_by_id_and_position() {
+            assert_eq!(select("2"), Some(2));
+            assert_eq!(select("9"), None);
+            assert_eq!(select("latest"), Some(4));
+            assert_eq!(select("latest~1"), Some(3));
+            assert_eq!(select("latest~3"), Some(1));
+            assert_eq!(select("latest~4"), None);
+            assert_eq!(select("first"), Some(1));
+        }
+
+        #[test]
+        fn it_selects_newest_at_or_before_time() {
+            assert_eq!(select("2025-01-03T09:00:00Z"), Some(3));
+            assert_eq!(select("2025-01-03T08:59:59Z"), Some(2));
+            assert_eq!(select("2030-01-01T00:00:00Z"), Some(4));
+            assert_eq!(select("2024-12-31T00:00:00Z"), None);
+        }
+
+        #[test]
+        fn it_selects_newest_note_match() {
+            assert_eq!(select("note:before"), Some(3));
+            assert_eq!(select("UPDATE"), Some(1));
+            assert_eq!(select("monthly"), None);
+        }
+
+        #[test]
+        fn it_returns_none_if_no_backups() {
+            let selector = BackupSelector::parse("latest").unwrap();
+            assert!(selector.select(&[]).is_none());
+        }
+    }
+}
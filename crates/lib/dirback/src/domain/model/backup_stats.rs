@@ -0,0 +1,49 @@
+//!
+//! # BackupStats
+//!
+//! The sizes of a backup, recorded when its archive is created.
+//!
+
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackupStats {
+    /// The size of the archive file in bytes.
+    pub size: u64,
+
+    /// The total size of the archived files in bytes.
+    pub uncompressed_size: u64,
+
+    /// The number of the archived files, without directories.
+    pub files: u64,
+
+    /// The time taken to create the archive, in milliseconds.
+    pub duration_ms: u64,
+}
+
+impl BackupStats {
+    /// Returns the archive size divided by the uncompressed size,
+    /// or `None` if nothing was archived.
+    pub fn ratio(&self) -> Option<f64> {
+        (self.uncompressed_size > 0).then(|| self.size as f64 / self.uncompressed_size as f64)
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ratio() {
+        let stats = BackupStats {
+            size: 25,
+            uncompressed_size: 100,
+            ..Default::default()
+        };
+        assert_eq!(stats.ratio(), Some(0.25));
+        assert_eq!(BackupStats::default().ratio(), None);
+    }
+}
@@ -0,0 +1,81 @@
+//!
+//! # Bundle
+//!
+//! A bundle is a single portable file containing a target, its backup
+//! entries and their archives, to move a target to another data directory.
+//!
+//! The manifest describes the contents of the bundle.
+//!
+
+use crate::domain::model::target::Target;
+use crate::domain::model::timestamp::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// The bundle format version written by this version of dirback.
+pub const BUNDLE_FORMAT_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BundleManifest {
+    pub format_version: u32,
+
+    /// When the bundle was created.
+    pub created_at: Timestamp,
+
+    /// The target, with the backup entries included in the bundle.
+    pub target: Target,
+
+    pub archives: Vec<BundleArchive>,
+}
+
+/// An archive file in the bundle.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BundleArchive {
+    pub backup_id: u32,
+
+    /// The file name in the bundle.
+    pub file_name: String,
+
+    /// SHA-256 of the archive file.
+    pub checksum: String,
+}
+
+impl BundleManifest {
+    pub fn new(target: &Target) -> Self {
+        Self {
+            format_version: BUNDLE_FORMAT_VERSION,
+            created_at: Timestamp::now(),
+            target: target.clone(),
+            archives: Vec::new(),
+        }
+    }
+
+    pub fn find_archive(&self, backup_id: u32) -> Option<&BundleArchive> {
+        self.archives.iter().find(|a| a.backup_id == backup_id)
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn it_serializable() {
+        let target = Target::new("xxx", "Test Target", Path::new("target"));
+        let mut src = BundleManifest::new(&target);
+        src.archives.push(BundleArchive {
+            backup_id: 1,
+            file_name: String::from("0001_20250123T123456Z.tar.gz"),
+            checksum: String::from("abc"),
+        });
+
+        let s = serde_json::to_string(&src).unwrap();
+        let dst: BundleManifest = serde_json::from_str(&s).unwrap();
+        assert_eq!(dst, src);
+        assert!(dst.find_archive(1).is_some());
+        assert!(dst.find_archive(2).is_none());
+    }
+}
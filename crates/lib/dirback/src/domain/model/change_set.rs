@@ -0,0 +1,121 @@
+//!
+//! # ChangeSet
+//!
+//! The changed paths in a target since the last backup,
+//! collected by the watch mode.
+//!
+
+use std::collections::BTreeSet;
+use std::path::{Path, PathBuf};
+
+/// The number of paths listed in the note.
+const NOTE_PATH_LIMIT: usize = 5;
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ChangeSet {
+    /// Changed paths relative to the target, sorted.
+    paths: BTreeSet<PathBuf>,
+}
+
+impl ChangeSet {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a changed path. Returns false if the path is not under the root.
+    pub fn add(&mut self, root: &Path, path: &Path) -> bool {
+        self.add_with_prefix(Path::new(""), root, path)
+    }
+
+    /// Adds a changed path of a named source, listed under the source name
+    /// as in the archives. Returns false if the path is not under the root.
+    pub fn add_source(&mut self, name: &str, root: &Path, path: &Path) -> bool {
+        self.add_with_prefix(Path::new(name), root, path)
+    }
+
+    fn add_with_prefix(&mut self, prefix: &Path, root: &Path, path: &Path) -> bool {
+        match path.strip_prefix(root) {
+            Ok(rel) if !rel.as_os_str().is_empty() => {
+                self.paths.insert(prefix.join(rel));
+                true
+            }
+            _ => false,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.paths.is_empty()
+    }
+
+    pub fn clear(&mut self) {
+        self.paths.clear();
+    }
+
+    /// Makes the note of the backup, e.g. `Changed (2): a.txt, dir/b.txt`.
+    pub fn note(&self) -> String {
+        let mut listed: Vec<String> = self
+            .paths
+            .iter()
+            .take(NOTE_PATH_LIMIT)
+            .map(|p| p.to_string_lossy().to_string())
+            .collect();
+        if self.paths.len() > NOTE_PATH_LIMIT {
+            listed.push(String::from("..."));
+        }
+
+        format!("Changed ({}): {}", self.paths.len(), listed.join(", "))
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_collects_relative_paths() {
+        let root = Path::new("/home/user/notes");
+        let mut changes = ChangeSet::new();
+
+        assert!(changes.add(root, &root.join("b.txt")));
+        assert!(changes.add(root, &root.join("a/c.txt")));
+        assert!(changes.add(root, &root.join("b.txt")));
+        assert!(!changes.add(root, root));
+        assert!(!changes.add(root, Path::new("/tmp/other.txt")));
+
+        assert_eq!(changes.note(), "Changed (2): a/c.txt, b.txt");
+
+        changes.clear();
+        assert!(changes.is_empty());
+    }
+
+    #[test]
+    fn it_lists_source_paths_under_source_name() {
+        let mut changes = ChangeSet::new();
+
+        assert!(changes.add_source(
+            "config",
+            Path::new("/etc/app"),
+            Path::new("/etc/app/a.conf")
+        ));
+        assert!(!changes.add_source("config", Path::new("/etc/app"), Path::new("/tmp/a.conf")));
+
+        assert_eq!(changes.note(), "Changed (1): config/a.conf");
+    }
+
+    #[test]
+    fn it_limits_paths_in_note() {
+        let root = Path::new("/root");
+        let mut changes = ChangeSet::new();
+        for i in 0..7 {
+            changes.add(root, &root.join(format!("{i}.txt")));
+        }
+
+        assert_eq!(
+            changes.note(),
+            "Changed (7): 0.txt, 1.txt, 2.txt, 3.txt, 4.txt, ..."
+        );
+    }
+}
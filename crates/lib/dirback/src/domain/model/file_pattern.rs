@@ -0,0 +1,143 @@
+//!
+//! # FilePattern
+//!
+//! A path or a glob pattern to find files in the backups.
+//!
+//! - A pattern with `/` is matched against the whole relative path in the backup,
+//!   e.g. `config/prod.yaml`, `config/*.yaml`, `src/**/*.rs`
+//! - A pattern without `/` is matched against the file name,
+//!   e.g. `prod.yaml`, `*.yaml`
+//!
+//! `*` and `?` do not match `/`, and `**` matches any number of directories.
+//! A leading `./` or `/` is ignored.
+//!
+
+use std::path::Path;
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum FilePatternError {
+    #[error("Empty file pattern.")]
+    Empty,
+
+    #[error("Invalid file pattern: '{0}' ({1})")]
+    Invalid(String, String),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct FilePattern {
+    pattern: glob::Pattern,
+
+    /// Whether the pattern is matched against the file name only.
+    by_name: bool,
+}
+
+const MATCH_OPTIONS: glob::MatchOptions = glob::MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+impl FilePattern {
+    pub fn as_str(&self) -> &str {
+        self.pattern.as_str()
+    }
+
+    /// Returns true if the relative path in the backup matches the pattern.
+    pub fn matches(&self, path: &Path) -> bool {
+        if self.by_name {
+            path.file_name().is_some_and(|name| {
+                self.pattern
+                    .matches_path_with(Path::new(name), MATCH_OPTIONS)
+            })
+        } else {
+            self.pattern.matches_path_with(path, MATCH_OPTIONS)
+        }
+    }
+}
+
+impl FromStr for FilePattern {
+    type Err = FilePatternError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let trimmed = s.trim();
+        let trimmed = trimmed.strip_prefix("./").unwrap_or(trimmed);
+        let trimmed = trimmed.trim_start_matches('/');
+        if trimmed.is_empty() {
+            return Err(FilePatternError::Empty);
+        }
+
+        let pattern = glob::Pattern::new(trimmed)
+            .map_err(|e| FilePatternError::Invalid(s.to_string(), e.msg.to_string()))?;
+        Ok(Self {
+            pattern,
+            by_name: !trimmed.contains('/'),
+        })
+    }
+}
+
+impl std::fmt::Display for FilePattern {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+impl serde::Serialize for FilePattern {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn matches(pattern: &str, path: &str) -> bool {
+        pattern
+            .parse::<FilePattern>()
+            .unwrap()
+            .matches(Path::new(path))
+    }
+
+    #[test]
+    fn it_matches_path() {
+        assert!(matches("config/prod.yaml", "config/prod.yaml"));
+        assert!(matches("./config/prod.yaml", "config/prod.yaml"));
+        assert!(matches("/config/prod.yaml", "config/prod.yaml"));
+        assert!(!matches("config/prod.yaml", "app/config/prod.yaml"));
+
+        assert!(matches("config/*.yaml", "config/prod.yaml"));
+        assert!(!matches("config/*.yaml", "config/env/prod.yaml"));
+        assert!(matches("config/**/*.yaml", "config/env/prod.yaml"));
+        assert!(matches("config/**/*.yaml", "config/prod.yaml"));
+        assert!(matches("**/prod.yaml", "app/config/prod.yaml"));
+    }
+
+    #[test]
+    fn it_matches_file_name() {
+        assert!(matches("prod.yaml", "prod.yaml"));
+        assert!(matches("prod.yaml", "app/config/prod.yaml"));
+        assert!(matches("*.yaml", "app/config/prod.yaml"));
+        assert!(matches(".env", "app/.env"));
+        assert!(!matches("prod.yaml", "prod.yaml.bak"));
+    }
+
+    #[test]
+    fn it_rejects_invalid_patterns() {
+        assert_eq!(
+            "".parse::<FilePattern>().unwrap_err(),
+            FilePatternError::Empty
+        );
+        assert_eq!(
+            "./".parse::<FilePattern>().unwrap_err(),
+            FilePatternError::Empty
+        );
+        assert!(matches!(
+            "config/[a".parse::<FilePattern>(),
+            Err(FilePatternError::Invalid(..))
+        ));
+    }
+}
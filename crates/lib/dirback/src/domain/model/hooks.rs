@@ -0,0 +1,179 @@
+//!
+//! # Hooks
+//!
+//! Commands run around the backups and restores of a target,
+//! e.g. to stop a database before its data directory is archived.
+//!
+//! - pre-backup / pre-restore ... A failure aborts the operation.
+//! - post-backup ... Runs right after the archive is created.
+//! - post-restore ... Runs after the restore.
+//! - on-failure ... Runs when the operation (or one of its hooks) fails.
+//!
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// The timeout of a hook command, if not set on the target.
+pub const DEFAULT_HOOK_TIMEOUT_SECS: u64 = 300;
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum HookError {
+    #[error(
+        "Unknown hook: '{0}' (use pre-backup, post-backup, pre-restore, post-restore or on-failure)"
+    )]
+    UnknownHook(String),
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HookEvent {
+    PreBackup,
+    PostBackup,
+    PreRestore,
+    PostRestore,
+    OnFailure,
+}
+
+impl HookEvent {
+    pub const ALL: [HookEvent; 5] = [
+        Self::PreBackup,
+        Self::PostBackup,
+        Self::PreRestore,
+        Self::PostRestore,
+        Self::OnFailure,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::PreBackup => "pre-backup",
+            Self::PostBackup => "post-backup",
+            Self::PreRestore => "pre-restore",
+            Self::PostRestore => "post-restore",
+            Self::OnFailure => "on-failure",
+        }
+    }
+}
+
+impl std::str::FromStr for HookEvent {
+    type Err = HookError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|e| e.name() == s)
+            .ok_or_else(|| HookError::UnknownHook(s.to_string()))
+    }
+}
+
+impl std::fmt::Display for HookEvent {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// The hook commands of a target, run by the shell.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Hooks {
+    pub pre_backup: Option<String>,
+    pub post_backup: Option<String>,
+    pub pre_restore: Option<String>,
+    pub post_restore: Option<String>,
+    pub on_failure: Option<String>,
+
+    /// The timeout of each command in seconds, `None` for `DEFAULT_HOOK_TIMEOUT_SECS`.
+    pub timeout_secs: Option<u64>,
+}
+
+impl Hooks {
+    pub fn command(&self, event: HookEvent) -> Option<&str> {
+        self.slot(event).as_deref()
+    }
+
+    /// Sets the command of the hook, `None` (or an empty command) removes it.
+    pub fn set_command(&mut self, event: HookEvent, command: Option<&str>) {
+        *self.slot_mut(event) = command
+            .map(str::trim)
+            .filter(|c| !c.is_empty())
+            .map(str::to_string);
+    }
+
+    pub fn timeout(&self) -> Duration {
+        Duration::from_secs(self.timeout_secs.unwrap_or(DEFAULT_HOOK_TIMEOUT_SECS))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        HookEvent::ALL.iter().all(|e| self.command(*e).is_none())
+    }
+
+    fn slot(&self, event: HookEvent) -> &Option<String> {
+        match event {
+            HookEvent::PreBackup => &self.pre_backup,
+            HookEvent::PostBackup => &self.post_backup,
+            HookEvent::PreRestore => &self.pre_restore,
+            HookEvent::PostRestore => &self.post_restore,
+            HookEvent::OnFailure => &self.on_failure,
+        }
+    }
+
+    fn slot_mut(&mut self, event: HookEvent) -> &mut Option<String> {
+        match event {
+            HookEvent::PreBackup => &mut self.pre_backup,
+            HookEvent::PostBackup => &mut self.post_backup,
+            HookEvent::PreRestore => &mut self.pre_restore,
+            HookEvent::PostRestore => &mut self.post_restore,
+            HookEvent::OnFailure => &mut self.on_failure,
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_event_names() {
+        for event in HookEvent::ALL {
+            assert_eq!(event.name().parse::<HookEvent>().unwrap(), event);
+        }
+        assert!("pre_backup".parse::<HookEvent>().is_err());
+    }
+
+    #[test]
+    fn it_sets_and_removes_commands() {
+        let mut hooks = Hooks::default();
+        assert!(hooks.is_empty());
+
+        hooks.set_command(HookEvent::PreBackup, Some(" systemctl stop db "));
+        assert_eq!(
+            hooks.command(HookEvent::PreBackup),
+            Some("systemctl stop db")
+        );
+        assert_eq!(hooks.command(HookEvent::PostBackup), None);
+        assert!(!hooks.is_empty());
+
+        hooks.set_command(HookEvent::PreBackup, Some(""));
+        assert!(hooks.is_empty());
+    }
+
+    #[test]
+    fn it_has_default_timeout() {
+        let mut hooks = Hooks::default();
+        assert_eq!(
+            hooks.timeout(),
+            Duration::from_secs(DEFAULT_HOOK_TIMEOUT_SECS)
+        );
+
+        hooks.timeout_secs = Some(5);
+        assert_eq!(hooks.timeout(), Duration::from_secs(5));
+    }
+
+    #[test]
+    fn it_deserializes_partial_hooks() {
+        let hooks: Hooks = serde_json::from_str(r#"{ "pre_backup": "true" }"#).unwrap();
+        assert_eq!(hooks.command(HookEvent::PreBackup), Some("true"));
+        assert_eq!(hooks.timeout_secs, None);
+    }
+}
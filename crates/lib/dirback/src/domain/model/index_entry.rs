@@ -0,0 +1,35 @@
+//!
+//! # IndexEntry
+//!
+//! An entry in the index of a backup: a file, a directory or a symbolic link,
+//! to browse the backup without restoring it.
+//!
+
+use crate::domain::model::timestamp::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub enum IndexEntryKind {
+    File,
+    Dir,
+
+    /// A symbolic link to the path.
+    Symlink(PathBuf),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct IndexEntry {
+    /// The relative path in the backup.
+    pub path: PathBuf,
+    pub kind: IndexEntryKind,
+
+    /// The size of the file in bytes, 0 for the others.
+    pub size: u64,
+
+    /// The modification time, `None` if not recorded in the backup.
+    pub mtime: Option<Timestamp>,
+
+    /// The permission bits, e.g. `0o644`.
+    pub mode: u32,
+}
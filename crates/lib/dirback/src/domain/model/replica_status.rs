@@ -0,0 +1,94 @@
+//!
+//! # ReplicaStatus
+//!
+//! ReplicaStatus represents the state of a backup in a replica location.
+//!
+//! A replica location is a secondary data directory (e.g. an external drive,
+//! a NAS mount) that holds copies of the archives and the target information.
+//!
+
+use crate::domain::model::timestamp::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReplicaState {
+    /// The archive was copied and its checksum verified.
+    Synced,
+
+    /// The last replication failed.
+    Failed,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplicaStatus {
+    /// The replica location.
+    pub replica: PathBuf,
+
+    /// Path to the archive in the replica.
+    pub path: PathBuf,
+
+    pub state: ReplicaState,
+
+    /// When the state was updated.
+    pub timestamp: Timestamp,
+
+    /// The error message if the replication failed.
+    pub message: String,
+}
+
+impl ReplicaStatus {
+    pub fn synced(replica: &Path, path: &Path) -> Self {
+        Self {
+            replica: replica.to_path_buf(),
+            path: path.to_path_buf(),
+            state: ReplicaState::Synced,
+            timestamp: Timestamp::now(),
+            message: String::new(),
+        }
+    }
+
+    pub fn failed(replica: &Path, path: &Path, message: &str) -> Self {
+        Self {
+            replica: replica.to_path_buf(),
+            path: path.to_path_buf(),
+            state: ReplicaState::Failed,
+            timestamp: Timestamp::now(),
+            message: message.to_string(),
+        }
+    }
+
+    pub fn is_synced(&self) -> bool {
+        self.state == ReplicaState::Synced
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_synced() {
+        let status = ReplicaStatus::synced(Path::new("/mnt/nas"), Path::new("/mnt/nas/1.tar.gz"));
+        assert!(status.is_synced());
+        assert_eq!(status.message, "");
+    }
+
+    #[test]
+    fn test_failed() {
+        let status = ReplicaStatus::failed(Path::new("/mnt/nas"), Path::new(""), "disk full");
+        assert!(!status.is_synced());
+        assert_eq!(status.message, "disk full");
+    }
+
+    #[test]
+    fn it_serializes_state_in_lowercase() {
+        let status = ReplicaStatus::synced(Path::new("/mnt/nas"), Path::new("/mnt/nas/1.tar.gz"));
+        let json = serde_json::to_value(&status).unwrap();
+        assert_eq!(json["state"], "synced");
+    }
+}
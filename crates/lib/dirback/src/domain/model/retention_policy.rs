@@ -0,0 +1,127 @@
+//!
+//! # Retention policy
+//!
+//! Decides which backups are kept when old backups are pruned.
+//!
+
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::model::timestamp::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// A backup is kept if any of the rules keeps it.
+///
+/// A policy without rules keeps every backup.
+/// Pinned backups are always kept, and not counted by `keep_last`.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct RetentionPolicy {
+    /// Keeps the latest N backups.
+    pub keep_last: Option<usize>,
+
+    /// Keeps the backups taken within N days.
+    pub keep_days: Option<u32>,
+}
+
+impl RetentionPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.keep_last.is_none() && self.keep_days.is_none()
+    }
+
+    /// Returns the IDs of the backups which are not kept.
+    pub fn expired(&self, backups: &[BackupEntry], now: &Timestamp) -> Vec<u32> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let mut sorted: Vec<&BackupEntry> = backups.iter().filter(|b| !b.pinned).collect();
+        sorted.sort_by(|a, b| (*b.timestamp).cmp(&*a.timestamp).then(b.id.cmp(&a.id)));
+
+        let threshold = self
+            .keep_days
+            .map(|days| **now - chrono::Duration::days(i64::from(days)));
+
+        let mut expired: Vec<u32> = sorted
+            .iter()
+            .enumerate()
+            .filter(|(i, entry)| {
+                let by_count = self.keep_last.is_some_and(|n| *i < n);
+                let by_age = threshold.is_some_and(|t| *entry.timestamp >= t);
+                !(by_count || by_age)
+            })
+            .map(|(_, entry)| entry.id)
+            .collect();
+        expired.sort();
+        expired
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// Backups taken 1, 2, ... days ago, the newest has the largest ID.
+    fn backups(now: &Timestamp, count: u32) -> Vec<BackupEntry> {
+        (1..=count)
+            .map(|id| {
+                let ts = **now - chrono::Duration::days(i64::from(count - id + 1));
+                BackupEntry::new(id, Path::new("backup"), Timestamp::from_utc(&ts), "")
+            })
+            .collect()
+    }
+
+    #[test]
+    fn empty_policy_keeps_everything() {
+        let now = Timestamp::now();
+        let policy = RetentionPolicy::default();
+        assert!(policy.expired(&backups(&now, 5), &now).is_empty());
+    }
+
+    #[test]
+    fn it_keeps_last_n() {
+        let now = Timestamp::now();
+        let policy = RetentionPolicy {
+            keep_last: Some(2),
+            ..Default::default()
+        };
+        assert_eq!(policy.expired(&backups(&now, 5), &now), vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn it_keeps_within_days() {
+        let now = Timestamp::now();
+        let policy = RetentionPolicy {
+            keep_days: Some(3),
+            ..Default::default()
+        };
+        // Taken 5, 4, 3, 2, 1 days ago.
+        assert_eq!(policy.expired(&backups(&now, 5), &now), vec![1, 2]);
+    }
+
+    #[test]
+    fn it_keeps_pinned_backups() {
+        let now = Timestamp::now();
+        let policy = RetentionPolicy {
+            keep_last: Some(2),
+            ..Default::default()
+        };
+        let mut backups = backups(&now, 5);
+        backups[0].pinned = true;
+        backups[4].pinned = true;
+
+        // The pinned 5th is not one of the latest two.
+        assert_eq!(policy.expired(&backups, &now), vec![2]);
+    }
+
+    #[test]
+    fn rules_are_combined() {
+        let now = Timestamp::now();
+        let policy = RetentionPolicy {
+            keep_last: Some(4),
+            keep_days: Some(1),
+        };
+        assert_eq!(policy.expired(&backups(&now, 5), &now), vec![1]);
+    }
+}
@@ -0,0 +1,331 @@
+//!
+//! # Schedule
+//!
+//! When the scheduled backups of a target run.
+//!
+//! A schedule is written as a string:
+//! - `every <duration>` ... e.g. `every 6h`, `every 1d12h`, `every 30m`
+//! - a cron expression ... e.g. `0 3 * * *`, `@daily`
+//!
+//! Cron expressions have 5 fields (minute, hour, day of month, month, day of week),
+//! the seconds and the year fields may also be given.
+//! The day of week is numbered as in crontab (0 or 7 is Sunday, 1 is Monday),
+//! or written by the names (e.g. `MON-FRI`).
+//! They are evaluated in the local time zone.
+//!
+
+use crate::domain::model::timestamp::Timestamp;
+use chrono::Local;
+use serde::{Deserialize, Serialize};
+use std::str::FromStr;
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum ScheduleError {
+    #[error("Invalid interval: '{0}' (e.g. 'every 6h', 'every 1d12h')")]
+    InvalidInterval(String),
+
+    #[error("Invalid cron expression: '{0}' ({1})")]
+    InvalidCron(String, String),
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub enum Schedule {
+    /// Runs when the interval has passed since the latest backup.
+    Interval { seconds: u64 },
+
+    /// Runs at the times of the cron expression.
+    Cron { expression: String },
+}
+
+const INTERVAL_KEYWORD: &str = "every";
+
+/// Units of intervals, from the largest.
+const INTERVAL_UNITS: [(char, u64); 4] = [('d', 86400), ('h', 3600), ('m', 60), ('s', 1)];
+
+impl Schedule {
+    pub fn parse(s: &str) -> Result<Self, ScheduleError> {
+        let s = s.trim();
+        let interval = s
+            .strip_prefix(INTERVAL_KEYWORD)
+            .filter(|rest| rest.is_empty() || rest.starts_with(char::is_whitespace));
+        match interval {
+            Some(interval) => parse_interval(interval.trim())
+                .map(|seconds| Self::Interval { seconds })
+                .ok_or_else(|| ScheduleError::InvalidInterval(s.to_string())),
+            None => {
+                cron_schedule(s)?;
+                Ok(Self::Cron {
+                    expression: s.to_string(),
+                })
+            }
+        }
+    }
+
+    /// Returns the first run time after the time.
+    ///
+    /// Returns `None` if the cron expression has no more run times.
+    pub fn next_after(&self, after: &Timestamp) -> Option<Timestamp> {
+        match self {
+            Self::Interval { seconds } => {
+                let seconds = i64::try_from(*seconds).ok()?;
+                let next = after.checked_add_signed(chrono::Duration::seconds(seconds))?;
+                Some(Timestamp::from_utc(&next))
+            }
+            Self::Cron { expression } => {
+                let schedule = cron_schedule(expression).ok()?;
+                let next = schedule.after(&after.with_timezone(&Local)).next()?;
+                Some(Timestamp::from_utc(&next.to_utc()))
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for Schedule {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Interval { seconds } => {
+                write!(f, "{INTERVAL_KEYWORD} ")?;
+                let mut rest = *seconds;
+                for (unit, size) in INTERVAL_UNITS {
+                    if rest >= size {
+                        write!(f, "{}{unit}", rest / size)?;
+                        rest %= size;
+                    }
+                }
+                Ok(())
+            }
+            Self::Cron { expression } => write!(f, "{expression}"),
+        }
+    }
+}
+
+impl FromStr for Schedule {
+    type Err = ScheduleError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for Schedule {
+    type Error = ScheduleError;
+
+    fn try_from(s: String) -> Result<Self, Self::Error> {
+        Self::parse(&s)
+    }
+}
+
+impl From<Schedule> for String {
+    fn from(schedule: Schedule) -> Self {
+        schedule.to_string()
+    }
+}
+
+/// Parses the interval like `1d12h` into seconds.
+fn parse_interval(s: &str) -> Option<u64> {
+    let mut seconds: u64 = 0;
+    let mut number = String::new();
+    for c in s.chars() {
+        if c.is_ascii_digit() {
+            number.push(c);
+            continue;
+        }
+
+        let (_, size) = INTERVAL_UNITS.iter().find(|(unit, _)| *unit == c)?;
+        let n: u64 = number.parse().ok()?;
+        seconds = seconds.checked_add(n.checked_mul(*size)?)?;
+        number.clear();
+    }
+
+    if !number.is_empty() || seconds == 0 {
+        return None;
+    }
+    Some(seconds)
+}
+
+/// Parses the cron expression, adding the seconds field to a 5 fields expression.
+fn cron_schedule(expression: &str) -> Result<cron::Schedule, ScheduleError> {
+    let invalid = |e: String| ScheduleError::InvalidCron(expression.to_string(), e);
+
+    let mut fields: Vec<String> = expression.split_whitespace().map(String::from).collect();
+    if fields.len() == 5 {
+        fields.insert(0, String::from("0"));
+    }
+    if fields.len() > 5 {
+        fields[5] = day_of_week(&fields[5]).map_err(invalid)?;
+    }
+
+    cron::Schedule::from_str(&fields.join(" ")).map_err(|e| invalid(e.to_string()))
+}
+
+/// Converts the crontab numbers of the day of week field (0 or 7 is Sunday)
+/// to the numbers of the `cron` crate (1 is Sunday).
+///
+/// The numbers and the ranges are expanded to lists, and the names are left as they are.
+fn day_of_week(field: &str) -> Result<String, String> {
+    let mut items = Vec::new();
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, Some(step)),
+            None => (item, None),
+        };
+        let bounds: Vec<&str> = range.split('-').collect();
+        if !bounds
+            .iter()
+            .all(|b| !b.is_empty() && b.bytes().all(|c| c.is_ascii_digit()))
+        {
+            items.push(item.to_string());
+            continue;
+        }
+
+        let day = |s: &str| {
+            s.parse::<u32>()
+                .ok()
+                .filter(|d| *d <= 7)
+                .ok_or_else(|| format!("day of week out of range: '{s}'"))
+        };
+        let start = day(bounds[0])?;
+        let end = match (bounds.len(), step) {
+            (1, None) => start,
+            (1, Some(_)) => 6,
+            (2, _) => day(bounds[1])?,
+            _ => return Err(format!("invalid day of week: '{item}'")),
+        };
+        let step = match step {
+            Some(step) => step
+                .parse::<usize>()
+                .ok()
+                .filter(|s| *s > 0)
+                .ok_or_else(|| format!("invalid step: '{item}'"))?,
+            None => 1,
+        };
+        if start > end {
+            return Err(format!("invalid day of week range: '{item}'"));
+        }
+
+        items.extend((start..=end).step_by(step).map(|d| (d % 7 + 1).to_string()));
+    }
+    Ok(items.join(","))
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ts(s: &str) -> Timestamp {
+        Timestamp::from_fmt_str(s).unwrap()
+    }
+
+    mod parse {
+        use super::*;
+
+        #[test]
+        fn it_parses_intervals() {
+            let schedule = Schedule::parse("every 1d12h").unwrap();
+            assert_eq!(schedule, Schedule::Interval { seconds: 129600 });
+            assert_eq!(schedule.to_string(), "every 1d12h");
+
+            let schedule = Schedule::parse("every 90m").unwrap();
+            assert_eq!(schedule.to_string(), "every 1h30m");
+        }
+
+        #[test]
+        fn it_rejects_invalid_intervals() {
+            for s in ["every", "every 6", "every 6x", "every h", "every 0h"] {
+                assert!(
+                    matches!(Schedule::parse(s), Err(ScheduleError::InvalidInterval(_))),
+                    "{s}"
+                );
+            }
+        }
+
+        #[test]
+        fn it_parses_cron_expressions() {
+            for s in ["0 3 * * *", "@daily", "0 0 3 * * Mon-Fri *"] {
+                let schedule = Schedule::parse(s).unwrap();
+                assert_eq!(schedule.to_string(), s);
+            }
+        }
+
+        #[test]
+        fn it_rejects_invalid_cron_expressions() {
+            let result = Schedule::parse("61 * * * *");
+            assert!(matches!(result, Err(ScheduleError::InvalidCron(_, _))));
+        }
+
+        #[test]
+        fn it_serializes_as_string() {
+            let schedule = Schedule::parse("every 6h").unwrap();
+            let json = serde_json::to_string(&schedule).unwrap();
+            assert_eq!(json, "\"every 6h\"");
+
+            let parsed: Schedule = serde_json::from_str(&json).unwrap();
+            assert_eq!(parsed, schedule);
+            assert!(serde_json::from_str::<Schedule>("\"every\"").is_err());
+        }
+    }
+
+    mod next_after {
+        use super::*;
+
+        #[test]
+        fn it_adds_interval() {
+            let schedule = Schedule::parse("every 6h").unwrap();
+            let next = schedule.next_after(&ts("20250101T220000Z")).unwrap();
+            assert_eq!(next, ts("20250102T040000Z"));
+        }
+
+        /// Returns the weekday of the next run after Saturday, 2025-01-04.
+        fn next_weekday(s: &str) -> chrono::Weekday {
+            use chrono::Datelike;
+
+            let schedule = Schedule::parse(s).unwrap();
+            let next = schedule.next_after(&ts("20250104T120000Z")).unwrap();
+            next.with_timezone(&Local).weekday()
+        }
+
+        #[test]
+        fn it_numbers_days_of_week_as_crontab() {
+            use chrono::Weekday;
+
+            assert_eq!(next_weekday("0 9 * * 1-5"), Weekday::Mon);
+            assert_eq!(next_weekday("0 9 * * 0"), Weekday::Sun);
+            assert_eq!(next_weekday("0 9 * * 7"), Weekday::Sun);
+            assert_eq!(next_weekday("0 9 * * 5-7"), Weekday::Sun);
+            assert_eq!(next_weekday("0 9 * * 1,3"), Weekday::Mon);
+            assert_eq!(next_weekday("0 9 * * MON-FRI"), Weekday::Mon);
+            assert_eq!(next_weekday("0 0 9 * * 2 *"), Weekday::Tue);
+
+            // Monday to Friday never runs on the weekend.
+            let schedule = Schedule::parse("0 9 * * 1-5").unwrap();
+            let mut after = ts("20250104T000000Z");
+            for _ in 0..10 {
+                let next = schedule.next_after(&after).unwrap();
+                let weekday = chrono::Datelike::weekday(&next.with_timezone(&Local));
+                assert!(!matches!(weekday, Weekday::Sat | Weekday::Sun), "{weekday}");
+                after = next;
+            }
+        }
+
+        #[test]
+        fn it_rejects_invalid_days_of_week() {
+            for s in ["0 9 * * 8", "0 9 * * 5-1", "0 9 * * 1-2-3", "0 9 * * 1/0"] {
+                assert!(
+                    matches!(Schedule::parse(s), Err(ScheduleError::InvalidCron(_, _))),
+                    "{s}"
+                );
+            }
+        }
+
+        #[test]
+        fn it_returns_next_cron_time() {
+            let schedule = Schedule::parse("*/15 * * * *").unwrap();
+            let next = schedule.next_after(&ts("20250101T220100Z")).unwrap();
+            assert_eq!(next, ts("20250101T221500Z"));
+        }
+    }
+}
@@ -0,0 +1,73 @@
+//!
+//! # Source
+//!
+//! A source is a directory archived into the backups of a target.
+//!
+//! A target backs up its `path` only, unless it has additional sources.
+//! Then each directory is archived under its name (the `path` as `main`),
+//! e.g. `main/...`, `config/...`, `data/...`, and restored back to its own location.
+//!
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+/// The name of the target path in the archives with several sources.
+pub const MAIN_SOURCE_NAME: &str = "main";
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum SourceError {
+    #[error("Invalid source name: '{0}' (use letters, digits, '-', '_' or '.')")]
+    InvalidName(String),
+
+    #[error("Duplicate source: '{0}'")]
+    DuplicateName(String),
+
+    #[error("Sources can be added to directory targets only.")]
+    FileTarget,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Source {
+    /// The prefix of the directory in the archives.
+    pub name: String,
+
+    /// Path to the directory.
+    pub path: PathBuf,
+}
+
+impl Source {
+    pub fn new(name: &str, path: &Path) -> Self {
+        Self {
+            name: name.to_string(),
+            path: path.to_path_buf(),
+        }
+    }
+
+    /// Returns true if the name can be a prefix in the archives.
+    pub fn is_valid_name(name: &str) -> bool {
+        !name.is_empty()
+            && name != "."
+            && name != ".."
+            && name
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'))
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_validates_names() {
+        for name in ["main", "config", "app-data", "v1.2_x"] {
+            assert!(Source::is_valid_name(name), "{name}");
+        }
+        for name in ["", ".", "..", "a/b", "has space"] {
+            assert!(!Source::is_valid_name(name), "{name}");
+        }
+    }
+}
@@ -7,6 +7,11 @@
 //!
 
 use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::model::hooks::Hooks;
+use crate::domain::model::retention_policy::RetentionPolicy;
+use crate::domain::model::schedule::Schedule;
+use crate::domain::model::source::{MAIN_SOURCE_NAME, Source, SourceError};
+use crate::domain::model::target_kind::TargetKind;
 use crate::domain::model::timestamp::Timestamp;
 use serde::{Deserialize, Serialize};
 use std::path::{Path, PathBuf};
@@ -15,8 +20,21 @@ use std::path::{Path, PathBuf};
 pub enum TargetError {
     #[error("Duplicate ID")]
     DuplicateId,
+
+    #[error("Invalid tag: '{0}' (use letters, digits, '-', '_' or '.')")]
+    InvalidTag(String),
+
+    #[error("BackupEntry({0}) not found")]
+    BackupNotFound(u32),
 }
 
+/// The tag which matches every target in tag filters, so it cannot be added to a target.
+pub const ALL_TAG: &str = "all";
+
+/// A failed scheduled backup is retried after this delay,
+/// or at the next scheduled time if it is earlier.
+pub const SCHEDULE_RETRY_DELAY: chrono::Duration = chrono::Duration::hours(1);
+
 /// Target struct represents a backup target.
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 pub struct Target {
@@ -28,11 +46,58 @@ pub struct Target {
     /// Target Name.
     pub name: String,
 
-    /// Path to the directory of backup target.
+    /// Path to the directory (or the file) of backup target.
     pub path: PathBuf,
 
+    /// Whether the `path` is a directory or a single file.
+    #[serde(default)]
+    pub kind: TargetKind,
+
+    /// Additional directories archived with the `path`, each under its name.
+    #[serde(default)]
+    pub sources: Vec<Source>,
+
     /// Backup entries.
     pub backups: Vec<BackupEntry>,
+
+    /// The largest backup ID given to the target,
+    /// so the IDs of deleted (trashed) backups are not given again.
+    #[serde(default)]
+    pub last_backup_id: u32,
+
+    /// ID of the archive store where new backups are placed.
+    ///
+    /// `None` keeps backups in the data directory.
+    #[serde(default)]
+    pub store: Option<String>,
+
+    /// Replica locations (secondary data directories) the backups are copied to.
+    #[serde(default)]
+    pub replicas: Vec<PathBuf>,
+
+    /// Free-form description.
+    #[serde(default)]
+    pub description: String,
+
+    /// Tags to group targets, sorted.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// When the daemon takes backups. `None` takes backups manually only.
+    #[serde(default)]
+    pub schedule: Option<Schedule>,
+
+    /// The backups kept after each scheduled backup.
+    #[serde(default)]
+    pub retention: RetentionPolicy,
+
+    /// When the last scheduled backup failed.
+    #[serde(default)]
+    pub last_failed_run: Option<Timestamp>,
+
+    /// Commands run around the backups and restores.
+    #[serde(default)]
+    pub hooks: Hooks,
 }
 
 impl Target {
@@ -44,14 +109,25 @@ impl Target {
             id: id.to_string(),
             name: name.to_string(),
             path: target_dir_path.to_path_buf(),
+            kind: TargetKind::Directory,
+            sources: Vec::new(),
             backups: Vec::<BackupEntry>::new(),
+            last_backup_id: 0,
+            store: None,
+            replicas: Vec::new(),
+            description: String::new(),
+            tags: Vec::new(),
+            schedule: None,
+            retention: RetentionPolicy::default(),
+            last_failed_run: None,
+            hooks: Hooks::default(),
         }
     }
 
     /// Make a new backup entry.
     ///
     /// The following parameters are set automatically.
-    /// - id
+    /// - id ... after the latest backup, and any deleted backup
     /// - timestamp
     ///
     /// The backup path derived from the arguments.
@@ -61,7 +137,8 @@ impl Target {
     /// - `ext` ... The file extension of the backup file.
     ///     - example: `tar.gz`, `zip`
     pub fn new_backup_entry(&self, backup_dir: &Path, ext: &str) -> BackupEntry {
-        let next_id = self.backups.last().map_or(1, |last| last.id + 1);
+        let last_id = self.backups.last().map_or(0, |last| last.id);
+        let next_id = last_id.max(self.last_backup_id) + 1;
         let now = Timestamp::now();
 
         let mut path = backup_dir.to_path_buf();
@@ -76,13 +153,164 @@ impl Target {
             return Err(TargetError::DuplicateId);
         }
 
+        self.last_backup_id = self.last_backup_id.max(entry.id);
         self.backups.push(entry);
         Ok(())
     }
 
+    /// Put back a deleted backup entry, keeping the backups ordered by ID.
+    pub fn restore_backup_entry(&mut self, entry: BackupEntry) -> Result<(), TargetError> {
+        if self.backups.iter().any(|b| b.id == entry.id) {
+            return Err(TargetError::DuplicateId);
+        }
+
+        self.last_backup_id = self.last_backup_id.max(entry.id);
+        let pos = self.backups.partition_point(|b| b.id < entry.id);
+        self.backups.insert(pos, entry);
+        Ok(())
+    }
+
     pub fn find_backup_entry(&self, backup_id: u32) -> Option<BackupEntry> {
         self.backups.iter().find(|&b| b.id == backup_id).cloned()
     }
+
+    /// Pin or unpin the backup entry.
+    pub fn set_pinned(&mut self, backup_id: u32, pinned: bool) -> Result<(), TargetError> {
+        let entry = self
+            .backups
+            .iter_mut()
+            .find(|b| b.id == backup_id)
+            .ok_or(TargetError::BackupNotFound(backup_id))?;
+        entry.pinned = pinned;
+        Ok(())
+    }
+
+    /// Returns the IDs of the pinned backups.
+    pub fn pinned_backup_ids(&self) -> Vec<u32> {
+        self.backups
+            .iter()
+            .filter(|b| b.pinned)
+            .map(|b| b.id)
+            .collect()
+    }
+
+    /// Add a tag. Does nothing if the target already has it.
+    pub fn add_tag(&mut self, tag: &str) -> Result<(), TargetError> {
+        let valid = tag != ALL_TAG
+            && !tag.is_empty()
+            && tag
+                .chars()
+                .all(|c| c.is_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid {
+            return Err(TargetError::InvalidTag(tag.to_string()));
+        }
+
+        if let Err(pos) = self.tags.binary_search_by(|t| t.as_str().cmp(tag)) {
+            self.tags.insert(pos, tag.to_string());
+        }
+        Ok(())
+    }
+
+    /// Remove a tag. Returns false if the target does not have it.
+    pub fn remove_tag(&mut self, tag: &str) -> bool {
+        let len = self.tags.len();
+        self.tags.retain(|t| t != tag);
+        self.tags.len() != len
+    }
+
+    /// Returns true if the target has the tag. `all` matches every target.
+    pub fn matches_tag(&self, tag: &str) -> bool {
+        tag == ALL_TAG || self.tags.iter().any(|t| t == tag)
+    }
+
+    /// Add an additional source directory.
+    pub fn add_source(&mut self, name: &str, path: &Path) -> Result<(), SourceError> {
+        if self.kind == TargetKind::File {
+            return Err(SourceError::FileTarget);
+        }
+        if !Source::is_valid_name(name) {
+            return Err(SourceError::InvalidName(name.to_string()));
+        }
+        if name == MAIN_SOURCE_NAME || self.sources.iter().any(|s| s.name == name) {
+            return Err(SourceError::DuplicateName(name.to_string()));
+        }
+
+        self.sources.push(Source::new(name, path));
+        Ok(())
+    }
+
+    /// Remove an additional source directory. Returns false if the target does not have it.
+    pub fn remove_source(&mut self, name: &str) -> bool {
+        let len = self.sources.len();
+        self.sources.retain(|s| s.name != name);
+        self.sources.len() != len
+    }
+
+    /// Returns the directories archived under their names,
+    /// or an empty list if only the `path` is archived (as it is).
+    pub fn archive_sources(&self) -> Vec<Source> {
+        if self.sources.is_empty() {
+            return Vec::new();
+        }
+
+        let mut sources = vec![Source::new(MAIN_SOURCE_NAME, &self.path)];
+        sources.extend(self.sources.iter().cloned());
+        sources
+    }
+
+    /// Returns the current location of the source, `main` for the `path`.
+    pub fn source_path(&self, name: &str) -> Option<&Path> {
+        if name == MAIN_SOURCE_NAME {
+            return Some(&self.path);
+        }
+        self.sources
+            .iter()
+            .find(|s| s.name == name)
+            .map(|s| s.path.as_path())
+    }
+
+    /// Returns the directory the target lives in:
+    /// the `path` itself, or the parent directory of a file.
+    pub fn base_dir(&self) -> &Path {
+        match self.kind {
+            TargetKind::Directory => &self.path,
+            TargetKind::File => self.path.parent().unwrap_or(Path::new(".")),
+        }
+    }
+
+    /// Returns the time of the next scheduled backup, `None` if not scheduled.
+    ///
+    /// The next run is counted from the latest backup,
+    /// so a run missed while the daemon was stopped is due at once.
+    /// A target without backups is due at `now`.
+    ///
+    /// If the scheduled backup failed after the latest backup, the next run is
+    /// `SCHEDULE_RETRY_DELAY` after the failure, or the next scheduled time if it is earlier.
+    pub fn next_run(&self, now: &Timestamp) -> Option<Timestamp> {
+        let schedule = self.schedule.as_ref()?;
+        let latest = self
+            .backups
+            .iter()
+            .map(|b| &b.timestamp)
+            .max_by_key(|t| ***t);
+
+        match (&self.last_failed_run, latest) {
+            (Some(failed), latest) if latest.is_none_or(|latest| **failed > **latest) => {
+                let retry = Timestamp::from_utc(&failed.checked_add_signed(SCHEDULE_RETRY_DELAY)?);
+                match schedule.next_after(failed) {
+                    Some(next) if *next < *retry => Some(next),
+                    _ => Some(retry),
+                }
+            }
+            (_, Some(latest)) => schedule.next_after(latest),
+            (_, None) => Some(now.clone()),
+        }
+    }
+
+    /// Returns true if the scheduled backup should run at `now`.
+    pub fn is_due(&self, now: &Timestamp) -> bool {
+        self.next_run(now).is_some_and(|next| *next <= **now)
+    }
 }
 
 //-----------------------------------------------------------------------------
@@ -172,6 +400,239 @@ mod tests {
             let entry2 = target.new_backup_entry(&backup_dir, "tar.gz");
             assert_eq!(entry2.id, entry1.id + 1);
         }
+
+        #[test]
+        fn id_is_not_reused_after_delete() {
+            let mut target = prepare_target();
+            let backup_dir = prepare_backup_dir(&target);
+
+            for _ in 0..2 {
+                let entry = target.new_backup_entry(&backup_dir, "tar.gz");
+                target.register_backup_entry(entry).unwrap();
+            }
+            target.backups.pop();
+
+            let entry = target.new_backup_entry(&backup_dir, "tar.gz");
+            assert_eq!(entry.id, 3);
+        }
+    }
+
+    mod test_tags {
+        use super::*;
+
+        #[test]
+        fn it_keeps_tags_sorted_and_unique() {
+            let mut target = prepare_target();
+            target.add_tag("games").unwrap();
+            target.add_tag("clients").unwrap();
+            target.add_tag("games").unwrap();
+            assert_eq!(target.tags, vec!["clients", "games"]);
+
+            assert!(target.remove_tag("games"));
+            assert!(!target.remove_tag("games"));
+            assert_eq!(target.tags, vec!["clients"]);
+        }
+
+        #[test]
+        fn it_rejects_invalid_tags() {
+            let mut target = prepare_target();
+            for tag in ["", "all", "has space", "a/b"] {
+                assert_eq!(
+                    target.add_tag(tag),
+                    Err(TargetError::InvalidTag(tag.to_string()))
+                );
+            }
+            assert!(target.tags.is_empty());
+        }
+
+        #[test]
+        fn all_matches_every_target() {
+            let mut target = prepare_target();
+            assert!(target.matches_tag(ALL_TAG));
+            assert!(!target.matches_tag("clients"));
+
+            target.add_tag("clients").unwrap();
+            assert!(target.matches_tag("clients"));
+        }
+    }
+
+    #[test]
+    fn test_restore_backup_entry() {
+        let mut target = prepare_target();
+        let backup_dir = prepare_backup_dir(&target);
+        for _ in 0..3 {
+            let entry = target.new_backup_entry(&backup_dir, "tar.gz");
+            target.register_backup_entry(entry).unwrap();
+        }
+
+        let entry = target.backups.remove(1);
+        assert_eq!(
+            target.restore_backup_entry(target.backups[0].clone()),
+            Err(TargetError::DuplicateId)
+        );
+        target.restore_backup_entry(entry).unwrap();
+
+        let ids: Vec<u32> = target.backups.iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_set_pinned() {
+        let mut target = prepare_target();
+        let backup_dir = prepare_backup_dir(&target);
+        for _ in 0..3 {
+            let entry = target.new_backup_entry(&backup_dir, "tar.gz");
+            target.register_backup_entry(entry).unwrap();
+        }
+
+        target.set_pinned(1, true).unwrap();
+        target.set_pinned(3, true).unwrap();
+        target.set_pinned(3, false).unwrap();
+        assert_eq!(target.pinned_backup_ids(), vec![1]);
+        assert_eq!(
+            target.set_pinned(4, true),
+            Err(TargetError::BackupNotFound(4))
+        );
+    }
+
+    #[test]
+    fn test_base_dir() {
+        let mut target = Target::new("xxx", "Test Target", Path::new("/srv/app/app.db"));
+        assert_eq!(target.base_dir(), Path::new("/srv/app/app.db"));
+
+        target.kind = TargetKind::File;
+        assert_eq!(target.base_dir(), Path::new("/srv/app"));
+    }
+
+    mod test_sources {
+        use super::*;
+
+        #[test]
+        fn it_adds_and_removes_sources() {
+            let mut target = prepare_target();
+            assert!(target.archive_sources().is_empty());
+
+            target
+                .add_source("config", Path::new("/home/user/.config/app"))
+                .unwrap();
+            target
+                .add_source("data", Path::new("/home/user/.local/share/app"))
+                .unwrap();
+
+            let names: Vec<_> = target
+                .archive_sources()
+                .into_iter()
+                .map(|s| s.name)
+                .collect();
+            assert_eq!(names, vec!["main", "config", "data"]);
+            assert_eq!(target.source_path("main"), Some(target.path.as_path()));
+            assert_eq!(
+                target.source_path("data"),
+                Some(Path::new("/home/user/.local/share/app"))
+            );
+
+            assert!(target.remove_source("config"));
+            assert!(!target.remove_source("config"));
+            assert_eq!(target.sources.len(), 1);
+        }
+
+        #[test]
+        fn it_rejects_sources_of_file_target() {
+            let mut target = prepare_target();
+            target.kind = TargetKind::File;
+
+            assert_eq!(
+                target.add_source("config", Path::new("config")),
+                Err(SourceError::FileTarget)
+            );
+        }
+
+        #[test]
+        fn it_rejects_invalid_or_duplicate_names() {
+            let mut target = prepare_target();
+            target.add_source("config", Path::new("config")).unwrap();
+
+            assert_eq!(
+                target.add_source("config", Path::new("other")),
+                Err(SourceError::DuplicateName(String::from("config")))
+            );
+            assert_eq!(
+                target.add_source("main", Path::new("other")),
+                Err(SourceError::DuplicateName(String::from("main")))
+            );
+            assert_eq!(
+                target.add_source("a/b", Path::new("other")),
+                Err(SourceError::InvalidName(String::from("a/b")))
+            );
+        }
+    }
+
+    mod test_next_run {
+        use super::*;
+
+        fn ts(s: &str) -> Timestamp {
+            Timestamp::from_fmt_str(s).unwrap()
+        }
+
+        #[test]
+        fn it_returns_none_without_schedule() {
+            let target = prepare_target();
+            assert_eq!(target.next_run(&Timestamp::now()), None);
+        }
+
+        #[test]
+        fn it_is_due_without_backups() {
+            let mut target = prepare_target();
+            target.schedule = Some(Schedule::parse("every 1d").unwrap());
+
+            let now = ts("20250101T000000Z");
+            assert_eq!(target.next_run(&now), Some(now.clone()));
+            assert!(target.is_due(&now));
+        }
+
+        #[test]
+        fn it_counts_from_latest_backup() {
+            let mut target = prepare_target();
+            target.schedule = Some(Schedule::parse("every 1d").unwrap());
+            let bkdir = prepare_backup_dir(&target);
+            for t in ["20250101T000000Z", "20250103T000000Z"] {
+                let mut entry = target.new_backup_entry(&bkdir, "tar.gz");
+                entry.timestamp = ts(t);
+                target.register_backup_entry(entry).unwrap();
+            }
+
+            let next = target.next_run(&ts("20250103T120000Z"));
+            assert_eq!(next, Some(ts("20250104T000000Z")));
+            assert!(!target.is_due(&ts("20250103T120000Z")));
+
+            // A run missed during sleep is due at once.
+            assert!(target.is_due(&ts("20250110T000000Z")));
+        }
+
+        #[test]
+        fn it_retries_failed_run_after_delay() {
+            let mut target = prepare_target();
+            target.schedule = Some(Schedule::parse("every 1d").unwrap());
+            target.last_failed_run = Some(ts("20250101T000000Z"));
+
+            let next = target.next_run(&ts("20250101T000001Z"));
+            assert_eq!(next, Some(ts("20250101T010000Z")));
+            assert!(!target.is_due(&ts("20250101T000001Z")));
+            assert!(target.is_due(&ts("20250101T010000Z")));
+
+            // At the next scheduled time if it is earlier than the delay.
+            target.schedule = Some(Schedule::parse("every 10m").unwrap());
+            let next = target.next_run(&ts("20250101T000001Z"));
+            assert_eq!(next, Some(ts("20250101T001000Z")));
+
+            // A failure before the latest backup is ignored.
+            let bkdir = prepare_backup_dir(&target);
+            let mut entry = target.new_backup_entry(&bkdir, "tar.gz");
+            entry.timestamp = ts("20250101T003000Z");
+            target.register_backup_entry(entry).unwrap();
+            let next = target.next_run(&ts("20250101T003000Z"));
+            assert_eq!(next, Some(ts("20250101T004000Z")));
+        }
     }
 
     mod test_register_backup_entry {
@@ -0,0 +1,101 @@
+//!
+//! # TargetKind
+//!
+//! What the path of a target is: a directory tree, or a single file
+//! (e.g. a SQLite database or a password vault).
+//!
+//! A file is archived alone under its file name, and restored to the path as a file.
+//!
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum TargetKindError {
+    #[error("Target path is invalid: '{0}'")]
+    NotFound(PathBuf),
+
+    #[error("Target path is neither a directory nor a regular file: '{0}'")]
+    Unsupported(PathBuf),
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TargetKind {
+    #[default]
+    Directory,
+    File,
+}
+
+impl TargetKind {
+    /// Returns the kind of the existing path, following symlinks.
+    pub fn detect(path: &Path) -> Result<Self, TargetKindError> {
+        let metadata =
+            std::fs::metadata(path).map_err(|_| TargetKindError::NotFound(path.to_path_buf()))?;
+        if metadata.is_dir() {
+            Ok(Self::Directory)
+        } else if metadata.is_file() {
+            Ok(Self::File)
+        } else {
+            Err(TargetKindError::Unsupported(path.to_path_buf()))
+        }
+    }
+
+    /// Returns true if the path exists as this kind.
+    pub fn matches(&self, path: &Path) -> bool {
+        Self::detect(path).is_ok_and(|kind| kind == *self)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Directory => "directory",
+            Self::File => "file",
+        }
+    }
+}
+
+impl std::fmt::Display for TargetKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_detects_kind_of_path() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let file = temp.path().join("app.db");
+        std::fs::write(&file, "data").unwrap();
+
+        assert_eq!(TargetKind::detect(&temp.path()), Ok(TargetKind::Directory));
+        assert_eq!(TargetKind::detect(&file), Ok(TargetKind::File));
+        assert!(TargetKind::File.matches(&file));
+        assert!(!TargetKind::Directory.matches(&file));
+
+        let missing = temp.path().join("missing");
+        assert_eq!(
+            TargetKind::detect(&missing),
+            Err(TargetKindError::NotFound(missing))
+        );
+    }
+
+    #[test]
+    fn it_defaults_to_directory() {
+        #[derive(Deserialize)]
+        struct Wrapper {
+            #[serde(default)]
+            kind: TargetKind,
+        }
+
+        let w: Wrapper = serde_json::from_str("{}").unwrap();
+        assert_eq!(w.kind, TargetKind::Directory);
+        let w: Wrapper = serde_json::from_str(r#"{"kind":"file"}"#).unwrap();
+        assert_eq!(w.kind, TargetKind::File);
+    }
+}
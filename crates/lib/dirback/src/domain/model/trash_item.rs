@@ -0,0 +1,93 @@
+//!
+//! # TrashItem
+//!
+//! TrashItem represents a deleted target or backup kept in the trash,
+//! which can be restored until it expires.
+//!
+
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::model::target::Target;
+use crate::domain::model::timestamp::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// Days a deleted item is kept in the trash.
+pub const TRASH_EXPIRY_DAYS: i64 = 30;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrashItem {
+    pub id: u32,
+    pub target_id: String,
+    pub target_name: String,
+
+    /// The deleted backup, `None` if the whole target was deleted.
+    pub backup: Option<BackupEntry>,
+
+    pub deleted_at: Timestamp,
+    pub expires_at: Timestamp,
+}
+
+impl TrashItem {
+    pub fn new(
+        id: u32,
+        target: &Target,
+        backup: Option<BackupEntry>,
+        deleted_at: Timestamp,
+    ) -> Self {
+        let expires_at =
+            Timestamp::from_utc(&(*deleted_at + chrono::Duration::days(TRASH_EXPIRY_DAYS)));
+
+        Self {
+            id,
+            target_id: target.id.clone(),
+            target_name: target.name.clone(),
+            backup,
+            deleted_at,
+            expires_at,
+        }
+    }
+
+    /// Returns true if the item is a deleted target.
+    pub fn is_target(&self) -> bool {
+        self.backup.is_none()
+    }
+
+    pub fn is_expired(&self, now: &Timestamp) -> bool {
+        *self.expires_at <= **now
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    #[test]
+    fn test_new() {
+        let target = Target::new("target-id", "TestTarget", Path::new("/tmp/target"));
+        let deleted_at = Timestamp::from_fmt_str("20250101T000000Z").unwrap();
+
+        let item = TrashItem::new(1, &target, None, deleted_at.clone());
+        assert_eq!(item.target_id, "target-id");
+        assert_eq!(item.target_name, "TestTarget");
+        assert!(item.is_target());
+        assert_eq!(item.deleted_at, deleted_at);
+        assert_eq!(item.expires_at.fmt(), "20250131T000000Z");
+    }
+
+    #[test]
+    fn test_is_expired() {
+        let target = Target::new("target-id", "TestTarget", Path::new("/tmp/target"));
+        let deleted_at = Timestamp::from_fmt_str("20250101T000000Z").unwrap();
+        let entry = BackupEntry::new(1, Path::new("/tmp/1.tar.gz"), deleted_at.clone(), "");
+        let item = TrashItem::new(1, &target, Some(entry), deleted_at);
+        assert!(!item.is_target());
+
+        let before = Timestamp::from_fmt_str("20250130T235959Z").unwrap();
+        let after = Timestamp::from_fmt_str("20250131T000000Z").unwrap();
+        assert!(!item.is_expired(&before));
+        assert!(item.is_expired(&after));
+    }
+}
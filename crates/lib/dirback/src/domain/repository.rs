@@ -3,3 +3,4 @@
 //!
 
 pub mod targets;
+pub mod trash;
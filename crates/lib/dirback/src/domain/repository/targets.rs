@@ -4,6 +4,7 @@
 
 use crate::domain::model::backup_entry::BackupEntry;
 use crate::domain::model::target::Target;
+use crate::domain::service::archive_store::ArchiveStore;
 use std::path::{Path, PathBuf};
 
 pub trait TargetRepository {
@@ -19,12 +20,23 @@ pub trait TargetRepository {
     /// Add a new target information.
     fn add(&mut self, name: &str, target_path: &Path) -> anyhow::Result<Target>;
 
+    /// Add an existing target information, keeping its ID and backups.
+    ///
+    /// Returns error if the target ID is already registered.
+    fn import(&mut self, target: &Target) -> anyhow::Result<Target>;
+
     /// Delete a backup entry.
     fn delete_backup(&mut self, target_id: &str, backup_id: u32) -> anyhow::Result<BackupEntry>;
 
     /// Delete a target information.
     fn delete_target(&mut self, target_id: &str) -> anyhow::Result<Target>;
 
+    /// The data directory the targets and the backups are kept in, `None` if not on disk.
+    fn data_dir(&self) -> Option<&Path>;
+
     /// Make a backup path of the target.
     fn make_backup_dir_path(&self, target: &Target) -> PathBuf;
+
+    /// Open a registered archive store.
+    fn archive_store(&self, store_id: &str) -> anyhow::Result<Box<dyn ArchiveStore>>;
 }
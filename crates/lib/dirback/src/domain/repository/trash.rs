@@ -0,0 +1,25 @@
+//!
+//! # TrashRepository
+//!
+//! Deleted targets and backups are moved to the trash,
+//! and removed permanently when the trash is emptied.
+//!
+
+use crate::domain::model::timestamp::Timestamp;
+use crate::domain::model::trash_item::TrashItem;
+
+pub trait TrashRepository {
+    /// Load all items in the trash.
+    fn list_trash(&self) -> anyhow::Result<Vec<TrashItem>>;
+
+    /// Restore an item to where it was deleted from.
+    ///
+    /// Returns error if the restored target or backup ID is in use.
+    fn restore_trash(&mut self, trash_id: u32) -> anyhow::Result<TrashItem>;
+
+    /// Remove all items in the trash permanently.
+    fn empty_trash(&mut self) -> anyhow::Result<Vec<TrashItem>>;
+
+    /// Remove the items expired at `now` permanently.
+    fn purge_expired_trash(&mut self, now: &Timestamp) -> anyhow::Result<Vec<TrashItem>>;
+}
@@ -2,4 +2,9 @@
 //! # domain service module
 //!
 
+pub mod archive_store;
 pub mod backup_service;
+pub mod bundle_service;
+pub mod hook_service;
+pub mod replica_service;
+pub mod watch_service;
@@ -0,0 +1,21 @@
+//!
+//! # Archive Store
+//!
+//! Archive store is the place where backup files are kept,
+//! e.g. another local directory, an object storage or a remote host.
+//!
+//! Archives in a store are addressed by a key (`{target_id}/{file_name}`).
+//!
+
+use std::path::Path;
+
+pub trait ArchiveStore {
+    /// Move the local archive file into the store.
+    fn put(&self, src: &Path, key: &str) -> anyhow::Result<()>;
+
+    /// Fetch the archive to the local file.
+    fn get(&self, key: &str, dest: &Path) -> anyhow::Result<()>;
+
+    /// Delete the archive.
+    fn delete(&self, key: &str) -> anyhow::Result<()>;
+}
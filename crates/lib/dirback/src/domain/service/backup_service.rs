@@ -2,12 +2,51 @@
 //! # Backup Service
 //!
 
-use std::path::Path;
+use crate::domain::model::archived_file::ArchivedFile;
+use crate::domain::model::backup_stats::BackupStats;
+use crate::domain::model::file_pattern::FilePattern;
+use crate::domain::model::index_entry::IndexEntry;
+use crate::domain::model::source::Source;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
+/// The backup methods return the sizes of the backup and the number of its files,
+/// measured while creating it, leaving `duration_ms` to 0.
 pub trait BackupService {
     /// Backup directory.
-    fn backup(&self, src: &Path, dest: &Path) -> anyhow::Result<()>;
+    fn backup(&self, src: &Path, dest: &Path) -> anyhow::Result<BackupStats>;
+
+    /// Backup directories into one backup, each under its name.
+    fn backup_sources(&self, sources: &[Source], dest: &Path) -> anyhow::Result<BackupStats>;
+
+    /// Backup a single file.
+    fn backup_file(&self, src: &Path, dest: &Path) -> anyhow::Result<BackupStats>;
 
     /// Restore directory.
     fn restore(&self, src: &Path, dest: &Path) -> anyhow::Result<()>;
+
+    /// Restore the directory archived under the name by `backup_sources`.
+    fn restore_source(&self, src: &Path, name: &str, dest: &Path) -> anyhow::Result<()>;
+
+    /// Restore the file archived by `backup_file` to `dest` (the path of the file).
+    fn restore_file(&self, src: &Path, dest: &Path) -> anyhow::Result<()>;
+
+    /// List the relative paths of the entries in the backup.
+    fn list(&self, src: &Path) -> anyhow::Result<Vec<PathBuf>>;
+
+    /// Find the files matching the pattern in the backup, hashing their contents.
+    fn find(&self, src: &Path, pattern: &FilePattern) -> anyhow::Result<Vec<ArchivedFile>>;
+
+    /// List the files, directories and symbolic links in the backup, with their attributes.
+    fn index(&self, src: &Path) -> anyhow::Result<Vec<IndexEntry>>;
+
+    /// Write the content of the file at the relative path in the backup to `out`.
+    ///
+    /// Returns the size of the file, or `None` if the file is not in the backup.
+    fn read_file(
+        &self,
+        src: &Path,
+        path: &Path,
+        out: &mut dyn Write,
+    ) -> anyhow::Result<Option<u64>>;
 }
@@ -0,0 +1,30 @@
+//!
+//! # Bundle Service
+//!
+
+use crate::domain::model::bundle::BundleManifest;
+use crate::domain::model::target::Target;
+use std::path::{Path, PathBuf};
+
+pub trait BundleService {
+    /// Write a bundle file.
+    ///
+    /// - target ... The target with the backup entries to be bundled.
+    /// - archives ... Pairs of the backup ID and the local archive file.
+    fn write(
+        &self,
+        target: &Target,
+        archives: &[(u32, PathBuf)],
+        dest: &Path,
+    ) -> anyhow::Result<BundleManifest>;
+
+    /// Read a bundle file, extracting the archives into the work_dir.
+    ///
+    /// Returns the manifest and the path to the extracted archive of each backup.
+    /// Returns error if an archive does not match its checksum.
+    fn read(
+        &self,
+        src: &Path,
+        work_dir: &Path,
+    ) -> anyhow::Result<(BundleManifest, Vec<(u32, PathBuf)>)>;
+}
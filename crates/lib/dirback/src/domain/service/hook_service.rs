@@ -0,0 +1,21 @@
+//!
+//! # Hook Service
+//!
+//! Runs the hook commands of targets.
+//!
+
+use std::path::Path;
+use std::time::Duration;
+
+pub trait HookService: Sync {
+    /// Runs the command with the environment variables in the working directory.
+    ///
+    /// Returns an error if the command fails, or does not finish within the timeout.
+    fn run(
+        &self,
+        command: &str,
+        env: &[(String, String)],
+        work_dir: &Path,
+        timeout: Duration,
+    ) -> anyhow::Result<()>;
+}
@@ -0,0 +1,22 @@
+//!
+//! # Replica Service
+//!
+//! A replica location is a secondary data directory,
+//! which holds copies of the archives and the target information.
+//!
+
+use crate::domain::model::target::Target;
+use std::path::{Path, PathBuf};
+
+pub trait ReplicaService {
+    /// Calculate the checksum of the archive file.
+    fn checksum(&self, path: &Path) -> anyhow::Result<String>;
+
+    /// Copy the archive file into the replica location.
+    ///
+    /// Returns the path to the copy.
+    fn copy_archive(&self, replica: &Path, target_id: &str, src: &Path) -> anyhow::Result<PathBuf>;
+
+    /// Write the target information into the replica location.
+    fn write_target(&self, replica: &Path, target: &Target) -> anyhow::Result<()>;
+}
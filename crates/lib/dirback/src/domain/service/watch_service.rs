@@ -0,0 +1,16 @@
+//!
+//! # Watch Service
+//!
+//! Watches a directory tree for changes.
+//!
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub trait WatchService {
+    /// Waits for changes in the watched directory.
+    ///
+    /// Returns the changed paths, or an empty list if the timeout passes first.
+    /// `None` waits without timeout.
+    fn wait(&mut self, timeout: Option<Duration>) -> anyhow::Result<Vec<PathBuf>>;
+}
@@ -3,5 +3,6 @@
 //!
 
 pub mod app_path;
+pub mod config;
 pub mod repository;
 pub mod service;
@@ -26,6 +26,19 @@ pub fn data_dir() -> Option<PathBuf> {
     ProjectDirs::from("", "", pkgname).map(|pd| pd.data_dir().to_path_buf())
 }
 
+/// Returns the config directory path for the application.
+pub fn config_dir() -> Option<PathBuf> {
+    use directories::ProjectDirs;
+    let pkgname = env!("CARGO_PKG_NAME");
+
+    ProjectDirs::from("", "", pkgname).map(|pd| pd.config_dir().to_path_buf())
+}
+
+/// Returns the home directory path of the user.
+pub fn home_dir() -> Option<PathBuf> {
+    directories::BaseDirs::new().map(|bd| bd.home_dir().to_path_buf())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,513 @@
+//!
+//! # Config
+//!
+//! The settings shared by the dirback binaries.
+//!
+//! Each value is taken from the first of: the command line flags,
+//! the environment variables, the config file and the defaults.
+//!
+//! The config file is `config.toml` in the config directory
+//! (`~/.config/dirback` on Linux), or the file given by `--config` or `DIRBACK_CONFIG`.
+//!
+//! ```toml
+//! datadir = "~/backups/dirback"
+//! log_level = "info"
+//! compression = 6
+//! excludes = ["node_modules", "*.log"]
+//!
+//! [retention]
+//! keep_last = 10
+//! keep_days = 30
+//! ```
+//!
+
+use crate::domain::model::file_pattern::{FilePattern, FilePatternError};
+use crate::domain::model::retention_policy::RetentionPolicy;
+use crate::infra::app_path;
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+pub const CONFIG_FILE_NAME: &str = "config.toml";
+
+/// The environment variables.
+pub const CONFIG_ENV: &str = "DIRBACK_CONFIG";
+pub const DATADIR_ENV: &str = "DIRBACK_STORE_DIR";
+pub const LOG_LEVEL_ENV: &str = "DIRBACK_LOG_LEVEL";
+pub const COMPRESSION_ENV: &str = "DIRBACK_COMPRESSION";
+
+/// The default compression level of the archives.
+pub const DEFAULT_COMPRESSION: u32 = targz::DEFAULT_LEVEL;
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum ConfigError {
+    #[error("Failed to read the config file '{0}': {1}")]
+    Read(PathBuf, String),
+
+    #[error("Invalid config file '{0}': {1}")]
+    Parse(PathBuf, String),
+
+    #[error("Invalid log level: '{0}' (error, warn, info, debug or trace)")]
+    InvalidLogLevel(String),
+
+    #[error("Invalid compression level: '{0}' (0 to 9)")]
+    InvalidCompression(String),
+
+    #[error("Invalid exclude pattern: '{0}' ({1})")]
+    InvalidExclude(String, String),
+
+    #[error("Failed to get path to directory for application data.")]
+    NoDataDir,
+}
+
+/// Where a value of the config comes from.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ConfigSource {
+    Default,
+    File,
+
+    /// The environment variable.
+    Env(&'static str),
+
+    /// The command line flag.
+    Flag(&'static str),
+}
+
+impl std::fmt::Display for ConfigSource {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Default => write!(f, "default"),
+            Self::File => write!(f, "config file"),
+            Self::Env(name) => write!(f, "env {name}"),
+            Self::Flag(name) => write!(f, "flag {name}"),
+        }
+    }
+}
+
+impl Serialize for ConfigSource {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// A value of the config, with its source.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Setting<T> {
+    pub value: T,
+    pub source: ConfigSource,
+}
+
+impl<T> Setting<T> {
+    fn new(value: T, source: ConfigSource) -> Self {
+        Self { value, source }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl std::fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            Self::Error => "error",
+            Self::Warn => "warn",
+            Self::Info => "info",
+            Self::Debug => "debug",
+            Self::Trace => "trace",
+        };
+        write!(f, "{s}")
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = ConfigError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "error" => Ok(Self::Error),
+            "warn" => Ok(Self::Warn),
+            "info" => Ok(Self::Info),
+            "debug" => Ok(Self::Debug),
+            "trace" => Ok(Self::Trace),
+            _ => Err(ConfigError::InvalidLogLevel(s.to_string())),
+        }
+    }
+}
+
+/// The values given by the command line flags.
+#[derive(Clone, Debug, Default)]
+pub struct ConfigFlags {
+    pub config: Option<PathBuf>,
+    pub datadir: Option<PathBuf>,
+    pub log_level: Option<LogLevel>,
+}
+
+/// The effective config.
+#[derive(Clone, Debug, PartialEq, Serialize)]
+pub struct Config {
+    /// The config file, `None` if there is no config file.
+    pub file: Option<PathBuf>,
+
+    /// The data directory to store the targets and backups.
+    pub datadir: Setting<PathBuf>,
+
+    pub log_level: Setting<LogLevel>,
+
+    /// Compression level of new backups, from 0 (none) to 9 (best).
+    pub compression: Setting<u32>,
+
+    /// The retention policy used by `prune` without the options.
+    pub retention: Setting<RetentionPolicy>,
+
+    /// The files and directories left out of new backups,
+    /// matched against the path in each source directory.
+    pub excludes: Setting<Vec<FilePattern>>,
+}
+
+/// The contents of the config file.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    datadir: Option<PathBuf>,
+    log_level: Option<String>,
+    compression: Option<u32>,
+    excludes: Option<Vec<String>>,
+    retention: Option<RetentionFile>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RetentionFile {
+    keep_last: Option<usize>,
+    keep_days: Option<u32>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            file: None,
+            datadir: Setting::new(
+                app_path::data_dir().unwrap_or_default(),
+                ConfigSource::Default,
+            ),
+            log_level: Setting::new(LogLevel::default(), ConfigSource::Default),
+            compression: Setting::new(DEFAULT_COMPRESSION, ConfigSource::Default),
+            retention: Setting::new(RetentionPolicy::default(), ConfigSource::Default),
+            excludes: Setting::new(Vec::new(), ConfigSource::Default),
+        }
+    }
+}
+
+impl Config {
+    /// Loads the config with the flags, the environment variables and the config file.
+    pub fn load(flags: &ConfigFlags) -> anyhow::Result<Self> {
+        let env = |name: &str| std::env::var(name).ok();
+        let default_file = app_path::config_dir().map(|dir| dir.join(CONFIG_FILE_NAME));
+        Self::load_with(flags, env, default_file)
+    }
+
+    /// Loads the config with the environment variables given by `env`,
+    /// and `default_file` if no config file is given.
+    pub fn load_with<E>(
+        flags: &ConfigFlags,
+        env: E,
+        default_file: Option<PathBuf>,
+    ) -> anyhow::Result<Self>
+    where
+        E: Fn(&str) -> Option<String>,
+    {
+        let env = |name: &str| env(name).filter(|value| !value.is_empty());
+
+        // The given config file should exist, the default one may not.
+        let file = flags
+            .config
+            .clone()
+            .or_else(|| env(CONFIG_ENV).map(PathBuf::from))
+            .or_else(|| default_file.filter(|path| path.exists()));
+        let contents = match &file {
+            Some(path) => read_config_file(path)?,
+            None => ConfigFile::default(),
+        };
+
+        let mut config = Self {
+            file,
+            ..Self::default()
+        };
+
+        // datadir
+        if let Some(datadir) = contents.datadir {
+            config.datadir = Setting::new(expand_home(&datadir), ConfigSource::File);
+        }
+        if let Some(datadir) = env(DATADIR_ENV) {
+            config.datadir = Setting::new(PathBuf::from(datadir), ConfigSource::Env(DATADIR_ENV));
+        }
+        if let Some(datadir) = &flags.datadir {
+            config.datadir = Setting::new(datadir.clone(), ConfigSource::Flag("--datadir"));
+        }
+        if config.datadir.value.as_os_str().is_empty() {
+            return Err(ConfigError::NoDataDir.into());
+        }
+
+        // log_level
+        if let Some(level) = contents.log_level {
+            config.log_level = Setting::new(level.parse()?, ConfigSource::File);
+        }
+        if let Some(level) = env(LOG_LEVEL_ENV) {
+            config.log_level = Setting::new(level.parse()?, ConfigSource::Env(LOG_LEVEL_ENV));
+        }
+        if let Some(level) = flags.log_level {
+            config.log_level = Setting::new(level, ConfigSource::Flag("--log-level"));
+        }
+
+        // compression
+        if let Some(level) = contents.compression {
+            config.compression = Setting::new(check_compression(level)?, ConfigSource::File);
+        }
+        if let Some(level) = env(COMPRESSION_ENV) {
+            config.compression = Setting::new(
+                parse_compression(&level)?,
+                ConfigSource::Env(COMPRESSION_ENV),
+            );
+        }
+
+        // excludes
+        if let Some(excludes) = contents.excludes {
+            let patterns = excludes
+                .iter()
+                .map(|s| parse_exclude(s))
+                .collect::<Result<_, _>>()?;
+            config.excludes = Setting::new(patterns, ConfigSource::File);
+        }
+
+        // retention
+        if let Some(retention) = contents.retention {
+            let policy = RetentionPolicy {
+                keep_last: retention.keep_last,
+                keep_days: retention.keep_days,
+            };
+            config.retention = Setting::new(policy, ConfigSource::File);
+        }
+
+        Ok(config)
+    }
+}
+
+fn read_config_file(path: &Path) -> Result<ConfigFile, ConfigError> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| ConfigError::Read(path.to_path_buf(), e.to_string()))?;
+    toml::from_str(&text)
+        .map_err(|e| ConfigError::Parse(path.to_path_buf(), e.message().to_string()))
+}
+
+fn parse_compression(s: &str) -> Result<u32, ConfigError> {
+    s.trim()
+        .parse()
+        .map_err(|_| ConfigError::InvalidCompression(s.to_string()))
+        .and_then(check_compression)
+}
+
+fn check_compression(level: u32) -> Result<u32, ConfigError> {
+    match level {
+        0..=9 => Ok(level),
+        _ => Err(ConfigError::InvalidCompression(level.to_string())),
+    }
+}
+
+fn parse_exclude(s: &str) -> Result<FilePattern, ConfigError> {
+    s.parse()
+        .map_err(|e: FilePatternError| ConfigError::InvalidExclude(s.to_string(), e.to_string()))
+}
+
+/// Expands `~/` at the head of the path to the home directory.
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), app_path::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    fn load(
+        flags: &ConfigFlags,
+        env: &[(&str, &str)],
+        default_file: Option<PathBuf>,
+    ) -> anyhow::Result<Config> {
+        let env: HashMap<String, String> = env
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect();
+        Config::load_with(flags, |name| env.get(name).cloned(), default_file)
+    }
+
+    fn write_config(temp: &mktemp::TempDir, text: &str) -> PathBuf {
+        let path = temp.path().join(CONFIG_FILE_NAME);
+        std::fs::write(&path, text).unwrap();
+        path
+    }
+
+    #[test]
+    fn it_uses_defaults_without_config_file() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let missing = temp.path().join(CONFIG_FILE_NAME);
+
+        let config = load(&ConfigFlags::default(), &[], Some(missing)).unwrap();
+        assert_eq!(config.file, None);
+        assert_eq!(config.datadir.source, ConfigSource::Default);
+        assert_eq!(
+            config.log_level,
+            Setting::new(LogLevel::Info, ConfigSource::Default)
+        );
+        assert_eq!(config.compression.value, DEFAULT_COMPRESSION);
+        assert!(config.retention.value.is_empty());
+        assert!(config.excludes.value.is_empty());
+    }
+
+    #[test]
+    fn it_reads_config_file() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let path = write_config(
+            &temp,
+            r#"
+            datadir = "/mnt/backup/dirback"
+            log_level = "debug"
+            compression = 9
+            excludes = ["node_modules", "*.log"]
+
+            [retention]
+            keep_last = 10
+            "#,
+        );
+
+        let config = load(&ConfigFlags::default(), &[], Some(path.clone())).unwrap();
+        assert_eq!(config.file, Some(path));
+        assert_eq!(
+            config.datadir,
+            Setting::new(PathBuf::from("/mnt/backup/dirback"), ConfigSource::File)
+        );
+        assert_eq!(config.log_level.value, LogLevel::Debug);
+        assert_eq!(config.compression, Setting::new(9, ConfigSource::File));
+        assert_eq!(config.retention.value.keep_last, Some(10));
+        assert_eq!(config.retention.value.keep_days, None);
+        let excludes: Vec<String> = config
+            .excludes
+            .value
+            .iter()
+            .map(|p| p.to_string())
+            .collect();
+        assert_eq!(excludes, vec!["node_modules", "*.log"]);
+        assert_eq!(config.excludes.source, ConfigSource::File);
+    }
+
+    #[test]
+    fn it_layers_env_and_flags_over_config_file() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let path = write_config(&temp, "datadir = \"/from/file\"\nlog_level = \"warn\"\n");
+
+        let env = [
+            (DATADIR_ENV, "/from/env"),
+            (LOG_LEVEL_ENV, "trace"),
+            (COMPRESSION_ENV, "1"),
+        ];
+        let config = load(&ConfigFlags::default(), &env, Some(path.clone())).unwrap();
+        assert_eq!(config.datadir.value, PathBuf::from("/from/env"));
+        assert_eq!(config.datadir.source, ConfigSource::Env(DATADIR_ENV));
+        assert_eq!(config.log_level.value, LogLevel::Trace);
+        assert_eq!(
+            config.compression,
+            Setting::new(1, ConfigSource::Env(COMPRESSION_ENV))
+        );
+
+        let flags = ConfigFlags {
+            datadir: Some(PathBuf::from("/from/flag")),
+            log_level: Some(LogLevel::Error),
+            ..Default::default()
+        };
+        let config = load(&flags, &env, Some(path)).unwrap();
+        assert_eq!(config.datadir.value, PathBuf::from("/from/flag"));
+        assert_eq!(config.datadir.source, ConfigSource::Flag("--datadir"));
+        assert_eq!(config.log_level.value, LogLevel::Error);
+    }
+
+    #[test]
+    fn it_reads_given_config_file() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let path = write_config(&temp, "compression = 0\n");
+
+        let env = [(CONFIG_ENV, path.to_str().unwrap())];
+        let config = load(&ConfigFlags::default(), &env, None).unwrap();
+        assert_eq!(config.file, Some(path.clone()));
+        assert_eq!(config.compression.value, 0);
+
+        // The given file should exist.
+        let flags = ConfigFlags {
+            config: Some(temp.path().join("missing.toml")),
+            ..Default::default()
+        };
+        let err = load(&flags, &env, None).unwrap_err();
+        assert!(matches!(
+            err.downcast::<ConfigError>().unwrap(),
+            ConfigError::Read(..)
+        ));
+    }
+
+    #[test]
+    fn it_returns_err_if_invalid() {
+        let temp = mktemp::TempDir::new().unwrap();
+        for text in [
+            "log_level = \"loud\"",
+            "compression = 10",
+            "unknown = 1",
+            "datadir = ",
+            "[retention]\nkeep_weeks = 1",
+            "excludes = \"*.log\"",
+            "excludes = [\"\"]",
+            "excludes = [\"[\"]",
+        ] {
+            let path = write_config(&temp, text);
+            let result = load(&ConfigFlags::default(), &[], Some(path));
+            assert!(result.is_err(), "{text}");
+        }
+
+        let env = [(COMPRESSION_ENV, "best")];
+        let err = load(&ConfigFlags::default(), &env, None).unwrap_err();
+        assert_eq!(
+            err.downcast::<ConfigError>().unwrap(),
+            ConfigError::InvalidCompression(String::from("best"))
+        );
+    }
+
+    #[test]
+    fn it_expands_home() {
+        let home = app_path::home_dir().unwrap();
+        assert_eq!(expand_home(Path::new("~/backups")), home.join("backups"));
+        assert_eq!(
+            expand_home(Path::new("/backups")),
+            PathBuf::from("/backups")
+        );
+    }
+
+    #[test]
+    fn it_parses_log_levels() {
+        for level in ["error", "warn", "info", "debug", "trace"] {
+            let parsed: LogLevel = level.parse().unwrap();
+            assert_eq!(parsed.to_string(), level);
+        }
+        assert_eq!("INFO".parse::<LogLevel>(), Ok(LogLevel::Info));
+        assert!("loud".parse::<LogLevel>().is_err());
+    }
+}
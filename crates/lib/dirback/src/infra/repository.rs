@@ -4,3 +4,4 @@
 
 pub mod file_storage;
 pub mod in_memory;
+pub mod sqlite;
@@ -8,6 +8,7 @@
 //!
 //! ```ascii
 //! {base_dir}/
+//! ├─ stores.json (optional, see `infra::service::archive_store`)
 //! └─ targets/
 //!    └─ {target_id}/
 //!       ├─ info.json
@@ -15,12 +16,29 @@
 //!          └─ {backup_id}_{backup_timestamp}.tar.gz
 //! ```
 //!
+//! Backups of a target with an archive store are placed in the store instead.
+//!
+//! Deleted targets and backups are moved to the trash (see `trash`).
+//!
+//!
+//! ## Schema version
+//!
+//! `info.json` has a `schema_version` field.
+//! An outdated `info.json` is upgraded when it is loaded,
+//! and the original file is kept as `info.json.v{old_version}.bak`.
+//!
 
 use crate::domain::model::backup_entry::BackupEntry;
 use crate::domain::model::target::Target;
 use crate::domain::repository::targets::TargetRepository;
+use crate::domain::service::archive_store::ArchiveStore;
+use crate::infra::service::archive_store::open_archive_store;
+use serde::Serialize;
 use std::path::{Path, PathBuf};
 
+pub mod migration;
+pub mod trash;
+
 //-----------------------------------------------------------------------------
 // Helper
 //-----------------------------------------------------------------------------
@@ -28,7 +46,7 @@ const TARGET_INFO_DIR_NAME: &str = "targets";
 const TARGET_INFO_FILE_NAME: &str = "info.json";
 const BACKUP_DIR_NAME: &str = "backups";
 
-fn create_target_info_dir_path(base_dir: &Path, target_id: Option<&str>) -> PathBuf {
+pub(crate) fn create_target_info_dir_path(base_dir: &Path, target_id: Option<&str>) -> PathBuf {
     let path = base_dir.join(TARGET_INFO_DIR_NAME);
 
     match target_id {
@@ -42,11 +60,46 @@ fn create_target_info_file_path(base_dir: &Path, target_id: &str) -> PathBuf {
     path.join(TARGET_INFO_FILE_NAME)
 }
 
-fn create_backup_dir_path(base_dir: &Path, target_id: &str) -> PathBuf {
+pub(crate) fn create_backup_dir_path(base_dir: &Path, target_id: &str) -> PathBuf {
     let path = create_target_info_dir_path(base_dir, Some(target_id));
     path.join(BACKUP_DIR_NAME)
 }
 
+/// Stored format of the `info.json`.
+#[derive(Serialize)]
+struct TargetInfo<'a> {
+    schema_version: u32,
+
+    #[serde(flatten)]
+    target: &'a Target,
+}
+
+/// Reads a target information from the info.json.
+///
+/// If the info.json is outdated, it is upgraded to the current schema
+/// and the original file is kept as a backup.
+fn read_target_info(path: &Path) -> anyhow::Result<Target> {
+    let mut info: serde_json::Value = jsonfile::read(path)?;
+
+    if let Some(old_version) = migration::migrate(&mut info)? {
+        let mut bak_name = path.file_name().unwrap_or_default().to_os_string();
+        bak_name.push(format!(".v{old_version}.bak"));
+        std::fs::copy(path, path.with_file_name(bak_name))?;
+        jsonfile::write(path, &info)?;
+    }
+
+    Ok(serde_json::from_value(info)?)
+}
+
+/// Writes a target information to the info.json with the current schema version.
+fn write_target_info(path: &Path, target: &Target) -> anyhow::Result<()> {
+    let info = TargetInfo {
+        schema_version: migration::CURRENT_SCHEMA_VERSION,
+        target,
+    };
+    jsonfile::write(path, &info)
+}
+
 //-----------------------------------------------------------------------------
 // FileStorageTargetRepository
 //-----------------------------------------------------------------------------
@@ -88,7 +141,7 @@ impl TargetRepository for FileStorageTargetRepository {
 
             let dir = dir.unwrap().path();
             let info_file_path = dir.join(TARGET_INFO_FILE_NAME);
-            let target: Target = jsonfile::read(&info_file_path)?;
+            let target = read_target_info(&info_file_path)?;
             targets.push(target);
         }
 
@@ -97,12 +150,12 @@ impl TargetRepository for FileStorageTargetRepository {
 
     fn load(&self, target_id: &str) -> Option<Target> {
         let info_path = create_target_info_file_path(&self.base_dir, target_id);
-        jsonfile::read(&info_path).unwrap_or_default()
+        read_target_info(&info_path).ok()
     }
 
     fn update(&mut self, target: &Target) -> anyhow::Result<Target> {
         let info_path = create_target_info_file_path(&self.base_dir, &target.id);
-        jsonfile::write(&info_path, &target)?;
+        write_target_info(&info_path, target)?;
         Ok(target.clone())
     }
 
@@ -117,7 +170,7 @@ impl TargetRepository for FileStorageTargetRepository {
 
         // 3. create a target-info file.
         let info_path = dir.join(TARGET_INFO_FILE_NAME);
-        jsonfile::write(&info_path, &target)?;
+        write_target_info(&info_path, &target)?;
 
         // 4. make directory for backups of targets.
         let bk_dir = dir.join(BACKUP_DIR_NAME);
@@ -127,6 +180,19 @@ impl TargetRepository for FileStorageTargetRepository {
         Ok(target)
     }
 
+    fn import(&mut self, target: &Target) -> anyhow::Result<Target> {
+        // The backup directory may already exist, e.g. shared with the SQLite repository.
+        let dir = create_target_info_dir_path(&self.base_dir, Some(&target.id));
+        let info_path = dir.join(TARGET_INFO_FILE_NAME);
+        if info_path.exists() {
+            anyhow::bail!("Target already exists ('{}').", target.id);
+        }
+
+        std::fs::create_dir_all(dir.join(BACKUP_DIR_NAME))?;
+        write_target_info(&info_path, target)?;
+        Ok(target.clone())
+    }
+
     fn delete_backup(&mut self, target_id: &str, backup_id: u32) -> anyhow::Result<BackupEntry> {
         let mut target = self
             .load(target_id)
@@ -134,8 +200,8 @@ impl TargetRepository for FileStorageTargetRepository {
 
         if let Some(pos) = target.backups.iter().position(|b| b.id == backup_id) {
             let entry = target.backups.remove(pos);
+            self.trash_backup(&target, &entry)?;
             let _ = self.update(&target)?;
-            std::fs::remove_file(&entry.path)?;
             Ok(entry)
         } else {
             anyhow::bail!(
@@ -146,17 +212,24 @@ impl TargetRepository for FileStorageTargetRepository {
 
     fn delete_target(&mut self, target_id: &str) -> anyhow::Result<Target> {
         if let Some(target) = self.load(target_id) {
-            let dir = create_target_info_dir_path(&self.base_dir, Some(&target.id));
-            let _ = std::fs::remove_dir_all(&dir);
+            self.trash_target(&target)?;
             Ok(target)
         } else {
             anyhow::bail!("Target not found ('{target_id}').");
         }
     }
 
+    fn data_dir(&self) -> Option<&Path> {
+        Some(&self.base_dir)
+    }
+
     fn make_backup_dir_path(&self, target: &Target) -> PathBuf {
         create_backup_dir_path(&self.base_dir, &target.id)
     }
+
+    fn archive_store(&self, store_id: &str) -> anyhow::Result<Box<dyn ArchiveStore>> {
+        open_archive_store(&self.base_dir, store_id)
+    }
 }
 
 //-----------------------------------------------------------------------------
@@ -274,6 +347,36 @@ mod tests {
         }
     }
 
+    mod import {
+        use super::*;
+
+        #[test]
+        fn it_works() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = FileStorageTargetRepository::new(&temp.path());
+
+            let mut target = Target::new("xxx-xxx", "Test Target", Path::new("target"));
+            let bkdir = repo.make_backup_dir_path(&target);
+            let entry = target.new_backup_entry(&bkdir, "tar.gz");
+            let _ = target.register_backup_entry(entry);
+
+            let result = repo.import(&target);
+            assert!(result.is_ok());
+            assert_eq!(repo.load("xxx-xxx"), Some(target.clone()));
+            assert!(repo.make_backup_dir_path(&target).exists());
+        }
+
+        #[test]
+        fn it_returns_err_when_id_already_exists() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = FileStorageTargetRepository::new(&temp.path());
+            let target = repo.add("Test Target", Path::new("target")).unwrap();
+
+            let result = repo.import(&target);
+            assert!(result.is_err());
+        }
+    }
+
     mod load_all {
         use super::*;
 
@@ -520,6 +623,193 @@ mod tests {
         }
     }
 
+    mod schema_migration {
+        use super::*;
+
+        const FIXTURE_TARGET_ID: &str = "3f1c2a9e-8b7d-4e6f-9a10-2b3c4d5e6f70";
+
+        /// info.json fixtures of every prior schema version.
+        const FIXTURES: [(u32, &str); migration::CURRENT_SCHEMA_VERSION as usize] = [
+            (
+                0,
+                include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/fixtures/info/v0.json"
+                )),
+            ),
+            (
+                1,
+                include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/fixtures/info/v1.json"
+                )),
+            ),
+            (
+                2,
+                include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/fixtures/info/v2.json"
+                )),
+            ),
+            (
+                3,
+                include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/fixtures/info/v3.json"
+                )),
+            ),
+            (
+                4,
+                include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/fixtures/info/v4.json"
+                )),
+            ),
+            (
+                5,
+                include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/fixtures/info/v5.json"
+                )),
+            ),
+            (
+                6,
+                include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/fixtures/info/v6.json"
+                )),
+            ),
+            (
+                7,
+                include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/fixtures/info/v7.json"
+                )),
+            ),
+            (
+                8,
+                include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/fixtures/info/v8.json"
+                )),
+            ),
+            (
+                9,
+                include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/fixtures/info/v9.json"
+                )),
+            ),
+            (
+                10,
+                include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/fixtures/info/v10.json"
+                )),
+            ),
+            (
+                11,
+                include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/fixtures/info/v11.json"
+                )),
+            ),
+            (
+                12,
+                include_str!(concat!(
+                    env!("CARGO_MANIFEST_DIR"),
+                    "/fixtures/info/v12.json"
+                )),
+            ),
+        ];
+
+        fn put_info_file(base_dir: &Path, content: &str) -> PathBuf {
+            let dir = create_target_info_dir_path(base_dir, Some(FIXTURE_TARGET_ID));
+            std::fs::create_dir_all(dir.join(BACKUP_DIR_NAME)).unwrap();
+
+            let info_path = dir.join(TARGET_INFO_FILE_NAME);
+            std::fs::write(&info_path, content).unwrap();
+            info_path
+        }
+
+        #[test]
+        fn it_upgrades_fixtures_of_every_prior_version() {
+            for (version, fixture) in FIXTURES {
+                let temp = mktemp::TempDir::new().unwrap();
+                let repo = FileStorageTargetRepository::new(&temp.path());
+                let info_path = put_info_file(&temp.path(), fixture);
+
+                let target = repo.load(FIXTURE_TARGET_ID);
+                assert!(target.is_some(), "v{version} fixture should be loadable.");
+
+                let target = target.unwrap();
+                assert_eq!(target.id, FIXTURE_TARGET_ID);
+                assert_eq!(target.name, "Fixture Target");
+                assert_eq!(target.backups.len(), 2);
+                assert_eq!(target.backups[0].note, "first backup");
+                assert_eq!(target.last_backup_id, 2);
+
+                let info: serde_json::Value = jsonfile::read(&info_path).unwrap();
+                assert_eq!(
+                    migration::schema_version(&info).unwrap(),
+                    migration::CURRENT_SCHEMA_VERSION,
+                    "info.json should be upgraded to the current version."
+                );
+
+                let bak_path = info_path.with_file_name(format!("info.json.v{version}.bak"));
+                assert!(bak_path.exists(), "the original file should be kept.");
+                assert_eq!(std::fs::read_to_string(&bak_path).unwrap(), fixture);
+            }
+        }
+
+        #[test]
+        fn it_does_not_make_backup_if_already_current() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = FileStorageTargetRepository::new(&temp.path());
+            let target = repo.add("TestTarget", Path::new("target")).unwrap();
+
+            let result = repo.load(&target.id);
+            assert_eq!(result, Some(target.clone()));
+
+            let info_dir = create_target_info_dir_path(&temp.path(), Some(&target.id));
+            let files: Vec<_> = std::fs::read_dir(&info_dir)
+                .unwrap()
+                .filter_map(|entry| entry.ok())
+                .filter(|entry| entry.file_name().to_string_lossy().ends_with(".bak"))
+                .collect();
+            assert!(files.is_empty());
+        }
+
+        #[test]
+        fn it_writes_current_schema_version() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = FileStorageTargetRepository::new(&temp.path());
+            let target = repo.add("TestTarget", Path::new("target")).unwrap();
+
+            let info_path = create_target_info_file_path(&temp.path(), &target.id);
+            let info: serde_json::Value = jsonfile::read(&info_path).unwrap();
+            assert_eq!(
+                migration::schema_version(&info).unwrap(),
+                migration::CURRENT_SCHEMA_VERSION
+            );
+        }
+
+        #[test]
+        fn it_returns_err_if_schema_version_is_newer() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let repo = FileStorageTargetRepository::new(&temp.path());
+
+            let newer = migration::CURRENT_SCHEMA_VERSION + 1;
+            let content = FIXTURES[0]
+                .1
+                .replacen('{', &format!("{{\"schema_version\":{newer},"), 1);
+            let _ = put_info_file(&temp.path(), &content);
+
+            assert!(repo.load(FIXTURE_TARGET_ID).is_none());
+            assert!(repo.load_all().is_err());
+        }
+    }
+
     mod make_backup_dir_path {
         use super::*;
 
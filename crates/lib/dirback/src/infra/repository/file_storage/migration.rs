@@ -0,0 +1,454 @@
+//!
+//! # info.json migration
+//!
+//! Upgrades a stored target information (`info.json`) to the current schema.
+//!
+//! The schema version is stored in the `schema_version` field.
+//! Files written before the field was introduced have no version,
+//! they are treated as version 0.
+//!
+//! Each migration upgrades the raw json value by exactly one version,
+//! so an old file is upgraded step by step until it reaches
+//! `CURRENT_SCHEMA_VERSION`.
+//!
+
+use serde_json::Value;
+
+/// The schema version written by this version of dirback.
+pub const CURRENT_SCHEMA_VERSION: u32 = 13;
+
+/// The key of the schema version in the info.json.
+pub const SCHEMA_VERSION_KEY: &str = "schema_version";
+
+type Migration = fn(&mut Value) -> anyhow::Result<()>;
+
+/// Migrations, `MIGRATIONS[n]` upgrades version `n` to `n + 1`.
+const MIGRATIONS: [Migration; CURRENT_SCHEMA_VERSION as usize] = [
+    migrate_v0_to_v1,
+    migrate_v1_to_v2,
+    migrate_v2_to_v3,
+    migrate_v3_to_v4,
+    migrate_v4_to_v5,
+    migrate_v5_to_v6,
+    migrate_v6_to_v7,
+    migrate_v7_to_v8,
+    migrate_v8_to_v9,
+    migrate_v9_to_v10,
+    migrate_v10_to_v11,
+    migrate_v11_to_v12,
+    migrate_v12_to_v13,
+];
+
+/// Returns the schema version of the info.
+///
+/// Returns 0 if the info does not have the version field.
+pub fn schema_version(info: &Value) -> anyhow::Result<u32> {
+    match info.get(SCHEMA_VERSION_KEY) {
+        None => Ok(0),
+        Some(v) => v
+            .as_u64()
+            .and_then(|v| u32::try_from(v).ok())
+            .ok_or_else(|| anyhow::anyhow!("Invalid schema version: {v}")),
+    }
+}
+
+/// Upgrades the info to the current schema version.
+///
+/// Returns the original version if the info was upgraded,
+/// or `None` if it was already up to date.
+pub fn migrate(info: &mut Value) -> anyhow::Result<Option<u32>> {
+    let version = schema_version(info)?;
+    if version == CURRENT_SCHEMA_VERSION {
+        return Ok(None);
+    }
+
+    if version > CURRENT_SCHEMA_VERSION {
+        anyhow::bail!(
+            "Unsupported schema version {version} (supported up to {CURRENT_SCHEMA_VERSION}). Please upgrade dirback."
+        );
+    }
+
+    for (from, migration) in MIGRATIONS.iter().enumerate().skip(version as usize) {
+        migration(info)?;
+        set_schema_version(info, from as u32 + 1)?;
+    }
+
+    Ok(Some(version))
+}
+
+fn set_schema_version(info: &mut Value, version: u32) -> anyhow::Result<()> {
+    let obj = info
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Target information is not a json object."))?;
+    obj.insert(SCHEMA_VERSION_KEY.to_string(), Value::from(version));
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+// Migrations
+//-----------------------------------------------------------------------------
+
+/// v0 -> v1: introduces the schema version only.
+fn migrate_v0_to_v1(_info: &mut Value) -> anyhow::Result<()> {
+    Ok(())
+}
+
+/// v1 -> v2: adds `store` to the target and each backup entry.
+///
+/// Existing backups are in the data directory, so the store is `null`.
+fn migrate_v1_to_v2(info: &mut Value) -> anyhow::Result<()> {
+    let obj = info
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Target information is not a json object."))?;
+    obj.entry("store").or_insert(Value::Null);
+
+    if let Some(backups) = obj.get_mut("backups").and_then(Value::as_array_mut) {
+        for backup in backups.iter_mut().filter_map(Value::as_object_mut) {
+            backup.entry("store").or_insert(Value::Null);
+        }
+    }
+
+    Ok(())
+}
+
+/// v2 -> v3: adds replica locations to the target,
+/// and `checksum` / replica statuses to each backup entry.
+fn migrate_v2_to_v3(info: &mut Value) -> anyhow::Result<()> {
+    let obj = info
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Target information is not a json object."))?;
+    obj.entry("replicas").or_insert(Value::Array(Vec::new()));
+
+    if let Some(backups) = obj.get_mut("backups").and_then(Value::as_array_mut) {
+        for backup in backups.iter_mut().filter_map(Value::as_object_mut) {
+            backup.entry("checksum").or_insert(Value::Null);
+            backup.entry("replicas").or_insert(Value::Array(Vec::new()));
+        }
+    }
+
+    Ok(())
+}
+
+/// v3 -> v4: adds the description to the target.
+fn migrate_v3_to_v4(info: &mut Value) -> anyhow::Result<()> {
+    let obj = info
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Target information is not a json object."))?;
+    obj.entry("description")
+        .or_insert(Value::String(String::new()));
+
+    Ok(())
+}
+
+/// v4 -> v5: adds tags to the target.
+fn migrate_v4_to_v5(info: &mut Value) -> anyhow::Result<()> {
+    let obj = info
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Target information is not a json object."))?;
+    obj.entry("tags").or_insert(Value::Array(Vec::new()));
+
+    Ok(())
+}
+
+/// v5 -> v6: adds the schedule and the retention policy to the target.
+fn migrate_v5_to_v6(info: &mut Value) -> anyhow::Result<()> {
+    let obj = info
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Target information is not a json object."))?;
+    obj.entry("schedule").or_insert(Value::Null);
+    obj.entry("retention")
+        .or_insert(Value::Object(serde_json::Map::new()));
+
+    Ok(())
+}
+
+/// v6 -> v7: adds the hooks to the target.
+fn migrate_v6_to_v7(info: &mut Value) -> anyhow::Result<()> {
+    let obj = info
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Target information is not a json object."))?;
+    obj.entry("hooks")
+        .or_insert(Value::Object(serde_json::Map::new()));
+
+    Ok(())
+}
+
+/// v7 -> v8: adds the additional sources to the target and each backup entry.
+///
+/// Existing backups contain the target path only, so their sources are empty.
+fn migrate_v7_to_v8(info: &mut Value) -> anyhow::Result<()> {
+    let obj = info
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Target information is not a json object."))?;
+    obj.entry("sources").or_insert(Value::Array(Vec::new()));
+
+    if let Some(backups) = obj.get_mut("backups").and_then(Value::as_array_mut) {
+        for backup in backups.iter_mut().filter_map(Value::as_object_mut) {
+            backup.entry("sources").or_insert(Value::Array(Vec::new()));
+        }
+    }
+
+    Ok(())
+}
+
+/// v8 -> v9: adds the kind of the target path to the target and each backup entry.
+///
+/// Only directories could be registered before, so the kind is `directory`.
+fn migrate_v8_to_v9(info: &mut Value) -> anyhow::Result<()> {
+    let obj = info
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Target information is not a json object."))?;
+    obj.entry("kind")
+        .or_insert(Value::String(String::from("directory")));
+
+    if let Some(backups) = obj.get_mut("backups").and_then(Value::as_array_mut) {
+        for backup in backups.iter_mut().filter_map(Value::as_object_mut) {
+            backup
+                .entry("kind")
+                .or_insert(Value::String(String::from("directory")));
+        }
+    }
+
+    Ok(())
+}
+
+/// v9 -> v10: adds the pinned flag to each backup entry.
+fn migrate_v9_to_v10(info: &mut Value) -> anyhow::Result<()> {
+    let obj = info
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Target information is not a json object."))?;
+
+    if let Some(backups) = obj.get_mut("backups").and_then(Value::as_array_mut) {
+        for backup in backups.iter_mut().filter_map(Value::as_object_mut) {
+            backup.entry("pinned").or_insert(Value::Bool(false));
+        }
+    }
+
+    Ok(())
+}
+
+/// v10 -> v11: adds the sizes to each backup entry.
+///
+/// The sizes of existing backups were not recorded, so they are `null`.
+fn migrate_v10_to_v11(info: &mut Value) -> anyhow::Result<()> {
+    let obj = info
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Target information is not a json object."))?;
+
+    if let Some(backups) = obj.get_mut("backups").and_then(Value::as_array_mut) {
+        for backup in backups.iter_mut().filter_map(Value::as_object_mut) {
+            backup.entry("stats").or_insert(Value::Null);
+        }
+    }
+
+    Ok(())
+}
+
+/// v11 -> v12: adds the time of the last failed scheduled backup.
+fn migrate_v11_to_v12(info: &mut Value) -> anyhow::Result<()> {
+    let obj = info
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Target information is not a json object."))?;
+
+    obj.entry("last_failed_run").or_insert(Value::Null);
+
+    Ok(())
+}
+
+/// v12 -> v13: adds the largest backup ID given to the target.
+///
+/// The IDs of the backups deleted before are not known, so it is the largest ID of the backups.
+fn migrate_v12_to_v13(info: &mut Value) -> anyhow::Result<()> {
+    let obj = info
+        .as_object_mut()
+        .ok_or_else(|| anyhow::anyhow!("Target information is not a json object."))?;
+
+    let last_id = obj
+        .get("backups")
+        .and_then(Value::as_array)
+        .into_iter()
+        .flatten()
+        .filter_map(|backup| backup.get("id").and_then(Value::as_u64))
+        .max()
+        .unwrap_or(0);
+    obj.entry("last_backup_id").or_insert(Value::from(last_id));
+
+    Ok(())
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn it_treats_missing_version_as_v0() {
+        let info = json!({ "id": "xxx" });
+        assert_eq!(schema_version(&info).unwrap(), 0);
+    }
+
+    #[test]
+    fn it_returns_err_if_version_is_invalid() {
+        let info = json!({ "schema_version": "one" });
+        assert!(schema_version(&info).is_err());
+    }
+
+    #[test]
+    fn it_upgrades_to_current_version() {
+        let mut info = json!({ "id": "xxx" });
+
+        let result = migrate(&mut info).unwrap();
+        assert_eq!(result, Some(0));
+        assert_eq!(schema_version(&info).unwrap(), CURRENT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn it_adds_store_in_v2() {
+        let mut info = json!({
+            "schema_version": 1,
+            "id": "xxx",
+            "backups": [{ "id": 1 }, { "id": 2 }],
+        });
+
+        migrate(&mut info).unwrap();
+        assert_eq!(info["store"], Value::Null);
+        assert_eq!(info["backups"][0]["store"], Value::Null);
+        assert_eq!(info["backups"][1]["store"], Value::Null);
+    }
+
+    #[test]
+    fn it_adds_replicas_in_v3() {
+        let mut info = json!({
+            "schema_version": 2,
+            "id": "xxx",
+            "backups": [{ "id": 1 }],
+        });
+
+        migrate(&mut info).unwrap();
+        assert_eq!(info["replicas"], json!([]));
+        assert_eq!(info["backups"][0]["checksum"], Value::Null);
+        assert_eq!(info["backups"][0]["replicas"], json!([]));
+    }
+
+    #[test]
+    fn it_adds_description_in_v4() {
+        let mut info = json!({ "schema_version": 3, "id": "xxx" });
+
+        migrate(&mut info).unwrap();
+        assert_eq!(info["description"], json!(""));
+    }
+
+    #[test]
+    fn it_adds_tags_in_v5() {
+        let mut info = json!({ "schema_version": 4, "id": "xxx" });
+
+        migrate(&mut info).unwrap();
+        assert_eq!(info["tags"], json!([]));
+    }
+
+    #[test]
+    fn it_adds_schedule_in_v6() {
+        let mut info = json!({ "schema_version": 5, "id": "xxx" });
+
+        migrate(&mut info).unwrap();
+        assert_eq!(info["schedule"], Value::Null);
+        assert_eq!(info["retention"], json!({}));
+    }
+
+    #[test]
+    fn it_adds_hooks_in_v7() {
+        let mut info = json!({ "schema_version": 6, "id": "xxx" });
+
+        migrate(&mut info).unwrap();
+        assert_eq!(info["hooks"], json!({}));
+    }
+
+    #[test]
+    fn it_adds_sources_in_v8() {
+        let mut info = json!({
+            "schema_version": 7,
+            "id": "xxx",
+            "backups": [{ "id": 1 }],
+        });
+
+        migrate(&mut info).unwrap();
+        assert_eq!(info["sources"], json!([]));
+        assert_eq!(info["backups"][0]["sources"], json!([]));
+    }
+
+    #[test]
+    fn it_adds_kind_in_v9() {
+        let mut info = json!({
+            "schema_version": 8,
+            "id": "xxx",
+            "backups": [{ "id": 1 }],
+        });
+
+        migrate(&mut info).unwrap();
+        assert_eq!(info["kind"], json!("directory"));
+        assert_eq!(info["backups"][0]["kind"], json!("directory"));
+    }
+
+    #[test]
+    fn it_adds_pinned_in_v10() {
+        let mut info = json!({
+            "schema_version": 9,
+            "id": "xxx",
+            "backups": [{ "id": 1 }],
+        });
+
+        migrate(&mut info).unwrap();
+        assert_eq!(info["backups"][0]["pinned"], json!(false));
+    }
+
+    #[test]
+    fn it_adds_stats_in_v11() {
+        let mut info = json!({
+            "schema_version": 10,
+            "id": "xxx",
+            "backups": [{ "id": 1 }],
+        });
+
+        migrate(&mut info).unwrap();
+        assert_eq!(info["backups"][0]["stats"], Value::Null);
+    }
+
+    #[test]
+    fn it_adds_last_failed_run_in_v12() {
+        let mut info = json!({ "schema_version": 11, "id": "xxx" });
+
+        migrate(&mut info).unwrap();
+        assert_eq!(info["last_failed_run"], Value::Null);
+    }
+
+    #[test]
+    fn it_adds_last_backup_id_in_v13() {
+        let mut info =
+            json!({ "schema_version": 12, "id": "xxx", "backups": [{ "id": 1 }, { "id": 3 }] });
+        migrate(&mut info).unwrap();
+        assert_eq!(info["last_backup_id"], 3);
+
+        let mut info = json!({ "schema_version": 12, "id": "xxx", "backups": [] });
+        migrate(&mut info).unwrap();
+        assert_eq!(info["last_backup_id"], 0);
+    }
+
+    #[test]
+    fn it_does_nothing_if_already_current() {
+        let mut info = json!({ "id": "xxx", "schema_version": CURRENT_SCHEMA_VERSION });
+        let before = info.clone();
+
+        let result = migrate(&mut info).unwrap();
+        assert_eq!(result, None);
+        assert_eq!(info, before);
+    }
+
+    #[test]
+    fn it_returns_err_if_version_is_newer() {
+        let mut info = json!({ "id": "xxx", "schema_version": CURRENT_SCHEMA_VERSION + 1 });
+        assert!(migrate(&mut info).is_err());
+    }
+}
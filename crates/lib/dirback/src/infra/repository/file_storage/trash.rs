@@ -0,0 +1,404 @@
+//!
+//! # Trash of the FileStorage repository
+//!
+//! Deleted targets and backups are moved to the trash directory.
+//!
+//! ```ascii
+//! {base_dir}/
+//! └─ trash/
+//!    └─ {trash_id}/
+//!       ├─ trash.json
+//!       ├─ target/ (a deleted target directory)
+//!       └─ {backup_id}_{backup_timestamp}.tar.gz (a deleted backup file)
+//! ```
+//!
+//! Backup files in an archive store are left in the store
+//! until the item is removed from the trash.
+//! Expired items are removed when another item is moved to the trash.
+//!
+
+use super::{
+    FileStorageTargetRepository, TARGET_INFO_FILE_NAME, create_backup_dir_path,
+    create_target_info_dir_path, read_target_info,
+};
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::model::target::Target;
+use crate::domain::model::timestamp::Timestamp;
+use crate::domain::model::trash_item::TrashItem;
+use crate::domain::repository::targets::TargetRepository;
+use crate::domain::repository::trash::TrashRepository;
+use crate::infra::service::archive_store::remove_archive;
+use std::path::{Path, PathBuf};
+
+const TRASH_DIR_NAME: &str = "trash";
+const TRASH_INFO_FILE_NAME: &str = "trash.json";
+const TRASHED_TARGET_DIR_NAME: &str = "target";
+
+fn create_trash_dir_path(base_dir: &Path, trash_id: Option<u32>) -> PathBuf {
+    let path = base_dir.join(TRASH_DIR_NAME);
+
+    match trash_id {
+        Some(trash_id) => path.join(trash_id.to_string()),
+        None => path,
+    }
+}
+
+/// Makes a directory for a new trash item, and returns its ID and path.
+fn create_item_dir(base_dir: &Path) -> anyhow::Result<(u32, PathBuf)> {
+    let trash_dir = create_trash_dir_path(base_dir, None);
+    std::fs::create_dir_all(&trash_dir)?;
+
+    let last_id = std::fs::read_dir(&trash_dir)?
+        .filter_map(|dir| dir.ok()?.file_name().to_str()?.parse::<u32>().ok())
+        .max();
+    let trash_id = last_id.map_or(1, |id| id + 1);
+
+    let dir = create_trash_dir_path(base_dir, Some(trash_id));
+    std::fs::create_dir(&dir)?;
+    Ok((trash_id, dir))
+}
+
+/// Reads all trash items, ordered by ID.
+fn read_items(base_dir: &Path) -> anyhow::Result<Vec<TrashItem>> {
+    let trash_dir = create_trash_dir_path(base_dir, None);
+    if !trash_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut items = Vec::new();
+    for dir in std::fs::read_dir(&trash_dir)? {
+        let Ok(dir) = dir else {
+            continue;
+        };
+
+        let info_path = dir.path().join(TRASH_INFO_FILE_NAME);
+        if let Ok(item) = jsonfile::read::<TrashItem>(&info_path) {
+            items.push(item);
+        }
+    }
+
+    items.sort_by_key(|item| item.id);
+    Ok(items)
+}
+
+/// Moves the file into the directory of a new trash item, and writes the item.
+///
+/// The directory is removed if the file can't be moved.
+fn write_item(
+    base_dir: &Path,
+    target: &Target,
+    backup: Option<&BackupEntry>,
+    src: Option<(&Path, PathBuf)>,
+) -> anyhow::Result<TrashItem> {
+    let (trash_id, dir) = create_item_dir(base_dir)?;
+
+    if let Some((from, name)) = src {
+        if let Err(e) = std::fs::rename(from, dir.join(name)) {
+            let _ = std::fs::remove_dir_all(&dir);
+            return Err(e.into());
+        }
+    }
+
+    let item = TrashItem::new(trash_id, target, backup.cloned(), Timestamp::now());
+    jsonfile::write(&dir.join(TRASH_INFO_FILE_NAME), &item)?;
+
+    let _ = purge_expired(base_dir, &item.deleted_at);
+    Ok(item)
+}
+
+/// Removes the trash item and its backup files permanently.
+fn purge(base_dir: &Path, item: &TrashItem) -> anyhow::Result<()> {
+    let dir = create_trash_dir_path(base_dir, Some(item.id));
+
+    let remote_entries = match &item.backup {
+        Some(entry) => vec![entry.clone()],
+        None => {
+            let info_path = dir
+                .join(TRASHED_TARGET_DIR_NAME)
+                .join(TARGET_INFO_FILE_NAME);
+            read_target_info(&info_path)
+                .map(|target| target.backups)
+                .unwrap_or_default()
+        }
+    };
+    for entry in remote_entries.iter().filter(|b| b.store.is_some()) {
+        let _ = remove_archive(base_dir, entry);
+    }
+
+    std::fs::remove_dir_all(&dir)?;
+    Ok(())
+}
+
+fn purge_expired(base_dir: &Path, now: &Timestamp) -> anyhow::Result<Vec<TrashItem>> {
+    let expired: Vec<TrashItem> = read_items(base_dir)?
+        .into_iter()
+        .filter(|item| item.is_expired(now))
+        .collect();
+
+    for item in expired.iter() {
+        purge(base_dir, item)?;
+    }
+
+    Ok(expired)
+}
+
+fn backup_file_name(entry: &BackupEntry) -> anyhow::Result<PathBuf> {
+    entry
+        .path
+        .file_name()
+        .map(PathBuf::from)
+        .ok_or_else(|| anyhow::anyhow!("Invalid backup path ('{}').", entry.path.display()))
+}
+
+impl FileStorageTargetRepository {
+    /// Moves the backup file to the trash.
+    pub(super) fn trash_backup(
+        &self,
+        target: &Target,
+        entry: &BackupEntry,
+    ) -> anyhow::Result<TrashItem> {
+        let src = match entry.store {
+            None => Some((entry.path.as_path(), backup_file_name(entry)?)),
+            Some(_) => None,
+        };
+
+        write_item(&self.base_dir, target, Some(entry), src)
+    }
+
+    /// Moves the target directory to the trash.
+    pub(super) fn trash_target(&self, target: &Target) -> anyhow::Result<TrashItem> {
+        let target_dir = create_target_info_dir_path(&self.base_dir, Some(&target.id));
+        let src = (target_dir.as_path(), PathBuf::from(TRASHED_TARGET_DIR_NAME));
+
+        write_item(&self.base_dir, target, None, Some(src))
+    }
+}
+
+impl TrashRepository for FileStorageTargetRepository {
+    fn list_trash(&self) -> anyhow::Result<Vec<TrashItem>> {
+        read_items(&self.base_dir)
+    }
+
+    fn restore_trash(&mut self, trash_id: u32) -> anyhow::Result<TrashItem> {
+        let item = self
+            .list_trash()?
+            .into_iter()
+            .find(|item| item.id == trash_id)
+            .ok_or_else(|| anyhow::anyhow!("Trash item not found ('{trash_id}')."))?;
+        let dir = create_trash_dir_path(&self.base_dir, Some(trash_id));
+
+        match &item.backup {
+            None => {
+                let target_dir = create_target_info_dir_path(&self.base_dir, Some(&item.target_id));
+                if target_dir.exists() {
+                    anyhow::bail!("Target already exists ('{}').", item.target_id);
+                }
+
+                std::fs::rename(dir.join(TRASHED_TARGET_DIR_NAME), &target_dir)?;
+            }
+            Some(entry) => {
+                let mut target = self.load(&item.target_id).ok_or_else(|| {
+                    anyhow::anyhow!(
+                        "Target not found ('{}'), restore the target first.",
+                        item.target_id
+                    )
+                })?;
+
+                let mut entry = entry.clone();
+                let file_name = backup_file_name(&entry)?;
+                if entry.store.is_none() {
+                    let bk_dir = create_backup_dir_path(&self.base_dir, &target.id);
+                    entry.path = bk_dir.join(&file_name);
+                }
+
+                target.restore_backup_entry(entry.clone()).map_err(|_| {
+                    anyhow::anyhow!(
+                        "Target('{}') already has a backup(id='{}').",
+                        target.id,
+                        entry.id
+                    )
+                })?;
+
+                if entry.store.is_none() {
+                    std::fs::create_dir_all(create_backup_dir_path(&self.base_dir, &target.id))?;
+                    std::fs::rename(dir.join(&file_name), &entry.path)?;
+                }
+                self.update(&target)?;
+            }
+        }
+
+        std::fs::remove_dir_all(&dir)?;
+        Ok(item)
+    }
+
+    fn empty_trash(&mut self) -> anyhow::Result<Vec<TrashItem>> {
+        let items = read_items(&self.base_dir)?;
+        for item in items.iter() {
+            purge(&self.base_dir, item)?;
+        }
+
+        Ok(items)
+    }
+
+    fn purge_expired_trash(&mut self, now: &Timestamp) -> anyhow::Result<Vec<TrashItem>> {
+        purge_expired(&self.base_dir, now)
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prepare_target(repo: &mut FileStorageTargetRepository, count: usize) -> Target {
+        let mut target = repo.add("TestTarget", Path::new(".")).unwrap();
+        let bkdir = repo.make_backup_dir_path(&target);
+        for _ in 0..count {
+            let entry = target.new_backup_entry(&bkdir, "tar.gz");
+            std::fs::write(&entry.path, "dummy").unwrap();
+            target.register_backup_entry(entry).unwrap();
+        }
+        repo.update(&target).unwrap()
+    }
+
+    mod trash_backup {
+        use super::*;
+
+        #[test]
+        fn it_restores_deleted_backup() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = FileStorageTargetRepository::new(&temp.path());
+            let target = prepare_target(&mut repo, 3);
+
+            let deleted = repo.delete_backup(&target.id, 2).unwrap();
+            assert!(!deleted.path.exists());
+
+            let items = repo.list_trash().unwrap();
+            assert_eq!(items.len(), 1);
+            assert_eq!(items[0].target_id, target.id);
+            assert_eq!(items[0].backup.as_ref().unwrap().id, 2);
+
+            repo.restore_trash(items[0].id).unwrap();
+            assert!(deleted.path.exists());
+            assert!(repo.list_trash().unwrap().is_empty());
+
+            let loaded = repo.load(&target.id).unwrap();
+            let ids: Vec<u32> = loaded.backups.iter().map(|b| b.id).collect();
+            assert_eq!(ids, vec![1, 2, 3]);
+        }
+
+        #[test]
+        fn it_does_not_reuse_id_of_trashed_backup() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = FileStorageTargetRepository::new(&temp.path());
+            let target = prepare_target(&mut repo, 2);
+
+            // Trash the newest backup, then take a new backup.
+            let _ = repo.delete_backup(&target.id, 2).unwrap();
+            let mut target = repo.load(&target.id).unwrap();
+            let entry = target.new_backup_entry(&repo.make_backup_dir_path(&target), "tar.gz");
+            assert_eq!(entry.id, 3);
+            std::fs::write(&entry.path, "dummy").unwrap();
+            target.register_backup_entry(entry).unwrap();
+            repo.update(&target).unwrap();
+
+            let trash_id = repo.list_trash().unwrap()[0].id;
+            repo.restore_trash(trash_id).unwrap();
+
+            let loaded = repo.load(&target.id).unwrap();
+            let ids: Vec<u32> = loaded.backups.iter().map(|b| b.id).collect();
+            assert_eq!(ids, vec![1, 2, 3]);
+        }
+
+        #[test]
+        fn it_returns_err_if_backup_id_is_in_use() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = FileStorageTargetRepository::new(&temp.path());
+            let mut target = prepare_target(&mut repo, 2);
+
+            // A backup with the same ID is put back by other means, e.g. an import.
+            let _ = repo.delete_backup(&target.id, 2).unwrap();
+            target.backups.truncate(1);
+            let path = repo.make_backup_dir_path(&target).join("0002.tar.gz");
+            let entry = BackupEntry::new(2, &path, Timestamp::now(), "");
+            target.register_backup_entry(entry).unwrap();
+            repo.update(&target).unwrap();
+
+            let trash_id = repo.list_trash().unwrap()[0].id;
+            assert!(repo.restore_trash(trash_id).is_err());
+            assert_eq!(repo.list_trash().unwrap().len(), 1);
+        }
+    }
+
+    mod trash_target {
+        use super::*;
+
+        #[test]
+        fn it_restores_deleted_target() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = FileStorageTargetRepository::new(&temp.path());
+            let target = prepare_target(&mut repo, 2);
+
+            repo.delete_target(&target.id).unwrap();
+            assert!(repo.load(&target.id).is_none());
+
+            let items = repo.list_trash().unwrap();
+            assert_eq!(items.len(), 1);
+            assert!(items[0].is_target());
+            assert_eq!(items[0].target_name, "TestTarget");
+
+            repo.restore_trash(items[0].id).unwrap();
+            let loaded = repo.load(&target.id).unwrap();
+            assert_eq!(loaded, target);
+            assert!(loaded.backups.iter().all(|b| b.path.exists()));
+        }
+
+        #[test]
+        fn it_returns_err_if_trash_item_not_found() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = FileStorageTargetRepository::new(&temp.path());
+            assert!(repo.restore_trash(1).is_err());
+        }
+    }
+
+    mod empty_trash {
+        use super::*;
+
+        #[test]
+        fn it_removes_all_items() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = FileStorageTargetRepository::new(&temp.path());
+            let target = prepare_target(&mut repo, 2);
+            let _ = repo.delete_backup(&target.id, 1).unwrap();
+            let _ = repo.delete_target(&target.id).unwrap();
+
+            let removed = repo.empty_trash().unwrap();
+            assert_eq!(removed.len(), 2);
+            assert!(repo.list_trash().unwrap().is_empty());
+            assert_eq!(
+                std::fs::read_dir(create_trash_dir_path(&temp.path(), None))
+                    .unwrap()
+                    .count(),
+                0
+            );
+        }
+
+        #[test]
+        fn it_removes_expired_items() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = FileStorageTargetRepository::new(&temp.path());
+            let target = prepare_target(&mut repo, 2);
+            let _ = repo.delete_backup(&target.id, 1).unwrap();
+
+            let now = Timestamp::now();
+            assert!(repo.purge_expired_trash(&now).unwrap().is_empty());
+
+            let item = &repo.list_trash().unwrap()[0];
+            let removed = repo.purge_expired_trash(&item.expires_at).unwrap();
+            assert_eq!(removed.len(), 1);
+            assert!(repo.list_trash().unwrap().is_empty());
+        }
+    }
+}
@@ -7,20 +7,34 @@
 
 use crate::domain::model::backup_entry::BackupEntry;
 use crate::domain::model::target::Target;
+use crate::domain::model::timestamp::Timestamp;
+use crate::domain::model::trash_item::TrashItem;
 use crate::domain::repository::targets::TargetRepository;
+use crate::domain::repository::trash::TrashRepository;
+use crate::domain::service::archive_store::ArchiveStore;
 use std::path::{Path, PathBuf};
 
 #[derive(Default)]
 pub struct InMemoryTargetRepository {
     targets: Vec<Target>,
+
+    /// Trash items with the deleted targets.
+    trash: Vec<(TrashItem, Target)>,
 }
 
 impl InMemoryTargetRepository {
     pub fn new() -> Self {
         Self {
             targets: Vec::<Target>::new(),
+            trash: Vec::new(),
         }
     }
+
+    fn push_trash(&mut self, target: &Target, backup: Option<BackupEntry>) {
+        let id = self.trash.last().map_or(1, |(item, _)| item.id + 1);
+        let item = TrashItem::new(id, target, backup, Timestamp::now());
+        self.trash.push((item, target.clone()));
+    }
 }
 
 impl TargetRepository for InMemoryTargetRepository {
@@ -50,6 +64,15 @@ impl TargetRepository for InMemoryTargetRepository {
         Ok(target)
     }
 
+    fn import(&mut self, target: &Target) -> anyhow::Result<Target> {
+        if self.load(&target.id).is_some() {
+            anyhow::bail!("Target already exists ('{}').", target.id);
+        }
+
+        self.targets.push(target.clone());
+        Ok(target.clone())
+    }
+
     fn delete_backup(&mut self, target_id: &str, backup_id: u32) -> anyhow::Result<BackupEntry> {
         let mut target = self
             .load(target_id)
@@ -58,6 +81,7 @@ impl TargetRepository for InMemoryTargetRepository {
         if let Some(pos) = target.backups.iter().position(|b| b.id == backup_id) {
             let entry = target.backups.remove(pos);
             let _ = self.update(&target)?;
+            self.push_trash(&target, Some(entry.clone()));
             Ok(entry)
         } else {
             anyhow::bail!(
@@ -68,16 +92,75 @@ impl TargetRepository for InMemoryTargetRepository {
 
     fn delete_target(&mut self, target_id: &str) -> anyhow::Result<Target> {
         if let Some(pos) = self.targets.iter().position(|t| t.id == target_id) {
-            Ok(self.targets.remove(pos))
+            let target = self.targets.remove(pos);
+            self.push_trash(&target, None);
+            Ok(target)
         } else {
             anyhow::bail!("Target not found ('{target_id}')")
         }
     }
 
+    fn data_dir(&self) -> Option<&Path> {
+        None
+    }
+
     /// For testing.
     fn make_backup_dir_path(&self, _target: &Target) -> PathBuf {
         PathBuf::new()
     }
+
+    /// For testing, no archive stores are registered.
+    fn archive_store(&self, store_id: &str) -> anyhow::Result<Box<dyn ArchiveStore>> {
+        anyhow::bail!("Archive store not found ('{store_id}').")
+    }
+}
+
+impl TrashRepository for InMemoryTargetRepository {
+    fn list_trash(&self) -> anyhow::Result<Vec<TrashItem>> {
+        Ok(self.trash.iter().map(|(item, _)| item.clone()).collect())
+    }
+
+    fn restore_trash(&mut self, trash_id: u32) -> anyhow::Result<TrashItem> {
+        let pos = self
+            .trash
+            .iter()
+            .position(|(item, _)| item.id == trash_id)
+            .ok_or_else(|| anyhow::anyhow!("Trash item not found ('{trash_id}')."))?;
+        let (item, target) = self.trash[pos].clone();
+
+        match &item.backup {
+            None => {
+                let _ = self.import(&target)?;
+            }
+            Some(entry) => {
+                let mut target = self
+                    .load(&item.target_id)
+                    .ok_or_else(|| anyhow::anyhow!("Target not found ('{}').", item.target_id))?;
+                target
+                    .restore_backup_entry(entry.clone())
+                    .map_err(|e| anyhow::anyhow!("{e}"))?;
+                let _ = self.update(&target)?;
+            }
+        }
+
+        self.trash.remove(pos);
+        Ok(item)
+    }
+
+    fn empty_trash(&mut self) -> anyhow::Result<Vec<TrashItem>> {
+        let items = self.list_trash()?;
+        self.trash.clear();
+        Ok(items)
+    }
+
+    fn purge_expired_trash(&mut self, now: &Timestamp) -> anyhow::Result<Vec<TrashItem>> {
+        let (expired, kept) = self
+            .trash
+            .drain(..)
+            .partition(|(item, _)| item.is_expired(now));
+        self.trash = kept;
+        Ok(expired.into_iter().map(|(item, _)| item).collect())
+    }
 }
 
 //-----------------------------------------------------------------------------
@@ -209,6 +292,30 @@ mod tests {
         assert_eq!(target.backups.len(), 0);
     }
 
+    mod import {
+        use super::*;
+
+        #[test]
+        fn it_works() {
+            let mut repo = InMemoryTargetRepository::new();
+            let target = Target::new("xxx-xxx", "Test Target", Path::new("target"));
+
+            let result = repo.import(&target);
+            assert!(result.is_ok());
+            assert_eq!(repo.load("xxx-xxx"), Some(target));
+        }
+
+        #[test]
+        fn it_returns_err_when_id_already_exists() {
+            let mut repo = InMemoryTargetRepository::new();
+            let target = repo.add("Test Target", Path::new("target")).unwrap();
+
+            let result = repo.import(&target);
+            assert!(result.is_err());
+            assert_eq!(repo.targets.len(), 1);
+        }
+    }
+
     mod delete_backup {
         use super::*;
 
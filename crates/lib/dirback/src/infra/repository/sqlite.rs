@@ -0,0 +1,1077 @@
+//!
+//! # SQLite Repository
+//!
+//! Store target informations to a SQLite database.
+//!
+//! Only the target informations are stored in the database,
+//! backup files are stored in the same place as the FileStorage repository.
+//!
+//! The commands use the FileStorage repository (`info.json`), and the database is a copy of it:
+//! the targets are copied in and out (`import` / `export`), and searched by `query`
+//! after the copy is refreshed with `refresh`, so a search never sees stale targets.
+//!
+//!
+//! ## Direcotry structure
+//!
+//! ```ascii
+//! {base_dir}/
+//! ├─ dirback.sqlite3
+//! └─ targets/
+//!    └─ {target_id}/
+//!       └─ backups/
+//!          └─ {backup_id}_{backup_timestamp}.tar.gz
+//! ```
+//!
+
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::model::schedule::Schedule;
+use crate::domain::model::target::Target;
+use crate::domain::model::target_kind::TargetKind;
+use crate::domain::model::timestamp::Timestamp;
+use crate::domain::repository::targets::TargetRepository;
+use crate::domain::service::archive_store::ArchiveStore;
+use crate::infra::repository::file_storage::{create_backup_dir_path, create_target_info_dir_path};
+use crate::infra::service::archive_store::{open_archive_store, remove_archive};
+use rusqlite::{Connection, OptionalExtension, Row, params};
+use std::path::{Path, PathBuf};
+
+/// The database file name.
+pub const DATABASE_FILE_NAME: &str = "dirback.sqlite3";
+
+/// Database migrations, `MIGRATIONS[n]` upgrades `user_version` `n` to `n + 1`.
+const MIGRATIONS: [&str; 13] = [
+    r#"
+    CREATE TABLE targets (
+        id   TEXT PRIMARY KEY NOT NULL,
+        name TEXT NOT NULL,
+        path TEXT NOT NULL
+    );
+
+    CREATE TABLE backups (
+        target_id TEXT NOT NULL REFERENCES targets(id) ON DELETE CASCADE,
+        id        INTEGER NOT NULL,
+        path      TEXT NOT NULL,
+        timestamp TEXT NOT NULL,
+        note      TEXT NOT NULL,
+        PRIMARY KEY (target_id, id)
+    );
+
+    CREATE INDEX backups_timestamp ON backups(timestamp);
+"#,
+    r#"
+    ALTER TABLE targets ADD COLUMN store TEXT;
+    ALTER TABLE backups ADD COLUMN store TEXT;
+"#,
+    r#"
+    ALTER TABLE targets ADD COLUMN replicas TEXT NOT NULL DEFAULT '[]';
+    ALTER TABLE backups ADD COLUMN checksum TEXT;
+    ALTER TABLE backups ADD COLUMN replicas TEXT NOT NULL DEFAULT '[]';
+"#,
+    r#"
+    ALTER TABLE targets ADD COLUMN description TEXT NOT NULL DEFAULT '';
+"#,
+    r#"
+    ALTER TABLE targets ADD COLUMN tags TEXT NOT NULL DEFAULT '[]';
+"#,
+    r#"
+    ALTER TABLE targets ADD COLUMN schedule TEXT;
+    ALTER TABLE targets ADD COLUMN retention TEXT NOT NULL DEFAULT '{}';
+"#,
+    r#"
+    ALTER TABLE targets ADD COLUMN hooks TEXT NOT NULL DEFAULT '{}';
+"#,
+    r#"
+    ALTER TABLE targets ADD COLUMN sources TEXT NOT NULL DEFAULT '[]';
+    ALTER TABLE backups ADD COLUMN sources TEXT NOT NULL DEFAULT '[]';
+"#,
+    r#"
+    ALTER TABLE targets ADD COLUMN kind TEXT NOT NULL DEFAULT 'directory';
+    ALTER TABLE backups ADD COLUMN kind TEXT NOT NULL DEFAULT 'directory';
+"#,
+    r#"
+    ALTER TABLE backups ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
+"#,
+    r#"
+    ALTER TABLE backups ADD COLUMN stats TEXT;
+"#,
+    r#"
+    ALTER TABLE targets ADD COLUMN last_failed_run TEXT;
+"#,
+    r#"
+    ALTER TABLE targets ADD COLUMN last_backup_id INTEGER NOT NULL DEFAULT 0;
+    UPDATE targets
+       SET last_backup_id = COALESCE((SELECT MAX(id) FROM backups WHERE target_id = targets.id), 0);
+"#,
+];
+
+//-----------------------------------------------------------------------------
+// TargetQuery
+//-----------------------------------------------------------------------------
+
+/// Conditions to search targets.
+///
+/// Each condition is ignored if it is `None`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TargetQuery {
+    /// The target name contains this string.
+    pub name: Option<String>,
+
+    /// The target has this tag.
+    pub tag: Option<String>,
+
+    /// Only backups taken at or after this time.
+    pub since: Option<Timestamp>,
+
+    /// Only backups taken at or before this time.
+    pub until: Option<Timestamp>,
+
+    /// Only backups whose archive size is at least this many bytes.
+    pub min_size: Option<u64>,
+
+    /// Only backups whose archive size is at most this many bytes.
+    pub max_size: Option<u64>,
+}
+
+impl TargetQuery {
+    fn has_backup_condition(&self) -> bool {
+        self.since.is_some()
+            || self.until.is_some()
+            || self.min_size.is_some()
+            || self.max_size.is_some()
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Helper
+//-----------------------------------------------------------------------------
+
+/// Timestamps are stored in a fixed-width format, so that they can be compared as strings.
+fn timestamp_to_sql(ts: &Timestamp) -> String {
+    ts.to_rfc3339_opts(chrono::SecondsFormat::Nanos, true)
+}
+
+/// Lists (e.g. replica informations, tags) are stored as json text.
+fn json_to_sql<T: serde::Serialize>(value: &T) -> anyhow::Result<String> {
+    Ok(serde_json::to_string(value)?)
+}
+
+fn timestamp_from_sql(idx: usize, ts: String) -> rusqlite::Result<Timestamp> {
+    let ts = chrono::DateTime::parse_from_rfc3339(&ts).map_err(|e| conversion_error(idx, e))?;
+    Ok(Timestamp::from_utc(&ts.to_utc()))
+}
+
+/// Target kinds are stored by their names.
+fn kind_from_sql(idx: usize, kind: String) -> rusqlite::Result<TargetKind> {
+    serde_json::from_value(serde_json::Value::String(kind)).map_err(|e| conversion_error(idx, e))
+}
+
+fn conversion_error<E>(idx: usize, e: E) -> rusqlite::Error
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+}
+
+/// Columns read by `target_from_row`.
+const TARGET_COLUMNS: &str = "id, name, path, store, replicas, description, tags, schedule, retention, hooks, sources, kind, last_failed_run, last_backup_id";
+
+/// Makes a target (without backups) from a row of `TARGET_COLUMNS`.
+fn target_from_row(row: &Row) -> rusqlite::Result<Target> {
+    let id: String = row.get(0)?;
+    let name: String = row.get(1)?;
+    let path: String = row.get(2)?;
+
+    let mut target = Target::new(&id, &name, Path::new(&path));
+    target.store = row.get(3)?;
+    target.replicas =
+        serde_json::from_str(&row.get::<_, String>(4)?).map_err(|e| conversion_error(4, e))?;
+    target.description = row.get(5)?;
+    target.tags =
+        serde_json::from_str(&row.get::<_, String>(6)?).map_err(|e| conversion_error(6, e))?;
+    target.schedule = row
+        .get::<_, Option<String>>(7)?
+        .map(|s| Schedule::parse(&s).map_err(|e| conversion_error(7, e)))
+        .transpose()?;
+    target.retention =
+        serde_json::from_str(&row.get::<_, String>(8)?).map_err(|e| conversion_error(8, e))?;
+    target.hooks =
+        serde_json::from_str(&row.get::<_, String>(9)?).map_err(|e| conversion_error(9, e))?;
+    target.sources =
+        serde_json::from_str(&row.get::<_, String>(10)?).map_err(|e| conversion_error(10, e))?;
+    target.kind = kind_from_sql(11, row.get(11)?)?;
+    target.last_failed_run = row
+        .get::<_, Option<String>>(12)?
+        .map(|ts| timestamp_from_sql(12, ts))
+        .transpose()?;
+    target.last_backup_id = row.get(13)?;
+    Ok(target)
+}
+
+/// Columns read by `backup_from_row`.
+const BACKUP_COLUMNS: &str =
+    "id, path, timestamp, note, store, checksum, replicas, sources, kind, pinned, stats";
+
+/// Makes a backup entry from a row of `BACKUP_COLUMNS`.
+fn backup_from_row(row: &Row) -> rusqlite::Result<BackupEntry> {
+    let id: u32 = row.get(0)?;
+    let path: String = row.get(1)?;
+    let timestamp: String = row.get(2)?;
+    let note: String = row.get(3)?;
+
+    let timestamp = timestamp_from_sql(2, timestamp)?;
+    let mut entry = BackupEntry::new(id, Path::new(&path), timestamp, &note);
+    entry.store = row.get(4)?;
+    entry.checksum = row.get(5)?;
+    entry.replicas =
+        serde_json::from_str(&row.get::<_, String>(6)?).map_err(|e| conversion_error(6, e))?;
+    entry.sources =
+        serde_json::from_str(&row.get::<_, String>(7)?).map_err(|e| conversion_error(7, e))?;
+    entry.kind = kind_from_sql(8, row.get(8)?)?;
+    entry.pinned = row.get(9)?;
+    entry.stats = row
+        .get::<_, Option<String>>(10)?
+        .map(|stats| serde_json::from_str(&stats).map_err(|e| conversion_error(10, e)))
+        .transpose()?;
+    Ok(entry)
+}
+
+fn insert_target(conn: &Connection, target: &Target) -> anyhow::Result<()> {
+    conn.execute(
+        "INSERT INTO targets (id, name, path, store, replicas, description, tags, schedule, retention, hooks, sources, kind, last_failed_run, last_backup_id)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)",
+        params![
+            target.id,
+            target.name,
+            target.path.to_string_lossy(),
+            target.store,
+            json_to_sql(&target.replicas)?,
+            target.description,
+            json_to_sql(&target.tags)?,
+            target.schedule.as_ref().map(Schedule::to_string),
+            json_to_sql(&target.retention)?,
+            json_to_sql(&target.hooks)?,
+            json_to_sql(&target.sources)?,
+            target.kind.name(),
+            target.last_failed_run.as_ref().map(timestamp_to_sql),
+            target.last_backup_id,
+        ],
+    )?;
+    insert_backups(conn, target)
+}
+
+fn insert_backups(conn: &Connection, target: &Target) -> anyhow::Result<()> {
+    let mut stmt = conn.prepare(
+        "INSERT INTO backups (target_id, id, path, timestamp, note, store, checksum, replicas, sources, kind, pinned, stats)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)",
+    )?;
+
+    for entry in target.backups.iter() {
+        stmt.execute(params![
+            target.id,
+            entry.id,
+            entry.path.to_string_lossy(),
+            timestamp_to_sql(&entry.timestamp),
+            entry.note,
+            entry.store,
+            entry.checksum,
+            json_to_sql(&entry.replicas)?,
+            json_to_sql(&entry.sources)?,
+            entry.kind.name(),
+            entry.pinned,
+            entry.stats.as_ref().map(json_to_sql).transpose()?,
+        ])?;
+    }
+
+    Ok(())
+}
+
+fn select_backups(
+    conn: &Connection,
+    target_id: &str,
+    query: &TargetQuery,
+) -> anyhow::Result<Vec<BackupEntry>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {BACKUP_COLUMNS} FROM backups
+          WHERE target_id = ?1
+            AND (?2 IS NULL OR timestamp >= ?2)
+            AND (?3 IS NULL OR timestamp <= ?3)
+            AND (?4 IS NULL OR json_extract(stats, '$.size') >= ?4)
+            AND (?5 IS NULL OR json_extract(stats, '$.size') <= ?5)
+          ORDER BY id",
+    ))?;
+
+    let since = query.since.as_ref().map(timestamp_to_sql);
+    let until = query.until.as_ref().map(timestamp_to_sql);
+    let rows = stmt.query_map(
+        params![target_id, since, until, query.min_size, query.max_size],
+        backup_from_row,
+    )?;
+
+    Ok(rows.collect::<Result<Vec<_>, _>>()?)
+}
+
+fn select_targets(conn: &Connection, query: &TargetQuery) -> anyhow::Result<Vec<Target>> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {TARGET_COLUMNS} FROM targets
+          WHERE (?1 IS NULL OR instr(name, ?1) > 0)
+            AND (?2 IS NULL OR EXISTS (SELECT 1 FROM json_each(tags) WHERE value = ?2))
+          ORDER BY rowid",
+    ))?;
+
+    let rows = stmt.query_map(params![query.name, query.tag], target_from_row)?;
+
+    let mut targets = Vec::new();
+    for target in rows {
+        let mut target = target?;
+        target.backups = select_backups(conn, &target.id, query)?;
+
+        if query.has_backup_condition() && target.backups.is_empty() {
+            continue;
+        }
+
+        targets.push(target);
+    }
+
+    Ok(targets)
+}
+
+fn select_target(conn: &Connection, target_id: &str) -> anyhow::Result<Option<Target>> {
+    let target = conn
+        .query_row(
+            &format!("SELECT {TARGET_COLUMNS} FROM targets WHERE id = ?1"),
+            params![target_id],
+            target_from_row,
+        )
+        .optional()?;
+
+    match target {
+        Some(mut target) => {
+            target.backups = select_backups(conn, target_id, &TargetQuery::default())?;
+            Ok(Some(target))
+        }
+        None => Ok(None),
+    }
+}
+
+//-----------------------------------------------------------------------------
+// SqliteTargetRepository
+//-----------------------------------------------------------------------------
+pub struct SqliteTargetRepository {
+    conn: Connection,
+    base_dir: PathBuf,
+}
+
+impl SqliteTargetRepository {
+    /// Open (or create) the database in the base_dir.
+    ///
+    /// - base_dir ... The base path for the application data directory.
+    pub fn build(base_dir: &Path) -> anyhow::Result<Self> {
+        std::fs::create_dir_all(create_target_info_dir_path(base_dir, None))?;
+
+        let conn = Connection::open(base_dir.join(DATABASE_FILE_NAME))?;
+        conn.pragma_update(None, "foreign_keys", true)?;
+
+        let mut repo = Self {
+            conn,
+            base_dir: base_dir.to_path_buf(),
+        };
+        repo.migrate()?;
+
+        Ok(repo)
+    }
+
+    /// Upgrades the database schema to the latest version.
+    fn migrate(&mut self) -> anyhow::Result<()> {
+        let version: usize = self
+            .conn
+            .pragma_query_value(None, "user_version", |row| row.get(0))?;
+
+        if version > MIGRATIONS.len() {
+            anyhow::bail!(
+                "Unsupported database version {version} (supported up to {}). Please upgrade dirback.",
+                MIGRATIONS.len()
+            );
+        }
+
+        let tx = self.conn.transaction()?;
+        for (from, migration) in MIGRATIONS.iter().enumerate().skip(version) {
+            tx.execute_batch(migration)?;
+            tx.pragma_update(None, "user_version", from + 1)?;
+        }
+        tx.commit()?;
+
+        Ok(())
+    }
+
+    /// Replaces the targets in the database with the targets of the repository.
+    ///
+    /// Only the database is changed, the backup files are left as they are.
+    /// Returns the number of the targets.
+    pub fn refresh<R: TargetRepository>(&mut self, src: &R) -> anyhow::Result<usize> {
+        let targets = src.load_all()?;
+
+        let tx = self.conn.transaction()?;
+        tx.execute("DELETE FROM backups", [])?;
+        tx.execute("DELETE FROM targets", [])?;
+        for target in targets.iter() {
+            insert_target(&tx, target)?;
+        }
+        tx.commit()?;
+
+        Ok(targets.len())
+    }
+
+    /// Search targets.
+    ///
+    /// If a backup date range or size range is specified, the backups of each target are
+    /// narrowed down to the range, and targets without matching backups are excluded.
+    /// The backups made before the sizes were recorded don't match a size range.
+    pub fn query(&self, query: &TargetQuery) -> anyhow::Result<Vec<Target>> {
+        select_targets(&self.conn, query)
+    }
+}
+
+impl TargetRepository for SqliteTargetRepository {
+    fn load_all(&self) -> anyhow::Result<Vec<Target>> {
+        select_targets(&self.conn, &TargetQuery::default())
+    }
+
+    fn load(&self, target_id: &str) -> Option<Target> {
+        select_target(&self.conn, target_id).unwrap_or_default()
+    }
+
+    fn update(&mut self, target: &Target) -> anyhow::Result<Target> {
+        let tx = self.conn.transaction()?;
+
+        let updated = tx.execute(
+            "UPDATE targets
+                SET name = ?2, path = ?3, store = ?4, replicas = ?5, description = ?6, tags = ?7,
+                    schedule = ?8, retention = ?9, hooks = ?10, sources = ?11, kind = ?12,
+                    last_failed_run = ?13, last_backup_id = ?14
+              WHERE id = ?1",
+            params![
+                target.id,
+                target.name,
+                target.path.to_string_lossy(),
+                target.store,
+                json_to_sql(&target.replicas)?,
+                target.description,
+                json_to_sql(&target.tags)?,
+                target.schedule.as_ref().map(Schedule::to_string),
+                json_to_sql(&target.retention)?,
+                json_to_sql(&target.hooks)?,
+                json_to_sql(&target.sources)?,
+                target.kind.name(),
+                target.last_failed_run.as_ref().map(timestamp_to_sql),
+                target.last_backup_id,
+            ],
+        )?;
+        if updated == 0 {
+            anyhow::bail!("Target not found ('{}').", target.id);
+        }
+
+        tx.execute(
+            "DELETE FROM backups WHERE target_id = ?1",
+            params![target.id],
+        )?;
+        insert_backups(&tx, target)?;
+
+        tx.commit()?;
+        Ok(target.clone())
+    }
+
+    fn add(&mut self, name: &str, target_path: &Path) -> anyhow::Result<Target> {
+        let new_id = uuid::Uuid::new_v4();
+        let target = Target::new(&new_id.to_string(), name, target_path);
+        self.import(&target)
+    }
+
+    fn import(&mut self, target: &Target) -> anyhow::Result<Target> {
+        if self.load(&target.id).is_some() {
+            anyhow::bail!("Target already exists ('{}').", target.id);
+        }
+
+        let bk_dir = self.make_backup_dir_path(target);
+        let tx = self.conn.transaction()?;
+        insert_target(&tx, target)?;
+        std::fs::create_dir_all(bk_dir)?;
+        tx.commit()?;
+
+        Ok(target.clone())
+    }
+
+    fn delete_backup(&mut self, target_id: &str, backup_id: u32) -> anyhow::Result<BackupEntry> {
+        let target = self
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target not found ('{target_id}')."))?;
+
+        let entry = target.find_backup_entry(backup_id).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Target('{target_id}') does not have specified backup(id='{backup_id}')."
+            )
+        })?;
+
+        self.conn.execute(
+            "DELETE FROM backups WHERE target_id = ?1 AND id = ?2",
+            params![target_id, backup_id],
+        )?;
+        remove_archive(&self.base_dir, &entry)?;
+
+        Ok(entry)
+    }
+
+    fn delete_target(&mut self, target_id: &str) -> anyhow::Result<Target> {
+        let target = self
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target not found ('{target_id}')."))?;
+
+        self.conn
+            .execute("DELETE FROM targets WHERE id = ?1", params![target_id])?;
+
+        for entry in target.backups.iter().filter(|b| b.store.is_some()) {
+            let _ = remove_archive(&self.base_dir, entry);
+        }
+
+        let dir = create_target_info_dir_path(&self.base_dir, Some(&target.id));
+        let _ = std::fs::remove_dir_all(&dir);
+
+        Ok(target)
+    }
+
+    fn data_dir(&self) -> Option<&Path> {
+        Some(&self.base_dir)
+    }
+
+    fn make_backup_dir_path(&self, target: &Target) -> PathBuf {
+        create_backup_dir_path(&self.base_dir, &target.id)
+    }
+
+    fn archive_store(&self, store_id: &str) -> anyhow::Result<Box<dyn ArchiveStore>> {
+        open_archive_store(&self.base_dir, store_id)
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::backup_stats::BackupStats;
+    use crate::domain::model::hooks::HookEvent;
+
+    fn add_test_backups(repo: &mut SqliteTargetRepository, target: &mut Target, count: u32) {
+        let bkdir = repo.make_backup_dir_path(target);
+        for i in 1..=count {
+            let ts = Timestamp::from_fmt_str(&format!("202501{:0>2}T120000Z", i)).unwrap();
+            let bk_path = bkdir.join(BackupEntry::generate_backup_filename(i, &ts, "tar.gz"));
+            let _ = std::fs::File::create(&bk_path);
+
+            let entry = BackupEntry::new(i, &bk_path, ts, &format!("backup {i}"));
+            let _ = target.register_backup_entry(entry);
+        }
+        repo.update(target).unwrap();
+    }
+
+    #[test]
+    fn test_build_creates_database() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let result = SqliteTargetRepository::build(&temp.path());
+        assert!(result.is_ok());
+        assert!(temp.path().join(DATABASE_FILE_NAME).exists());
+
+        // Reopen the existing database.
+        drop(result);
+        let result = SqliteTargetRepository::build(&temp.path());
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_build_returns_err_if_database_is_newer() {
+        let temp = mktemp::TempDir::new().unwrap();
+        {
+            let conn = Connection::open(temp.path().join(DATABASE_FILE_NAME)).unwrap();
+            conn.pragma_update(None, "user_version", MIGRATIONS.len() + 1)
+                .unwrap();
+        }
+
+        let result = SqliteTargetRepository::build(&temp.path());
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_build_upgrades_v1_database() {
+        let temp = mktemp::TempDir::new().unwrap();
+        {
+            let conn = Connection::open(temp.path().join(DATABASE_FILE_NAME)).unwrap();
+            conn.execute_batch(MIGRATIONS[0]).unwrap();
+            conn.pragma_update(None, "user_version", 1).unwrap();
+            conn.execute(
+                "INSERT INTO targets (id, name, path) VALUES ('xxx', 'Old', 'target')",
+                [],
+            )
+            .unwrap();
+        }
+
+        let repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+        let target = repo.load("xxx").unwrap();
+        assert_eq!(target.name, "Old");
+        assert_eq!(target.store, None);
+    }
+
+    #[test]
+    fn test_refresh_replaces_targets() {
+        use crate::infra::repository::in_memory::InMemoryTargetRepository;
+
+        let temp = mktemp::TempDir::new().unwrap();
+        let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+        let stale = repo.add("Stale", Path::new("stale")).unwrap();
+
+        let mut src = InMemoryTargetRepository::new();
+        let mut target = src.add("Test Target", Path::new("target")).unwrap();
+        let entry = target.new_backup_entry(Path::new("backups"), "tar.gz");
+        target.register_backup_entry(entry).unwrap();
+        src.update(&target).unwrap();
+
+        assert_eq!(repo.refresh(&src).unwrap(), 1);
+        assert!(repo.load(&stale.id).is_none());
+        assert_eq!(repo.load(&target.id), Some(target));
+    }
+
+    #[test]
+    fn test_store_is_persisted() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+        let mut target = repo.add("Test Target", Path::new("target")).unwrap();
+        target.store = Some("nas".to_string());
+        let mut entry = BackupEntry::new(1, Path::new("xxx/1.tar.gz"), Timestamp::now(), "");
+        entry.store = Some("nas".to_string());
+        let _ = target.register_backup_entry(entry);
+        repo.update(&target).unwrap();
+
+        let result = repo.load(&target.id).unwrap();
+        assert_eq!(result.store.as_deref(), Some("nas"));
+        assert_eq!(result.backups[0].store.as_deref(), Some("nas"));
+    }
+
+    mod add {
+        use super::*;
+
+        #[test]
+        fn it_works() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let target = repo.add("Test Target", Path::new("target")).unwrap();
+            assert_eq!(target.name, "Test Target");
+            assert!(
+                repo.make_backup_dir_path(&target).exists(),
+                "it should create a backup directory."
+            );
+
+            let result = repo.load(&target.id);
+            assert_eq!(result, Some(target));
+        }
+    }
+
+    mod import {
+        use super::*;
+
+        #[test]
+        fn it_keeps_id_and_backups() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let mut target = Target::new("xxx-xxx", "Test Target", Path::new("target"));
+            let bkdir = repo.make_backup_dir_path(&target);
+            for _ in 1..=3 {
+                let entry = target.new_backup_entry(&bkdir, "tar.gz");
+                let _ = target.register_backup_entry(entry);
+            }
+
+            let result = repo.import(&target);
+            assert!(result.is_ok());
+            assert_eq!(repo.load("xxx-xxx"), Some(target));
+        }
+
+        #[test]
+        fn it_returns_err_when_id_already_exists() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+            let target = repo.add("Test Target", Path::new("target")).unwrap();
+
+            let result = repo.import(&target);
+            assert!(result.is_err());
+            assert_eq!(repo.load_all().unwrap().len(), 1);
+        }
+    }
+
+    mod load_all {
+        use super::*;
+
+        #[test]
+        fn it_returns_targets_in_registration_order() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let mut targets = Vec::new();
+            for i in 1..=3 {
+                let name = format!("target{i}");
+                targets.push(repo.add(&name, Path::new(&name)).unwrap());
+            }
+
+            let result = repo.load_all().unwrap();
+            assert_eq!(result, targets);
+        }
+    }
+
+    mod update {
+        use super::*;
+
+        #[test]
+        fn it_works() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let mut target = repo.add("Test Target", Path::new("target")).unwrap();
+            add_test_backups(&mut repo, &mut target, 3);
+
+            target.name = String::from("Renamed");
+            target.backups.remove(1);
+            let result = repo.update(&target);
+            assert!(result.is_ok());
+
+            let loaded = repo.load(&target.id).unwrap();
+            assert_eq!(loaded, target);
+            assert_eq!(loaded.backups.len(), 2);
+        }
+
+        #[test]
+        fn it_saves_schedule_and_retention() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let mut target = repo.add("Test Target", Path::new("target")).unwrap();
+            target.schedule = Some(Schedule::parse("0 3 * * *").unwrap());
+            target.retention.keep_last = Some(7);
+            target.last_failed_run = Some(Timestamp::from_fmt_str("20250101T000000Z").unwrap());
+            target.last_backup_id = 5;
+            repo.update(&target).unwrap();
+
+            let loaded = repo.load(&target.id).unwrap();
+            assert_eq!(loaded.schedule, target.schedule);
+            assert_eq!(loaded.retention, target.retention);
+            assert_eq!(loaded.last_failed_run, target.last_failed_run);
+            assert_eq!(loaded.last_backup_id, 5);
+        }
+
+        #[test]
+        fn it_saves_sources() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let mut target = repo.add("Test Target", Path::new("target")).unwrap();
+            target.add_source("config", Path::new("config")).unwrap();
+            add_test_backups(&mut repo, &mut target, 1);
+            target.backups[0].sources = target.archive_sources();
+            repo.update(&target).unwrap();
+
+            let loaded = repo.load(&target.id).unwrap();
+            assert_eq!(loaded.sources, target.sources);
+            assert_eq!(loaded.backups[0].sources.len(), 2);
+        }
+
+        #[test]
+        fn it_saves_kind() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let mut target = repo.add("Test Target", Path::new("app.db")).unwrap();
+            target.kind = TargetKind::File;
+            add_test_backups(&mut repo, &mut target, 1);
+            target.backups[0].kind = TargetKind::File;
+            repo.update(&target).unwrap();
+
+            let loaded = repo.load(&target.id).unwrap();
+            assert_eq!(loaded.kind, TargetKind::File);
+            assert_eq!(loaded.backups[0].kind, TargetKind::File);
+        }
+
+        #[test]
+        fn it_saves_pinned() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let mut target = repo.add("Test Target", Path::new("target")).unwrap();
+            add_test_backups(&mut repo, &mut target, 2);
+            target.set_pinned(2, true).unwrap();
+            repo.update(&target).unwrap();
+
+            let loaded = repo.load(&target.id).unwrap();
+            assert_eq!(loaded.pinned_backup_ids(), vec![2]);
+        }
+
+        #[test]
+        fn it_saves_stats() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let mut target = repo.add("Test Target", Path::new("target")).unwrap();
+            add_test_backups(&mut repo, &mut target, 2);
+            let stats = BackupStats {
+                size: 10,
+                uncompressed_size: 40,
+                files: 3,
+                duration_ms: 5,
+            };
+            target.backups[1].stats = Some(stats.clone());
+            repo.update(&target).unwrap();
+
+            let loaded = repo.load(&target.id).unwrap();
+            assert_eq!(loaded.backups[0].stats, None);
+            assert_eq!(loaded.backups[1].stats, Some(stats));
+        }
+
+        #[test]
+        fn it_saves_hooks() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let mut target = repo.add("Test Target", Path::new("target")).unwrap();
+            target
+                .hooks
+                .set_command(HookEvent::PreBackup, Some("systemctl stop db"));
+            target.hooks.timeout_secs = Some(60);
+            repo.update(&target).unwrap();
+
+            let loaded = repo.load(&target.id).unwrap();
+            assert_eq!(loaded.hooks, target.hooks);
+        }
+
+        #[test]
+        fn it_returns_err_if_target_is_not_exists() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let target = Target::new("xxx", "Fake target", Path::new("tmp"));
+            let result = repo.update(&target);
+            assert!(result.is_err());
+            assert!(repo.load("xxx").is_none());
+        }
+
+        #[test]
+        fn it_rolls_back_on_failure() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let mut target = repo.add("Test Target", Path::new("target")).unwrap();
+            add_test_backups(&mut repo, &mut target, 2);
+
+            // Duplicate backup ids violate the primary key.
+            let mut broken = target.clone();
+            broken.name = String::from("Broken");
+            broken.backups.push(broken.backups[0].clone());
+
+            let result = repo.update(&broken);
+            assert!(result.is_err());
+            assert_eq!(repo.load(&target.id), Some(target));
+        }
+    }
+
+    mod delete_backup {
+        use super::*;
+
+        #[test]
+        fn it_works() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let mut target = repo.add("Test Target", Path::new("target")).unwrap();
+            add_test_backups(&mut repo, &mut target, 3);
+            let del_entry = target.backups[1].clone();
+
+            let result = repo.delete_backup(&target.id, del_entry.id);
+            assert!(result.is_ok());
+            assert_eq!(result.unwrap(), del_entry);
+            assert!(
+                !del_entry.path.exists(),
+                "The backup file should be deleted."
+            );
+
+            let target = repo.load(&target.id).unwrap();
+            assert_eq!(target.backups.len(), 2);
+        }
+
+        #[test]
+        fn it_returns_err_when_non_existent_backup_id() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+            let target = repo.add("Test Target", Path::new("target")).unwrap();
+
+            let result = repo.delete_backup(&target.id, 123);
+            assert!(result.is_err());
+        }
+    }
+
+    mod delete_target {
+        use super::*;
+
+        #[test]
+        fn it_works() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let mut target = repo.add("Test Target", Path::new("target")).unwrap();
+            add_test_backups(&mut repo, &mut target, 2);
+            let target_dir = create_target_info_dir_path(&temp.path(), Some(&target.id));
+
+            let result = repo.delete_target(&target.id);
+            assert!(result.is_ok());
+            assert!(repo.load(&target.id).is_none());
+            assert!(!target_dir.exists(), "The backup files should be deleted.");
+
+            let count: u32 = repo
+                .conn
+                .query_row("SELECT COUNT(*) FROM backups", [], |row| row.get(0))
+                .unwrap();
+            assert_eq!(count, 0, "The backup entries should be deleted.");
+        }
+
+        #[test]
+        fn it_returns_err_when_non_existent_target_id() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let result = repo.delete_target("non-exists-target-id");
+            assert!(result.is_err());
+        }
+    }
+
+    mod query {
+        use super::*;
+
+        fn prepare(temp: &mktemp::TempDir) -> SqliteTargetRepository {
+            let mut repo = SqliteTargetRepository::build(&temp.path()).unwrap();
+
+            let mut target = repo.add("Project Alpha", Path::new("alpha")).unwrap();
+            add_test_backups(&mut repo, &mut target, 5);
+
+            // Only the backups of 'Project Beta' have recorded sizes, 100 and 200 bytes.
+            let mut target = repo.add("Project Beta", Path::new("beta")).unwrap();
+            target.tags = vec![String::from("work"), String::from("daily")];
+            add_test_backups(&mut repo, &mut target, 2);
+            for (entry, size) in target.backups.iter_mut().zip([100, 200]) {
+                entry.stats = Some(BackupStats {
+                    size,
+                    uncompressed_size: size * 2,
+                    files: 1,
+                    duration_ms: 1,
+                });
+            }
+            repo.update(&target).unwrap();
+
+            let mut target = repo.add("Dotfiles", Path::new("dotfiles")).unwrap();
+            target.tags = vec![String::from("daily")];
+            repo.update(&target).unwrap();
+            repo
+        }
+
+        #[test]
+        fn it_returns_all_without_conditions() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let repo = prepare(&temp);
+
+            let result = repo.query(&TargetQuery::default()).unwrap();
+            assert_eq!(result, repo.load_all().unwrap());
+        }
+
+        #[test]
+        fn it_filters_by_name() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let repo = prepare(&temp);
+
+            let query = TargetQuery {
+                name: Some(String::from("Project")),
+                ..Default::default()
+            };
+            let result = repo.query(&query).unwrap();
+            assert_eq!(result.len(), 2);
+            assert!(result.iter().all(|t| t.name.starts_with("Project")));
+        }
+
+        #[test]
+        fn it_filters_by_date_range() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let repo = prepare(&temp);
+
+            let query = TargetQuery {
+                since: Some(Timestamp::from_fmt_str("20250103T000000Z").unwrap()),
+                until: Some(Timestamp::from_fmt_str("20250104T235959Z").unwrap()),
+                ..Default::default()
+            };
+            let result = repo.query(&query).unwrap();
+            assert_eq!(
+                result.len(),
+                1,
+                "only 'Project Alpha' has backups in range."
+            );
+
+            let ids: Vec<u32> = result[0].backups.iter().map(|b| b.id).collect();
+            assert_eq!(ids, vec![3, 4]);
+        }
+
+        #[test]
+        fn it_filters_by_tag() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let repo = prepare(&temp);
+
+            let query = TargetQuery {
+                tag: Some(String::from("daily")),
+                ..Default::default()
+            };
+            let names: Vec<String> = repo
+                .query(&query)
+                .unwrap()
+                .into_iter()
+                .map(|t| t.name)
+                .collect();
+            assert_eq!(names, ["Project Beta", "Dotfiles"]);
+
+            let query = TargetQuery {
+                tag: Some(String::from("dai")),
+                ..Default::default()
+            };
+            assert!(
+                repo.query(&query).unwrap().is_empty(),
+                "tags match exactly."
+            );
+        }
+
+        #[test]
+        fn it_filters_by_size() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let repo = prepare(&temp);
+
+            let query = TargetQuery {
+                min_size: Some(150),
+                ..Default::default()
+            };
+            let result = repo.query(&query).unwrap();
+            assert_eq!(result.len(), 1);
+            assert_eq!(result[0].name, "Project Beta");
+            let ids: Vec<u32> = result[0].backups.iter().map(|b| b.id).collect();
+            assert_eq!(ids, vec![2]);
+
+            let query = TargetQuery {
+                max_size: Some(100),
+                tag: Some(String::from("work")),
+                ..Default::default()
+            };
+            let result = repo.query(&query).unwrap();
+            assert_eq!(result.len(), 1);
+            let ids: Vec<u32> = result[0].backups.iter().map(|b| b.id).collect();
+            assert_eq!(ids, vec![1]);
+        }
+    }
+}
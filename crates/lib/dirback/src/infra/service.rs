@@ -2,4 +2,9 @@
 //! # infra/service module
 //!
 
+pub mod archive_store;
+pub mod file_replica_service;
+pub mod notify_watch_service;
+pub mod shell_hook_service;
 pub mod targz_backup_service;
+pub mod targz_bundle_service;
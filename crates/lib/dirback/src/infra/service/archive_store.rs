@@ -0,0 +1,325 @@
+//!
+//! # Archive stores
+//!
+//! Archive stores are registered in `{base_dir}/stores.json`,
+//! a map of the store ID to its settings.
+//!
+//! ```json
+//! {
+//!   "nas": { "type": "local", "root": "/mnt/nas/dirback" },
+//!   "offsite": {
+//!     "type": "s3",
+//!     "endpoint": "https://s3.ap-northeast-1.amazonaws.com",
+//!     "region": "ap-northeast-1",
+//!     "bucket": "my-backups",
+//!     "prefix": "dirback"
+//!   },
+//!   "backup-host": {
+//!     "type": "sftp",
+//!     "host": "backup.example.com",
+//!     "user": "dirback",
+//!     "root": "/srv/dirback"
+//!   }
+//! }
+//! ```
+//!
+//! S3 credentials are read from `access_key` / `secret_key`,
+//! or the `AWS_ACCESS_KEY_ID` / `AWS_SECRET_ACCESS_KEY` environment variables.
+//!
+
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::service::archive_store::ArchiveStore;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+pub mod local_dir;
+pub mod s3;
+pub mod sftp;
+
+/// The file name of the archive store settings.
+pub const STORE_CONFIG_FILE_NAME: &str = "stores.json";
+
+/// Settings of an archive store.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum StoreConfig {
+    /// A directory on the local machine (e.g. another disk, a mounted NAS).
+    Local { root: PathBuf },
+
+    /// An S3-compatible object storage.
+    S3 {
+        endpoint: String,
+        region: String,
+        bucket: String,
+        #[serde(default)]
+        prefix: String,
+        #[serde(default)]
+        access_key: Option<String>,
+        #[serde(default)]
+        secret_key: Option<String>,
+    },
+
+    /// A remote host over SFTP, using the system `sftp` command.
+    Sftp {
+        host: String,
+        #[serde(default)]
+        port: Option<u16>,
+        #[serde(default)]
+        user: Option<String>,
+        #[serde(default)]
+        identity_file: Option<PathBuf>,
+        root: String,
+    },
+}
+
+impl StoreConfig {
+    /// Returns the store type name.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::Local { .. } => "local",
+            Self::S3 { .. } => "s3",
+            Self::Sftp { .. } => "sftp",
+        }
+    }
+
+    /// Returns where the archives are stored, for display.
+    pub fn location(&self) -> String {
+        match self {
+            Self::Local { root } => root.display().to_string(),
+            Self::S3 {
+                endpoint,
+                bucket,
+                prefix,
+                ..
+            } => format!("{}/{bucket}/{prefix}", endpoint.trim_end_matches('/')),
+            Self::Sftp {
+                host, user, root, ..
+            } => match user {
+                Some(user) => format!("{user}@{host}:{root}"),
+                None => format!("{host}:{root}"),
+            },
+        }
+    }
+
+    /// Opens the archive store.
+    pub fn open(&self) -> anyhow::Result<Box<dyn ArchiveStore>> {
+        match self {
+            Self::Local { root } => Ok(Box::new(local_dir::LocalDirArchiveStore::new(root))),
+            Self::S3 {
+                endpoint,
+                region,
+                bucket,
+                prefix,
+                access_key,
+                secret_key,
+            } => {
+                let config = s3client::Config {
+                    endpoint: endpoint.clone(),
+                    region: region.clone(),
+                    bucket: bucket.clone(),
+                    access_key: credential(access_key, "AWS_ACCESS_KEY_ID")?,
+                    secret_key: credential(secret_key, "AWS_SECRET_ACCESS_KEY")?,
+                };
+                Ok(Box::new(s3::S3ArchiveStore::new(config, prefix)))
+            }
+            Self::Sftp {
+                host,
+                port,
+                user,
+                identity_file,
+                root,
+            } => Ok(Box::new(sftp::SftpArchiveStore {
+                host: host.clone(),
+                port: *port,
+                user: user.clone(),
+                identity_file: identity_file.clone(),
+                root: root.clone(),
+            })),
+        }
+    }
+}
+
+fn credential(value: &Option<String>, env_name: &str) -> anyhow::Result<String> {
+    match value {
+        Some(value) => Ok(value.clone()),
+        None => std::env::var(env_name)
+            .map_err(|_| anyhow::anyhow!("S3 credential is not set (set {env_name}).")),
+    }
+}
+
+/// Loads the archive store settings.
+///
+/// Returns an empty map if the settings file does not exist.
+pub fn load_store_configs(base_dir: &Path) -> anyhow::Result<BTreeMap<String, StoreConfig>> {
+    let path = base_dir.join(STORE_CONFIG_FILE_NAME);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+
+    jsonfile::read(&path)
+}
+
+/// Opens the archive store registered in the base_dir.
+pub fn open_archive_store(
+    base_dir: &Path,
+    store_id: &str,
+) -> anyhow::Result<Box<dyn ArchiveStore>> {
+    let configs = load_store_configs(base_dir)?;
+    let config = configs
+        .get(store_id)
+        .ok_or_else(|| anyhow::anyhow!("Archive store not found ('{store_id}')."))?;
+    config.open()
+}
+
+/// Removes the backup file of the entry, wherever it is stored.
+pub(crate) fn remove_archive(base_dir: &Path, entry: &BackupEntry) -> anyhow::Result<()> {
+    match &entry.store {
+        None => Ok(std::fs::remove_file(&entry.path)?),
+        Some(store_id) => {
+            let store = open_archive_store(base_dir, store_id)?;
+            store.delete(&entry.path.to_string_lossy())
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::timestamp::Timestamp;
+
+    fn write_configs(base_dir: &Path, json: &str) {
+        std::fs::write(base_dir.join(STORE_CONFIG_FILE_NAME), json).unwrap();
+    }
+
+    mod load_store_configs {
+        use super::*;
+
+        #[test]
+        fn it_returns_empty_if_file_not_exists() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let configs = load_store_configs(&temp.path()).unwrap();
+            assert!(configs.is_empty());
+        }
+
+        #[test]
+        fn it_reads_every_type() {
+            let temp = mktemp::TempDir::new().unwrap();
+            write_configs(
+                &temp.path(),
+                r#"{
+                    "nas": { "type": "local", "root": "/mnt/nas" },
+                    "offsite": {
+                        "type": "s3",
+                        "endpoint": "http://localhost:9000",
+                        "region": "us-east-1",
+                        "bucket": "backups"
+                    },
+                    "host": { "type": "sftp", "host": "example.com", "root": "/srv" }
+                }"#,
+            );
+
+            let configs = load_store_configs(&temp.path()).unwrap();
+            assert_eq!(
+                configs["nas"],
+                StoreConfig::Local {
+                    root: PathBuf::from("/mnt/nas")
+                }
+            );
+            assert_eq!(configs["offsite"].kind(), "s3");
+            assert_eq!(
+                configs["offsite"].location(),
+                "http://localhost:9000/backups/"
+            );
+            assert_eq!(configs["host"].kind(), "sftp");
+            assert_eq!(configs["host"].location(), "example.com:/srv");
+        }
+
+        #[test]
+        fn it_returns_err_if_type_is_unknown() {
+            let temp = mktemp::TempDir::new().unwrap();
+            write_configs(&temp.path(), r#"{ "x": { "type": "ftp" } }"#);
+            assert!(load_store_configs(&temp.path()).is_err());
+        }
+    }
+
+    mod open_archive_store {
+        use super::*;
+
+        #[test]
+        fn it_returns_err_if_store_not_registered() {
+            let temp = mktemp::TempDir::new().unwrap();
+            assert!(open_archive_store(&temp.path(), "nas").is_err());
+        }
+
+        #[test]
+        fn it_opens_registered_store() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let root = temp.path().join("nas");
+            write_configs(
+                &temp.path(),
+                &format!(r#"{{ "nas": {{ "type": "local", "root": {:?} }} }}"#, root),
+            );
+
+            let store = open_archive_store(&temp.path(), "nas").unwrap();
+            let src = temp.path().join("backup.tar.gz");
+            std::fs::write(&src, "data").unwrap();
+            store.put(&src, "xxx/backup.tar.gz").unwrap();
+            assert!(root.join("xxx").join("backup.tar.gz").exists());
+        }
+
+        #[test]
+        fn it_returns_err_if_s3_credential_missing() {
+            let config = StoreConfig::S3 {
+                endpoint: "http://localhost:9000".to_string(),
+                region: "us-east-1".to_string(),
+                bucket: "backups".to_string(),
+                prefix: String::new(),
+                access_key: Some("access".to_string()),
+                secret_key: None,
+            };
+
+            // Only fails when the environment does not provide the secret.
+            if std::env::var("AWS_SECRET_ACCESS_KEY").is_err() {
+                assert!(config.open().is_err());
+            }
+        }
+    }
+
+    mod remove_archive {
+        use super::*;
+
+        #[test]
+        fn it_removes_local_file() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let path = temp.path().join("backup.tar.gz");
+            std::fs::write(&path, "data").unwrap();
+
+            let entry = BackupEntry::new(1, &path, Timestamp::now(), "");
+            remove_archive(&temp.path(), &entry).unwrap();
+            assert!(!path.exists());
+        }
+
+        #[test]
+        fn it_removes_archive_in_store() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let root = temp.path().join("nas");
+            write_configs(
+                &temp.path(),
+                &format!(r#"{{ "nas": {{ "type": "local", "root": {:?} }} }}"#, root),
+            );
+            std::fs::create_dir_all(root.join("xxx")).unwrap();
+            std::fs::write(root.join("xxx").join("backup.tar.gz"), "data").unwrap();
+
+            let mut entry =
+                BackupEntry::new(1, Path::new("xxx/backup.tar.gz"), Timestamp::now(), "");
+            entry.store = Some("nas".to_string());
+
+            remove_archive(&temp.path(), &entry).unwrap();
+            assert!(!root.join("xxx").join("backup.tar.gz").exists());
+        }
+    }
+}
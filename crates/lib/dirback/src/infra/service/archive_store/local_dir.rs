@@ -0,0 +1,116 @@
+//!
+//! # Local directory archive store
+//!
+//! Keeps archives in a directory on the local machine,
+//! e.g. another disk or a mounted NAS.
+//!
+//! ```ascii
+//! {root}/
+//! └─ {target_id}/
+//!    └─ {backup_id}_{backup_timestamp}.tar.gz
+//! ```
+//!
+
+use crate::domain::service::archive_store::ArchiveStore;
+use std::path::{Path, PathBuf};
+
+pub struct LocalDirArchiveStore {
+    root: PathBuf,
+}
+
+impl LocalDirArchiveStore {
+    pub fn new(root: &Path) -> Self {
+        Self {
+            root: root.to_path_buf(),
+        }
+    }
+
+    fn archive_path(&self, key: &str) -> PathBuf {
+        self.root.join(key)
+    }
+}
+
+impl ArchiveStore for LocalDirArchiveStore {
+    fn put(&self, src: &Path, key: &str) -> anyhow::Result<()> {
+        let dest = self.archive_path(key);
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        // rename() does not work across file systems, fall back to copy.
+        if std::fs::rename(src, &dest).is_err() {
+            std::fs::copy(src, &dest)?;
+            std::fs::remove_file(src)?;
+        }
+
+        Ok(())
+    }
+
+    fn get(&self, key: &str, dest: &Path) -> anyhow::Result<()> {
+        std::fs::copy(self.archive_path(key), dest)?;
+        Ok(())
+    }
+
+    fn delete(&self, key: &str) -> anyhow::Result<()> {
+        std::fs::remove_file(self.archive_path(key))?;
+        Ok(())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prepare() -> (mktemp::TempDir, LocalDirArchiveStore, PathBuf) {
+        let temp = mktemp::TempDir::new().unwrap();
+        let store = LocalDirArchiveStore::new(&temp.path().join("store"));
+
+        let src = temp.path().join("backup.tar.gz");
+        std::fs::write(&src, "archive data").unwrap();
+
+        (temp, store, src)
+    }
+
+    #[test]
+    fn it_moves_archive_into_store() {
+        let (temp, store, src) = prepare();
+
+        store.put(&src, "xxx/backup.tar.gz").unwrap();
+
+        let stored = temp.path().join("store").join("xxx").join("backup.tar.gz");
+        assert!(!src.exists(), "the local file should be moved.");
+        assert_eq!(std::fs::read_to_string(&stored).unwrap(), "archive data");
+    }
+
+    #[test]
+    fn it_fetches_archive() {
+        let (temp, store, src) = prepare();
+        store.put(&src, "xxx/backup.tar.gz").unwrap();
+
+        let dest = temp.path().join("fetched.tar.gz");
+        store.get("xxx/backup.tar.gz", &dest).unwrap();
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "archive data");
+    }
+
+    #[test]
+    fn it_deletes_archive() {
+        let (temp, store, src) = prepare();
+        store.put(&src, "xxx/backup.tar.gz").unwrap();
+
+        store.delete("xxx/backup.tar.gz").unwrap();
+        let stored = temp.path().join("store").join("xxx").join("backup.tar.gz");
+        assert!(!stored.exists());
+    }
+
+    #[test]
+    fn it_returns_err_if_archive_not_found() {
+        let (temp, store, _) = prepare();
+
+        let dest = temp.path().join("fetched.tar.gz");
+        assert!(store.get("xxx/missing.tar.gz", &dest).is_err());
+        assert!(store.delete("xxx/missing.tar.gz").is_err());
+    }
+}
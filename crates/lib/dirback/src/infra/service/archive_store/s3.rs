@@ -0,0 +1,114 @@
+//!
+//! # S3 archive store
+//!
+//! Keeps archives in an S3-compatible object storage.
+//!
+//! ```ascii
+//! {bucket}/
+//! └─ {prefix}/
+//!    └─ {target_id}/
+//!       └─ {backup_id}_{backup_timestamp}.tar.gz
+//! ```
+//!
+
+use crate::domain::service::archive_store::ArchiveStore;
+use std::path::Path;
+
+pub struct S3ArchiveStore {
+    client: s3client::Client,
+    prefix: String,
+}
+
+impl S3ArchiveStore {
+    pub fn new(config: s3client::Config, prefix: &str) -> Self {
+        Self {
+            client: s3client::Client::new(config),
+            prefix: prefix.trim_matches('/').to_string(),
+        }
+    }
+
+    fn object_key(&self, key: &str) -> String {
+        if self.prefix.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{key}", self.prefix)
+        }
+    }
+}
+
+impl ArchiveStore for S3ArchiveStore {
+    fn put(&self, src: &Path, key: &str) -> anyhow::Result<()> {
+        self.client.put_object(&self.object_key(key), src)?;
+        std::fs::remove_file(src)?;
+        Ok(())
+    }
+
+    fn get(&self, key: &str, dest: &Path) -> anyhow::Result<()> {
+        self.client.get_object(&self.object_key(key), dest)
+    }
+
+    fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.client.delete_object(&self.object_key(key))
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(endpoint: &str, bucket: &str) -> s3client::Config {
+        s3client::Config {
+            endpoint: endpoint.to_string(),
+            region: std::env::var("DIRBACK_TEST_S3_REGION").unwrap_or("us-east-1".to_string()),
+            bucket: bucket.to_string(),
+            access_key: std::env::var("AWS_ACCESS_KEY_ID").unwrap_or_default(),
+            secret_key: std::env::var("AWS_SECRET_ACCESS_KEY").unwrap_or_default(),
+        }
+    }
+
+    #[test]
+    fn it_prepends_prefix_to_key() {
+        let store = S3ArchiveStore::new(config("http://localhost:9000", "b"), "/dirback/");
+        assert_eq!(
+            store.object_key("xxx/backup.tar.gz"),
+            "dirback/xxx/backup.tar.gz"
+        );
+
+        let store = S3ArchiveStore::new(config("http://localhost:9000", "b"), "");
+        assert_eq!(store.object_key("xxx/backup.tar.gz"), "xxx/backup.tar.gz");
+    }
+
+    /// Runs against a real S3-compatible server, e.g. MinIO:
+    ///
+    /// ```sh
+    /// docker run -p 9000:9000 minio/minio server /data
+    /// # create the bucket, then
+    /// DIRBACK_TEST_S3_ENDPOINT=http://localhost:9000 DIRBACK_TEST_S3_BUCKET=dirback-test \
+    /// AWS_ACCESS_KEY_ID=minioadmin AWS_SECRET_ACCESS_KEY=minioadmin \
+    ///     cargo test -p dirback -- --ignored s3
+    /// ```
+    #[test]
+    #[ignore]
+    fn it_works_with_s3_server() {
+        let endpoint = std::env::var("DIRBACK_TEST_S3_ENDPOINT").unwrap();
+        let bucket = std::env::var("DIRBACK_TEST_S3_BUCKET").unwrap();
+        let store = S3ArchiveStore::new(config(&endpoint, &bucket), "dirback-test");
+
+        let temp = mktemp::TempDir::new().unwrap();
+        let src = temp.path().join("backup.tar.gz");
+        std::fs::write(&src, "archive data").unwrap();
+
+        store.put(&src, "xxx/backup.tar.gz").unwrap();
+        assert!(!src.exists());
+
+        let dest = temp.path().join("fetched.tar.gz");
+        store.get("xxx/backup.tar.gz", &dest).unwrap();
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "archive data");
+
+        store.delete("xxx/backup.tar.gz").unwrap();
+        assert!(store.get("xxx/backup.tar.gz", &dest).is_err());
+    }
+}
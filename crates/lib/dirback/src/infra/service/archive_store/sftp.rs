@@ -0,0 +1,217 @@
+//!
+//! # SFTP archive store
+//!
+//! Keeps archives on a remote host over SFTP.
+//!
+//! Runs the system `sftp` command in batch mode, so the authentication
+//! follows the user's ssh settings (`~/.ssh/config`, ssh-agent, ...).
+//! Password authentication is not supported.
+//!
+//! ```ascii
+//! {root}/
+//! └─ {target_id}/
+//!    └─ {backup_id}_{backup_timestamp}.tar.gz
+//! ```
+//!
+
+use crate::domain::service::archive_store::ArchiveStore;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+pub struct SftpArchiveStore {
+    pub host: String,
+    pub port: Option<u16>,
+    pub user: Option<String>,
+    pub identity_file: Option<PathBuf>,
+
+    /// The directory on the remote host.
+    pub root: String,
+}
+
+impl SftpArchiveStore {
+    fn remote_path(&self, key: &str) -> String {
+        format!("{}/{key}", self.root.trim_end_matches('/'))
+    }
+
+    fn destination(&self) -> String {
+        match &self.user {
+            Some(user) => format!("{user}@{}", self.host),
+            None => self.host.clone(),
+        }
+    }
+
+    /// Batch commands to upload the file, making the parent directories.
+    fn put_batch(&self, src: &Path, key: &str) -> String {
+        let mut batch = String::new();
+
+        // "-" prefix ignores the error (the directory already exists).
+        let mut dir = self.root.trim_end_matches('/').to_string();
+        batch.push_str(&format!("-mkdir {}\n", quote(&dir)));
+        if let Some((parents, _)) = key.rsplit_once('/') {
+            for name in parents.split('/') {
+                dir = format!("{dir}/{name}");
+                batch.push_str(&format!("-mkdir {}\n", quote(&dir)));
+            }
+        }
+
+        batch.push_str(&format!(
+            "put {} {}\n",
+            quote(&src.to_string_lossy()),
+            quote(&self.remote_path(key))
+        ));
+        batch
+    }
+
+    fn get_batch(&self, key: &str, dest: &Path) -> String {
+        format!(
+            "get {} {}\n",
+            quote(&self.remote_path(key)),
+            quote(&dest.to_string_lossy())
+        )
+    }
+
+    fn delete_batch(&self, key: &str) -> String {
+        format!("rm {}\n", quote(&self.remote_path(key)))
+    }
+
+    /// Runs the sftp batch commands.
+    fn run(&self, batch: &str) -> anyhow::Result<()> {
+        let mut cmd = Command::new("sftp");
+        cmd.args(["-b", "-", "-o", "BatchMode=yes"]);
+        if let Some(port) = self.port {
+            cmd.arg("-P").arg(port.to_string());
+        }
+        if let Some(identity_file) = &self.identity_file {
+            cmd.arg("-i").arg(identity_file);
+        }
+        cmd.arg(self.destination());
+
+        let mut child = cmd
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("Failed to run sftp: {e}"))?;
+
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(batch.as_bytes())?;
+        }
+
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            anyhow::bail!(
+                "sftp failed ({}): {}",
+                self.destination(),
+                String::from_utf8_lossy(&output.stderr).trim()
+            );
+        }
+
+        Ok(())
+    }
+}
+
+impl ArchiveStore for SftpArchiveStore {
+    fn put(&self, src: &Path, key: &str) -> anyhow::Result<()> {
+        self.run(&self.put_batch(src, key))?;
+        std::fs::remove_file(src)?;
+        Ok(())
+    }
+
+    fn get(&self, key: &str, dest: &Path) -> anyhow::Result<()> {
+        self.run(&self.get_batch(key, dest))
+    }
+
+    fn delete(&self, key: &str) -> anyhow::Result<()> {
+        self.run(&self.delete_batch(key))
+    }
+}
+
+/// Quotes the argument of a sftp batch command.
+fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store(root: &str) -> SftpArchiveStore {
+        SftpArchiveStore {
+            host: "localhost".to_string(),
+            port: None,
+            user: Some("dirback".to_string()),
+            identity_file: None,
+            root: root.to_string(),
+        }
+    }
+
+    #[test]
+    fn it_makes_put_batch() {
+        let batch = store("/srv/dirback/").put_batch(Path::new("/tmp/a b.tar.gz"), "xxx/1.tar.gz");
+        assert_eq!(
+            batch,
+            "-mkdir \"/srv/dirback\"\n\
+             -mkdir \"/srv/dirback/xxx\"\n\
+             put \"/tmp/a b.tar.gz\" \"/srv/dirback/xxx/1.tar.gz\"\n"
+        );
+    }
+
+    #[test]
+    fn it_makes_get_and_delete_batch() {
+        let store = store("/srv/dirback");
+        assert_eq!(
+            store.get_batch("xxx/1.tar.gz", Path::new("/tmp/1.tar.gz")),
+            "get \"/srv/dirback/xxx/1.tar.gz\" \"/tmp/1.tar.gz\"\n"
+        );
+        assert_eq!(
+            store.delete_batch("xxx/1.tar.gz"),
+            "rm \"/srv/dirback/xxx/1.tar.gz\"\n"
+        );
+    }
+
+    #[test]
+    fn it_quotes_arguments() {
+        assert_eq!(quote(r#"a "b" \c"#), r#""a \"b\" \\c""#);
+    }
+
+    /// Runs against a real sshd, e.g. a local one:
+    ///
+    /// ```sh
+    /// DIRBACK_TEST_SFTP_HOST=localhost DIRBACK_TEST_SFTP_PORT=2222 \
+    /// DIRBACK_TEST_SFTP_USER=$USER DIRBACK_TEST_SFTP_ROOT=/tmp/dirback-sftp \
+    ///     cargo test -p dirback -- --ignored sftp
+    /// ```
+    #[test]
+    #[ignore]
+    fn it_works_with_sshd() {
+        let store = SftpArchiveStore {
+            host: std::env::var("DIRBACK_TEST_SFTP_HOST").unwrap(),
+            port: std::env::var("DIRBACK_TEST_SFTP_PORT")
+                .ok()
+                .map(|p| p.parse().unwrap()),
+            user: std::env::var("DIRBACK_TEST_SFTP_USER").ok(),
+            identity_file: std::env::var("DIRBACK_TEST_SFTP_IDENTITY")
+                .ok()
+                .map(PathBuf::from),
+            root: std::env::var("DIRBACK_TEST_SFTP_ROOT").unwrap(),
+        };
+
+        let temp = mktemp::TempDir::new().unwrap();
+        let src = temp.path().join("backup.tar.gz");
+        std::fs::write(&src, "archive data").unwrap();
+
+        store.put(&src, "xxx/backup.tar.gz").unwrap();
+        assert!(!src.exists());
+
+        let dest = temp.path().join("fetched.tar.gz");
+        store.get("xxx/backup.tar.gz", &dest).unwrap();
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "archive data");
+
+        store.delete("xxx/backup.tar.gz").unwrap();
+        assert!(store.get("xxx/backup.tar.gz", &dest).is_err());
+    }
+}
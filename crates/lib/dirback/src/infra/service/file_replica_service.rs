@@ -0,0 +1,117 @@
+//!
+//! # File replica service
+//!
+//! Replica locations have the same layout as the data directory
+//! (see `FileStorageTargetRepository`), so a replica can be used
+//! as the data directory as it is.
+//!
+
+use crate::domain::model::target::Target;
+use crate::domain::repository::targets::TargetRepository;
+use crate::domain::service::replica_service::ReplicaService;
+use crate::infra::repository::file_storage::{FileStorageTargetRepository, create_backup_dir_path};
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
+
+#[derive(Default)]
+pub struct FileReplicaService {}
+
+impl FileReplicaService {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl ReplicaService for FileReplicaService {
+    /// SHA-256 in hex.
+    fn checksum(&self, path: &Path) -> anyhow::Result<String> {
+        let mut file = std::fs::File::open(path)?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher)?;
+        Ok(hex::encode(hasher.finalize()))
+    }
+
+    fn copy_archive(&self, replica: &Path, target_id: &str, src: &Path) -> anyhow::Result<PathBuf> {
+        let file_name = src
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("Invalid archive path: '{}'", src.display()))?;
+        let dir = create_backup_dir_path(replica, target_id);
+        std::fs::create_dir_all(&dir)?;
+
+        // Copy to a temporary name first, so that an interrupted copy is never taken as the archive.
+        let dest = dir.join(file_name);
+        let mut part_name = file_name.to_os_string();
+        part_name.push(".part");
+        let part = dir.join(part_name);
+
+        std::fs::copy(src, &part)?;
+        std::fs::rename(&part, &dest)?;
+
+        Ok(dest)
+    }
+
+    fn write_target(&self, replica: &Path, target: &Target) -> anyhow::Result<()> {
+        let mut repo = FileStorageTargetRepository::new(replica);
+        if repo.load(&target.id).is_some() {
+            repo.update(target)?;
+        } else {
+            repo.import(target)?;
+        }
+        Ok(())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checksum() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let path = temp.path().join("file");
+        std::fs::write(&path, "abc").unwrap();
+
+        let service = FileReplicaService::new();
+        assert_eq!(
+            service.checksum(&path).unwrap(),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn test_copy_archive() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let src = temp.path().join("0001_20250123T123456Z.tar.gz");
+        std::fs::write(&src, "archive data").unwrap();
+        let replica = temp.path().join("replica");
+
+        let service = FileReplicaService::new();
+        let dest = service.copy_archive(&replica, "xxx", &src).unwrap();
+
+        assert_eq!(
+            dest,
+            create_backup_dir_path(&replica, "xxx").join("0001_20250123T123456Z.tar.gz")
+        );
+        assert_eq!(std::fs::read_to_string(&dest).unwrap(), "archive data");
+        assert!(src.exists(), "the source should be kept.");
+    }
+
+    #[test]
+    fn test_write_target() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let replica = temp.path().join("replica");
+        let service = FileReplicaService::new();
+
+        let mut target = Target::new("xxx", "Test Target", Path::new("target"));
+        service.write_target(&replica, &target).unwrap();
+
+        target.name = String::from("Renamed");
+        service.write_target(&replica, &target).unwrap();
+
+        let repo = FileStorageTargetRepository::new(&replica);
+        assert_eq!(repo.load("xxx"), Some(target));
+    }
+}
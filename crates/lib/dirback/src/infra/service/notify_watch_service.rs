@@ -0,0 +1,167 @@
+//!
+//! # Notify Watch Service
+//!
+//! WatchService implementation with the `notify` crate
+//! (inotify on Linux, FSEvents on macOS, ReadDirectoryChangesW on Windows).
+//!
+//! An error of the watcher (e.g. a queue overflow) is passed to the error handler,
+//! and the watching goes on.
+//!
+
+use crate::domain::service::watch_service::WatchService;
+use notify::{Event, RecommendedWatcher, RecursiveMode, Watcher};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{Receiver, RecvTimeoutError, channel};
+use std::time::Duration;
+
+pub struct NotifyWatchService {
+    // Watching stops when the watcher is dropped.
+    _watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    on_error: Box<dyn FnMut(&anyhow::Error)>,
+}
+
+impl NotifyWatchService {
+    /// Starts watching the directory trees.
+    ///
+    /// A file is watched through its directory (not recursively),
+    /// so that it is still watched after an editor replaces it.
+    pub fn build(paths: &[&Path]) -> anyhow::Result<Self> {
+        let (tx, events) = channel();
+        let mut watcher = notify::recommended_watcher(tx)?;
+        for path in paths {
+            match path.parent() {
+                Some(parent) if path.is_file() => {
+                    watcher.watch(parent, RecursiveMode::NonRecursive)?
+                }
+                _ => watcher.watch(path, RecursiveMode::Recursive)?,
+            }
+        }
+
+        Ok(Self {
+            _watcher: watcher,
+            events,
+            on_error: Box::new(|_| {}),
+        })
+    }
+
+    /// Calls `f` with the errors of the watcher, which are ignored by default.
+    pub fn on_error<F: FnMut(&anyhow::Error) + 'static>(mut self, f: F) -> Self {
+        self.on_error = Box::new(f);
+        self
+    }
+
+    /// Collects the paths of an event, ignoring accesses and errors.
+    fn paths(&mut self, event: notify::Result<Event>) -> Vec<PathBuf> {
+        match event {
+            Ok(event) if event.kind.is_access() => Vec::new(),
+            Ok(event) => event.paths,
+            Err(e) => {
+                (self.on_error)(&e.into());
+                Vec::new()
+            }
+        }
+    }
+}
+
+impl WatchService for NotifyWatchService {
+    fn wait(&mut self, timeout: Option<Duration>) -> anyhow::Result<Vec<PathBuf>> {
+        let first = match timeout {
+            Some(timeout) => match self.events.recv_timeout(timeout) {
+                Ok(event) => event,
+                Err(RecvTimeoutError::Timeout) => return Ok(Vec::new()),
+                Err(RecvTimeoutError::Disconnected) => anyhow::bail!("The watcher has stopped."),
+            },
+            None => self
+                .events
+                .recv()
+                .map_err(|_| anyhow::anyhow!("The watcher has stopped."))?,
+        };
+
+        // Take the queued events together.
+        let mut paths = self.paths(first);
+        while let Ok(event) = self.events.try_recv() {
+            let more = self.paths(event);
+            paths.extend(more);
+        }
+
+        Ok(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_returns_changed_paths() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let dir = std::fs::canonicalize(temp.path()).unwrap();
+        let mut service = NotifyWatchService::build(&[&dir]).unwrap();
+
+        std::fs::write(dir.join("file.txt"), "hello").unwrap();
+
+        let mut paths = Vec::new();
+        for _ in 0..10 {
+            paths.extend(service.wait(Some(Duration::from_millis(200))).unwrap());
+            if paths.iter().any(|p| p.ends_with("file.txt")) {
+                break;
+            }
+        }
+        assert!(paths.iter().any(|p| p.ends_with("file.txt")));
+    }
+
+    #[test]
+    fn it_watches_every_path() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let dir = std::fs::canonicalize(temp.path()).unwrap();
+        let (main, config) = (dir.join("main"), dir.join("config"));
+        std::fs::create_dir_all(&main).unwrap();
+        std::fs::create_dir_all(&config).unwrap();
+        let mut service = NotifyWatchService::build(&[&main, &config]).unwrap();
+
+        std::fs::write(config.join("app.conf"), "hello").unwrap();
+
+        let mut paths = Vec::new();
+        for _ in 0..10 {
+            paths.extend(service.wait(Some(Duration::from_millis(200))).unwrap());
+            if paths.iter().any(|p| p.ends_with("app.conf")) {
+                break;
+            }
+        }
+        assert!(paths.iter().any(|p| p.ends_with("app.conf")));
+    }
+
+    #[test]
+    fn it_keeps_waiting_after_error() {
+        use std::cell::RefCell;
+        use std::rc::Rc;
+
+        let temp = mktemp::TempDir::new().unwrap();
+        let errors = Rc::new(RefCell::new(Vec::new()));
+        let errors_ = errors.clone();
+        let mut service = NotifyWatchService::build(&[&temp.path()])
+            .unwrap()
+            .on_error(move |e| errors_.borrow_mut().push(e.to_string()));
+
+        let (tx, rx) = channel();
+        service.events = rx;
+        tx.send(Err(notify::Error::generic("overflow"))).unwrap();
+        let event = Event::new(notify::EventKind::Any).add_path(PathBuf::from("/a.txt"));
+        tx.send(Ok(event)).unwrap();
+
+        let paths = service.wait(Some(Duration::from_millis(50))).unwrap();
+        assert_eq!(paths, vec![PathBuf::from("/a.txt")]);
+        assert_eq!(errors.borrow().len(), 1);
+        assert!(errors.borrow()[0].contains("overflow"));
+    }
+
+    #[test]
+    fn it_returns_empty_on_timeout() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let mut service = NotifyWatchService::build(&[&temp.path()]).unwrap();
+
+        let paths = service.wait(Some(Duration::from_millis(50))).unwrap();
+        assert!(paths.is_empty());
+    }
+}
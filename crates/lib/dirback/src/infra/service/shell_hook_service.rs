@@ -0,0 +1,142 @@
+//!
+//! # Shell Hook Service
+//!
+//! HookService implementation running the commands by the shell
+//! (`sh -c` on Unix, `cmd /C` on Windows).
+//!
+//! The output of the commands is passed through to the stdout / stderr,
+//! discarded if built by `quiet` (e.g. under a terminal UI),
+//! or all written to the stderr if built by `to_stderr` (e.g. under JSON output).
+//!
+
+use crate::domain::service::hook_service::HookService;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::Duration;
+use wait_timeout::ChildExt;
+
+/// Where the output of the commands is written.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+enum HookOutput {
+    #[default]
+    Inherit,
+    Discard,
+    Stderr,
+}
+
+#[derive(Default)]
+pub struct ShellHookService {
+    output: HookOutput,
+}
+
+impl ShellHookService {
+    pub fn new() -> Self {
+        Self {
+            output: HookOutput::Inherit,
+        }
+    }
+
+    /// Discards the output of the commands.
+    pub fn quiet() -> Self {
+        Self {
+            output: HookOutput::Discard,
+        }
+    }
+
+    /// Writes the stdout of the commands to the stderr, keeping the stdout for the results.
+    pub fn to_stderr() -> Self {
+        Self {
+            output: HookOutput::Stderr,
+        }
+    }
+
+    fn shell(command: &str) -> Command {
+        if cfg!(windows) {
+            let mut cmd = Command::new("cmd");
+            cmd.args(["/C", command]);
+            cmd
+        } else {
+            let mut cmd = Command::new("sh");
+            cmd.args(["-c", command]);
+            cmd
+        }
+    }
+}
+
+impl HookService for ShellHookService {
+    fn run(
+        &self,
+        command: &str,
+        env: &[(String, String)],
+        work_dir: &Path,
+        timeout: Duration,
+    ) -> anyhow::Result<()> {
+        let mut cmd = Self::shell(command);
+        cmd.envs(env.iter().map(|(k, v)| (k, v)));
+        if work_dir.is_dir() {
+            cmd.current_dir(work_dir);
+        }
+        match self.output {
+            HookOutput::Inherit => {}
+            HookOutput::Discard => {
+                cmd.stdout(Stdio::null()).stderr(Stdio::null());
+            }
+            HookOutput::Stderr => {
+                cmd.stdout(std::io::stderr());
+            }
+        }
+
+        let mut child = cmd
+            .spawn()
+            .map_err(|e| anyhow::anyhow!("Failed to run '{command}': {e}"))?;
+
+        match child.wait_timeout(timeout)? {
+            Some(status) if status.success() => Ok(()),
+            Some(status) => anyhow::bail!("'{command}' failed ({status})."),
+            None => {
+                let _ = child.kill();
+                let _ = child.wait();
+                anyhow::bail!("'{command}' timed out after {} seconds.", timeout.as_secs())
+            }
+        }
+    }
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_runs_command_with_env() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let env = vec![(String::from("DIRBACK_TEST"), String::from("hello"))];
+
+        let service = ShellHookService::new();
+        let result = service.run(
+            "echo $DIRBACK_TEST > out.txt",
+            &env,
+            &temp.path(),
+            Duration::from_secs(5),
+        );
+        assert!(result.is_ok());
+
+        let out = std::fs::read_to_string(temp.path().join("out.txt")).unwrap();
+        assert_eq!(out.trim(), "hello");
+    }
+
+    #[test]
+    fn it_returns_err_if_command_fails() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let service = ShellHookService::new();
+        let result = service.run("exit 3", &[], &temp.path(), Duration::from_secs(5));
+        assert!(result.unwrap_err().to_string().contains("exit status: 3"));
+    }
+
+    #[test]
+    fn it_kills_command_on_timeout() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let service = ShellHookService::new();
+        let result = service.run("sleep 5", &[], &temp.path(), Duration::from_millis(100));
+        assert!(result.unwrap_err().to_string().contains("timed out"));
+    }
+}
@@ -2,26 +2,155 @@
 //! # tar.gz backup service
 //!
 
+use crate::domain::model::archived_file::ArchivedFile;
+use crate::domain::model::backup_stats::BackupStats;
+use crate::domain::model::file_pattern::FilePattern;
+use crate::domain::model::index_entry::{IndexEntry, IndexEntryKind};
+use crate::domain::model::source::Source;
+use crate::domain::model::timestamp::Timestamp;
 use crate::domain::service::backup_service::BackupService;
-use std::path::Path;
+use sha2::{Digest, Sha256};
+use std::path::{Path, PathBuf};
 
-#[derive(Default)]
-pub struct TargzBackupService {}
+pub struct TargzBackupService {
+    /// Compression level of the archives, from 0 (none) to 9 (best).
+    level: u32,
+
+    /// The files and directories left out of the directory archives.
+    excludes: Vec<FilePattern>,
+}
+
+impl Default for TargzBackupService {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl TargzBackupService {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            level: targz::DEFAULT_LEVEL,
+            excludes: Vec::new(),
+        }
+    }
+
+    pub fn with_compression(mut self, level: u32) -> Self {
+        self.level = level;
+        self
+    }
+
+    /// Leaves the matching paths out of the directory archives.
+    ///
+    /// The patterns are matched against the path relative to each source directory.
+    pub fn with_excludes(mut self, excludes: &[FilePattern]) -> Self {
+        self.excludes = excludes.to_vec();
+        self
+    }
+
+    fn archive_dirs(&self, dirs: &[(&str, &Path)], dest: &Path) -> anyhow::Result<BackupStats> {
+        let archived = targz::archive_dirs_excluding(dirs, dest, self.level, |path| {
+            self.excludes.iter().any(|pattern| pattern.matches(path))
+        })?;
+        backup_stats(dest, archived)
     }
 }
 
+/// Makes the stats of the created archive from the files counted while archiving them.
+fn backup_stats(dest: &Path, archived: targz::ArchiveStats) -> anyhow::Result<BackupStats> {
+    Ok(BackupStats {
+        size: std::fs::metadata(dest)?.len(),
+        uncompressed_size: archived.size,
+        files: archived.files,
+        duration_ms: 0,
+    })
+}
+
 impl BackupService for TargzBackupService {
-    fn backup(&self, src: &Path, dest: &Path) -> anyhow::Result<()> {
-        targz::archive(src, dest)
+    fn backup(&self, src: &Path, dest: &Path) -> anyhow::Result<BackupStats> {
+        self.archive_dirs(&[(".", src)], dest)
+    }
+
+    fn backup_sources(&self, sources: &[Source], dest: &Path) -> anyhow::Result<BackupStats> {
+        let dirs: Vec<(&str, &Path)> = sources
+            .iter()
+            .map(|s| (s.name.as_str(), s.path.as_path()))
+            .collect();
+        self.archive_dirs(&dirs, dest)
+    }
+
+    fn backup_file(&self, src: &Path, dest: &Path) -> anyhow::Result<BackupStats> {
+        let archived = targz::archive_file_with_level(src, dest, self.level)?;
+        backup_stats(dest, archived)
     }
 
     fn restore(&self, src: &Path, dest: &Path) -> anyhow::Result<()> {
         targz::extract(src, dest)
     }
+
+    fn restore_source(&self, src: &Path, name: &str, dest: &Path) -> anyhow::Result<()> {
+        targz::extract_dir(src, name, dest)
+    }
+
+    fn restore_file(&self, src: &Path, dest: &Path) -> anyhow::Result<()> {
+        targz::extract_file(src, dest)
+    }
+
+    fn list(&self, src: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        targz::list(src)
+    }
+
+    fn find(&self, src: &Path, pattern: &FilePattern) -> anyhow::Result<Vec<ArchivedFile>> {
+        let mut found = Vec::new();
+        targz::for_each_file(src, |file, content| {
+            if !pattern.matches(&file.path) {
+                return Ok(());
+            }
+
+            let mut hasher = Sha256::new();
+            std::io::copy(content, &mut hasher)?;
+            found.push(ArchivedFile {
+                path: file.path.clone(),
+                size: file.size,
+                mtime: to_timestamp(file.mtime),
+                sha256: hex::encode(hasher.finalize()),
+            });
+            Ok(())
+        })?;
+
+        Ok(found)
+    }
+
+    fn index(&self, src: &Path) -> anyhow::Result<Vec<IndexEntry>> {
+        let entries = targz::entries(src)?;
+        Ok(entries
+            .into_iter()
+            .map(|entry| IndexEntry {
+                path: entry.path,
+                kind: match entry.kind {
+                    targz::EntryKind::File => IndexEntryKind::File,
+                    targz::EntryKind::Dir => IndexEntryKind::Dir,
+                    targz::EntryKind::Symlink(target) => IndexEntryKind::Symlink(target),
+                },
+                size: entry.size,
+                mtime: to_timestamp(entry.mtime),
+                mode: entry.mode,
+            })
+            .collect())
+    }
+
+    fn read_file(
+        &self,
+        src: &Path,
+        path: &Path,
+        out: &mut dyn std::io::Write,
+    ) -> anyhow::Result<Option<u64>> {
+        targz::copy_file(src, path, out)
+    }
+}
+
+/// Converts the seconds since the Unix epoch in a tar header.
+fn to_timestamp(secs: u64) -> Option<Timestamp> {
+    chrono::DateTime::from_timestamp(secs as i64, 0).map(|ts| Timestamp::from_utc(&ts))
 }
 
 //-----------------------------------------------------------------------------
@@ -46,9 +175,36 @@ mod tests {
         let targz = temp.path().join("test.tar.gz");
         let service = TargzBackupService::new();
         let result = service.backup(&test_dir, &targz);
-        assert!(result.is_ok());
         assert!(targz.exists(), "test.tar.gz should be created.");
 
+        // Stats test.
+        let stats = result.unwrap();
+        assert_eq!(stats.size, std::fs::metadata(&targz).unwrap().len());
+        assert_eq!(stats.uncompressed_size, ts.len() as u64);
+        assert_eq!(stats.files, 1);
+
+        // Find test.
+        let found = service.find(&targz, &"*.txt".parse().unwrap()).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, PathBuf::from(ts_filename));
+        assert_eq!(found[0].size, ts.len() as u64);
+        assert_eq!(found[0].sha256, hex::encode(Sha256::digest(&ts)));
+        assert!(found[0].mtime.is_some());
+        let found = service.find(&targz, &"*.md".parse().unwrap()).unwrap();
+        assert!(found.is_empty());
+
+        // Index test.
+        let index = service.index(&targz).unwrap();
+        assert_eq!(index.len(), 1);
+        assert_eq!(index[0].kind, IndexEntryKind::File);
+        assert_eq!(index[0].size, ts.len() as u64);
+
+        // Read file test.
+        let mut out = Vec::new();
+        let size = service.read_file(&targz, Path::new(ts_filename), &mut out);
+        assert_eq!(size.unwrap(), Some(ts.len() as u64));
+        assert_eq!(out, ts.as_bytes());
+
         // Extract test.
         let extr_dir = temp.path().join("extract");
         let result = service.restore(&targz, &extr_dir);
@@ -79,4 +235,41 @@ mod tests {
         }
         //---------- test -----------*/
     }
+
+    #[test]
+    fn it_leaves_out_excluded_paths() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let src = temp.path().join("origin");
+        std::fs::create_dir_all(src.join("node_modules/lib")).unwrap();
+        std::fs::create_dir_all(src.join("logs")).unwrap();
+        std::fs::write(src.join("node_modules/lib/index.js"), "").unwrap();
+        std::fs::write(src.join("logs/app.log"), "").unwrap();
+        std::fs::write(src.join("logs/keep.txt"), "").unwrap();
+        std::fs::write(src.join("main.js"), "").unwrap();
+
+        let excludes = ["node_modules", "*.log"].map(|p| p.parse().unwrap());
+        let service = TargzBackupService::new().with_excludes(&excludes);
+        let targz = temp.path().join("test.tar.gz");
+        service.backup(&src, &targz).unwrap();
+
+        let mut files: Vec<PathBuf> = service
+            .index(&targz)
+            .unwrap()
+            .into_iter()
+            .filter(|e| e.kind == IndexEntryKind::File)
+            .map(|e| e.path)
+            .collect();
+        files.sort();
+        assert_eq!(
+            files,
+            vec![PathBuf::from("logs/keep.txt"), PathBuf::from("main.js")]
+        );
+
+        // Matched against the path in each source directory.
+        let sources = [Source::new("main", &src)];
+        service.backup_sources(&sources, &targz).unwrap();
+        let found = service.find(&targz, &"*.js".parse().unwrap()).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].path, PathBuf::from("main/main.js"));
+    }
 }
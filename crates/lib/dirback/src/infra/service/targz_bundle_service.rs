@@ -0,0 +1,232 @@
+//!
+//! # tar.gz bundle service
+//!
+//! A bundle is a tar.gz file with the following layout.
+//!
+//! ```ascii
+//! {bundle}.tar.gz
+//! ├─ manifest.json
+//! └─ archives/
+//!    └─ {backup_id}_{backup_timestamp}.tar.gz
+//! ```
+//!
+
+use crate::domain::model::bundle::{BUNDLE_FORMAT_VERSION, BundleArchive, BundleManifest};
+use crate::domain::model::target::Target;
+use crate::domain::service::bundle_service::BundleService;
+use sha2::{Digest, Sha256};
+use std::path::{Component, Path, PathBuf};
+
+const MANIFEST_FILE_NAME: &str = "manifest.json";
+const ARCHIVE_DIR_NAME: &str = "archives";
+
+#[derive(Default)]
+pub struct TargzBundleService {}
+
+impl TargzBundleService {
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+fn checksum(path: &Path) -> anyhow::Result<String> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Returns true if the name is a single file name, without a directory or `..`.
+///
+/// The archive file names are read from the manifest of an untrusted bundle.
+fn is_plain_file_name(name: &str) -> bool {
+    let mut components = Path::new(name).components();
+    matches!(
+        (components.next(), components.next()),
+        (Some(Component::Normal(_)), None)
+    )
+}
+
+impl BundleService for TargzBundleService {
+    fn write(
+        &self,
+        target: &Target,
+        archives: &[(u32, PathBuf)],
+        dest: &Path,
+    ) -> anyhow::Result<BundleManifest> {
+        let temp = mktemp::TempDir::new()?;
+        let stage = temp.path().join("bundle");
+        let archive_dir = stage.join(ARCHIVE_DIR_NAME);
+        std::fs::create_dir_all(&archive_dir)?;
+
+        let mut manifest = BundleManifest::new(target);
+        for (backup_id, src) in archives.iter() {
+            let file_name = src
+                .file_name()
+                .ok_or_else(|| anyhow::anyhow!("Invalid archive path: '{}'", src.display()))?
+                .to_string_lossy()
+                .to_string();
+            std::fs::copy(src, archive_dir.join(&file_name))?;
+
+            manifest.archives.push(BundleArchive {
+                backup_id: *backup_id,
+                file_name,
+                checksum: checksum(src)?,
+            });
+        }
+
+        jsonfile::write(&stage.join(MANIFEST_FILE_NAME), &manifest)?;
+        targz::archive(&stage, dest)?;
+
+        Ok(manifest)
+    }
+
+    fn read(
+        &self,
+        src: &Path,
+        work_dir: &Path,
+    ) -> anyhow::Result<(BundleManifest, Vec<(u32, PathBuf)>)> {
+        targz::extract(src, work_dir)?;
+
+        let manifest_path = work_dir.join(MANIFEST_FILE_NAME);
+        if !manifest_path.exists() {
+            anyhow::bail!("Not a dirback bundle: '{}'", src.display());
+        }
+        let manifest: BundleManifest = jsonfile::read(&manifest_path)?;
+        if manifest.format_version > BUNDLE_FORMAT_VERSION {
+            anyhow::bail!(
+                "Unsupported bundle format version: {} (supported up to {}).",
+                manifest.format_version,
+                BUNDLE_FORMAT_VERSION
+            );
+        }
+
+        let mut archives = Vec::new();
+        for archive in manifest.archives.iter() {
+            if !is_plain_file_name(&archive.file_name) {
+                anyhow::bail!(
+                    "Invalid archive name in the bundle: '{}'",
+                    archive.file_name
+                );
+            }
+
+            let path = work_dir.join(ARCHIVE_DIR_NAME).join(&archive.file_name);
+            if !path.symlink_metadata().is_ok_and(|m| m.is_file()) {
+                anyhow::bail!("Archive not found in the bundle: '{}'", archive.file_name);
+            }
+            if checksum(&path)? != archive.checksum {
+                anyhow::bail!(
+                    "Checksum mismatch, the bundle may be corrupted: '{}'",
+                    archive.file_name
+                );
+            }
+            archives.push((archive.backup_id, path));
+        }
+
+        Ok((manifest, archives))
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prepare(temp: &Path) -> (Target, Vec<(u32, PathBuf)>) {
+        let target = Target::new("xxx", "Test target", Path::new("/src"));
+        let archive = temp.join("0001_20250123T123456Z.tar.gz");
+        std::fs::write(&archive, "archive data").unwrap();
+        (target, vec![(1, archive)])
+    }
+
+    #[test]
+    fn it_writes_and_reads_bundle() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (target, archives) = prepare(&temp.path());
+        let bundle = temp.path().join("bundle.tar.gz");
+
+        let service = TargzBundleService::new();
+        let written = service.write(&target, &archives, &bundle).unwrap();
+        assert!(bundle.exists());
+        assert_eq!(written.archives.len(), 1);
+
+        let work_dir = temp.path().join("work");
+        let (manifest, extracted) = service.read(&bundle, &work_dir).unwrap();
+        assert_eq!(manifest, written);
+        assert_eq!(extracted[0].0, 1);
+        assert_eq!(
+            std::fs::read_to_string(&extracted[0].1).unwrap(),
+            "archive data"
+        );
+    }
+
+    #[test]
+    fn it_returns_err_if_archive_is_corrupted() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (target, archives) = prepare(&temp.path());
+        let bundle = temp.path().join("bundle.tar.gz");
+
+        let service = TargzBundleService::new();
+        let mut manifest = service.write(&target, &archives, &bundle).unwrap();
+
+        // Rebuild the bundle with a wrong checksum.
+        let stage = temp.path().join("stage");
+        targz::extract(&bundle, &stage).unwrap();
+        manifest.archives[0].checksum = String::from("xxx");
+        jsonfile::write(&stage.join(MANIFEST_FILE_NAME), &manifest).unwrap();
+        targz::archive(&stage, &bundle).unwrap();
+
+        let result = service.read(&bundle, &temp.path().join("work"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_returns_err_if_archive_name_is_not_plain_file_name() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (target, archives) = prepare(&temp.path());
+        let bundle = temp.path().join("bundle.tar.gz");
+
+        let service = TargzBundleService::new();
+        let mut manifest = service.write(&target, &archives, &bundle).unwrap();
+
+        // A file outside the work directory, with the checksum in the manifest.
+        let outside = temp.path().join("outside.txt");
+        std::fs::write(&outside, "secret").unwrap();
+        manifest.archives[0].checksum = checksum(&outside).unwrap();
+
+        let stage = temp.path().join("stage");
+        targz::extract(&bundle, &stage).unwrap();
+        for name in [
+            "../../outside.txt",
+            "/etc/passwd",
+            "sub/archive.tar.gz",
+            ".",
+            "",
+        ] {
+            manifest.archives[0].file_name = String::from(name);
+            jsonfile::write(&stage.join(MANIFEST_FILE_NAME), &manifest).unwrap();
+            targz::archive(&stage, &bundle).unwrap();
+
+            let work_dir = temp.path().join("work");
+            let _ = std::fs::remove_dir_all(&work_dir);
+            let result = service.read(&bundle, &work_dir);
+            assert!(result.is_err(), "'{name}' should be rejected.");
+        }
+        assert!(outside.exists());
+    }
+
+    #[test]
+    fn it_returns_err_if_not_bundle() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("file.txt"), "hello").unwrap();
+        let file = temp.path().join("other.tar.gz");
+        targz::archive(&src, &file).unwrap();
+
+        let service = TargzBundleService::new();
+        assert!(service.read(&file, &temp.path().join("work")).is_err());
+    }
+}
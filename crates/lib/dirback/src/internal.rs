@@ -8,3 +8,5 @@
 //!
 
 pub use crate::domain::repository::targets::TargetRepository;
+pub use crate::domain::repository::trash::TrashRepository;
+pub use crate::domain::service::archive_store::ArchiveStore;
@@ -2,12 +2,38 @@
 //! # usecase module
 //!
 
+pub mod add_replica;
+pub mod add_source;
 pub mod backup;
+pub mod backup_all;
+pub mod browse_backups;
+pub mod cat_file;
 pub mod delete_backup;
 pub mod delete_target;
 pub mod dto;
+pub mod edit_target;
+pub mod empty_trash;
+pub mod export_target;
+pub mod find_file;
+mod hook_runner;
+pub mod import_target;
+mod open_archive;
+pub mod pin_backup;
+pub mod prune;
 pub mod register_target;
+pub mod remove_replica;
+pub mod remove_source;
+pub mod replicate;
 pub mod restore;
+pub mod restore_trash;
+pub mod run_scheduled;
+pub mod set_hooks;
+pub mod set_schedule;
+pub mod set_store;
+pub mod tag_target;
+pub mod target_path;
+pub mod transfer_targets;
+pub mod watch;
 
 #[cfg(test)]
 pub mod usecase_test_helper;
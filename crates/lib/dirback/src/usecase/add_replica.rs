@@ -0,0 +1,82 @@
+//!
+//! # Add replica usecase
+//!
+//! Adds a replica location (a secondary data directory) to the target.
+//! Archives are copied by the `ReplicateUsecase`.
+//!
+
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::Target;
+use std::path::Path;
+
+pub struct AddReplicaUsecase<'a, R: TargetRepository> {
+    repo: &'a mut R,
+}
+
+impl<'a, R: TargetRepository> AddReplicaUsecase<'a, R> {
+    pub fn new(repo: &'a mut R) -> Self {
+        Self { repo }
+    }
+
+    pub fn execute(&mut self, target_id: &str, replica: &Path) -> anyhow::Result<Target> {
+        let mut target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        if !replica.is_absolute() {
+            anyhow::bail!(
+                "Replica location must be an absolute path: '{}'",
+                replica.display()
+            );
+        }
+
+        if target.replicas.iter().any(|r| r == replica) {
+            anyhow::bail!("Replica location already added: '{}'", replica.display());
+        }
+
+        target.replicas.push(replica.to_path_buf());
+        let target = self.repo.update(&target)?;
+        Ok(target.into())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use std::path::PathBuf;
+
+    #[test]
+    fn it_works() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target = repo.add("Test target", Path::new("target")).unwrap();
+
+        let mut usecase = AddReplicaUsecase::new(&mut repo);
+        let result = usecase.execute(&target.id, Path::new("/mnt/nas")).unwrap();
+        assert_eq!(result.replicas, vec![PathBuf::from("/mnt/nas")]);
+        assert_eq!(repo.load(&target.id).unwrap().replicas.len(), 1);
+    }
+
+    #[test]
+    fn it_returns_err_if_already_added() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target = repo.add("Test target", Path::new("target")).unwrap();
+
+        let mut usecase = AddReplicaUsecase::new(&mut repo);
+        let _ = usecase.execute(&target.id, Path::new("/mnt/nas"));
+        assert!(usecase.execute(&target.id, Path::new("/mnt/nas")).is_err());
+    }
+
+    #[test]
+    fn it_returns_err_if_relative_path() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target = repo.add("Test target", Path::new("target")).unwrap();
+
+        let mut usecase = AddReplicaUsecase::new(&mut repo);
+        assert!(usecase.execute(&target.id, Path::new("nas")).is_err());
+    }
+}
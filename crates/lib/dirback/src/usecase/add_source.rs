@@ -0,0 +1,159 @@
+//!
+//! # Add source usecase
+//!
+//! Adds a source directory to the target, archived with the target path
+//! into the same backups under the source name.
+//!
+//! The path is canonicalized, and validated as the path of a new target.
+//!
+
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::Target;
+use crate::usecase::target_path::TargetPathValidator;
+use std::path::Path;
+
+pub struct AddSourceUsecase<'a, R: TargetRepository> {
+    repo: &'a mut R,
+    allow_overlap: bool,
+}
+
+impl<'a, R: TargetRepository> AddSourceUsecase<'a, R> {
+    pub fn new(repo: &'a mut R) -> Self {
+        Self {
+            repo,
+            allow_overlap: false,
+        }
+    }
+
+    /// Allows the path inside a target, or containing a target.
+    pub fn allow_overlap(mut self) -> Self {
+        self.allow_overlap = true;
+        self
+    }
+
+    /// Adds the source with the canonical path.
+    ///
+    /// Returns `TargetPathError` if the path overlaps the data directory or a target.
+    pub fn execute(&mut self, target_id: &str, name: &str, path: &Path) -> anyhow::Result<Target> {
+        let mut target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        if !path.is_dir() {
+            anyhow::bail!("Source path is invalid: '{}'", path.display());
+        }
+
+        let path = TargetPathValidator::new(&*self.repo)
+            .allow_overlap(self.allow_overlap)
+            .validate(path)?;
+
+        target.add_source(name, &path)?;
+        let target = self.repo.update(&target)?;
+        Ok(target.into())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+
+    #[test]
+    fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let mut repo = InMemoryTargetRepository::new();
+        let target = repo.add("Test target", Path::new("target")).unwrap();
+
+        let mut usecase = AddSourceUsecase::new(&mut repo);
+        let result = usecase.execute(&target.id, "config", &temp.path()).unwrap();
+        assert_eq!(result.sources.len(), 1);
+        assert_eq!(result.sources[0].name, "config");
+        assert_eq!(repo.load(&target.id).unwrap().sources.len(), 1);
+    }
+
+    #[test]
+    fn it_returns_err_if_already_added() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let mut repo = InMemoryTargetRepository::new();
+        let target = repo.add("Test target", Path::new("target")).unwrap();
+
+        let mut usecase = AddSourceUsecase::new(&mut repo);
+        let _ = usecase.execute(&target.id, "config", &temp.path());
+        assert!(usecase.execute(&target.id, "other", &temp.path()).is_err());
+        assert!(
+            usecase
+                .execute(&target.id, "config", &temp.path().join("x"))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn it_rejects_data_dir_and_overlapping_path() {
+        use crate::infra::repository::file_storage::FileStorageTargetRepository;
+        use crate::usecase::target_path::TargetPathError;
+
+        let temp = mktemp::TempDir::new().unwrap();
+        let data_dir = temp.path().join("data");
+        let app = temp.path().join("app");
+        let other = temp.path().join("other");
+        std::fs::create_dir_all(&data_dir).unwrap();
+        std::fs::create_dir_all(app.join("config")).unwrap();
+        std::fs::create_dir_all(other.join("inner")).unwrap();
+
+        let mut repo = FileStorageTargetRepository::new(&data_dir);
+        let target = repo.add("app", &app).unwrap();
+        repo.add("other", &other.join("inner")).unwrap();
+
+        let error = |repo: &mut FileStorageTargetRepository, path: &Path| {
+            AddSourceUsecase::new(repo)
+                .execute(&target.id, "config", path)
+                .unwrap_err()
+                .downcast::<TargetPathError>()
+                .unwrap()
+        };
+
+        assert!(matches!(
+            error(&mut repo, &data_dir),
+            TargetPathError::DataDir { .. }
+        ));
+        assert!(matches!(
+            error(&mut repo, &app.join("config")),
+            TargetPathError::InsideTarget { .. }
+        ));
+        assert!(matches!(
+            error(&mut repo, &other),
+            TargetPathError::ContainsTarget { .. }
+        ));
+        assert!(matches!(
+            error(&mut repo, &other.join("inner/../inner")),
+            TargetPathError::Duplicate { .. }
+        ));
+        assert!(repo.load(&target.id).unwrap().sources.is_empty());
+
+        let result = AddSourceUsecase::new(&mut repo)
+            .allow_overlap()
+            .execute(&target.id, "config", &app.join("config"))
+            .unwrap();
+        assert_eq!(
+            result.sources[0].path,
+            std::fs::canonicalize(app.join("config")).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_returns_err_if_path_is_not_dir() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target = repo.add("Test target", Path::new("target")).unwrap();
+
+        let mut usecase = AddSourceUsecase::new(&mut repo);
+        assert!(
+            usecase
+                .execute(&target.id, "config", Path::new("not-exists"))
+                .is_err()
+        );
+    }
+}
@@ -2,12 +2,24 @@
 //! # Backup usecase
 //!
 
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::model::backup_stats::BackupStats;
+use crate::domain::model::hooks::HookEvent;
+use crate::domain::model::target::Target;
+use crate::domain::model::target_kind::TargetKind;
 use crate::domain::repository::targets::TargetRepository;
+use crate::domain::service::archive_store::ArchiveStore;
 use crate::domain::service::backup_service::BackupService;
+use crate::domain::service::hook_service::HookService;
+use crate::usecase::dto;
+use crate::usecase::hook_runner::HookRunner;
+use std::path::{Path, PathBuf};
+use std::time::Instant;
 
 pub struct BackupUsecase<'a, R: TargetRepository, B: BackupService> {
     repo: &'a mut R,
     backup_service: &'a B,
+    hooks: HookRunner<'a>,
 }
 
 impl<'a, R: TargetRepository, B: BackupService> BackupUsecase<'a, R, B> {
@@ -15,44 +27,186 @@ impl<'a, R: TargetRepository, B: BackupService> BackupUsecase<'a, R, B> {
         Self {
             repo,
             backup_service,
+            hooks: HookRunner::default(),
         }
     }
 
-    pub fn execute(&mut self, target_id: &str, note: &str) -> anyhow::Result<()> {
-        let target = self.repo.load(target_id);
-        if target.is_none() {
-            anyhow::bail!("target not found: {target_id}");
-        }
-        let mut target = target.unwrap();
+    /// Runs the hooks of the target around the backup.
+    pub fn with_hooks(self, hook_service: &'a dyn HookService) -> Self {
+        self.with_hook_runner(HookRunner::new(Some(hook_service)))
+    }
 
-        // Make path to the backup file
-        let backup_path = self.repo.make_backup_dir_path(&target);
+    pub(crate) fn with_hook_runner(mut self, hooks: HookRunner<'a>) -> Self {
+        self.hooks = hooks;
+        self
+    }
 
-        // Make a backup entry
-        let mut entry = target.new_backup_entry(&backup_path, "tar.gz");
-        entry.note = note.to_string();
+    /// Returns the created backup.
+    ///
+    /// A failure of the pre-backup hook aborts the backup.
+    pub fn execute(&mut self, target_id: &str, note: &str) -> anyhow::Result<dto::BackupEntry> {
+        let mut pending = prepare_backup(self.repo, target_id, note)?;
+        start_backup(&self.hooks, &pending.target, &pending.entry)?;
+        let (target, entry) = (pending.target.clone(), pending.entry.clone());
 
         // Backup
-        self.backup_service.backup(&target.path, &entry.path)?;
+        let result = create_archive(self.backup_service, &pending.target.path, &pending.entry)
+            .and_then(|stats| {
+                pending.entry.stats = Some(stats);
+                finish_backup(self.repo, pending)
+            });
 
-        // Save the backup entry.
-        #[allow(clippy::never_loop)]
-        loop {
-            if target.register_backup_entry(entry).is_err() {
-                break;
-            }
-            if self.repo.update(&target).is_err() {
-                break;
-            }
+        let entry = end_backup(&self.hooks, &target, &entry, result)?;
+        Ok(entry.into())
+    }
+}
+
+/// A backup whose archive is not created yet.
+pub(crate) struct PendingBackup {
+    pub target: Target,
+    pub entry: BackupEntry,
+    store: Option<(String, Box<dyn ArchiveStore>)>,
+}
+
+/// Loads the target and makes a new backup entry for it.
+pub(crate) fn prepare_backup<R: TargetRepository>(
+    repo: &R,
+    target_id: &str,
+    note: &str,
+) -> anyhow::Result<PendingBackup> {
+    let target = repo.load(target_id);
+    if target.is_none() {
+        anyhow::bail!("target not found: {target_id}");
+    }
+    let target = target.unwrap();
+
+    // Open the archive store first, so that a misconfigured store fails before the backup.
+    let store = match &target.store {
+        Some(store_id) => Some((store_id.clone(), repo.archive_store(store_id)?)),
+        None => None,
+    };
+
+    // Make path to the backup file
+    let backup_path = repo.make_backup_dir_path(&target);
+
+    // Make a backup entry
+    let mut entry = target.new_backup_entry(&backup_path, "tar.gz");
+    entry.note = note.to_string();
+    entry.sources = target.archive_sources();
+    entry.kind = target.kind;
+
+    Ok(PendingBackup {
+        target,
+        entry,
+        store,
+    })
+}
 
-            return Ok(());
+/// Creates the archive of the backup entry: the file of a file target,
+/// or the directory with each source under its name if the target has several.
+///
+/// Returns the stats of the archive, measured while creating it.
+pub(crate) fn create_archive<B: BackupService + ?Sized>(
+    backup_service: &B,
+    target_path: &Path,
+    entry: &BackupEntry,
+) -> anyhow::Result<BackupStats> {
+    let started = Instant::now();
+    let stats = match entry.kind {
+        TargetKind::File => backup_service.backup_file(target_path, &entry.path),
+        TargetKind::Directory if entry.sources.is_empty() => {
+            backup_service.backup(target_path, &entry.path)
         }
+        TargetKind::Directory => backup_service.backup_sources(&entry.sources, &entry.path),
+    }?;
+    let duration_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+    Ok(BackupStats {
+        duration_ms,
+        ..stats
+    })
+}
+
+/// Runs the pre-backup hook, a failure aborts the backup.
+pub(crate) fn start_backup(
+    hooks: &HookRunner,
+    target: &Target,
+    entry: &BackupEntry,
+) -> anyhow::Result<()> {
+    hooks
+        .run(HookEvent::PreBackup, target, Some(entry))
+        .map_err(|e| hooks.fail(target, Some(entry), e))
+}
 
-        // TODO: remove backup file??
-        // The backup was created successfully,
-        // but failed to save the backup entry.
-        anyhow::bail!("Error: failed to save the backup entry.");
+/// Runs the post-backup hook, or the on-failure hook if the backup failed.
+///
+/// A failure of the post-backup hook is returned as an error, though the backup is kept.
+pub(crate) fn end_backup(
+    hooks: &HookRunner,
+    target: &Target,
+    entry: &BackupEntry,
+    result: anyhow::Result<BackupEntry>,
+) -> anyhow::Result<BackupEntry> {
+    let entry = result.map_err(|e| hooks.fail(target, Some(entry), e))?;
+    run_post_backup(hooks, target, &entry)?;
+    Ok(entry)
+}
+
+/// Runs the post-backup hook of the created backup.
+pub(crate) fn run_post_backup(
+    hooks: &HookRunner,
+    target: &Target,
+    entry: &BackupEntry,
+) -> anyhow::Result<()> {
+    hooks
+        .run(HookEvent::PostBackup, target, Some(entry))
+        .map_err(|e| {
+            let e = anyhow::anyhow!("Backup {} was created, but {e}", entry.id);
+            hooks.fail(target, Some(entry), e)
+        })
+}
+
+/// Moves the created archive into the store and saves the backup entry.
+pub(crate) fn finish_backup<R: TargetRepository>(
+    repo: &mut R,
+    pending: PendingBackup,
+) -> anyhow::Result<BackupEntry> {
+    let PendingBackup {
+        mut target,
+        mut entry,
+        store,
+    } = pending;
+
+    // Move the backup file into the archive store.
+    if let Some((store_id, store)) = store {
+        let file_name = entry.path.file_name().unwrap_or_default().to_string_lossy();
+        let key = format!("{}/{file_name}", target.id);
+        if let Err(e) = store.put(&entry.path, &key) {
+            let _ = std::fs::remove_file(&entry.path);
+            return Err(e);
+        }
+
+        entry.path = PathBuf::from(key);
+        entry.store = Some(store_id);
     }
+
+    // Save the backup entry.
+    #[allow(clippy::never_loop)]
+    loop {
+        if target.register_backup_entry(entry.clone()).is_err() {
+            break;
+        }
+        if repo.update(&target).is_err() {
+            break;
+        }
+
+        return Ok(entry);
+    }
+
+    // TODO: remove backup file??
+    // The backup was created successfully,
+    // but failed to save the backup entry.
+    anyhow::bail!("Error: failed to save the backup entry.");
 }
 
 //-----------------------------------------------------------------------------
@@ -96,6 +250,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_records_stats() {
+        let mut repo = InMemoryTargetRepository::new();
+        let (backup_service, _, _) = TestBackupService::new();
+        let target_id = repo.add("Test target", Path::new("target")).unwrap().id;
+
+        let entry = BackupUsecase::new(&mut repo, &backup_service)
+            .execute(&target_id, "")
+            .unwrap();
+        let stats = entry.stats.unwrap();
+        assert_eq!(
+            (stats.size, stats.uncompressed_size, stats.files),
+            (10, 40, 2)
+        );
+
+        let target = repo.load(&target_id).unwrap();
+        assert_eq!(target.backups[0].stats, Some(stats));
+    }
+
     #[test]
     fn it_can_be_called_multiple_times() {
         let mut repo = InMemoryTargetRepository::new();
@@ -128,6 +301,154 @@ mod tests {
         );
     }
 
+    mod archive_store {
+        use super::*;
+        use crate::infra::repository::file_storage::FileStorageTargetRepository;
+        use crate::infra::service::targz_backup_service::TargzBackupService;
+
+        #[test]
+        fn it_moves_backup_into_store() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let store_root = register_local_store(&temp.path(), "nas");
+            let mut repo = FileStorageTargetRepository::new(&temp.path());
+            let backup_service = TargzBackupService::new();
+
+            let src = temp.path().join("src");
+            std::fs::create_dir_all(&src).unwrap();
+            let mut target = repo.add("Test target", &src).unwrap();
+            target.store = Some("nas".to_string());
+            repo.update(&target).unwrap();
+
+            let mut backup = BackupUsecase::new(&mut repo, &backup_service);
+            backup.execute(&target.id, "stored").unwrap();
+
+            let entry = &repo.load(&target.id).unwrap().backups[0];
+            assert_eq!(entry.store.as_deref(), Some("nas"));
+            assert!(entry.path.starts_with(&target.id));
+            assert!(store_root.join(&entry.path).exists());
+
+            let local_dir = repo.make_backup_dir_path(&target);
+            assert_eq!(std::fs::read_dir(local_dir).unwrap().count(), 0);
+        }
+
+        #[test]
+        fn it_returns_err_if_store_not_registered() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = FileStorageTargetRepository::new(&temp.path());
+            let (backup_service, backup_counter, _) = TestBackupService::new();
+
+            let mut target = repo.add("Test target", Path::new("target")).unwrap();
+            target.store = Some("nas".to_string());
+            repo.update(&target).unwrap();
+
+            let mut backup = BackupUsecase::new(&mut repo, &backup_service);
+            assert!(backup.execute(&target.id, "").is_err());
+            assert_eq!(*backup_counter.borrow(), 0);
+            assert!(repo.load(&target.id).unwrap().backups.is_empty());
+        }
+    }
+
+    mod hooks {
+        use super::*;
+        use crate::domain::model::hooks::HookEvent;
+
+        fn prepare(repo: &mut InMemoryTargetRepository, hooks: &[(HookEvent, &str)]) -> String {
+            let mut target = repo.add("Test target", Path::new("target")).unwrap();
+            for (event, command) in hooks {
+                target.hooks.set_command(*event, Some(command));
+            }
+            repo.update(&target).unwrap();
+            target.id
+        }
+
+        #[test]
+        fn it_runs_hooks_around_backup() {
+            let mut repo = InMemoryTargetRepository::new();
+            let target_id = prepare(
+                &mut repo,
+                &[
+                    (HookEvent::PreBackup, "stop db"),
+                    (HookEvent::PostBackup, "start db"),
+                    (HookEvent::OnFailure, "notify"),
+                ],
+            );
+            let (backup_service, backup_counter, _) = TestBackupService::new();
+            let hook_service = TestHookService::new();
+
+            BackupUsecase::new(&mut repo, &backup_service)
+                .with_hooks(&hook_service)
+                .execute(&target_id, "hooked")
+                .unwrap();
+
+            assert_eq!(hook_service.events(), vec!["pre-backup", "post-backup"]);
+            let post = &hook_service.calls()[1];
+            assert_eq!(post.env("DIRBACK_BACKUP_ID"), Some("1"));
+            assert_eq!(post.env("DIRBACK_BACKUP_NOTE"), Some("hooked"));
+            assert_eq!(*backup_counter.borrow(), 1);
+        }
+
+        #[test]
+        fn failing_pre_backup_hook_aborts_backup() {
+            let mut repo = InMemoryTargetRepository::new();
+            let target_id = prepare(
+                &mut repo,
+                &[
+                    (HookEvent::PreBackup, "fail to stop db"),
+                    (HookEvent::PostBackup, "start db"),
+                    (HookEvent::OnFailure, "notify"),
+                ],
+            );
+            let (backup_service, backup_counter, _) = TestBackupService::new();
+            let hook_service = TestHookService::new();
+
+            let result = BackupUsecase::new(&mut repo, &backup_service)
+                .with_hooks(&hook_service)
+                .execute(&target_id, "");
+
+            assert!(
+                result
+                    .unwrap_err()
+                    .to_string()
+                    .contains("pre-backup hook failed")
+            );
+            assert_eq!(hook_service.events(), vec!["pre-backup", "on-failure"]);
+            assert_eq!(*backup_counter.borrow(), 0);
+            assert!(repo.load(&target_id).unwrap().backups.is_empty());
+        }
+
+        #[test]
+        fn failing_post_backup_hook_keeps_backup() {
+            let mut repo = InMemoryTargetRepository::new();
+            let target_id = prepare(&mut repo, &[(HookEvent::PostBackup, "fail to start db")]);
+            let (backup_service, _, _) = TestBackupService::new();
+            let hook_service = TestHookService::new();
+
+            let result = BackupUsecase::new(&mut repo, &backup_service)
+                .with_hooks(&hook_service)
+                .execute(&target_id, "");
+
+            assert!(
+                result
+                    .unwrap_err()
+                    .to_string()
+                    .contains("post-backup hook failed")
+            );
+            assert_eq!(repo.load(&target_id).unwrap().backups.len(), 1);
+        }
+
+        #[test]
+        fn it_does_not_run_hooks_without_hook_service() {
+            let mut repo = InMemoryTargetRepository::new();
+            let target_id = prepare(&mut repo, &[(HookEvent::PreBackup, "fail")]);
+            let (backup_service, backup_counter, _) = TestBackupService::new();
+
+            BackupUsecase::new(&mut repo, &backup_service)
+                .execute(&target_id, "")
+                .unwrap();
+            assert_eq!(*backup_counter.borrow(), 1);
+        }
+    }
+
     #[test]
     fn it_returns_err_when_target_not_found() {
         let mut repo = InMemoryTargetRepository::new();
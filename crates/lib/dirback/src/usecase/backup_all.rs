@@ -0,0 +1,491 @@
+//!
+//! # Backup all usecase
+//!
+//! Takes a backup of every target (or the targets with a tag),
+//! continuing past failures.
+//!
+//! The archives are created by up to `jobs` threads in parallel,
+//! each between the pre-backup and post-backup hooks of its target.
+//! The backup entries are saved one by one afterwards.
+//!
+
+use crate::domain::model::backup_entry::BackupEntry as ModelBackupEntry;
+use crate::domain::model::backup_stats::BackupStats;
+use crate::domain::model::target::Target;
+use crate::domain::repository::targets::TargetRepository;
+use crate::domain::service::backup_service::BackupService;
+use crate::domain::service::hook_service::HookService;
+use crate::usecase::backup::{
+    PendingBackup, create_archive, finish_backup, prepare_backup, run_post_backup, start_backup,
+};
+use crate::usecase::dto::{BackupEntry, BackupSummary, TargetBackupResult};
+use crate::usecase::hook_runner::HookRunner;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+
+/// The result of an archive job, and the time it took.
+type ArchiveResult = (anyhow::Result<Archived>, Duration);
+
+/// A created archive.
+struct Archived {
+    stats: BackupStats,
+    /// The result of the post-backup hook, which does not discard the archive.
+    post_backup: anyhow::Result<()>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BackupAllOptions {
+    /// Backs up only the targets with the tag.
+    pub tag: Option<String>,
+    pub note: String,
+    /// The maximum number of archives created at the same time.
+    pub jobs: usize,
+}
+
+impl Default for BackupAllOptions {
+    fn default() -> Self {
+        Self {
+            tag: None,
+            note: String::new(),
+            jobs: 1,
+        }
+    }
+}
+
+pub struct BackupAllUsecase<'a, R: TargetRepository, B: BackupService + Sync> {
+    repo: &'a mut R,
+    backup_service: &'a B,
+    hooks: HookRunner<'a>,
+}
+
+impl<'a, R: TargetRepository, B: BackupService + Sync> BackupAllUsecase<'a, R, B> {
+    pub fn new(repo: &'a mut R, backup_service: &'a B) -> Self {
+        Self {
+            repo,
+            backup_service,
+            hooks: HookRunner::default(),
+        }
+    }
+
+    /// Runs the hooks of each target around its backup.
+    ///
+    /// The pre-backup and post-backup hooks run right before and after
+    /// the archive of the target is created, so that a target is stopped
+    /// only while its own archive is created.
+    pub fn with_hooks(mut self, hook_service: &'a dyn HookService) -> Self {
+        self.hooks = HookRunner::new(Some(hook_service));
+        self
+    }
+
+    /// Returns the result of each target.
+    ///
+    /// A failure of a target does not stop the others,
+    /// only a failure of loading the targets is returned as an error.
+    pub fn execute(&mut self, options: &BackupAllOptions) -> anyhow::Result<BackupSummary> {
+        let mut targets = self.repo.load_all()?;
+        if let Some(tag) = &options.tag {
+            targets.retain(|t| t.matches_tag(tag));
+        }
+
+        // Prepare
+        let mut prepared = Vec::new();
+        for target in targets.iter() {
+            let started = Instant::now();
+            let pending = prepare_backup(self.repo, &target.id, &options.note);
+            prepared.push((pending, started.elapsed()));
+        }
+
+        // Create the archives.
+        let jobs: Vec<(Target, ModelBackupEntry)> = prepared
+            .iter()
+            .filter_map(|(pending, _)| pending.as_ref().ok())
+            .map(|p| (p.target.clone(), p.entry.clone()))
+            .collect();
+        let mut archived = self.archive(&jobs, options.jobs).into_iter();
+
+        // Save the backup entries.
+        let mut summary = BackupSummary::default();
+        for (target, (pending, elapsed)) in targets.iter().zip(prepared) {
+            let (result, elapsed) = match pending {
+                Err(e) => (Err(e), elapsed),
+                Ok(pending) => {
+                    let (archive_result, archive_elapsed) = archived.next().unwrap();
+                    let started = Instant::now();
+                    let result = self.finish(pending, archive_result);
+                    (result, elapsed + archive_elapsed + started.elapsed())
+                }
+            };
+
+            summary.results.push(TargetBackupResult {
+                target_id: target.id.clone(),
+                target_name: target.name.clone(),
+                backup: result.as_ref().ok().cloned(),
+                error: result.err().map(|e| e.to_string()),
+                duration_ms: u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX),
+            });
+        }
+
+        Ok(summary)
+    }
+
+    /// Saves the backup entry of the created archive.
+    ///
+    /// The hooks of a failed archive have already run in its job.
+    fn finish(
+        &mut self,
+        mut pending: PendingBackup,
+        archived: anyhow::Result<Archived>,
+    ) -> anyhow::Result<BackupEntry> {
+        let archived = archived?;
+        let (target, entry) = (pending.target.clone(), pending.entry.clone());
+        pending.entry.stats = Some(archived.stats);
+        let entry = finish_backup(self.repo, pending)
+            .map_err(|e| self.hooks.fail(&target, Some(&entry), e))?;
+        archived.post_backup?;
+        Ok(entry.into())
+    }
+
+    /// Runs the archive jobs with up to `max_jobs` threads.
+    ///
+    /// Returns the result of each job in the same order.
+    fn archive(&self, jobs: &[(Target, ModelBackupEntry)], max_jobs: usize) -> Vec<ArchiveResult> {
+        let results: Vec<Mutex<Option<ArchiveResult>>> =
+            jobs.iter().map(|_| Mutex::new(None)).collect();
+        let next = AtomicUsize::new(0);
+        let (hooks, backup_service) = (self.hooks, self.backup_service);
+
+        let threads = max_jobs.clamp(1, jobs.len().max(1));
+        std::thread::scope(|scope| {
+            for _ in 0..threads {
+                scope.spawn(|| {
+                    loop {
+                        let i = next.fetch_add(1, Ordering::SeqCst);
+                        let Some((target, entry)) = jobs.get(i) else {
+                            break;
+                        };
+
+                        let started = Instant::now();
+                        let result = archive_job(&hooks, backup_service, target, entry);
+                        *results[i].lock().unwrap() = Some((result, started.elapsed()));
+                    }
+                });
+            }
+        });
+
+        results
+            .into_iter()
+            .map(|r| {
+                r.into_inner().unwrap().unwrap_or_else(|| {
+                    (
+                        Err(anyhow::anyhow!("Archive was not created.")),
+                        Duration::ZERO,
+                    )
+                })
+            })
+            .collect()
+    }
+}
+
+/// Creates the archive between the pre-backup and post-backup hooks of the target.
+///
+/// A failure of the pre-backup hook aborts the job.
+fn archive_job<B: BackupService>(
+    hooks: &HookRunner,
+    backup_service: &B,
+    target: &Target,
+    entry: &ModelBackupEntry,
+) -> anyhow::Result<Archived> {
+    start_backup(hooks, target, entry)?;
+    let stats = create_archive(backup_service, &target.path, entry)
+        .map_err(|e| hooks.fail(target, Some(entry), e))?;
+    Ok(Archived {
+        stats,
+        post_backup: run_post_backup(hooks, target, entry),
+    })
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::archived_file::ArchivedFile;
+    use crate::domain::model::file_pattern::FilePattern;
+    use crate::domain::model::index_entry::IndexEntry;
+    use crate::domain::model::source::Source;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use std::path::{Path, PathBuf};
+
+    /// Fails the backup of the directories named "broken".
+    struct TestBackupService {
+        count: AtomicUsize,
+        running: AtomicUsize,
+        max_running: AtomicUsize,
+    }
+
+    impl TestBackupService {
+        fn new() -> Self {
+            Self {
+                count: AtomicUsize::new(0),
+                running: AtomicUsize::new(0),
+                max_running: AtomicUsize::new(0),
+            }
+        }
+    }
+
+    impl BackupService for TestBackupService {
+        fn backup(&self, src: &Path, _dest: &Path) -> anyhow::Result<BackupStats> {
+            let running = self.running.fetch_add(1, Ordering::SeqCst) + 1;
+            self.max_running.fetch_max(running, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(20));
+            self.running.fetch_sub(1, Ordering::SeqCst);
+
+            if src.ends_with("broken") {
+                anyhow::bail!("broken target");
+            }
+            self.count.fetch_add(1, Ordering::SeqCst);
+            Ok(BackupStats::default())
+        }
+
+        fn backup_sources(&self, sources: &[Source], dest: &Path) -> anyhow::Result<BackupStats> {
+            self.backup(&sources[0].path, dest)
+        }
+
+        fn backup_file(&self, src: &Path, dest: &Path) -> anyhow::Result<BackupStats> {
+            self.backup(src, dest)
+        }
+
+        fn restore(&self, _src: &Path, _dest: &Path) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn restore_source(&self, _src: &Path, _name: &str, _dest: &Path) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn restore_file(&self, _src: &Path, _dest: &Path) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn list(&self, _src: &Path) -> anyhow::Result<Vec<PathBuf>> {
+            Ok(Vec::new())
+        }
+
+        fn find(&self, _src: &Path, _pattern: &FilePattern) -> anyhow::Result<Vec<ArchivedFile>> {
+            Ok(Vec::new())
+        }
+
+        fn index(&self, _src: &Path) -> anyhow::Result<Vec<IndexEntry>> {
+            Ok(Vec::new())
+        }
+
+        fn read_file(
+            &self,
+            _src: &Path,
+            _path: &Path,
+            _out: &mut dyn std::io::Write,
+        ) -> anyhow::Result<Option<u64>> {
+            Ok(None)
+        }
+    }
+
+    fn prepare(repo: &mut InMemoryTargetRepository, names: &[&str]) -> Vec<String> {
+        names
+            .iter()
+            .map(|name| repo.add(name, Path::new(name)).unwrap().id)
+            .collect()
+    }
+
+    #[test]
+    fn it_backs_up_all_targets() {
+        let mut repo = InMemoryTargetRepository::new();
+        let ids = prepare(&mut repo, &["a", "b", "c"]);
+        let service = TestBackupService::new();
+
+        let options = BackupAllOptions {
+            note: String::from("nightly"),
+            ..Default::default()
+        };
+        let summary = BackupAllUsecase::new(&mut repo, &service)
+            .execute(&options)
+            .unwrap();
+
+        assert_eq!(summary.results.len(), 3);
+        assert_eq!(summary.failed(), 0);
+        assert_eq!(service.count.load(Ordering::SeqCst), 3);
+        for id in ids.iter() {
+            let target = repo.load(id).unwrap();
+            assert_eq!(target.backups.len(), 1);
+            assert_eq!(target.backups[0].note, "nightly");
+        }
+    }
+
+    #[test]
+    fn it_continues_past_failures() {
+        let mut repo = InMemoryTargetRepository::new();
+        let ids = prepare(&mut repo, &["a", "broken", "c"]);
+        let service = TestBackupService::new();
+
+        let summary = BackupAllUsecase::new(&mut repo, &service)
+            .execute(&BackupAllOptions::default())
+            .unwrap();
+
+        assert_eq!(summary.succeeded(), 2);
+        assert_eq!(summary.failed(), 1);
+
+        let failed = summary.results.iter().find(|r| !r.is_ok()).unwrap();
+        assert_eq!(failed.target_name, "broken");
+        assert!(failed.backup.is_none());
+        assert!(failed.error.as_ref().unwrap().contains("broken target"));
+
+        let counts: Vec<usize> = ids
+            .iter()
+            .map(|id| repo.load(id).unwrap().backups.len())
+            .collect();
+        let broken = ids.iter().position(|id| *id == failed.target_id).unwrap();
+        for (i, count) in counts.iter().enumerate() {
+            assert_eq!(*count, usize::from(i != broken));
+        }
+    }
+
+    #[test]
+    fn it_filters_targets_by_tag() {
+        let mut repo = InMemoryTargetRepository::new();
+        let ids = prepare(&mut repo, &["a", "b"]);
+        let mut target = repo.load(&ids[1]).unwrap();
+        target.add_tag("clients").unwrap();
+        repo.update(&target).unwrap();
+        let service = TestBackupService::new();
+
+        let options = BackupAllOptions {
+            tag: Some(String::from("clients")),
+            ..Default::default()
+        };
+        let summary = BackupAllUsecase::new(&mut repo, &service)
+            .execute(&options)
+            .unwrap();
+
+        assert_eq!(summary.results.len(), 1);
+        assert_eq!(summary.results[0].target_id, ids[1]);
+        assert_eq!(repo.load(&ids[0]).unwrap().backups.len(), 0);
+    }
+
+    #[test]
+    fn it_runs_hooks_of_each_target() {
+        use crate::domain::model::hooks::HookEvent;
+        use crate::usecase::usecase_test_helper::TestHookService;
+
+        let mut repo = InMemoryTargetRepository::new();
+        let ids = prepare(&mut repo, &["a", "broken", "c"]);
+        for (id, pre_backup) in ids.iter().zip(["stop a", "stop broken", "fail to stop c"]) {
+            let mut target = repo.load(id).unwrap();
+            let hooks = &mut target.hooks;
+            hooks.set_command(HookEvent::PreBackup, Some(pre_backup));
+            hooks.set_command(HookEvent::PostBackup, Some("start"));
+            hooks.set_command(HookEvent::OnFailure, Some("notify"));
+            repo.update(&target).unwrap();
+        }
+        let service = TestBackupService::new();
+        let hook_service = TestHookService::new();
+
+        let summary = BackupAllUsecase::new(&mut repo, &service)
+            .with_hooks(&hook_service)
+            .execute(&BackupAllOptions::default())
+            .unwrap();
+
+        // "broken" fails to archive, "c" fails in the pre-backup hook.
+        assert_eq!(summary.succeeded(), 1);
+        assert_eq!(service.count.load(Ordering::SeqCst), 1);
+
+        let mut calls: Vec<(String, String)> = hook_service
+            .calls()
+            .iter()
+            .map(|c| {
+                let name = c.env("DIRBACK_TARGET_NAME").unwrap().to_string();
+                (name, c.env("DIRBACK_HOOK").unwrap().to_string())
+            })
+            .collect();
+        calls.sort();
+        let expected = [
+            ("a", "post-backup"),
+            ("a", "pre-backup"),
+            ("broken", "on-failure"),
+            ("broken", "pre-backup"),
+            ("c", "on-failure"),
+            ("c", "pre-backup"),
+        ];
+        assert_eq!(calls.len(), expected.len());
+        for ((name, event), (expected_name, expected_event)) in calls.iter().zip(expected) {
+            assert_eq!(
+                (name.as_str(), event.as_str()),
+                (expected_name, expected_event)
+            );
+        }
+    }
+
+    #[test]
+    fn it_runs_hooks_around_archive_of_each_target() {
+        use crate::domain::model::hooks::HookEvent;
+        use crate::usecase::usecase_test_helper::TestHookService;
+
+        let mut repo = InMemoryTargetRepository::new();
+        let ids = prepare(&mut repo, &["a", "b"]);
+        for id in ids.iter() {
+            let mut target = repo.load(id).unwrap();
+            target.hooks.set_command(HookEvent::PreBackup, Some("stop"));
+            target
+                .hooks
+                .set_command(HookEvent::PostBackup, Some("start"));
+            repo.update(&target).unwrap();
+        }
+        let service = TestBackupService::new();
+        let hook_service = TestHookService::new();
+
+        let summary = BackupAllUsecase::new(&mut repo, &service)
+            .with_hooks(&hook_service)
+            .execute(&BackupAllOptions::default())
+            .unwrap();
+        assert_eq!(summary.succeeded(), 2);
+
+        // A target is started again before the next one is stopped.
+        let calls: Vec<(String, String)> = hook_service
+            .calls()
+            .iter()
+            .map(|c| {
+                let id = c.env("DIRBACK_TARGET_ID").unwrap().to_string();
+                (id, c.env("DIRBACK_HOOK").unwrap().to_string())
+            })
+            .collect();
+        let first = calls[0].0.clone();
+        let second = ids.iter().find(|id| **id != first).unwrap().clone();
+        assert_eq!(
+            calls,
+            vec![
+                (first.clone(), String::from("pre-backup")),
+                (first, String::from("post-backup")),
+                (second.clone(), String::from("pre-backup")),
+                (second, String::from("post-backup")),
+            ]
+        );
+    }
+
+    #[test]
+    fn it_limits_parallel_jobs() {
+        let mut repo = InMemoryTargetRepository::new();
+        prepare(&mut repo, &["a", "b", "c", "d", "e"]);
+        let service = TestBackupService::new();
+
+        let options = BackupAllOptions {
+            jobs: 2,
+            ..Default::default()
+        };
+        let summary = BackupAllUsecase::new(&mut repo, &service)
+            .execute(&options)
+            .unwrap();
+
+        assert_eq!(summary.succeeded(), 5);
+        assert!(service.max_running.load(Ordering::SeqCst) <= 2);
+    }
+}
@@ -0,0 +1,189 @@
+//!
+//! # Browse backups usecase
+//!
+//! Reads the backups of a target without restoring them, e.g. for a mounted file system.
+//!
+//! The index of each backup is read on the first request and kept,
+//! and so is the archive (fetched from an archive store if needed),
+//! so each archive is indexed and fetched only once.
+//!
+
+use crate::domain::model::index_entry::IndexEntry;
+use crate::domain::model::target::Target;
+use crate::domain::repository::targets::TargetRepository;
+use crate::domain::service::backup_service::BackupService;
+use crate::usecase::dto::BackupEntry;
+use crate::usecase::open_archive::open_archive;
+use std::collections::HashMap;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+pub struct BrowseBackupsUsecase<'a, R: TargetRepository, B: BackupService> {
+    repo: &'a R,
+    backup_service: &'a B,
+    target: Target,
+
+    /// The indexes of the backups, by the backup ID.
+    indexes: HashMap<u32, Vec<IndexEntry>>,
+
+    /// The archives, by the backup ID, with the temporary directories holding the fetched ones.
+    archives: HashMap<u32, (PathBuf, Option<mktemp::TempDir>)>,
+}
+
+impl<'a, R: TargetRepository, B: BackupService> BrowseBackupsUsecase<'a, R, B> {
+    /// Opens the backups of the target, as they are at this time.
+    pub fn open(repo: &'a R, backup_service: &'a B, target_id: &str) -> anyhow::Result<Self> {
+        let target = repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        Ok(Self {
+            repo,
+            backup_service,
+            target,
+            indexes: HashMap::new(),
+            archives: HashMap::new(),
+        })
+    }
+
+    /// Returns the backups, the oldest first.
+    pub fn backups(&self) -> Vec<BackupEntry> {
+        let mut backups: Vec<BackupEntry> = self
+            .target
+            .backups
+            .iter()
+            .cloned()
+            .map(Into::into)
+            .collect();
+        backups.sort_by_key(|entry| entry.id);
+        backups
+    }
+
+    /// Returns the files, directories and symbolic links in the backup.
+    pub fn index(&mut self, backup_id: u32) -> anyhow::Result<&[IndexEntry]> {
+        if !self.indexes.contains_key(&backup_id) {
+            let archive = self.archive(backup_id)?;
+            let index = self.backup_service.index(&archive)?;
+            return Ok(self.indexes.entry(backup_id).or_insert(index));
+        }
+
+        Ok(&self.indexes[&backup_id])
+    }
+
+    /// Writes the file at the relative path in the backup to `out`.
+    ///
+    /// Returns the size of the file, or `None` if the file is not in the backup.
+    pub fn read_file(
+        &mut self,
+        backup_id: u32,
+        path: &Path,
+        out: &mut dyn Write,
+    ) -> anyhow::Result<Option<u64>> {
+        let archive = self.archive(backup_id)?;
+        self.backup_service.read_file(&archive, path, out)
+    }
+
+    /// Returns the path to the backup file, fetching it from the archive store if needed.
+    fn archive(&mut self, backup_id: u32) -> anyhow::Result<PathBuf> {
+        if let Some((archive, _)) = self.archives.get(&backup_id) {
+            return Ok(archive.clone());
+        }
+
+        let entry = self
+            .target
+            .find_backup_entry(backup_id)
+            .ok_or_else(|| anyhow::anyhow!("BackupEntry({}) not found", backup_id))?;
+
+        let (archive, temp) = open_archive(self.repo, &entry)?;
+        self.archives.insert(backup_id, (archive.clone(), temp));
+        Ok(archive)
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::index_entry::IndexEntryKind;
+    use crate::infra::repository::file_storage::FileStorageTargetRepository;
+    use crate::infra::service::targz_backup_service::TargzBackupService;
+    use crate::usecase::backup::BackupUsecase;
+    use crate::usecase::usecase_test_helper::register_local_store;
+
+    fn prepare(
+        temp: &mktemp::TempDir,
+        store: Option<&str>,
+    ) -> (FileStorageTargetRepository, String) {
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(src.join("config")).unwrap();
+        std::fs::write(src.join("config/prod.yaml"), "v1").unwrap();
+
+        let mut repo = FileStorageTargetRepository::new(&temp.path());
+        let mut target = repo.add("app", &src).unwrap();
+        target.store = store.map(String::from);
+        repo.update(&target).unwrap();
+
+        let service = TargzBackupService::new();
+        BackupUsecase::new(&mut repo, &service)
+            .execute(&target.id, "")
+            .unwrap();
+        (repo, target.id)
+    }
+
+    #[test]
+    fn it_reads_index_and_files() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (repo, target_id) = prepare(&temp, None);
+        let service = TargzBackupService::new();
+
+        let mut browse = BrowseBackupsUsecase::open(&repo, &service, &target_id).unwrap();
+        let backups = browse.backups();
+        assert_eq!(backups.len(), 1);
+
+        let index = browse.index(backups[0].id).unwrap();
+        let file = index
+            .iter()
+            .find(|e| e.path == Path::new("config/prod.yaml"))
+            .unwrap();
+        assert_eq!(file.kind, IndexEntryKind::File);
+        assert_eq!(file.size, 2);
+        assert!(
+            index
+                .iter()
+                .any(|e| e.path == Path::new("config") && e.kind == IndexEntryKind::Dir)
+        );
+
+        let mut out = Vec::new();
+        let size = browse
+            .read_file(1, Path::new("config/prod.yaml"), &mut out)
+            .unwrap();
+        assert_eq!(size, Some(2));
+        assert_eq!(out, b"v1");
+        let size = browse.read_file(1, Path::new("nothing"), &mut out).unwrap();
+        assert_eq!(size, None);
+
+        assert!(browse.index(2).is_err());
+        assert!(BrowseBackupsUsecase::open(&repo, &service, "unknown").is_err());
+    }
+
+    #[test]
+    fn it_keeps_archives_fetched_from_store() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let store_root = register_local_store(&temp.path(), "nas");
+        let (repo, target_id) = prepare(&temp, Some("nas"));
+        let service = TargzBackupService::new();
+
+        let mut browse = BrowseBackupsUsecase::open(&repo, &service, &target_id).unwrap();
+        assert!(!browse.index(1).unwrap().is_empty());
+
+        // The archive is fetched once, and kept while browsing.
+        std::fs::remove_dir_all(&store_root).unwrap();
+        let mut out = Vec::new();
+        let size = browse
+            .read_file(1, Path::new("config/prod.yaml"), &mut out)
+            .unwrap();
+        assert_eq!(size, Some(2));
+    }
+}
@@ -0,0 +1,113 @@
+//!
+//! # Cat file usecase
+//!
+//! Writes the content of a file in a backup, without restoring the backup.
+//!
+
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::repository::targets::TargetRepository;
+use crate::domain::service::backup_service::BackupService;
+use crate::usecase::open_archive::open_archive;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum CatFileError {
+    #[error("'{0}' is not found in the backup.")]
+    FileNotFound(PathBuf),
+}
+
+pub struct CatFileUsecase<'a, R: TargetRepository, B: BackupService> {
+    repo: &'a R,
+    backup_service: &'a B,
+}
+
+impl<'a, R: TargetRepository, B: BackupService> CatFileUsecase<'a, R, B> {
+    pub fn new(repo: &'a R, backup_service: &'a B) -> Self {
+        Self {
+            repo,
+            backup_service,
+        }
+    }
+
+    /// Writes the file at the relative path in the backup to `out`, and returns its size.
+    ///
+    /// The content is streamed from the archive. A backup in an archive store
+    /// is fetched into a temporary file first.
+    pub fn execute(
+        &self,
+        target_id: &str,
+        backup_id: u32,
+        path: &Path,
+        out: &mut dyn Write,
+    ) -> anyhow::Result<u64> {
+        let target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        let entry = target
+            .find_backup_entry(backup_id)
+            .ok_or_else(|| anyhow::anyhow!("BackupEntry({}) not found", backup_id))?;
+
+        let size = self.read_file(&entry, path, out)?;
+        size.ok_or_else(|| CatFileError::FileNotFound(path.to_path_buf()).into())
+    }
+
+    fn read_file(
+        &self,
+        entry: &BackupEntry,
+        path: &Path,
+        out: &mut dyn Write,
+    ) -> anyhow::Result<Option<u64>> {
+        let (archive, _temp) = open_archive(self.repo, entry)?;
+        self.backup_service.read_file(&archive, path, out)
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::file_storage::FileStorageTargetRepository;
+    use crate::infra::service::targz_backup_service::TargzBackupService;
+    use crate::usecase::backup::BackupUsecase;
+
+    #[test]
+    fn it_writes_the_file() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let dir = temp.path().join("app");
+        std::fs::create_dir_all(dir.join("config")).unwrap();
+        std::fs::write(dir.join("config/prod.yaml"), "v1").unwrap();
+
+        let mut repo = FileStorageTargetRepository::new(&temp.path().join("data"));
+        let target_id = repo.add("app", &dir).unwrap().id;
+        let service = TargzBackupService::new();
+        BackupUsecase::new(&mut repo, &service)
+            .execute(&target_id, "")
+            .unwrap();
+        std::fs::write(dir.join("config/prod.yaml"), "v2").unwrap();
+
+        let usecase = CatFileUsecase::new(&repo, &service);
+        let mut out = Vec::new();
+        let size = usecase
+            .execute(&target_id, 1, Path::new("config/prod.yaml"), &mut out)
+            .unwrap();
+        assert_eq!(size, 2);
+        assert_eq!(out, b"v1");
+
+        let err = usecase
+            .execute(&target_id, 1, Path::new("config/dev.yaml"), &mut out)
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<CatFileError>().unwrap(),
+            CatFileError::FileNotFound(PathBuf::from("config/dev.yaml"))
+        );
+
+        let path = Path::new("config/prod.yaml");
+        assert!(usecase.execute(&target_id, 2, path, &mut out).is_err());
+        assert!(usecase.execute("unknown", 1, path, &mut out).is_err());
+    }
+}
@@ -1,6 +1,8 @@
 //!
 //! # Delete backup usecase
 //!
+//! A pinned backup cannot be deleted until it is unpinned.
+//!
 
 use crate::domain::repository::targets::TargetRepository;
 use crate::usecase::dto::BackupEntry;
@@ -15,6 +17,17 @@ impl<'a, R: TargetRepository> DeleteBackupUsecase<'a, R> {
     }
 
     pub fn execute(&mut self, target_id: &str, backup_id: u32) -> anyhow::Result<BackupEntry> {
+        let target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+        if target
+            .find_backup_entry(backup_id)
+            .is_some_and(|b| b.pinned)
+        {
+            anyhow::bail!("Backup {backup_id} is pinned. Unpin it before deleting.");
+        }
+
         let entry = self.repo.delete_backup(target_id, backup_id)?;
         Ok(entry.into())
     }
@@ -51,4 +64,19 @@ mod tests {
         let target = repo.load(&target.id).unwrap();
         assert_eq!(target.backups.len(), before_backup_count - 1);
     }
+
+    #[test]
+    fn it_returns_err_if_backup_is_pinned() {
+        let mut repo = InMemoryTargetRepository::new();
+
+        let mut target = repo.add("TestTarget", Path::new(".")).unwrap();
+        let entry = target.new_backup_entry(Path::new("backups"), "tar.gz");
+        target.register_backup_entry(entry).unwrap();
+        target.set_pinned(1, true).unwrap();
+        repo.update(&target).unwrap();
+
+        let result = DeleteBackupUsecase::new(&mut repo).execute(&target.id, 1);
+        assert!(result.is_err());
+        assert_eq!(repo.load(&target.id).unwrap().backups.len(), 1);
+    }
 }
@@ -1,20 +1,48 @@
 //!
 //! # Delete target usecase
 //!
+//! A target with pinned backups is deleted only if allowed explicitly.
+//!
 
 use crate::domain::repository::targets::TargetRepository;
 use crate::usecase::dto::Target;
 
 pub struct DeleteTargetUsecase<'a, R: TargetRepository> {
     repo: &'a mut R,
+    allow_pinned: bool,
 }
 
 impl<'a, R: TargetRepository> DeleteTargetUsecase<'a, R> {
     pub fn new(repo: &'a mut R) -> Self {
-        Self { repo }
+        Self {
+            repo,
+            allow_pinned: false,
+        }
+    }
+
+    /// Deletes the target even if it has pinned backups.
+    pub fn allow_pinned(mut self, allow: bool) -> Self {
+        self.allow_pinned = allow;
+        self
     }
 
     pub fn execute(&mut self, target_id: &str) -> anyhow::Result<Target> {
+        if !self.allow_pinned {
+            let pinned = self
+                .repo
+                .load(target_id)
+                .map(|t| t.pinned_backup_ids())
+                .unwrap_or_default();
+            if !pinned.is_empty() {
+                let ids: Vec<String> = pinned.iter().map(u32::to_string).collect();
+                anyhow::bail!(
+                    "Target({}) has pinned backups ({}). Unpin them, or allow deleting pinned backups.",
+                    target_id,
+                    ids.join(", ")
+                );
+            }
+        }
+
         let target = self.repo.delete_target(target_id)?;
         Ok(target.into())
     }
@@ -40,4 +68,25 @@ mod tests {
         let targets = repo.load_all().unwrap();
         assert_eq!(targets.len(), 0);
     }
+
+    #[test]
+    fn it_requires_override_for_pinned_backups() {
+        let mut repo = InMemoryTargetRepository::new();
+
+        let mut target = repo.add("TestTarget", std::path::Path::new(".")).unwrap();
+        let entry = target.new_backup_entry(std::path::Path::new("backups"), "tar.gz");
+        target.register_backup_entry(entry).unwrap();
+        target.set_pinned(1, true).unwrap();
+        repo.update(&target).unwrap();
+
+        let result = DeleteTargetUsecase::new(&mut repo).execute(&target.id);
+        assert!(result.is_err());
+        assert!(repo.load(&target.id).is_some());
+
+        let result = DeleteTargetUsecase::new(&mut repo)
+            .allow_pinned(true)
+            .execute(&target.id);
+        assert!(result.is_ok());
+        assert!(repo.load(&target.id).is_none());
+    }
 }
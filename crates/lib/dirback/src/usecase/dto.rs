@@ -3,8 +3,31 @@
 //!
 
 pub mod backup_entry;
+pub mod backup_summary;
+pub mod file_version;
+pub mod replica_status;
+pub mod scheduled_run;
+pub mod stats;
 pub mod target;
+pub mod trash_item;
 
+pub use crate::domain::model::backup_selector::{BackupSelector, BackupSelectorError};
+pub use crate::domain::model::backup_stats::BackupStats;
+pub use crate::domain::model::file_pattern::{FilePattern, FilePatternError};
+pub use crate::domain::model::hooks::{HookEvent, Hooks};
+pub use crate::domain::model::index_entry::{IndexEntry, IndexEntryKind};
+pub use crate::domain::model::replica_status::ReplicaState;
+pub use crate::domain::model::retention_policy::RetentionPolicy;
+pub use crate::domain::model::schedule::Schedule;
+pub use crate::domain::model::source::{MAIN_SOURCE_NAME, Source};
+pub use crate::domain::model::target_kind::TargetKind;
 pub use crate::domain::model::timestamp::Timestamp;
+pub use crate::domain::model::trash_item::TRASH_EXPIRY_DAYS;
 pub use backup_entry::BackupEntry;
+pub use backup_summary::{BackupSummary, TargetBackupResult};
+pub use file_version::{FileVersion, FindFileReport};
+pub use replica_status::ReplicaStatus;
+pub use scheduled_run::ScheduledRun;
+pub use stats::{BackupUsage, StatsReport, TargetStats};
 pub use target::Target;
+pub use trash_item::TrashItem;
@@ -3,7 +3,7 @@
 //!
 
 use crate::domain::model;
-use crate::usecase::dto::Timestamp;
+use crate::usecase::dto::{BackupStats, ReplicaStatus, Source, TargetKind, Timestamp};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -13,6 +13,13 @@ pub struct BackupEntry {
     pub path: PathBuf,
     pub timestamp: Timestamp,
     pub note: String,
+    pub store: Option<String>,
+    pub checksum: Option<String>,
+    pub replicas: Vec<ReplicaStatus>,
+    pub sources: Vec<Source>,
+    pub kind: TargetKind,
+    pub pinned: bool,
+    pub stats: Option<BackupStats>,
 }
 
 impl std::convert::From<model::BackupEntry> for BackupEntry {
@@ -22,6 +29,17 @@ impl std::convert::From<model::BackupEntry> for BackupEntry {
             path: entry.path,
             timestamp: entry.timestamp,
             note: entry.note,
+            store: entry.store,
+            checksum: entry.checksum,
+            replicas: entry
+                .replicas
+                .into_iter()
+                .map(ReplicaStatus::from)
+                .collect(),
+            sources: entry.sources,
+            kind: entry.kind,
+            pinned: entry.pinned,
+            stats: entry.stats,
         }
     }
 }
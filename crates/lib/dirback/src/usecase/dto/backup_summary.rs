@@ -0,0 +1,39 @@
+//!
+//! # BackupSummary DTO
+//!
+
+use crate::usecase::dto::BackupEntry;
+use serde::{Deserialize, Serialize};
+
+/// The result of a backup of one target.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TargetBackupResult {
+    pub target_id: String,
+    pub target_name: String,
+    /// The created backup, `None` if the backup failed.
+    pub backup: Option<BackupEntry>,
+    pub error: Option<String>,
+    pub duration_ms: u64,
+}
+
+impl TargetBackupResult {
+    pub fn is_ok(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// The results of a backup of several targets.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct BackupSummary {
+    pub results: Vec<TargetBackupResult>,
+}
+
+impl BackupSummary {
+    pub fn succeeded(&self) -> usize {
+        self.results.iter().filter(|r| r.is_ok()).count()
+    }
+
+    pub fn failed(&self) -> usize {
+        self.results.len() - self.succeeded()
+    }
+}
@@ -0,0 +1,45 @@
+//!
+//! # FileVersion DTO
+//!
+//! The versions of the files found in the backups of a target.
+//!
+
+use crate::usecase::dto::Timestamp;
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// A file in a backup.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct FileVersion {
+    pub backup_id: u32,
+    pub timestamp: Timestamp,
+    pub note: String,
+
+    /// The relative path in the backup.
+    pub path: PathBuf,
+    pub size: u64,
+    pub mtime: Option<Timestamp>,
+
+    /// The SHA-256 hash of the content, in hex.
+    pub sha256: String,
+
+    /// Whether the content differs from the previous backup with the same path,
+    /// always true for the oldest one.
+    pub changed: bool,
+}
+
+/// The result of a search for files in the backups of a target.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct FindFileReport {
+    pub target_id: String,
+    pub pattern: String,
+
+    /// The found files, ordered by the path, and then by the backup, the oldest first.
+    pub versions: Vec<FileVersion>,
+
+    /// The number of the searched backups.
+    pub searched: usize,
+
+    /// The IDs of the backups whose archive could not be read, e.g. a lost backup file.
+    pub unreadable: Vec<u32>,
+}
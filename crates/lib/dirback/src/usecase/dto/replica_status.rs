@@ -0,0 +1,29 @@
+//!
+//! # ReplicaStatus DTO
+//!
+
+use crate::domain::model;
+use crate::usecase::dto::{ReplicaState, Timestamp};
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ReplicaStatus {
+    pub replica: PathBuf,
+    pub path: PathBuf,
+    pub state: ReplicaState,
+    pub timestamp: Timestamp,
+    pub message: String,
+}
+
+impl std::convert::From<model::ReplicaStatus> for ReplicaStatus {
+    fn from(status: model::ReplicaStatus) -> Self {
+        Self {
+            replica: status.replica,
+            path: status.path,
+            state: status.state,
+            timestamp: status.timestamp,
+            message: status.message,
+        }
+    }
+}
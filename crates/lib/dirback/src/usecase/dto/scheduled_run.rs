@@ -0,0 +1,18 @@
+//!
+//! # ScheduledRun DTO
+//!
+
+use crate::usecase::dto::BackupEntry;
+use serde::{Deserialize, Serialize};
+
+/// The result of a scheduled backup of a target.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct ScheduledRun {
+    pub target_id: String,
+    pub target_name: String,
+    /// The created backup, `None` if the backup failed.
+    pub backup: Option<BackupEntry>,
+    /// The backups deleted by the retention policy.
+    pub pruned: Vec<BackupEntry>,
+    pub error: Option<String>,
+}
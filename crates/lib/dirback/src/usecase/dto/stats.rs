@@ -0,0 +1,69 @@
+//!
+//! # Stats DTO
+//!
+//! The disk usage of the targets and their backups.
+//!
+
+use crate::usecase::dto::Timestamp;
+use serde::{Deserialize, Serialize};
+
+/// The sizes of a backup.
+///
+/// The sizes are `None` if unknown, e.g. the backups made before the sizes were recorded.
+/// The archive size of such a backup is read from its file if it is in the data directory.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct BackupUsage {
+    pub id: u32,
+    pub timestamp: Timestamp,
+    pub note: String,
+    pub size: Option<u64>,
+    pub uncompressed_size: Option<u64>,
+    pub files: Option<u64>,
+    pub duration_ms: Option<u64>,
+
+    /// The archive size divided by the uncompressed size.
+    pub ratio: Option<f64>,
+
+    /// The change of the uncompressed size from the previous backup with a known size.
+    pub growth: Option<i64>,
+}
+
+/// The sizes of the backups of a target, the oldest backup first.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TargetStats {
+    pub id: String,
+    pub name: String,
+
+    /// The total archive size of the backups with a known size.
+    pub size: u64,
+
+    /// The total uncompressed size of the backups with recorded sizes.
+    pub uncompressed_size: u64,
+
+    /// The total archive size divided by the total uncompressed size,
+    /// of the backups with recorded sizes.
+    pub ratio: Option<f64>,
+
+    /// The number of the backups whose archive size is unknown.
+    pub unknown: usize,
+
+    pub backups: Vec<BackupUsage>,
+}
+
+/// The sizes of the targets.
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct StatsReport {
+    pub targets: Vec<TargetStats>,
+
+    /// The total number of the backups.
+    pub backup_count: usize,
+
+    /// The total archive size of the targets.
+    pub size: u64,
+
+    /// The total uncompressed size of the targets.
+    pub uncompressed_size: u64,
+
+    /// The disk usage of the whole data directory, `None` if not measured.
+    pub datadir_size: Option<u64>,
+}
@@ -3,7 +3,7 @@
 //!
 
 use crate::domain::model;
-use crate::usecase::dto::BackupEntry;
+use crate::usecase::dto::{BackupEntry, Hooks, RetentionPolicy, Schedule, Source, TargetKind};
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
@@ -12,7 +12,16 @@ pub struct Target {
     pub id: String,
     pub name: String,
     pub path: PathBuf,
+    pub kind: TargetKind,
+    pub sources: Vec<Source>,
     pub backups: Vec<BackupEntry>,
+    pub store: Option<String>,
+    pub replicas: Vec<PathBuf>,
+    pub description: String,
+    pub tags: Vec<String>,
+    pub schedule: Option<Schedule>,
+    pub retention: RetentionPolicy,
+    pub hooks: Hooks,
 }
 
 impl std::convert::From<model::Target> for Target {
@@ -21,7 +30,16 @@ impl std::convert::From<model::Target> for Target {
             id: target.id,
             name: target.name,
             path: target.path,
+            kind: target.kind,
+            sources: target.sources,
             backups: target.backups.into_iter().map(BackupEntry::from).collect(),
+            store: target.store,
+            replicas: target.replicas,
+            description: target.description,
+            tags: target.tags,
+            schedule: target.schedule,
+            retention: target.retention,
+            hooks: target.hooks,
         }
     }
 }
@@ -0,0 +1,30 @@
+//!
+//! # TrashItem DTO
+//!
+
+use crate::domain::model;
+use crate::usecase::dto::{BackupEntry, Timestamp};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TrashItem {
+    pub id: u32,
+    pub target_id: String,
+    pub target_name: String,
+    pub backup: Option<BackupEntry>,
+    pub deleted_at: Timestamp,
+    pub expires_at: Timestamp,
+}
+
+impl std::convert::From<model::trash_item::TrashItem> for TrashItem {
+    fn from(item: model::trash_item::TrashItem) -> Self {
+        Self {
+            id: item.id,
+            target_id: item.target_id,
+            target_name: item.target_name,
+            backup: item.backup.map(BackupEntry::from),
+            deleted_at: item.deleted_at,
+            expires_at: item.expires_at,
+        }
+    }
+}
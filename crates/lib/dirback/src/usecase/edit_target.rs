@@ -0,0 +1,421 @@
+//!
+//! # Edit target usecase
+//!
+//! Edits the metadata of a target: the name, the path, the description,
+//! and the notes of the backup entries.
+//!
+//! The new path should be the same directory tree as before (e.g. moved or renamed),
+//! so it is checked against the latest backup. The check compares the top level
+//! entries of the directory, and fails only if none of them are in common.
+//! The path of a file target should be a file, and is not compared.
+//!
+//! The new path is canonicalized, and validated as the path of a new target.
+//!
+
+use crate::domain::model::source::MAIN_SOURCE_NAME;
+use crate::domain::model::target::Target as TargetModel;
+use crate::domain::model::target_kind::TargetKind;
+use crate::domain::repository::targets::TargetRepository;
+use crate::domain::service::backup_service::BackupService;
+use crate::usecase::dto::Target;
+use crate::usecase::target_path::TargetPathValidator;
+use std::collections::BTreeSet;
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum EditTargetError {
+    #[error(
+        "'{0}' does not look like the same directory tree as the backups. Use force to change anyway."
+    )]
+    NotSameTree(PathBuf),
+}
+
+/// Changes to a target. `None` leaves the field as it is.
+#[derive(Debug, Default)]
+pub struct TargetEdit {
+    pub name: Option<String>,
+    pub path: Option<PathBuf>,
+    pub description: Option<String>,
+
+    /// New notes of the backup entries, by the backup ID.
+    pub notes: Vec<(u32, String)>,
+
+    /// Skips the check that the new path is the same directory tree.
+    pub force: bool,
+
+    /// Allows the new path inside another target, or containing another target.
+    pub allow_overlap: bool,
+}
+
+pub struct EditTargetUsecase<'a, R: TargetRepository, B: BackupService> {
+    repo: &'a mut R,
+    backup_service: &'a B,
+}
+
+impl<'a, R: TargetRepository, B: BackupService> EditTargetUsecase<'a, R, B> {
+    pub fn new(repo: &'a mut R, backup_service: &'a B) -> Self {
+        Self {
+            repo,
+            backup_service,
+        }
+    }
+
+    pub fn execute(&mut self, target_id: &str, edit: &TargetEdit) -> anyhow::Result<Target> {
+        let mut target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        if let Some(name) = &edit.name {
+            if name.trim().is_empty() {
+                anyhow::bail!("Name is empty.");
+            }
+            target.name = name.clone();
+        }
+
+        if let Some(path) = &edit.path {
+            let path = TargetPathValidator::new(&*self.repo)
+                .allow_overlap(edit.allow_overlap)
+                .replacing(&target.id)
+                .validate(path)?;
+            if path != target.path {
+                self.check_path(&target, &path, edit.force)?;
+                target.path = path;
+            }
+        }
+
+        if let Some(description) = &edit.description {
+            target.description = description.clone();
+        }
+
+        for (backup_id, note) in edit.notes.iter() {
+            let entry = target
+                .backups
+                .iter_mut()
+                .find(|b| b.id == *backup_id)
+                .ok_or_else(|| anyhow::anyhow!("BackupEntry({}) not found", backup_id))?;
+            entry.note = note.clone();
+        }
+
+        let target = self.repo.update(&target)?;
+        Ok(target.into())
+    }
+
+    fn check_path(&self, target: &TargetModel, path: &Path, force: bool) -> anyhow::Result<()> {
+        if !target.kind.matches(path) {
+            anyhow::bail!(
+                "Target path is invalid: '{}' (not a {})",
+                path.display(),
+                target.kind
+            );
+        }
+
+        if force || target.kind == TargetKind::File {
+            return Ok(());
+        }
+
+        let Some(expected) = self.known_top_level_names(target) else {
+            return Ok(());
+        };
+        let actual = dir_top_level_names(path)?;
+        if !expected.is_empty() && expected.is_disjoint(&actual) {
+            return Err(EditTargetError::NotSameTree(path.to_path_buf()).into());
+        }
+
+        Ok(())
+    }
+
+    /// Returns the top level names of the latest local backup,
+    /// or of the current path if it still exists.
+    ///
+    /// Returns `None` if neither is available.
+    fn known_top_level_names(&self, target: &TargetModel) -> Option<BTreeSet<OsString>> {
+        let latest = target
+            .backups
+            .iter()
+            .rev()
+            .find(|b| b.store.is_none() && b.path.exists());
+        if let Some(entry) = latest {
+            // The target path is archived under `main` with other sources.
+            let prefix = match entry.sources.is_empty() {
+                true => Path::new(""),
+                false => Path::new(MAIN_SOURCE_NAME),
+            };
+            if let Ok(paths) = self.backup_service.list(&entry.path) {
+                return Some(
+                    paths
+                        .iter()
+                        .filter_map(|p| p.strip_prefix(prefix).ok())
+                        .filter_map(|p| p.components().next())
+                        .map(|c| c.as_os_str().to_os_string())
+                        .collect(),
+                );
+            }
+        }
+
+        if target.path.is_dir() {
+            return dir_top_level_names(&target.path).ok();
+        }
+
+        None
+    }
+}
+
+fn dir_top_level_names(dir: &Path) -> anyhow::Result<BTreeSet<OsString>> {
+    let mut names = BTreeSet::new();
+    for entry in std::fs::read_dir(dir)? {
+        names.insert(entry?.file_name());
+    }
+    Ok(names)
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::file_storage::FileStorageTargetRepository;
+    use crate::infra::service::targz_backup_service::TargzBackupService;
+    use crate::usecase::backup::BackupUsecase;
+    use crate::usecase::target_path::TargetPathError;
+    use crate::usecase::usecase_test_helper::prepare_target_with_backups;
+
+    #[test]
+    fn it_edits_name_description_and_note() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (mut repo, target_id) = prepare_target_with_backups(&temp.path(), 1);
+        let service = TargzBackupService::new();
+
+        let edit = TargetEdit {
+            name: Some(String::from("Renamed")),
+            description: Some(String::from("My documents")),
+            notes: vec![(1, String::from("edited"))],
+            ..Default::default()
+        };
+        let result = EditTargetUsecase::new(&mut repo, &service)
+            .execute(&target_id, &edit)
+            .unwrap();
+        assert_eq!(result.name, "Renamed");
+        assert_eq!(result.description, "My documents");
+        assert_eq!(result.backups[0].note, "edited");
+
+        let target = repo.load(&target_id).unwrap();
+        assert_eq!(target.name, "Renamed");
+        assert_eq!(target.backups[0].note, "edited");
+    }
+
+    #[test]
+    fn it_changes_path_to_moved_tree() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (mut repo, target_id) = prepare_target_with_backups(&temp.path(), 1);
+        let service = TargzBackupService::new();
+
+        let moved = temp.path().join("moved");
+        std::fs::rename(temp.path().join("src"), &moved).unwrap();
+
+        let edit = TargetEdit {
+            path: Some(moved.clone()),
+            ..Default::default()
+        };
+        let result = EditTargetUsecase::new(&mut repo, &service)
+            .execute(&target_id, &edit)
+            .unwrap();
+        assert_eq!(result.path, moved);
+    }
+
+    #[test]
+    fn it_checks_path_in_backup_with_sources() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let mut repo = FileStorageTargetRepository::new(&temp.path().join("data"));
+        let service = TargzBackupService::new();
+
+        let src = temp.path().join("src");
+        let config = temp.path().join("config");
+        std::fs::create_dir_all(&config).unwrap();
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("README.md"), "hello").unwrap();
+
+        let mut target = repo.add("Test target", &src).unwrap();
+        target.add_source("config", &config).unwrap();
+        repo.update(&target).unwrap();
+        BackupUsecase::new(&mut repo, &service)
+            .execute(&target.id, "")
+            .unwrap();
+
+        let moved = temp.path().join("moved");
+        std::fs::rename(&src, &moved).unwrap();
+        let other = temp.path().join("other");
+        std::fs::create_dir_all(&other).unwrap();
+        std::fs::write(other.join("config"), "x").unwrap();
+
+        let edit = TargetEdit {
+            path: Some(other),
+            ..Default::default()
+        };
+        let result = EditTargetUsecase::new(&mut repo, &service).execute(&target.id, &edit);
+        assert!(
+            result.is_err(),
+            "the source names are not in the target path."
+        );
+
+        let edit = TargetEdit {
+            path: Some(moved.clone()),
+            ..Default::default()
+        };
+        let result = EditTargetUsecase::new(&mut repo, &service)
+            .execute(&target.id, &edit)
+            .unwrap();
+        assert_eq!(result.path, moved);
+    }
+
+    #[test]
+    fn it_changes_path_of_file_target_to_file_only() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let mut repo = FileStorageTargetRepository::new(&temp.path().join("data"));
+        let service = TargzBackupService::new();
+
+        let db = temp.path().join("app.db");
+        let moved = temp.path().join("moved.db");
+        std::fs::write(&db, "data").unwrap();
+        std::fs::write(&moved, "data").unwrap();
+
+        let mut target = repo.add("Database", &db).unwrap();
+        target.kind = TargetKind::File;
+        repo.update(&target).unwrap();
+
+        let edit = TargetEdit {
+            path: Some(temp.path().to_path_buf()),
+            ..Default::default()
+        };
+        let result = EditTargetUsecase::new(&mut repo, &service).execute(&target.id, &edit);
+        assert!(result.is_err(), "a directory is not a file target.");
+
+        let edit = TargetEdit {
+            path: Some(moved.clone()),
+            ..Default::default()
+        };
+        let result = EditTargetUsecase::new(&mut repo, &service)
+            .execute(&target.id, &edit)
+            .unwrap();
+        assert_eq!(result.path, moved);
+    }
+
+    #[test]
+    fn it_returns_err_if_path_is_another_tree() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (mut repo, target_id) = prepare_target_with_backups(&temp.path(), 1);
+        let service = TargzBackupService::new();
+
+        let other = temp.path().join("other");
+        std::fs::create_dir_all(&other).unwrap();
+        std::fs::write(other.join("unrelated.txt"), "x").unwrap();
+
+        let mut edit = TargetEdit {
+            path: Some(other.clone()),
+            ..Default::default()
+        };
+        let result = EditTargetUsecase::new(&mut repo, &service).execute(&target_id, &edit);
+        let err = result.unwrap_err();
+        assert_eq!(
+            err.downcast_ref::<EditTargetError>(),
+            Some(&EditTargetError::NotSameTree(other.clone()))
+        );
+
+        edit.force = true;
+        let result = EditTargetUsecase::new(&mut repo, &service).execute(&target_id, &edit);
+        assert_eq!(result.unwrap().path, other);
+    }
+
+    #[test]
+    fn it_returns_err_if_path_is_used_by_another_target() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (mut repo, target_id) = prepare_target_with_backups(&temp.path(), 1);
+        let service = TargzBackupService::new();
+
+        let other = temp.path().join("other");
+        std::fs::create_dir_all(&other).unwrap();
+        repo.add("Other", &other).unwrap();
+
+        let edit = TargetEdit {
+            path: Some(other),
+            force: true,
+            ..Default::default()
+        };
+        let result = EditTargetUsecase::new(&mut repo, &service).execute(&target_id, &edit);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_rejects_data_dir_and_overlapping_path_even_if_forced() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (mut repo, target_id) = prepare_target_with_backups(&temp.path(), 1);
+        let service = TargzBackupService::new();
+
+        let other = temp.path().join("other");
+        std::fs::create_dir_all(other.join("inner")).unwrap();
+        repo.add("Other", &other.join("inner")).unwrap();
+
+        let error = |repo: &mut FileStorageTargetRepository, path: PathBuf| {
+            let edit = TargetEdit {
+                path: Some(path),
+                force: true,
+                ..Default::default()
+            };
+            EditTargetUsecase::new(repo, &service)
+                .execute(&target_id, &edit)
+                .unwrap_err()
+                .downcast::<TargetPathError>()
+                .unwrap()
+        };
+
+        assert!(matches!(
+            error(&mut repo, temp.path().join("data")),
+            TargetPathError::DataDir { .. }
+        ));
+        assert!(matches!(
+            error(&mut repo, temp.path().to_path_buf()),
+            TargetPathError::DataDir { .. }
+        ));
+        assert!(matches!(
+            error(&mut repo, other.clone()),
+            TargetPathError::ContainsTarget { .. }
+        ));
+        assert!(matches!(
+            error(&mut repo, other.join("inner/.")),
+            TargetPathError::Duplicate { .. }
+        ));
+
+        let edit = TargetEdit {
+            path: Some(other.clone()),
+            force: true,
+            allow_overlap: true,
+            ..Default::default()
+        };
+        let result = EditTargetUsecase::new(&mut repo, &service)
+            .execute(&target_id, &edit)
+            .unwrap();
+        assert_eq!(result.path, std::fs::canonicalize(&other).unwrap());
+    }
+
+    #[test]
+    fn it_returns_err_if_backup_not_found() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (mut repo, target_id) = prepare_target_with_backups(&temp.path(), 1);
+        let service = TargzBackupService::new();
+
+        let edit = TargetEdit {
+            name: Some(String::from("Renamed")),
+            notes: vec![(9, String::from("edited"))],
+            ..Default::default()
+        };
+        let result = EditTargetUsecase::new(&mut repo, &service).execute(&target_id, &edit);
+        assert!(result.is_err());
+        assert_eq!(
+            repo.load(&target_id).unwrap().name,
+            "Test target",
+            "nothing should be saved."
+        );
+    }
+}
@@ -0,0 +1,62 @@
+//!
+//! # Empty trash usecase
+//!
+//! Removes deleted targets and backups in the trash permanently.
+//!
+
+use crate::domain::model::timestamp::Timestamp;
+use crate::domain::repository::trash::TrashRepository;
+use crate::usecase::dto::TrashItem;
+
+pub struct EmptyTrashUsecase<'a, R: TrashRepository> {
+    repo: &'a mut R,
+}
+
+impl<'a, R: TrashRepository> EmptyTrashUsecase<'a, R> {
+    pub fn new(repo: &'a mut R) -> Self {
+        Self { repo }
+    }
+
+    /// Returns the removed items.
+    ///
+    /// With expired_only, removes only the items past their expiry.
+    pub fn execute(&mut self, expired_only: bool) -> anyhow::Result<Vec<TrashItem>> {
+        let items = if expired_only {
+            self.repo.purge_expired_trash(&Timestamp::now())?
+        } else {
+            self.repo.empty_trash()?
+        };
+
+        Ok(items.into_iter().map(TrashItem::from).collect())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repository::targets::TargetRepository;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use std::path::Path;
+
+    #[test]
+    fn it_empties_trash() {
+        let mut repo = InMemoryTargetRepository::new();
+        for i in 0..2 {
+            let target = repo
+                .add(&format!("Target{i}"), Path::new("target"))
+                .unwrap();
+            repo.delete_target(&target.id).unwrap();
+        }
+
+        let removed = EmptyTrashUsecase::new(&mut repo).execute(true).unwrap();
+        assert!(removed.is_empty());
+        assert_eq!(repo.list_trash().unwrap().len(), 2);
+
+        let removed = EmptyTrashUsecase::new(&mut repo).execute(false).unwrap();
+        assert_eq!(removed.len(), 2);
+        assert!(repo.list_trash().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,142 @@
+//!
+//! # Export target usecase
+//!
+//! Writes a target and its backups into a single bundle file,
+//! which can be imported into another data directory.
+//!
+
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::repository::targets::TargetRepository;
+use crate::domain::service::bundle_service::BundleService;
+use crate::usecase::dto::Target;
+use std::path::{Path, PathBuf};
+
+pub struct ExportTargetUsecase<'a, R: TargetRepository, S: BundleService> {
+    repo: &'a mut R,
+    bundle_service: &'a S,
+}
+
+impl<'a, R: TargetRepository, S: BundleService> ExportTargetUsecase<'a, R, S> {
+    pub fn new(repo: &'a mut R, bundle_service: &'a S) -> Self {
+        Self {
+            repo,
+            bundle_service,
+        }
+    }
+
+    /// Returns the exported target, with the bundled backups only.
+    ///
+    /// - backup_ids ... The backups to be bundled. `None` bundles all backups.
+    pub fn execute(
+        &mut self,
+        target_id: &str,
+        backup_ids: Option<&[u32]>,
+        dest: &Path,
+    ) -> anyhow::Result<Target> {
+        let mut target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        if let Some(ids) = backup_ids {
+            if let Some(id) = ids
+                .iter()
+                .find(|id| target.find_backup_entry(**id).is_none())
+            {
+                anyhow::bail!("BackupEntry({}) not found", id);
+            }
+            target.backups.retain(|b| ids.contains(&b.id));
+        }
+
+        // Archives in the archive stores are fetched into the temporary directory.
+        let temp = mktemp::TempDir::new()?;
+        let mut archives = Vec::new();
+        for entry in target.backups.iter() {
+            archives.push((entry.id, self.local_archive(entry, &temp.path())?));
+        }
+
+        // Location specific settings are not carried to another data directory.
+        target.store = None;
+        target.replicas.clear();
+        for entry in target.backups.iter_mut() {
+            entry.path = PathBuf::from(entry.path.file_name().unwrap_or_default());
+            entry.store = None;
+            entry.replicas.clear();
+        }
+
+        let manifest = self.bundle_service.write(&target, &archives, dest)?;
+        for entry in target.backups.iter_mut() {
+            entry.checksum = manifest.find_archive(entry.id).map(|a| a.checksum.clone());
+        }
+
+        Ok(target.into())
+    }
+
+    fn local_archive(&self, entry: &BackupEntry, temp_dir: &Path) -> anyhow::Result<PathBuf> {
+        match &entry.store {
+            None => {
+                if !entry.path.exists() {
+                    anyhow::bail!("Backup file not found: '{}'", entry.path.display());
+                }
+                Ok(entry.path.clone())
+            }
+            Some(store_id) => {
+                let store = self.repo.archive_store(store_id)?;
+                let path = temp_dir.join(entry.path.file_name().unwrap_or_default());
+                store.get(&entry.path.to_string_lossy(), &path)?;
+                Ok(path)
+            }
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::service::targz_bundle_service::TargzBundleService;
+    use crate::usecase::usecase_test_helper::prepare_target_with_backups;
+
+    #[test]
+    fn it_exports_all_backups() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (mut repo, target_id) = prepare_target_with_backups(&temp.path(), 2);
+        let bundle = temp.path().join("bundle.tar.gz");
+
+        let service = TargzBundleService::new();
+        let mut usecase = ExportTargetUsecase::new(&mut repo, &service);
+        let result = usecase.execute(&target_id, None, &bundle).unwrap();
+
+        assert!(bundle.exists());
+        assert_eq!(result.backups.len(), 2);
+        assert!(result.backups.iter().all(|b| b.checksum.is_some()));
+    }
+
+    #[test]
+    fn it_exports_selected_backups() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (mut repo, target_id) = prepare_target_with_backups(&temp.path(), 3);
+        let bundle = temp.path().join("bundle.tar.gz");
+
+        let service = TargzBundleService::new();
+        let mut usecase = ExportTargetUsecase::new(&mut repo, &service);
+        let result = usecase.execute(&target_id, Some(&[2]), &bundle).unwrap();
+
+        assert_eq!(result.backups.len(), 1);
+        assert_eq!(result.backups[0].id, 2);
+    }
+
+    #[test]
+    fn it_returns_err_if_backup_not_found() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (mut repo, target_id) = prepare_target_with_backups(&temp.path(), 1);
+        let bundle = temp.path().join("bundle.tar.gz");
+
+        let service = TargzBundleService::new();
+        let mut usecase = ExportTargetUsecase::new(&mut repo, &service);
+        assert!(usecase.execute(&target_id, Some(&[9]), &bundle).is_err());
+        assert!(!bundle.exists());
+    }
+}
@@ -0,0 +1,197 @@
+//!
+//! # Find file usecase
+//!
+//! Finds the files matching a path or a glob pattern in every backup of a target,
+//! with their sizes, modification times and hashes to compare the versions.
+//!
+
+use crate::domain::model::archived_file::ArchivedFile;
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::model::file_pattern::FilePattern;
+use crate::domain::repository::targets::TargetRepository;
+use crate::domain::service::backup_service::BackupService;
+use crate::usecase::dto::{FileVersion, FindFileReport};
+use crate::usecase::open_archive::open_archive;
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+pub struct FindFileUsecase<'a, R: TargetRepository, B: BackupService> {
+    repo: &'a R,
+    backup_service: &'a B,
+}
+
+impl<'a, R: TargetRepository, B: BackupService> FindFileUsecase<'a, R, B> {
+    pub fn new(repo: &'a R, backup_service: &'a B) -> Self {
+        Self {
+            repo,
+            backup_service,
+        }
+    }
+
+    /// Searches the backups, the oldest first.
+    ///
+    /// A backup whose archive cannot be read is reported in `unreadable`,
+    /// and does not stop the search.
+    pub fn execute(
+        &self,
+        target_id: &str,
+        pattern: &FilePattern,
+    ) -> anyhow::Result<FindFileReport> {
+        let target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        let mut entries: Vec<&BackupEntry> = target.backups.iter().collect();
+        entries.sort_by_key(|entry| entry.id);
+
+        let mut versions = Vec::new();
+        let mut unreadable = Vec::new();
+        for entry in entries.iter() {
+            match self.find_in(entry, pattern) {
+                Ok(files) => versions.extend(files.into_iter().map(|file| version_of(entry, file))),
+                Err(_) => unreadable.push(entry.id),
+            }
+        }
+
+        // Compare each version with the previous one of the same path.
+        versions.sort_by(|a, b| a.path.cmp(&b.path).then(a.backup_id.cmp(&b.backup_id)));
+        let mut last_hashes: HashMap<PathBuf, String> = HashMap::new();
+        for version in versions.iter_mut() {
+            let last = last_hashes.insert(version.path.clone(), version.sha256.clone());
+            version.changed = last.is_none_or(|hash| hash != version.sha256);
+        }
+
+        Ok(FindFileReport {
+            target_id: target.id.clone(),
+            pattern: pattern.to_string(),
+            versions,
+            searched: entries.len(),
+            unreadable,
+        })
+    }
+
+    fn find_in(
+        &self,
+        entry: &BackupEntry,
+        pattern: &FilePattern,
+    ) -> anyhow::Result<Vec<ArchivedFile>> {
+        let (archive, _temp) = open_archive(self.repo, entry)?;
+        self.backup_service.find(&archive, pattern)
+    }
+}
+
+fn version_of(entry: &BackupEntry, file: ArchivedFile) -> FileVersion {
+    FileVersion {
+        backup_id: entry.id,
+        timestamp: entry.timestamp.clone(),
+        note: entry.note.clone(),
+        path: file.path,
+        size: file.size,
+        mtime: file.mtime,
+        sha256: file.sha256,
+        changed: false,
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use crate::infra::service::targz_backup_service::TargzBackupService;
+    use std::path::Path;
+
+    /// Backs up the directory after writing the files, and returns the backup ID.
+    fn backup(
+        repo: &mut InMemoryTargetRepository,
+        target_id: &str,
+        dir: &Path,
+        files: &[(&str, &str)],
+    ) -> u32 {
+        for (path, content) in files {
+            let path = dir.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, content).unwrap();
+        }
+
+        let mut target = repo.load(target_id).unwrap();
+        let entry = target.new_backup_entry(&dir.with_extension("backups"), "tar.gz");
+        std::fs::create_dir_all(entry.path.parent().unwrap()).unwrap();
+        targz::archive(dir, &entry.path).unwrap();
+        let id = entry.id;
+        target.register_backup_entry(entry).unwrap();
+        repo.update(&target).unwrap();
+        id
+    }
+
+    #[test]
+    fn it_finds_versions_and_changes() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let dir = temp.path().join("app");
+        let mut repo = InMemoryTargetRepository::new();
+        let target_id = repo.add("app", &dir).unwrap().id;
+
+        let files = [("config/prod.yaml", "v1"), ("config/dev.yaml", "dev")];
+        backup(&mut repo, &target_id, &dir, &files);
+        backup(&mut repo, &target_id, &dir, &[("README.md", "readme")]);
+        backup(&mut repo, &target_id, &dir, &[("config/prod.yaml", "v2")]);
+
+        let service = TargzBackupService::new();
+        let usecase = FindFileUsecase::new(&repo, &service);
+
+        let pattern = "config/prod.yaml".parse().unwrap();
+        let report = usecase.execute(&target_id, &pattern).unwrap();
+        assert_eq!(report.searched, 3);
+        assert!(report.unreadable.is_empty());
+        let found: Vec<(u32, bool)> = report
+            .versions
+            .iter()
+            .map(|v| (v.backup_id, v.changed))
+            .collect();
+        assert_eq!(found, [(1, true), (2, false), (3, true)]);
+        assert_eq!(report.versions[0].size, 2);
+        assert_eq!(report.versions[0].sha256, report.versions[1].sha256);
+        assert_ne!(report.versions[1].sha256, report.versions[2].sha256);
+
+        // Ordered by the path first.
+        let pattern = "*.yaml".parse().unwrap();
+        let report = usecase.execute(&target_id, &pattern).unwrap();
+        let found: Vec<(String, u32)> = report
+            .versions
+            .iter()
+            .map(|v| (v.path.to_string_lossy().to_string(), v.backup_id))
+            .collect();
+        assert_eq!(found[0], ("config/dev.yaml".to_string(), 1));
+        assert_eq!(found.len(), 6);
+
+        let pattern = "missing.txt".parse().unwrap();
+        let report = usecase.execute(&target_id, &pattern).unwrap();
+        assert!(report.versions.is_empty());
+
+        assert!(usecase.execute("unknown", &pattern).is_err());
+    }
+
+    #[test]
+    fn it_reports_unreadable_backups() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let dir = temp.path().join("app");
+        let mut repo = InMemoryTargetRepository::new();
+        let target_id = repo.add("app", &dir).unwrap().id;
+
+        backup(&mut repo, &target_id, &dir, &[("a.txt", "a")]);
+        backup(&mut repo, &target_id, &dir, &[("a.txt", "b")]);
+        let target = repo.load(&target_id).unwrap();
+        std::fs::remove_file(&target.backups[0].path).unwrap();
+
+        let service = TargzBackupService::new();
+        let report = FindFileUsecase::new(&repo, &service)
+            .execute(&target_id, &"a.txt".parse().unwrap())
+            .unwrap();
+        assert_eq!(report.unreadable, [1]);
+        assert_eq!(report.versions.len(), 1);
+        assert!(report.versions[0].changed);
+    }
+}
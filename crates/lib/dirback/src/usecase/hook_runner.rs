@@ -0,0 +1,166 @@
+//!
+//! # Hook runner
+//!
+//! Runs the hook commands of a target from the usecases.
+//!
+//! The commands run in the target directory (the directory of a file target)
+//! with these environment variables:
+//!
+//! - `DIRBACK_HOOK` ... The hook name, e.g. `pre-backup`.
+//! - `DIRBACK_TARGET_ID`, `DIRBACK_TARGET_NAME`, `DIRBACK_TARGET_PATH`
+//! - `DIRBACK_BACKUP_ID`, `DIRBACK_BACKUP_PATH`, `DIRBACK_BACKUP_NOTE` ... The backup entry.
+//! - `DIRBACK_ERROR` ... The error message (on-failure only).
+//!
+
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::model::hooks::HookEvent;
+use crate::domain::model::target::Target;
+use crate::domain::service::hook_service::HookService;
+
+#[derive(Clone, Copy, Default)]
+pub(crate) struct HookRunner<'a> {
+    service: Option<&'a dyn HookService>,
+}
+
+impl<'a> HookRunner<'a> {
+    /// `None` runs no hooks.
+    pub fn new(service: Option<&'a dyn HookService>) -> Self {
+        Self { service }
+    }
+
+    /// Runs the hook of the target, does nothing if the hook is not set.
+    pub fn run(
+        &self,
+        event: HookEvent,
+        target: &Target,
+        entry: Option<&BackupEntry>,
+    ) -> anyhow::Result<()> {
+        self.run_with_error(event, target, entry, None)
+    }
+
+    /// Runs the on-failure hook for the error, and returns the error.
+    ///
+    /// A failure of the on-failure hook is added to the error message.
+    pub fn fail(
+        &self,
+        target: &Target,
+        entry: Option<&BackupEntry>,
+        error: anyhow::Error,
+    ) -> anyhow::Error {
+        let message = error.to_string();
+        match self.run_with_error(HookEvent::OnFailure, target, entry, Some(&message)) {
+            Ok(()) => error,
+            Err(e) => anyhow::anyhow!("{message} ({e})"),
+        }
+    }
+
+    fn run_with_error(
+        &self,
+        event: HookEvent,
+        target: &Target,
+        entry: Option<&BackupEntry>,
+        error: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let Some(service) = self.service else {
+            return Ok(());
+        };
+        let Some(command) = target.hooks.command(event) else {
+            return Ok(());
+        };
+
+        let mut env = vec![
+            (String::from("DIRBACK_HOOK"), event.to_string()),
+            (String::from("DIRBACK_TARGET_ID"), target.id.clone()),
+            (String::from("DIRBACK_TARGET_NAME"), target.name.clone()),
+            (
+                String::from("DIRBACK_TARGET_PATH"),
+                target.path.to_string_lossy().to_string(),
+            ),
+        ];
+        if let Some(entry) = entry {
+            env.push((String::from("DIRBACK_BACKUP_ID"), entry.id.to_string()));
+            env.push((
+                String::from("DIRBACK_BACKUP_PATH"),
+                entry.path.to_string_lossy().to_string(),
+            ));
+            env.push((String::from("DIRBACK_BACKUP_NOTE"), entry.note.clone()));
+        }
+        if let Some(error) = error {
+            env.push((String::from("DIRBACK_ERROR"), error.to_string()));
+        }
+
+        service
+            .run(command, &env, target.base_dir(), target.hooks.timeout())
+            .map_err(|e| anyhow::anyhow!("{event} hook failed: {e}"))
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::usecase::usecase_test_helper::TestHookService;
+    use std::path::Path;
+
+    fn prepare_target() -> Target {
+        let mut target = Target::new("xxx", "Test target", Path::new("target"));
+        target
+            .hooks
+            .set_command(HookEvent::PreBackup, Some("stop db"));
+        target
+            .hooks
+            .set_command(HookEvent::OnFailure, Some("fail notify"));
+        target
+    }
+
+    #[test]
+    fn it_passes_target_and_backup_as_env() {
+        let target = prepare_target();
+        let entry = target.new_backup_entry(Path::new("backups"), "tar.gz");
+        let service = TestHookService::new();
+
+        let runner = HookRunner::new(Some(&service));
+        runner
+            .run(HookEvent::PreBackup, &target, Some(&entry))
+            .unwrap();
+
+        let calls = service.calls();
+        assert_eq!(calls.len(), 1);
+        assert_eq!(calls[0].command, "stop db");
+        assert_eq!(calls[0].env("DIRBACK_HOOK"), Some("pre-backup"));
+        assert_eq!(calls[0].env("DIRBACK_TARGET_ID"), Some("xxx"));
+        assert_eq!(calls[0].env("DIRBACK_BACKUP_ID"), Some("1"));
+    }
+
+    #[test]
+    fn it_does_nothing_without_hook_or_service() {
+        let target = prepare_target();
+        let service = TestHookService::new();
+
+        let runner = HookRunner::new(Some(&service));
+        runner.run(HookEvent::PostBackup, &target, None).unwrap();
+        assert!(service.calls().is_empty());
+
+        let runner = HookRunner::new(None);
+        runner.run(HookEvent::PreBackup, &target, None).unwrap();
+    }
+
+    #[test]
+    fn it_adds_on_failure_error_to_message() {
+        let target = prepare_target();
+        let service = TestHookService::new();
+
+        let runner = HookRunner::new(Some(&service));
+        let error = runner.fail(&target, None, anyhow::anyhow!("disk full"));
+
+        let calls = service.calls();
+        assert_eq!(calls[0].env("DIRBACK_ERROR"), Some("disk full"));
+        assert!(
+            error
+                .to_string()
+                .starts_with("disk full (on-failure hook failed")
+        );
+    }
+}
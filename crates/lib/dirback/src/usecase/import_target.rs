@@ -0,0 +1,192 @@
+//!
+//! # Import target usecase
+//!
+//! Recreates a target from a bundle file written by the export usecase.
+//!
+//! The target keeps its ID if it is not used in the data directory,
+//! otherwise a new ID is assigned.
+//! Backup IDs are kept as they are, since they are numbered per target.
+//!
+
+use crate::domain::repository::targets::TargetRepository;
+use crate::domain::service::bundle_service::BundleService;
+use crate::usecase::dto::Target;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, Default)]
+pub struct ImportOptions {
+    /// Replaces the path of the backup target, e.g. on another machine.
+    pub path: Option<PathBuf>,
+}
+
+pub struct ImportTargetUsecase<'a, R: TargetRepository, S: BundleService> {
+    repo: &'a mut R,
+    bundle_service: &'a S,
+}
+
+impl<'a, R: TargetRepository, S: BundleService> ImportTargetUsecase<'a, R, S> {
+    pub fn new(repo: &'a mut R, bundle_service: &'a S) -> Self {
+        Self {
+            repo,
+            bundle_service,
+        }
+    }
+
+    /// Returns the imported target.
+    ///
+    /// Returns error if another target already backs up the same path.
+    pub fn execute(&mut self, src: &Path, options: &ImportOptions) -> anyhow::Result<Target> {
+        let temp = mktemp::TempDir::new()?;
+        let (manifest, archives) = self.bundle_service.read(src, &temp.path())?;
+
+        let mut target = manifest.target.clone();
+        if let Some(path) = &options.path {
+            target.path = path.clone();
+        }
+
+        let targets = self.repo.load_all()?;
+        if let Some(other) = targets.iter().find(|t| t.path == target.path) {
+            anyhow::bail!(
+                "Target({}) already backs up '{}'. Use another path.",
+                other.id,
+                target.path.display()
+            );
+        }
+        if targets.iter().any(|t| t.id == target.id) {
+            target.id = uuid::Uuid::new_v4().to_string();
+        }
+
+        // Place the archives into the backup directory of the new target.
+        let backup_dir = self.repo.make_backup_dir_path(&target);
+        std::fs::create_dir_all(&backup_dir)?;
+        for entry in target.backups.iter_mut() {
+            let (_, archive) =
+                archives
+                    .iter()
+                    .find(|(id, _)| *id == entry.id)
+                    .ok_or_else(|| {
+                        anyhow::anyhow!("Archive of BackupEntry({}) not in the bundle.", entry.id)
+                    })?;
+
+            let dest = backup_dir.join(archive.file_name().unwrap_or_default());
+            if std::fs::rename(archive, &dest).is_err() {
+                std::fs::copy(archive, &dest)?;
+            }
+            entry.path = dest;
+            entry.store = None;
+            entry.replicas.clear();
+            entry.checksum = manifest.find_archive(entry.id).map(|a| a.checksum.clone());
+        }
+        target.store = None;
+        target.replicas.clear();
+
+        let target = self.repo.import(&target)?;
+        Ok(target.into())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::file_storage::FileStorageTargetRepository;
+    use crate::infra::service::targz_bundle_service::TargzBundleService;
+    use crate::usecase::export_target::ExportTargetUsecase;
+    use crate::usecase::usecase_test_helper::prepare_target_with_backups;
+
+    /// Exports a target with 2 backups from `{base_dir}/data` to `{base_dir}/bundle.tar.gz`.
+    ///
+    /// Returns the target ID and the bundle path.
+    fn export_bundle(base_dir: &Path) -> (String, PathBuf) {
+        let (mut repo, target_id) = prepare_target_with_backups(base_dir, 2);
+        let bundle = base_dir.join("bundle.tar.gz");
+        ExportTargetUsecase::new(&mut repo, &TargzBundleService::new())
+            .execute(&target_id, None, &bundle)
+            .unwrap();
+        (target_id, bundle)
+    }
+
+    #[test]
+    fn it_imports_into_another_data_dir() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (target_id, bundle) = export_bundle(&temp.path());
+        let mut repo = FileStorageTargetRepository::new(&temp.path().join("other"));
+        let service = TargzBundleService::new();
+
+        let mut usecase = ImportTargetUsecase::new(&mut repo, &service);
+        let result = usecase.execute(&bundle, &ImportOptions::default()).unwrap();
+
+        assert_eq!(result.id, target_id, "the ID should be kept.");
+        assert_eq!(result.backups.len(), 2);
+        for entry in result.backups.iter() {
+            assert!(entry.path.exists());
+            assert!(entry.path.starts_with(temp.path().join("other")));
+        }
+        assert!(repo.load(&target_id).is_some());
+    }
+
+    #[test]
+    fn it_remaps_id_if_already_used() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (target_id, bundle) = export_bundle(&temp.path());
+        let mut repo = FileStorageTargetRepository::new(&temp.path().join("data"));
+        let service = TargzBundleService::new();
+
+        let options = ImportOptions {
+            path: Some(temp.path().join("restored")),
+        };
+        let mut usecase = ImportTargetUsecase::new(&mut repo, &service);
+        let result = usecase.execute(&bundle, &options).unwrap();
+
+        assert_ne!(result.id, target_id);
+        assert_eq!(result.path, temp.path().join("restored"));
+        assert_eq!(repo.load_all().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn it_imports_bundle_of_format_v1() {
+        // A bundle written before the description, tags and schedule of targets existed.
+        const MANIFEST: &str = include_str!(concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/fixtures/bundle/v1.json"
+        ));
+
+        let temp = mktemp::TempDir::new().unwrap();
+        let stage = temp.path().join("stage");
+        std::fs::create_dir_all(stage.join("archives")).unwrap();
+        std::fs::write(stage.join("manifest.json"), MANIFEST).unwrap();
+        std::fs::write(stage.join("archives/0001_20250123T123456Z.tar.gz"), "test").unwrap();
+        let bundle = temp.path().join("bundle.tar.gz");
+        targz::archive(&stage, &bundle).unwrap();
+
+        let mut repo = FileStorageTargetRepository::new(&temp.path().join("data"));
+        let service = TargzBundleService::new();
+        let result = ImportTargetUsecase::new(&mut repo, &service)
+            .execute(&bundle, &ImportOptions::default())
+            .unwrap();
+
+        assert_eq!(result.name, "Fixture Target");
+        assert_eq!(result.description, "");
+        assert!(result.tags.is_empty());
+        assert_eq!(result.schedule, None);
+        assert_eq!(result.backups.len(), 1);
+        assert_eq!(result.backups[0].note, "first backup");
+        assert!(result.backups[0].path.exists());
+        assert!(repo.load(&result.id).is_some());
+    }
+
+    #[test]
+    fn it_returns_err_if_path_conflicts() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (_, bundle) = export_bundle(&temp.path());
+        let mut repo = FileStorageTargetRepository::new(&temp.path().join("data"));
+        let service = TargzBundleService::new();
+
+        let mut usecase = ImportTargetUsecase::new(&mut repo, &service);
+        let result = usecase.execute(&bundle, &ImportOptions::default());
+        assert!(result.is_err());
+        assert_eq!(repo.load_all().unwrap().len(), 1);
+    }
+}
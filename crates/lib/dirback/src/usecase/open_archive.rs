@@ -0,0 +1,98 @@
+//!
+//! # Open archive
+//!
+//! Finds the backup file to read for a backup entry.
+//!
+
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::repository::targets::TargetRepository;
+use std::path::PathBuf;
+
+/// Returns the path to the backup file, and the temporary directory holding it if fetched.
+///
+/// If the primary backup file is lost, a synced replica is returned.
+/// A backup in an archive store is fetched into a temporary directory,
+/// which should be kept while the backup file is read.
+pub(crate) fn open_archive<R: TargetRepository>(
+    repo: &R,
+    entry: &BackupEntry,
+) -> anyhow::Result<(PathBuf, Option<mktemp::TempDir>)> {
+    match &entry.store {
+        None => {
+            let replica = entry
+                .replicas
+                .iter()
+                .find(|r| r.is_synced() && r.path.exists());
+            let archive = match replica {
+                Some(replica) if !entry.path.exists() => replica.path.clone(),
+                _ => entry.path.clone(),
+            };
+            Ok((archive, None))
+        }
+        Some(store_id) => {
+            let store = repo.archive_store(store_id)?;
+            let temp = mktemp::TempDir::new()?;
+            let archive = temp.path().join(entry.path.file_name().unwrap_or_default());
+            store.get(&entry.path.to_string_lossy(), &archive)?;
+            Ok((archive, Some(temp)))
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::replica_status::ReplicaStatus;
+    use crate::domain::model::timestamp::Timestamp;
+    use crate::infra::repository::file_storage::FileStorageTargetRepository;
+    use crate::usecase::usecase_test_helper::register_local_store;
+    use std::path::Path;
+
+    #[test]
+    fn it_returns_synced_replica_if_primary_is_lost() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let repo = FileStorageTargetRepository::new(&temp.path());
+        let primary = temp.path().join("001.tar.gz");
+        let failed = temp.path().join("failed/001.tar.gz");
+        let synced = temp.path().join("synced/001.tar.gz");
+        std::fs::create_dir_all(synced.parent().unwrap()).unwrap();
+        std::fs::write(&synced, "dummy").unwrap();
+
+        let mut entry = BackupEntry::new(1, &primary, Timestamp::now(), "");
+        entry.replicas = vec![
+            ReplicaStatus::failed(&temp.path().join("failed"), &failed, "error"),
+            ReplicaStatus::synced(&temp.path().join("synced"), &synced),
+        ];
+        let (archive, fetched) = open_archive(&repo, &entry).unwrap();
+        assert_eq!(archive, synced);
+        assert!(fetched.is_none());
+
+        std::fs::write(&primary, "dummy").unwrap();
+        let (archive, _) = open_archive(&repo, &entry).unwrap();
+        assert_eq!(archive, primary);
+    }
+
+    #[test]
+    fn it_fetches_archive_from_store() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let store_root = register_local_store(&temp.path(), "nas");
+        let repo = FileStorageTargetRepository::new(&temp.path());
+        std::fs::create_dir_all(store_root.join("app")).unwrap();
+        std::fs::write(store_root.join("app/001.tar.gz"), "dummy").unwrap();
+
+        let mut entry = BackupEntry::new(1, Path::new("app/001.tar.gz"), Timestamp::now(), "");
+        entry.store = Some(String::from("nas"));
+        let (archive, fetched) = open_archive(&repo, &entry).unwrap();
+        assert!(archive.starts_with(fetched.as_ref().unwrap().path()));
+        assert_eq!(std::fs::read_to_string(&archive).unwrap(), "dummy");
+
+        drop(fetched);
+        assert!(
+            !archive.exists(),
+            "the fetched archive is removed with the directory."
+        );
+    }
+}
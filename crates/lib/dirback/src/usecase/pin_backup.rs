@@ -0,0 +1,77 @@
+//!
+//! # Pin backup usecase
+//!
+//! Pins a backup to protect it from pruning and deletion, or unpins it.
+//!
+
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::BackupEntry;
+
+pub struct PinBackupUsecase<'a, R: TargetRepository> {
+    repo: &'a mut R,
+}
+
+impl<'a, R: TargetRepository> PinBackupUsecase<'a, R> {
+    pub fn new(repo: &'a mut R) -> Self {
+        Self { repo }
+    }
+
+    /// Sets the pinned flag of the backup, and returns the backup.
+    pub fn execute(
+        &mut self,
+        target_id: &str,
+        backup_id: u32,
+        pinned: bool,
+    ) -> anyhow::Result<BackupEntry> {
+        let mut target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        target.set_pinned(backup_id, pinned)?;
+        let target = self.repo.update(&target)?;
+
+        let entry = target
+            .find_backup_entry(backup_id)
+            .ok_or_else(|| anyhow::anyhow!("BackupEntry({}) not found", backup_id))?;
+        Ok(entry.into())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use std::path::Path;
+
+    #[test]
+    fn it_pins_and_unpins_backup() {
+        let mut repo = InMemoryTargetRepository::new();
+        let mut target = repo.add("TestTarget", Path::new("target")).unwrap();
+        let entry = target.new_backup_entry(Path::new("backups"), "tar.gz");
+        target.register_backup_entry(entry).unwrap();
+        repo.update(&target).unwrap();
+
+        let entry = PinBackupUsecase::new(&mut repo)
+            .execute(&target.id, 1, true)
+            .unwrap();
+        assert!(entry.pinned);
+        assert_eq!(repo.load(&target.id).unwrap().pinned_backup_ids(), vec![1]);
+
+        PinBackupUsecase::new(&mut repo)
+            .execute(&target.id, 1, false)
+            .unwrap();
+        assert!(
+            repo.load(&target.id)
+                .unwrap()
+                .pinned_backup_ids()
+                .is_empty()
+        );
+
+        let result = PinBackupUsecase::new(&mut repo).execute(&target.id, 2, true);
+        assert!(result.is_err());
+    }
+}
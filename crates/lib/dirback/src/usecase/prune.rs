@@ -0,0 +1,126 @@
+//!
+//! # Prune usecase
+//!
+//! Deletes the backups which are not kept by the retention policy.
+//! Pinned backups are never pruned.
+//!
+
+use crate::domain::model::retention_policy::RetentionPolicy;
+use crate::domain::model::timestamp::Timestamp;
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::BackupEntry;
+
+pub struct PruneUsecase<'a, R: TargetRepository> {
+    repo: &'a mut R,
+}
+
+impl<'a, R: TargetRepository> PruneUsecase<'a, R> {
+    pub fn new(repo: &'a mut R) -> Self {
+        Self { repo }
+    }
+
+    /// Returns the deleted backups.
+    ///
+    /// With dry_run, returns the backups to be deleted without deleting them.
+    pub fn execute(
+        &mut self,
+        target_id: &str,
+        policy: &RetentionPolicy,
+        dry_run: bool,
+    ) -> anyhow::Result<Vec<BackupEntry>> {
+        let target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        let expired = policy.expired(&target.backups, &Timestamp::now());
+        let mut pruned = Vec::new();
+        for backup_id in expired {
+            let entry = if dry_run {
+                target.find_backup_entry(backup_id)
+            } else {
+                Some(self.repo.delete_backup(target_id, backup_id)?)
+            };
+            pruned.extend(entry.map(BackupEntry::from));
+        }
+
+        Ok(pruned)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use std::path::Path;
+
+    fn prepare(repo: &mut InMemoryTargetRepository) -> String {
+        let mut target = repo.add("TestTarget", Path::new("target")).unwrap();
+        for _ in 0..5 {
+            let entry = target.new_backup_entry(Path::new("backups"), "tar.gz");
+            target.register_backup_entry(entry).unwrap();
+        }
+        repo.update(&target).unwrap();
+        target.id
+    }
+
+    #[test]
+    fn it_deletes_expired_backups() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target_id = prepare(&mut repo);
+
+        let policy = RetentionPolicy {
+            keep_last: Some(2),
+            ..Default::default()
+        };
+        let mut usecase = PruneUsecase::new(&mut repo);
+        let pruned = usecase.execute(&target_id, &policy, false).unwrap();
+
+        let ids: Vec<u32> = pruned.iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+
+        let target = repo.load(&target_id).unwrap();
+        let ids: Vec<u32> = target.backups.iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![4, 5]);
+    }
+
+    #[test]
+    fn it_keeps_pinned_backups() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target_id = prepare(&mut repo);
+        let mut target = repo.load(&target_id).unwrap();
+        target.set_pinned(1, true).unwrap();
+        repo.update(&target).unwrap();
+
+        let policy = RetentionPolicy {
+            keep_last: Some(2),
+            ..Default::default()
+        };
+        let pruned = PruneUsecase::new(&mut repo)
+            .execute(&target_id, &policy, false)
+            .unwrap();
+
+        let ids: Vec<u32> = pruned.iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![2, 3]);
+
+        let target = repo.load(&target_id).unwrap();
+        let ids: Vec<u32> = target.backups.iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![1, 4, 5]);
+    }
+
+    #[test]
+    fn dry_run_deletes_nothing() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target_id = prepare(&mut repo);
+
+        let policy = RetentionPolicy {
+            keep_last: Some(1),
+            ..Default::default()
+        };
+        let mut usecase = PruneUsecase::new(&mut repo);
+        let pruned = usecase.execute(&target_id, &policy, true).unwrap();
+
+        assert_eq!(pruned.len(), 4);
+        assert_eq!(repo.load(&target_id).unwrap().backups.len(), 5);
+    }
+}
@@ -1,22 +1,62 @@
 //!
 //! # Register target usecase
 //!
+//! The target path should be an existing directory or a regular file,
+//! and its kind is recorded on the target.
+//!
+//! The path is canonicalized, and is rejected if it is already registered,
+//! overlaps another target (the path or a source of the target),
+//! or overlaps the data directory, which would back up its own backups.
+//!
 
+use crate::domain::model::target_kind::TargetKind;
 use crate::domain::repository::targets::TargetRepository;
 use crate::usecase::dto::Target;
+use crate::usecase::target_path::TargetPathValidator;
 use std::path::Path;
 
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum RegisterTargetError {
+    #[error("Target name is empty.")]
+    EmptyName,
+}
+
 pub struct RegisterTargetUsecase<'a, R: TargetRepository> {
     repo: &'a mut R,
+    allow_overlap: bool,
 }
 
 impl<'a, R: TargetRepository> RegisterTargetUsecase<'a, R> {
     pub fn new(repo: &'a mut R) -> Self {
-        Self { repo }
+        Self {
+            repo,
+            allow_overlap: false,
+        }
+    }
+
+    /// Allows the path inside another target, or containing another target.
+    pub fn allow_overlap(mut self) -> Self {
+        self.allow_overlap = true;
+        self
     }
 
+    /// Registers the target with the canonical path.
+    ///
+    /// Returns `RegisterTargetError`, `TargetKindError` or `TargetPathError` if the target cannot be registered.
     pub fn execute(&mut self, target_name: &str, target_path: &Path) -> anyhow::Result<Target> {
-        let target = self.repo.add(target_name, target_path)?;
+        if target_name.trim().is_empty() {
+            return Err(RegisterTargetError::EmptyName.into());
+        }
+        let kind = TargetKind::detect(target_path)?;
+        let path = TargetPathValidator::new(&*self.repo)
+            .allow_overlap(self.allow_overlap)
+            .validate(target_path)?;
+
+        let mut target = self.repo.add(target_name, &path)?;
+        if target.kind != kind {
+            target.kind = kind;
+            target = self.repo.update(&target)?;
+        }
         Ok(target.into())
     }
 }
@@ -28,14 +68,16 @@ impl<'a, R: TargetRepository> RegisterTargetUsecase<'a, R> {
 mod tests {
     use super::*;
     use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use crate::usecase::target_path::TargetPathError;
 
     #[test]
     fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
         let mut repo = InMemoryTargetRepository::new();
         let mut usecase = RegisterTargetUsecase::new(&mut repo);
 
         let target_name = "Test Target";
-        let target_path = Path::new("path-to-target");
+        let target_path = &temp.path();
 
         let targets = usecase.repo.load_all().unwrap();
         assert_eq!(targets.len(), 0);
@@ -45,9 +87,170 @@ mod tests {
 
         let target = result.unwrap();
         assert_eq!(target.name, "Test Target");
-        assert_eq!(target.path, Path::new("path-to-target"));
+        assert_eq!(target.path, temp.path());
+        assert_eq!(target.kind, TargetKind::Directory);
 
         let targets = usecase.repo.load_all().unwrap();
         assert_eq!(targets.len(), 1);
     }
+
+    #[test]
+    fn it_records_file_target() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let file = temp.path().join("vault.kdbx");
+        std::fs::write(&file, "secret").unwrap();
+
+        let mut repo = InMemoryTargetRepository::new();
+        let target = RegisterTargetUsecase::new(&mut repo)
+            .execute("Vault", &file)
+            .unwrap();
+        assert_eq!(target.kind, TargetKind::File);
+        assert_eq!(repo.load(&target.id).unwrap().kind, TargetKind::File);
+    }
+
+    #[test]
+    fn it_canonicalizes_path() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let dir = temp.path().join("app");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let mut repo = InMemoryTargetRepository::new();
+        let target = RegisterTargetUsecase::new(&mut repo)
+            .execute("app", &dir.join("../app/."))
+            .unwrap();
+        assert_eq!(target.path, std::fs::canonicalize(&dir).unwrap());
+    }
+
+    #[test]
+    fn it_rejects_empty_name() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let mut repo = InMemoryTargetRepository::new();
+        let err = RegisterTargetUsecase::new(&mut repo)
+            .execute(" ", &temp.path())
+            .unwrap_err();
+        assert_eq!(
+            err.downcast::<RegisterTargetError>().unwrap(),
+            RegisterTargetError::EmptyName
+        );
+    }
+
+    #[test]
+    fn it_rejects_duplicate_and_overlapping_targets() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let root = std::fs::canonicalize(temp.path()).unwrap();
+        let app = root.join("app");
+        let config = root.join("config");
+        std::fs::create_dir_all(app.join("src")).unwrap();
+        std::fs::create_dir_all(&config).unwrap();
+
+        let mut repo = InMemoryTargetRepository::new();
+        let target = RegisterTargetUsecase::new(&mut repo)
+            .execute("app", &app)
+            .unwrap();
+        let mut stored = repo.load(&target.id).unwrap();
+        stored.add_source("config", &config).unwrap();
+        repo.update(&stored).unwrap();
+
+        let error = |repo: &mut InMemoryTargetRepository, path: &Path| {
+            RegisterTargetUsecase::new(repo)
+                .execute("other", path)
+                .unwrap_err()
+                .downcast::<TargetPathError>()
+                .unwrap()
+        };
+
+        assert!(matches!(
+            error(&mut repo, &app.join(".")),
+            TargetPathError::Duplicate { id, .. } if id == target.id
+        ));
+        assert!(matches!(
+            error(&mut repo, &config),
+            TargetPathError::Duplicate { .. }
+        ));
+        assert!(matches!(
+            error(&mut repo, &app.join("src")),
+            TargetPathError::InsideTarget { target_path, .. } if target_path == app
+        ));
+        assert!(matches!(
+            error(&mut repo, &root),
+            TargetPathError::ContainsTarget { .. }
+        ));
+        assert_eq!(repo.load_all().unwrap().len(), 1);
+
+        // Overlapping targets can be allowed, but not the same path.
+        let result = RegisterTargetUsecase::new(&mut repo)
+            .allow_overlap()
+            .execute("src", &app.join("src"));
+        assert!(result.is_ok());
+        let result = RegisterTargetUsecase::new(&mut repo)
+            .allow_overlap()
+            .execute("app", &app);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_rejects_data_dir_and_its_ancestors() {
+        use crate::infra::repository::file_storage::FileStorageTargetRepository;
+
+        let temp = mktemp::TempDir::new().unwrap();
+        let data_dir = temp.path().join("home/.local/share/dirback");
+        let mut repo = FileStorageTargetRepository::new(&data_dir);
+        std::fs::create_dir_all(temp.path().join("home/docs")).unwrap();
+
+        // The data directory is not made yet.
+        for path in [temp.path().join("home"), temp.path()] {
+            let err = RegisterTargetUsecase::new(&mut repo)
+                .allow_overlap()
+                .execute("home", &path)
+                .unwrap_err();
+            assert!(matches!(
+                err.downcast::<TargetPathError>().unwrap(),
+                TargetPathError::DataDir { .. }
+            ));
+        }
+
+        let result =
+            RegisterTargetUsecase::new(&mut repo).execute("docs", &temp.path().join("home/docs"));
+        assert!(result.is_ok());
+        let result = RegisterTargetUsecase::new(&mut repo).execute("data", &data_dir);
+        assert!(result.is_err());
+        let result =
+            RegisterTargetUsecase::new(&mut repo).execute("targets", &data_dir.join("targets"));
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn it_returns_err_if_targets_cannot_be_loaded() {
+        use crate::infra::repository::file_storage::FileStorageTargetRepository;
+
+        let temp = mktemp::TempDir::new().unwrap();
+        let mut repo = FileStorageTargetRepository::new(&temp.path().join("data"));
+        let docs = temp.path().join("docs");
+        std::fs::create_dir_all(docs.join("notes")).unwrap();
+
+        let target = RegisterTargetUsecase::new(&mut repo)
+            .execute("docs", &docs)
+            .unwrap();
+        let info_path = temp
+            .path()
+            .join("data/targets")
+            .join(&target.id)
+            .join("info.json");
+        std::fs::write(&info_path, "{").unwrap();
+
+        // The overlap with the unreadable target cannot be checked.
+        let err = RegisterTargetUsecase::new(&mut repo)
+            .execute("notes", &docs.join("notes"))
+            .unwrap_err();
+        assert!(err.downcast_ref::<TargetPathError>().is_none());
+        assert!(repo.load_all().is_err());
+    }
+
+    #[test]
+    fn it_returns_err_if_path_does_not_exist() {
+        let mut repo = InMemoryTargetRepository::new();
+        let result = RegisterTargetUsecase::new(&mut repo).execute("Test", Path::new("missing"));
+        assert!(result.is_err());
+        assert!(repo.load_all().unwrap().is_empty());
+    }
 }
@@ -0,0 +1,80 @@
+//!
+//! # Remove replica usecase
+//!
+//! Removes a replica location from the target.
+//! The archives already copied to the replica location are left as they are.
+//!
+
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::Target;
+use std::path::Path;
+
+pub struct RemoveReplicaUsecase<'a, R: TargetRepository> {
+    repo: &'a mut R,
+}
+
+impl<'a, R: TargetRepository> RemoveReplicaUsecase<'a, R> {
+    pub fn new(repo: &'a mut R) -> Self {
+        Self { repo }
+    }
+
+    pub fn execute(&mut self, target_id: &str, replica: &Path) -> anyhow::Result<Target> {
+        let mut target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        let pos = target
+            .replicas
+            .iter()
+            .position(|r| r == replica)
+            .ok_or_else(|| {
+                anyhow::anyhow!("Replica location not found: '{}'", replica.display())
+            })?;
+        target.replicas.remove(pos);
+
+        for entry in target.backups.iter_mut() {
+            entry.replicas.retain(|r| r.replica != replica);
+        }
+
+        let target = self.repo.update(&target)?;
+        Ok(target.into())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::ReplicaStatus;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+
+    #[test]
+    fn it_works() {
+        let mut repo = InMemoryTargetRepository::new();
+        let mut target = repo.add("Test target", Path::new("target")).unwrap();
+        let nas = Path::new("/mnt/nas");
+        target.replicas.push(nas.to_path_buf());
+
+        let mut entry = target.new_backup_entry(Path::new("backups"), "tar.gz");
+        entry.set_replica_status(ReplicaStatus::synced(nas, Path::new("/mnt/nas/1.tar.gz")));
+        let _ = target.register_backup_entry(entry);
+        repo.update(&target).unwrap();
+
+        let mut usecase = RemoveReplicaUsecase::new(&mut repo);
+        let result = usecase.execute(&target.id, nas).unwrap();
+        assert!(result.replicas.is_empty());
+        assert!(result.backups[0].replicas.is_empty());
+    }
+
+    #[test]
+    fn it_returns_err_if_not_added() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target = repo.add("Test target", Path::new("target")).unwrap();
+
+        let mut usecase = RemoveReplicaUsecase::new(&mut repo);
+        assert!(usecase.execute(&target.id, Path::new("/mnt/nas")).is_err());
+    }
+}
@@ -0,0 +1,56 @@
+//!
+//! # Remove source usecase
+//!
+//! Removes a source directory from the target.
+//! Existing backups keep the source, and can still restore it.
+//!
+
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::Target;
+
+pub struct RemoveSourceUsecase<'a, R: TargetRepository> {
+    repo: &'a mut R,
+}
+
+impl<'a, R: TargetRepository> RemoveSourceUsecase<'a, R> {
+    pub fn new(repo: &'a mut R) -> Self {
+        Self { repo }
+    }
+
+    pub fn execute(&mut self, target_id: &str, name: &str) -> anyhow::Result<Target> {
+        let mut target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        if !target.remove_source(name) {
+            anyhow::bail!("Source not found: '{name}'");
+        }
+
+        let target = self.repo.update(&target)?;
+        Ok(target.into())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use std::path::Path;
+
+    #[test]
+    fn it_works() {
+        let mut repo = InMemoryTargetRepository::new();
+        let mut target = repo.add("Test target", Path::new("target")).unwrap();
+        target.add_source("config", Path::new("config")).unwrap();
+        repo.update(&target).unwrap();
+
+        let mut usecase = RemoveSourceUsecase::new(&mut repo);
+        let result = usecase.execute(&target.id, "config").unwrap();
+        assert!(result.sources.is_empty());
+        assert!(usecase.execute(&target.id, "config").is_err());
+    }
+}
@@ -0,0 +1,299 @@
+//!
+//! # Replicate usecase
+//!
+//! Copies the archives which are missing in the replica locations,
+//! verifies them with the checksum, and writes the target information
+//! into each replica location.
+//!
+//! The result of each replica is recorded in the backup entries.
+//!
+
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::model::replica_status::ReplicaStatus;
+use crate::domain::repository::targets::TargetRepository;
+use crate::domain::service::replica_service::ReplicaService;
+use crate::usecase::dto::Target;
+use std::path::{Path, PathBuf};
+
+pub struct ReplicateUsecase<'a, R: TargetRepository, S: ReplicaService> {
+    repo: &'a mut R,
+    replica_service: &'a S,
+}
+
+impl<'a, R: TargetRepository, S: ReplicaService> ReplicateUsecase<'a, R, S> {
+    pub fn new(repo: &'a mut R, replica_service: &'a S) -> Self {
+        Self {
+            repo,
+            replica_service,
+        }
+    }
+
+    /// Returns the target with the updated replica statuses.
+    ///
+    /// Failures of each archive are recorded as the replica status,
+    /// only a failure to save the results is returned as an error.
+    pub fn execute(&mut self, target_id: &str) -> anyhow::Result<Target> {
+        let mut target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        if target.replicas.is_empty() {
+            anyhow::bail!("Target({}) has no replica locations.", target_id);
+        }
+
+        let replicas = target.replicas.clone();
+        for entry in target.backups.iter_mut() {
+            self.replicate_entry(target_id, entry, &replicas);
+        }
+        self.repo.update(&target)?;
+
+        // Metadata of the replica, pointing to the archives in the replica.
+        let mut errors = Vec::new();
+        for replica in replicas.iter() {
+            let mut copy = target.clone();
+            copy.store = None;
+            copy.replicas.clear();
+            copy.backups = target
+                .backups
+                .iter()
+                .filter_map(|entry| {
+                    let status = entry.replica_status(replica).filter(|s| s.is_synced())?;
+                    let mut entry = entry.clone();
+                    entry.path = status.path.clone();
+                    entry.store = None;
+                    entry.replicas.clear();
+                    Some(entry)
+                })
+                .collect();
+
+            if let Err(e) = self.replica_service.write_target(replica, &copy) {
+                errors.push(format!("{}: {e}", replica.display()));
+            }
+        }
+
+        if !errors.is_empty() {
+            anyhow::bail!(
+                "Failed to write the target information: {}",
+                errors.join(", ")
+            );
+        }
+
+        Ok(target.into())
+    }
+
+    fn replicate_entry(&self, target_id: &str, entry: &mut BackupEntry, replicas: &[PathBuf]) {
+        let pending: Vec<&PathBuf> = replicas
+            .iter()
+            .filter(|r| {
+                !entry
+                    .replica_status(r)
+                    .is_some_and(|s| s.is_synced() && s.path.exists())
+            })
+            .collect();
+        if pending.is_empty() {
+            return;
+        }
+
+        let result = mktemp::TempDir::new()
+            .map_err(anyhow::Error::from)
+            .and_then(|temp| {
+                let src = self.source_archive(entry, &temp.path())?;
+                let checksum = self.verified_checksum(entry, &src)?;
+                Ok((temp, src, checksum))
+            });
+
+        let (_temp, src, checksum) = match result {
+            Ok(v) => v,
+            Err(e) => {
+                for replica in pending {
+                    let status = ReplicaStatus::failed(replica, Path::new(""), &e.to_string());
+                    entry.set_replica_status(status);
+                }
+                return;
+            }
+        };
+
+        for replica in pending {
+            let status = match self.copy_verified(replica, target_id, &src, &checksum) {
+                Ok(path) => ReplicaStatus::synced(replica, &path),
+                Err(e) => ReplicaStatus::failed(replica, Path::new(""), &e.to_string()),
+            };
+            entry.set_replica_status(status);
+        }
+    }
+
+    /// Returns the path to the archive, fetching it from the archive store if needed.
+    fn source_archive(&self, entry: &BackupEntry, temp_dir: &Path) -> anyhow::Result<PathBuf> {
+        match &entry.store {
+            None => {
+                if !entry.path.exists() {
+                    anyhow::bail!("Backup file not found: '{}'", entry.path.display());
+                }
+                Ok(entry.path.clone())
+            }
+            Some(store_id) => {
+                let store = self.repo.archive_store(store_id)?;
+                let path = temp_dir.join(entry.path.file_name().unwrap_or_default());
+                store.get(&entry.path.to_string_lossy(), &path)?;
+                Ok(path)
+            }
+        }
+    }
+
+    /// Returns the checksum of the archive.
+    ///
+    /// The checksum is recorded on the first replication,
+    /// after that the archive is verified with it.
+    fn verified_checksum(&self, entry: &mut BackupEntry, src: &Path) -> anyhow::Result<String> {
+        let checksum = self.replica_service.checksum(src)?;
+        match &entry.checksum {
+            Some(expected) if *expected != checksum => {
+                anyhow::bail!("Checksum mismatch, the backup file may be corrupted.")
+            }
+            Some(_) => Ok(checksum),
+            None => {
+                entry.checksum = Some(checksum.clone());
+                Ok(checksum)
+            }
+        }
+    }
+
+    fn copy_verified(
+        &self,
+        replica: &Path,
+        target_id: &str,
+        src: &Path,
+        checksum: &str,
+    ) -> anyhow::Result<PathBuf> {
+        let dest = self.replica_service.copy_archive(replica, target_id, src)?;
+        if self.replica_service.checksum(&dest)? != checksum {
+            anyhow::bail!("Checksum mismatch after copy.");
+        }
+        Ok(dest)
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::file_storage::FileStorageTargetRepository;
+    use crate::infra::service::file_replica_service::FileReplicaService;
+    use crate::usecase::usecase_test_helper::prepare_target_with_backups;
+
+    /// Adds the `{base_dir}/usb` and `{base_dir}/nas` replicas to the target.
+    fn add_replicas(
+        repo: &mut FileStorageTargetRepository,
+        target_id: &str,
+        base_dir: &Path,
+    ) -> Vec<PathBuf> {
+        let mut target = repo.load(target_id).unwrap();
+        target.replicas = vec![base_dir.join("usb"), base_dir.join("nas")];
+        repo.update(&target).unwrap();
+        target.replicas
+    }
+
+    #[test]
+    fn it_copies_archives_to_every_replica() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (mut repo, target_id) = prepare_target_with_backups(&temp.path(), 2);
+        add_replicas(&mut repo, &target_id, &temp.path());
+        let service = FileReplicaService::new();
+
+        let mut usecase = ReplicateUsecase::new(&mut repo, &service);
+        let result = usecase.execute(&target_id).unwrap();
+
+        for entry in result.backups.iter() {
+            assert!(entry.checksum.is_some());
+            assert_eq!(entry.replicas.len(), 2);
+            for status in entry.replicas.iter() {
+                assert_eq!(status.state, crate::usecase::dto::ReplicaState::Synced);
+                assert!(status.path.exists());
+            }
+        }
+
+        let target = repo.load(&target_id).unwrap();
+        assert_eq!(
+            target.backups[0].replicas.len(),
+            2,
+            "statuses should be saved."
+        );
+    }
+
+    #[test]
+    fn it_writes_target_information_to_replicas() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (mut repo, target_id) = prepare_target_with_backups(&temp.path(), 1);
+        let replicas = add_replicas(&mut repo, &target_id, &temp.path());
+        let service = FileReplicaService::new();
+        ReplicateUsecase::new(&mut repo, &service)
+            .execute(&target_id)
+            .unwrap();
+
+        for replica in replicas.iter() {
+            let replica_repo = FileStorageTargetRepository::new(replica);
+            let copy = replica_repo.load(&target_id).unwrap();
+            assert_eq!(copy.backups.len(), 1);
+            assert!(copy.backups[0].path.starts_with(replica));
+            assert!(copy.replicas.is_empty());
+        }
+    }
+
+    #[test]
+    fn it_copies_only_missing_archives() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (mut repo, target_id) = prepare_target_with_backups(&temp.path(), 1);
+        add_replicas(&mut repo, &target_id, &temp.path());
+        let service = FileReplicaService::new();
+        let first = ReplicateUsecase::new(&mut repo, &service)
+            .execute(&target_id)
+            .unwrap();
+
+        // Remove the copy in a replica.
+        let lost = first.backups[0].replicas[0].path.clone();
+        std::fs::remove_file(&lost).unwrap();
+
+        let second = ReplicateUsecase::new(&mut repo, &service)
+            .execute(&target_id)
+            .unwrap();
+        assert!(lost.exists(), "the missing archive should be copied again.");
+        assert_eq!(
+            first.backups[0].replicas[1].timestamp, second.backups[0].replicas[1].timestamp,
+            "the synced archive should not be copied again."
+        );
+    }
+
+    #[test]
+    fn it_records_failure_if_archive_is_corrupted() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (mut repo, target_id) = prepare_target_with_backups(&temp.path(), 1);
+        add_replicas(&mut repo, &target_id, &temp.path());
+        let service = FileReplicaService::new();
+
+        // Record the checksum, then corrupt the archive.
+        let mut target = repo.load(&target_id).unwrap();
+        target.backups[0].checksum = Some(String::from("xxx"));
+        repo.update(&target).unwrap();
+
+        let result = ReplicateUsecase::new(&mut repo, &service)
+            .execute(&target_id)
+            .unwrap();
+        for status in result.backups[0].replicas.iter() {
+            assert_eq!(status.state, crate::usecase::dto::ReplicaState::Failed);
+            assert!(status.message.contains("Checksum"));
+        }
+    }
+
+    #[test]
+    fn it_returns_err_without_replicas() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let (mut repo, target_id) = prepare_target_with_backups(&temp.path(), 0);
+
+        let service = FileReplicaService::new();
+        let mut usecase = ReplicateUsecase::new(&mut repo, &service);
+        assert!(usecase.execute(&target_id).is_err());
+    }
+}
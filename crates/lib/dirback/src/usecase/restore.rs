@@ -2,12 +2,23 @@
 //! # Restore usecase
 //!
 
+use crate::domain::model::backup_entry::BackupEntry;
+use crate::domain::model::hooks::HookEvent;
+use crate::domain::model::source::MAIN_SOURCE_NAME;
+use crate::domain::model::target::Target;
+use crate::domain::model::target_kind::TargetKind;
 use crate::domain::repository::targets::TargetRepository;
 use crate::domain::service::backup_service::BackupService;
+use crate::domain::service::hook_service::HookService;
+use crate::usecase::hook_runner::HookRunner;
+use crate::usecase::open_archive::open_archive;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
 
 pub struct RestoreUsecase<'a, R: TargetRepository, B: BackupService> {
     repo: &'a mut R,
     backup_service: &'a B,
+    hooks: HookRunner<'a>,
 }
 
 impl<'a, R: TargetRepository, B: BackupService> RestoreUsecase<'a, R, B> {
@@ -15,10 +26,32 @@ impl<'a, R: TargetRepository, B: BackupService> RestoreUsecase<'a, R, B> {
         Self {
             repo,
             backup_service,
+            hooks: HookRunner::default(),
         }
     }
 
+    /// Runs the hooks of the target around the restore.
+    pub fn with_hooks(mut self, hook_service: &'a dyn HookService) -> Self {
+        self.hooks = HookRunner::new(Some(hook_service));
+        self
+    }
+
+    /// Restores the backup to the location of each source.
+    ///
+    /// A failure of the pre-restore hook aborts the restore.
     pub fn execute(&mut self, target_id: &str, backup_id: u32) -> anyhow::Result<()> {
+        self.execute_remapped(target_id, backup_id, &BTreeMap::new())
+    }
+
+    /// Restores the backup, the sources in `locations` to the given directories.
+    ///
+    /// The sources are named by `Source::name`, `main` for the target path.
+    pub fn execute_remapped(
+        &mut self,
+        target_id: &str,
+        backup_id: u32,
+        locations: &BTreeMap<String, PathBuf>,
+    ) -> anyhow::Result<()> {
         let target = self
             .repo
             .load(target_id)
@@ -28,7 +61,67 @@ impl<'a, R: TargetRepository, B: BackupService> RestoreUsecase<'a, R, B> {
             .find_backup_entry(backup_id)
             .ok_or_else(|| anyhow::anyhow!("BackupEntry({}) not found", backup_id))?;
 
-        self.backup_service.restore(&entry.path, &target.path)
+        let names: Vec<&str> = match entry.sources.is_empty() {
+            true => vec![MAIN_SOURCE_NAME],
+            false => entry.sources.iter().map(|s| s.name.as_str()).collect(),
+        };
+        if let Some(name) = locations.keys().find(|n| !names.contains(&n.as_str())) {
+            anyhow::bail!("Source '{name}' is not in the backup {backup_id}.");
+        }
+
+        let hooks = self.hooks;
+        let fail = |e| hooks.fail(&target, Some(&entry), e);
+
+        hooks
+            .run(HookEvent::PreRestore, &target, Some(&entry))
+            .map_err(fail)?;
+        self.restore(&target, &entry, locations).map_err(fail)?;
+        hooks
+            .run(HookEvent::PostRestore, &target, Some(&entry))
+            .map_err(fail)
+    }
+
+    fn restore(
+        &self,
+        target: &Target,
+        entry: &BackupEntry,
+        locations: &BTreeMap<String, PathBuf>,
+    ) -> anyhow::Result<()> {
+        let location = |name: &str, recorded: &Path| -> PathBuf {
+            locations
+                .get(name)
+                .map(PathBuf::as_path)
+                .or_else(|| target.source_path(name))
+                .unwrap_or(recorded)
+                .to_path_buf()
+        };
+
+        self.with_archive(entry, |archive| {
+            if entry.kind == TargetKind::File {
+                let dest = location(MAIN_SOURCE_NAME, &target.path);
+                return self.backup_service.restore_file(archive, &dest);
+            }
+            if entry.sources.is_empty() {
+                let dest = location(MAIN_SOURCE_NAME, &target.path);
+                return self.backup_service.restore(archive, &dest);
+            }
+
+            for source in entry.sources.iter() {
+                let dest = location(&source.name, &source.path);
+                self.backup_service
+                    .restore_source(archive, &source.name, &dest)?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Calls `f` with the path to the backup file.
+    fn with_archive<F>(&self, entry: &BackupEntry, f: F) -> anyhow::Result<()>
+    where
+        F: FnOnce(&Path) -> anyhow::Result<()>,
+    {
+        let (archive, _temp) = open_archive(&*self.repo, entry)?;
+        f(&archive)
     }
 }
 
@@ -72,6 +165,265 @@ mod tests {
         );
     }
 
+    #[test]
+    fn it_fetches_backup_from_store() {
+        use crate::infra::repository::file_storage::FileStorageTargetRepository;
+        use crate::infra::service::targz_backup_service::TargzBackupService;
+        use crate::usecase::backup::BackupUsecase;
+
+        let temp = mktemp::TempDir::new().unwrap();
+        register_local_store(&temp.path(), "nas");
+        let mut repo = FileStorageTargetRepository::new(&temp.path());
+        let backup_service = TargzBackupService::new();
+
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("file.txt"), "v1").unwrap();
+
+        let mut target = repo.add("Test target", &src).unwrap();
+        target.store = Some("nas".to_string());
+        repo.update(&target).unwrap();
+
+        BackupUsecase::new(&mut repo, &backup_service)
+            .execute(&target.id, "")
+            .unwrap();
+        std::fs::write(src.join("file.txt"), "v2").unwrap();
+
+        let mut restore = RestoreUsecase::new(&mut repo, &backup_service);
+        restore.execute(&target.id, 1).unwrap();
+        assert_eq!(std::fs::read_to_string(src.join("file.txt")).unwrap(), "v1");
+    }
+
+    #[test]
+    fn it_restores_from_replica_if_primary_is_missing() {
+        use crate::infra::repository::file_storage::FileStorageTargetRepository;
+        use crate::infra::service::file_replica_service::FileReplicaService;
+        use crate::infra::service::targz_backup_service::TargzBackupService;
+        use crate::usecase::backup::BackupUsecase;
+        use crate::usecase::replicate::ReplicateUsecase;
+
+        let temp = mktemp::TempDir::new().unwrap();
+        let mut repo = FileStorageTargetRepository::new(&temp.path().join("data"));
+        let backup_service = TargzBackupService::new();
+        let replica_service = FileReplicaService::new();
+
+        let src = temp.path().join("src");
+        std::fs::create_dir_all(&src).unwrap();
+        std::fs::write(src.join("file.txt"), "v1").unwrap();
+
+        let mut target = repo.add("Test target", &src).unwrap();
+        target.replicas = vec![temp.path().join("nas")];
+        repo.update(&target).unwrap();
+
+        BackupUsecase::new(&mut repo, &backup_service)
+            .execute(&target.id, "")
+            .unwrap();
+        ReplicateUsecase::new(&mut repo, &replica_service)
+            .execute(&target.id)
+            .unwrap();
+
+        // Lose the primary backup file.
+        let entry = repo.load(&target.id).unwrap().backups[0].clone();
+        std::fs::remove_file(&entry.path).unwrap();
+        std::fs::write(src.join("file.txt"), "v2").unwrap();
+
+        let mut restore = RestoreUsecase::new(&mut repo, &backup_service);
+        restore.execute(&target.id, entry.id).unwrap();
+        assert_eq!(std::fs::read_to_string(src.join("file.txt")).unwrap(), "v1");
+    }
+
+    mod sources {
+        use super::*;
+        use crate::infra::repository::file_storage::FileStorageTargetRepository;
+        use crate::infra::service::targz_backup_service::TargzBackupService;
+        use crate::usecase::backup::BackupUsecase;
+
+        /// A target of `{base_dir}/app` with the `config` and `data` sources, and a backup.
+        fn prepare(base_dir: &Path) -> (FileStorageTargetRepository, String) {
+            let mut repo = FileStorageTargetRepository::new(&base_dir.join("data-dir"));
+
+            for name in ["app", "config", "data"] {
+                let dir = base_dir.join(name);
+                std::fs::create_dir_all(&dir).unwrap();
+                std::fs::write(dir.join(format!("{name}.txt")), "v1").unwrap();
+            }
+
+            let mut target = repo.add("Test target", &base_dir.join("app")).unwrap();
+            for name in ["config", "data"] {
+                target.add_source(name, &base_dir.join(name)).unwrap();
+            }
+            repo.update(&target).unwrap();
+
+            let backup_service = TargzBackupService::new();
+            let entry = BackupUsecase::new(&mut repo, &backup_service)
+                .execute(&target.id, "")
+                .unwrap();
+            assert_eq!(entry.sources.len(), 3);
+
+            for name in ["app", "config", "data"] {
+                let file = base_dir.join(name).join(format!("{name}.txt"));
+                std::fs::write(file, "v2").unwrap();
+            }
+
+            (repo, target.id)
+        }
+
+        fn read(dir: &Path, name: &str) -> String {
+            std::fs::read_to_string(dir.join(format!("{name}.txt"))).unwrap()
+        }
+
+        #[test]
+        fn it_restores_each_source_to_its_location() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let (mut repo, target_id) = prepare(&temp.path());
+            let backup_service = TargzBackupService::new();
+
+            RestoreUsecase::new(&mut repo, &backup_service)
+                .execute(&target_id, 1)
+                .unwrap();
+
+            for name in ["app", "config", "data"] {
+                assert_eq!(read(&temp.path().join(name), name), "v1");
+            }
+            assert!(!temp.path().join("app/config").exists());
+        }
+
+        #[test]
+        fn it_restores_sources_to_remapped_locations() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let (mut repo, target_id) = prepare(&temp.path());
+            let backup_service = TargzBackupService::new();
+
+            let elsewhere = temp.path().join("elsewhere");
+            let locations = BTreeMap::from([
+                (String::from("main"), elsewhere.join("app")),
+                (String::from("data"), elsewhere.join("data")),
+            ]);
+            RestoreUsecase::new(&mut repo, &backup_service)
+                .execute_remapped(&target_id, 1, &locations)
+                .unwrap();
+
+            assert_eq!(read(&elsewhere.join("app"), "app"), "v1");
+            assert_eq!(read(&elsewhere.join("data"), "data"), "v1");
+            assert_eq!(read(&temp.path().join("app"), "app"), "v2");
+            assert_eq!(read(&temp.path().join("config"), "config"), "v1");
+        }
+
+        #[test]
+        fn it_returns_err_if_source_is_not_in_backup() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let (mut repo, target_id) = prepare(&temp.path());
+            let backup_service = TargzBackupService::new();
+
+            let locations = BTreeMap::from([(String::from("cache"), PathBuf::from("cache"))]);
+            let result = RestoreUsecase::new(&mut repo, &backup_service)
+                .execute_remapped(&target_id, 1, &locations);
+            assert!(result.is_err());
+        }
+    }
+
+    mod file_target {
+        use super::*;
+        use crate::infra::repository::file_storage::FileStorageTargetRepository;
+        use crate::infra::service::targz_backup_service::TargzBackupService;
+        use crate::usecase::backup::BackupUsecase;
+        use crate::usecase::register_target::RegisterTargetUsecase;
+
+        #[test]
+        fn it_restores_file_to_its_path() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let mut repo = FileStorageTargetRepository::new(&temp.path().join("data-dir"));
+            let db = temp.path().join("app.db");
+            std::fs::write(&db, "v1").unwrap();
+
+            let target = RegisterTargetUsecase::new(&mut repo)
+                .execute("Database", &db)
+                .unwrap();
+            let backup_service = TargzBackupService::new();
+            let entry = BackupUsecase::new(&mut repo, &backup_service)
+                .execute(&target.id, "")
+                .unwrap();
+            assert_eq!(entry.kind, TargetKind::File);
+
+            std::fs::write(&db, "v2").unwrap();
+            RestoreUsecase::new(&mut repo, &backup_service)
+                .execute(&target.id, entry.id)
+                .unwrap();
+            assert_eq!(std::fs::read_to_string(&db).unwrap(), "v1");
+
+            let copy = temp.path().join("copy/app.db");
+            let locations = BTreeMap::from([(String::from("main"), copy.clone())]);
+            RestoreUsecase::new(&mut repo, &backup_service)
+                .execute_remapped(&target.id, entry.id, &locations)
+                .unwrap();
+            assert_eq!(std::fs::read_to_string(&copy).unwrap(), "v1");
+        }
+    }
+
+    mod hooks {
+        use super::*;
+        use crate::domain::model::hooks::HookEvent;
+
+        fn prepare(repo: &mut InMemoryTargetRepository, pre_restore: &str) -> (String, u32) {
+            let mut target = repo.add("Test target", Path::new("test-target")).unwrap();
+            target
+                .hooks
+                .set_command(HookEvent::PreRestore, Some(pre_restore));
+            target
+                .hooks
+                .set_command(HookEvent::PostRestore, Some("start app"));
+            target
+                .hooks
+                .set_command(HookEvent::OnFailure, Some("notify"));
+
+            let entry = target.new_backup_entry(Path::new("test-backups"), "tar.gz");
+            let entry_id = entry.id;
+            target.register_backup_entry(entry).unwrap();
+            repo.update(&target).unwrap();
+            (target.id, entry_id)
+        }
+
+        #[test]
+        fn it_runs_hooks_around_restore() {
+            let mut repo = InMemoryTargetRepository::new();
+            let (target_id, entry_id) = prepare(&mut repo, "stop app");
+            let (backup_service, _, restore_counter) = TestBackupService::new();
+            let hook_service = TestHookService::new();
+
+            RestoreUsecase::new(&mut repo, &backup_service)
+                .with_hooks(&hook_service)
+                .execute(&target_id, entry_id)
+                .unwrap();
+
+            assert_eq!(hook_service.events(), vec!["pre-restore", "post-restore"]);
+            let pre = &hook_service.calls()[0];
+            assert_eq!(pre.env("DIRBACK_TARGET_PATH"), Some("test-target"));
+            assert_eq!(pre.env("DIRBACK_BACKUP_ID"), Some("1"));
+            assert_eq!(*restore_counter.borrow(), 1);
+        }
+
+        #[test]
+        fn failing_pre_restore_hook_aborts_restore() {
+            let mut repo = InMemoryTargetRepository::new();
+            let (target_id, entry_id) = prepare(&mut repo, "fail to stop app");
+            let (backup_service, _, restore_counter) = TestBackupService::new();
+            let hook_service = TestHookService::new();
+
+            let result = RestoreUsecase::new(&mut repo, &backup_service)
+                .with_hooks(&hook_service)
+                .execute(&target_id, entry_id);
+
+            assert!(
+                result
+                    .unwrap_err()
+                    .to_string()
+                    .contains("pre-restore hook failed")
+            );
+            assert_eq!(hook_service.events(), vec!["pre-restore", "on-failure"]);
+            assert_eq!(*restore_counter.borrow(), 0);
+        }
+    }
+
     #[test]
     fn it_returns_err_if_non_exsisting_target_id() {
         let mut repo = InMemoryTargetRepository::new();
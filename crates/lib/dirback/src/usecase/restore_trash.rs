@@ -0,0 +1,84 @@
+//!
+//! # Restore trash usecase
+//!
+//! Puts a deleted target or backup back from the trash.
+//!
+
+use crate::domain::repository::trash::TrashRepository;
+use crate::usecase::dto::TrashItem;
+
+pub struct RestoreTrashUsecase<'a, R: TrashRepository> {
+    repo: &'a mut R,
+}
+
+impl<'a, R: TrashRepository> RestoreTrashUsecase<'a, R> {
+    pub fn new(repo: &'a mut R) -> Self {
+        Self { repo }
+    }
+
+    /// Returns the restored item.
+    pub fn execute(&mut self, trash_id: u32) -> anyhow::Result<TrashItem> {
+        let item = self.repo.restore_trash(trash_id)?;
+        Ok(item.into())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::repository::targets::TargetRepository;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use std::path::Path;
+
+    fn prepare(repo: &mut InMemoryTargetRepository) -> String {
+        let mut target = repo.add("TestTarget", Path::new("target")).unwrap();
+        for _ in 0..3 {
+            let entry = target.new_backup_entry(Path::new("backups"), "tar.gz");
+            target.register_backup_entry(entry).unwrap();
+        }
+        repo.update(&target).unwrap().id
+    }
+
+    #[test]
+    fn it_restores_deleted_backup() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target_id = prepare(&mut repo);
+        repo.delete_backup(&target_id, 2).unwrap();
+
+        let item = RestoreTrashUsecase::new(&mut repo).execute(1).unwrap();
+        assert_eq!(item.backup.map(|b| b.id), Some(2));
+
+        let target = repo.load(&target_id).unwrap();
+        let ids: Vec<u32> = target.backups.iter().map(|b| b.id).collect();
+        assert_eq!(ids, vec![1, 2, 3]);
+        assert!(repo.list_trash().unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_restores_deleted_target() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target_id = prepare(&mut repo);
+        repo.delete_target(&target_id).unwrap();
+
+        let item = RestoreTrashUsecase::new(&mut repo).execute(1).unwrap();
+        assert_eq!(item.target_id, target_id);
+        assert_eq!(repo.load(&target_id).unwrap().backups.len(), 3);
+    }
+
+    #[test]
+    fn it_returns_err_if_target_of_backup_is_deleted() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target_id = prepare(&mut repo);
+        repo.delete_backup(&target_id, 3).unwrap();
+        repo.delete_target(&target_id).unwrap();
+
+        let mut usecase = RestoreTrashUsecase::new(&mut repo);
+        assert!(usecase.execute(1).is_err());
+        assert!(usecase.execute(2).is_ok());
+        assert!(usecase.execute(1).is_ok());
+        assert_eq!(repo.load(&target_id).unwrap().backups.len(), 3);
+    }
+}
@@ -0,0 +1,229 @@
+//!
+//! # Run scheduled usecase
+//!
+//! Takes a backup of every target whose scheduled backup is due,
+//! then deletes the backups which are not kept by its retention policy.
+//!
+//! The next run is counted from the latest backup,
+//! so runs missed while the daemon was stopped (or the machine was asleep)
+//! are caught up with a single backup.
+//!
+//! A failed backup is recorded in the target and retried after a delay
+//! (see `Target::next_run`), so it is not retried on every tick.
+//!
+
+use crate::domain::model::timestamp::Timestamp;
+use crate::domain::repository::targets::TargetRepository;
+use crate::domain::service::backup_service::BackupService;
+use crate::domain::service::hook_service::HookService;
+use crate::usecase::backup::BackupUsecase;
+use crate::usecase::dto::ScheduledRun;
+use crate::usecase::hook_runner::HookRunner;
+use crate::usecase::prune::PruneUsecase;
+
+/// The note of scheduled backups.
+pub const SCHEDULED_BACKUP_NOTE: &str = "scheduled backup";
+
+pub struct RunScheduledUsecase<'a, R: TargetRepository, B: BackupService> {
+    repo: &'a mut R,
+    backup_service: &'a B,
+    hooks: HookRunner<'a>,
+}
+
+impl<'a, R: TargetRepository, B: BackupService> RunScheduledUsecase<'a, R, B> {
+    pub fn new(repo: &'a mut R, backup_service: &'a B) -> Self {
+        Self {
+            repo,
+            backup_service,
+            hooks: HookRunner::default(),
+        }
+    }
+
+    /// Runs the hooks of the targets around the backups.
+    pub fn with_hooks(mut self, hook_service: &'a dyn HookService) -> Self {
+        self.hooks = HookRunner::new(Some(hook_service));
+        self
+    }
+
+    /// Returns the result of each target which was due.
+    ///
+    /// A failure of a target does not stop the others.
+    pub fn execute(&mut self, now: &Timestamp) -> anyhow::Result<Vec<ScheduledRun>> {
+        let due: Vec<_> = self
+            .repo
+            .load_all()?
+            .into_iter()
+            .filter(|t| t.is_due(now))
+            .collect();
+
+        let mut runs = Vec::new();
+        for target in due {
+            let mut run = ScheduledRun {
+                target_id: target.id.clone(),
+                target_name: target.name.clone(),
+                backup: None,
+                pruned: Vec::new(),
+                error: None,
+            };
+
+            let mut backup =
+                BackupUsecase::new(self.repo, self.backup_service).with_hook_runner(self.hooks);
+            match backup.execute(&target.id, SCHEDULED_BACKUP_NOTE) {
+                Ok(entry) => run.backup = Some(entry),
+                Err(e) => {
+                    run.error = Some(e.to_string());
+                    self.record_failure(&target.id, now)?;
+                }
+            }
+
+            // Old backups are deleted only after a successful backup.
+            if run.backup.is_some() && !target.retention.is_empty() {
+                let mut prune = PruneUsecase::new(self.repo);
+                match prune.execute(&target.id, &target.retention, false) {
+                    Ok(pruned) => run.pruned = pruned,
+                    Err(e) => run.error = Some(format!("Failed to prune: {e}")),
+                }
+            }
+
+            runs.push(run);
+        }
+
+        Ok(runs)
+    }
+
+    /// Records the failed run, so the target backs off before the next try.
+    fn record_failure(&mut self, id: &str, now: &Timestamp) -> anyhow::Result<()> {
+        let mut target = self
+            .repo
+            .load(id)
+            .ok_or_else(|| anyhow::anyhow!("target not found: {id}"))?;
+        target.last_failed_run = Some(now.clone());
+        self.repo.update(&target)?;
+        Ok(())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::model::schedule::Schedule;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use crate::usecase::usecase_test_helper::*;
+    use std::path::Path;
+
+    fn add_target(repo: &mut InMemoryTargetRepository, name: &str, schedule: &str) -> String {
+        let mut target = repo.add(name, Path::new(name)).unwrap();
+        target.schedule = Some(Schedule::parse(schedule).unwrap());
+        repo.update(&target).unwrap();
+        target.id
+    }
+
+    #[test]
+    fn it_backs_up_due_targets_only() {
+        let mut repo = InMemoryTargetRepository::new();
+        let (backup_service, backup_counter, _) = TestBackupService::new();
+        let due_id = add_target(&mut repo, "due", "every 1h");
+        let manual = repo.add("manual", Path::new("manual")).unwrap();
+
+        let now = Timestamp::now();
+        let mut usecase = RunScheduledUsecase::new(&mut repo, &backup_service);
+        let runs = usecase.execute(&now).unwrap();
+
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].target_id, due_id);
+        assert!(runs[0].error.is_none());
+        assert_eq!(*backup_counter.borrow(), 1);
+
+        let target = repo.load(&due_id).unwrap();
+        assert_eq!(target.backups[0].note, SCHEDULED_BACKUP_NOTE);
+        assert!(repo.load(&manual.id).unwrap().backups.is_empty());
+
+        // Not due until the interval has passed.
+        let mut usecase = RunScheduledUsecase::new(&mut repo, &backup_service);
+        assert!(usecase.execute(&now).unwrap().is_empty());
+    }
+
+    #[test]
+    fn it_applies_retention() {
+        let mut repo = InMemoryTargetRepository::new();
+        let (backup_service, _, _) = TestBackupService::new();
+        let id = add_target(&mut repo, "target", "every 1h");
+
+        let mut target = repo.load(&id).unwrap();
+        target.retention.keep_last = Some(2);
+        let past = Timestamp::from_fmt_str("20250101T000000Z").unwrap();
+        for _ in 0..3 {
+            let mut entry = target.new_backup_entry(Path::new("backups"), "tar.gz");
+            entry.timestamp = past.clone();
+            target.register_backup_entry(entry).unwrap();
+        }
+        repo.update(&target).unwrap();
+
+        let mut usecase = RunScheduledUsecase::new(&mut repo, &backup_service);
+        let runs = usecase.execute(&Timestamp::now()).unwrap();
+
+        assert_eq!(runs[0].backup.as_ref().unwrap().id, 4);
+        let pruned: Vec<u32> = runs[0].pruned.iter().map(|b| b.id).collect();
+        assert_eq!(pruned, vec![1, 2]);
+
+        let ids: Vec<u32> = repo
+            .load(&id)
+            .unwrap()
+            .backups
+            .iter()
+            .map(|b| b.id)
+            .collect();
+        assert_eq!(ids, vec![3, 4]);
+    }
+
+    #[test]
+    fn it_records_failures() {
+        let mut repo = InMemoryTargetRepository::new();
+        let (backup_service, _, _) = TestBackupService::new();
+        let id = add_target(&mut repo, "target", "every 1h");
+        let mut target = repo.load(&id).unwrap();
+        target.store = Some(String::from("missing-store"));
+        repo.update(&target).unwrap();
+
+        let mut usecase = RunScheduledUsecase::new(&mut repo, &backup_service);
+        let runs = usecase.execute(&Timestamp::now()).unwrap();
+
+        assert_eq!(runs.len(), 1);
+        assert!(runs[0].backup.is_none());
+        assert!(runs[0].error.is_some());
+    }
+
+    #[test]
+    fn it_does_not_retry_failed_target_on_next_tick() {
+        let mut repo = InMemoryTargetRepository::new();
+        let (backup_service, _, _) = TestBackupService::new();
+        let hook_service = TestHookService::new();
+        let id = add_target(&mut repo, "target", "every 1d");
+        let mut target = repo.load(&id).unwrap();
+        target.hooks.pre_backup = Some(String::from("fail"));
+        repo.update(&target).unwrap();
+
+        let now = Timestamp::from_fmt_str("20250101T000000Z").unwrap();
+        let mut usecase =
+            RunScheduledUsecase::new(&mut repo, &backup_service).with_hooks(&hook_service);
+        assert!(usecase.execute(&now).unwrap()[0].error.is_some());
+        assert_eq!(repo.load(&id).unwrap().last_failed_run, Some(now));
+        assert_eq!(hook_service.calls().len(), 1);
+
+        // The hooks are not run again on the next tick.
+        let next_tick = Timestamp::from_fmt_str("20250101T000001Z").unwrap();
+        let mut usecase =
+            RunScheduledUsecase::new(&mut repo, &backup_service).with_hooks(&hook_service);
+        assert!(usecase.execute(&next_tick).unwrap().is_empty());
+        assert_eq!(hook_service.calls().len(), 1);
+
+        // Retried after the delay.
+        let retry = Timestamp::from_fmt_str("20250101T010000Z").unwrap();
+        let mut usecase =
+            RunScheduledUsecase::new(&mut repo, &backup_service).with_hooks(&hook_service);
+        assert_eq!(usecase.execute(&retry).unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,63 @@
+//!
+//! # Set hooks usecase
+//!
+//! Changes the commands run around the backups and restores of the target.
+//!
+
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::{Hooks, Target};
+
+pub struct SetHooksUsecase<'a, R: TargetRepository> {
+    repo: &'a mut R,
+}
+
+impl<'a, R: TargetRepository> SetHooksUsecase<'a, R> {
+    pub fn new(repo: &'a mut R) -> Self {
+        Self { repo }
+    }
+
+    /// Replaces the hooks of the target.
+    pub fn execute(&mut self, target_id: &str, hooks: Hooks) -> anyhow::Result<Target> {
+        let mut target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        target.hooks = hooks;
+        let target = self.repo.update(&target)?;
+        Ok(target.into())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use crate::usecase::dto::HookEvent;
+    use std::path::Path;
+
+    #[test]
+    fn it_works() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target = repo.add("Test target", Path::new("target")).unwrap();
+
+        let mut hooks = Hooks::default();
+        hooks.set_command(HookEvent::PreBackup, Some("systemctl stop db"));
+        hooks.timeout_secs = Some(30);
+
+        let mut usecase = SetHooksUsecase::new(&mut repo);
+        let result = usecase.execute(&target.id, hooks.clone()).unwrap();
+        assert_eq!(result.hooks, hooks);
+        assert_eq!(repo.load(&target.id).unwrap().hooks, hooks);
+    }
+
+    #[test]
+    fn it_returns_err_if_target_not_found() {
+        let mut repo = InMemoryTargetRepository::new();
+        let mut usecase = SetHooksUsecase::new(&mut repo);
+        assert!(usecase.execute("xxx", Hooks::default()).is_err());
+    }
+}
@@ -0,0 +1,81 @@
+//!
+//! # Set schedule usecase
+//!
+//! Changes when the daemon takes backups of the target,
+//! and the backups kept after each scheduled backup.
+//!
+
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::{RetentionPolicy, Schedule, Target};
+
+pub struct SetScheduleUsecase<'a, R: TargetRepository> {
+    repo: &'a mut R,
+}
+
+impl<'a, R: TargetRepository> SetScheduleUsecase<'a, R> {
+    pub fn new(repo: &'a mut R) -> Self {
+        Self { repo }
+    }
+
+    /// - schedule ... `None` stops the scheduled backups.
+    pub fn execute(
+        &mut self,
+        target_id: &str,
+        schedule: Option<Schedule>,
+        retention: RetentionPolicy,
+    ) -> anyhow::Result<Target> {
+        let mut target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        target.schedule = schedule;
+        target.retention = retention;
+        let target = self.repo.update(&target)?;
+        Ok(target.into())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use std::path::Path;
+
+    #[test]
+    fn it_works() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target = repo.add("Test target", Path::new("target")).unwrap();
+
+        let schedule = Schedule::parse("every 6h").unwrap();
+        let retention = RetentionPolicy {
+            keep_last: Some(3),
+            ..Default::default()
+        };
+        let mut usecase = SetScheduleUsecase::new(&mut repo);
+        let result = usecase
+            .execute(&target.id, Some(schedule.clone()), retention.clone())
+            .unwrap();
+        assert_eq!(result.schedule, Some(schedule));
+
+        let loaded = repo.load(&target.id).unwrap();
+        assert_eq!(loaded.retention, retention);
+
+        let mut usecase = SetScheduleUsecase::new(&mut repo);
+        let result = usecase
+            .execute(&target.id, None, RetentionPolicy::default())
+            .unwrap();
+        assert_eq!(result.schedule, None);
+    }
+
+    #[test]
+    fn it_returns_err_if_target_not_found() {
+        let mut repo = InMemoryTargetRepository::new();
+        let mut usecase = SetScheduleUsecase::new(&mut repo);
+        let result = usecase.execute("xxx", None, RetentionPolicy::default());
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,83 @@
+//!
+//! # Set store usecase
+//!
+//! Changes the archive store where new backups of the target are placed.
+//! Existing backups stay where they are.
+//!
+
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::Target;
+
+pub struct SetStoreUsecase<'a, R: TargetRepository> {
+    repo: &'a mut R,
+}
+
+impl<'a, R: TargetRepository> SetStoreUsecase<'a, R> {
+    pub fn new(repo: &'a mut R) -> Self {
+        Self { repo }
+    }
+
+    /// - store_id ... `None` keeps new backups in the data directory.
+    pub fn execute(&mut self, target_id: &str, store_id: Option<&str>) -> anyhow::Result<Target> {
+        let mut target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        if let Some(store_id) = store_id {
+            // Make sure the store is registered and can be opened.
+            let _ = self.repo.archive_store(store_id)?;
+        }
+
+        target.store = store_id.map(str::to_string);
+        let target = self.repo.update(&target)?;
+        Ok(target.into())
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::file_storage::FileStorageTargetRepository;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use crate::usecase::usecase_test_helper::*;
+    use std::path::Path;
+
+    #[test]
+    fn it_works() {
+        let temp = mktemp::TempDir::new().unwrap();
+        register_local_store(&temp.path(), "nas");
+        let mut repo = FileStorageTargetRepository::new(&temp.path());
+        let target = repo.add("Test target", Path::new("target")).unwrap();
+
+        let mut usecase = SetStoreUsecase::new(&mut repo);
+        let result = usecase.execute(&target.id, Some("nas")).unwrap();
+        assert_eq!(result.store.as_deref(), Some("nas"));
+        assert_eq!(repo.load(&target.id).unwrap().store.as_deref(), Some("nas"));
+
+        let mut usecase = SetStoreUsecase::new(&mut repo);
+        let result = usecase.execute(&target.id, None).unwrap();
+        assert_eq!(result.store, None);
+    }
+
+    #[test]
+    fn it_returns_err_if_store_not_registered() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target = repo.add("Test target", Path::new("target")).unwrap();
+
+        let mut usecase = SetStoreUsecase::new(&mut repo);
+        assert!(usecase.execute(&target.id, Some("nas")).is_err());
+        assert_eq!(repo.load(&target.id).unwrap().store, None);
+    }
+
+    #[test]
+    fn it_returns_err_if_target_not_found() {
+        let mut repo = InMemoryTargetRepository::new();
+
+        let mut usecase = SetStoreUsecase::new(&mut repo);
+        assert!(usecase.execute("xxx", None).is_err());
+    }
+}
@@ -0,0 +1,79 @@
+//!
+//! # Tag target usecase
+//!
+
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::Target;
+
+pub struct TagTargetUsecase<'a, R: TargetRepository> {
+    repo: &'a mut R,
+}
+
+impl<'a, R: TargetRepository> TagTargetUsecase<'a, R> {
+    pub fn new(repo: &'a mut R) -> Self {
+        Self { repo }
+    }
+
+    /// Adds and removes tags of the target.
+    ///
+    /// Nothing is saved if any of the tags to add is invalid.
+    pub fn execute(
+        &mut self,
+        target_id: &str,
+        add: &[String],
+        remove: &[String],
+    ) -> anyhow::Result<Target> {
+        let mut target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        for tag in add.iter() {
+            target.add_tag(tag)?;
+        }
+        for tag in remove.iter() {
+            target.remove_tag(tag);
+        }
+
+        let target = self.repo.update(&target)?;
+        Ok(target.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use std::path::Path;
+
+    fn tags(tags: &[&str]) -> Vec<String> {
+        tags.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn it_works() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target = repo.add("TestTarget", Path::new("target")).unwrap();
+
+        let mut usecase = TagTargetUsecase::new(&mut repo);
+        let result = usecase
+            .execute(&target.id, &tags(&["games", "clients"]), &[])
+            .unwrap();
+        assert_eq!(result.tags, tags(&["clients", "games"]));
+
+        let result = usecase.execute(&target.id, &[], &tags(&["games"])).unwrap();
+        assert_eq!(result.tags, tags(&["clients"]));
+        assert_eq!(repo.load(&target.id).unwrap().tags, tags(&["clients"]));
+    }
+
+    #[test]
+    fn it_returns_err_if_tag_is_invalid() {
+        let mut repo = InMemoryTargetRepository::new();
+        let target = repo.add("TestTarget", Path::new("target")).unwrap();
+
+        let mut usecase = TagTargetUsecase::new(&mut repo);
+        let result = usecase.execute(&target.id, &tags(&["ok", "all"]), &[]);
+        assert!(result.is_err());
+        assert!(repo.load(&target.id).unwrap().tags.is_empty());
+    }
+}
@@ -0,0 +1,149 @@
+//!
+//! # Target path validation
+//!
+//! The paths of the targets and their sources are canonicalized,
+//! and are rejected if they are already backed up by a target,
+//! overlap another target (the path or a source of the target),
+//! or overlap the data directory, which would back up its own backups.
+//!
+
+use crate::domain::model::target::Target;
+use crate::domain::repository::targets::TargetRepository;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, PartialEq, thiserror::Error)]
+pub enum TargetPathError {
+    #[error("'{path}' is already registered as the target '{name}' ({id}).")]
+    Duplicate {
+        path: PathBuf,
+        id: String,
+        name: String,
+    },
+
+    #[error("'{path}' is inside the target '{name}' ({id}): '{target_path}'")]
+    InsideTarget {
+        path: PathBuf,
+        id: String,
+        name: String,
+        target_path: PathBuf,
+    },
+
+    #[error("'{path}' contains the target '{name}' ({id}): '{target_path}'")]
+    ContainsTarget {
+        path: PathBuf,
+        id: String,
+        name: String,
+        target_path: PathBuf,
+    },
+
+    #[error("'{path}' overlaps the data directory '{data_dir}', whose backups would be backed up.")]
+    DataDir { path: PathBuf, data_dir: PathBuf },
+}
+
+pub(crate) struct TargetPathValidator<'a, R: TargetRepository> {
+    repo: &'a R,
+    allow_overlap: bool,
+    replaced: Option<&'a str>,
+}
+
+impl<'a, R: TargetRepository> TargetPathValidator<'a, R> {
+    pub fn new(repo: &'a R) -> Self {
+        Self {
+            repo,
+            allow_overlap: false,
+            replaced: None,
+        }
+    }
+
+    /// Allows the path inside another target, or containing another target.
+    pub fn allow_overlap(mut self, allow: bool) -> Self {
+        self.allow_overlap = allow;
+        self
+    }
+
+    /// The path of the target is replaced, so it is not compared.
+    /// The sources of the target are still compared.
+    pub fn replacing(mut self, target_id: &'a str) -> Self {
+        self.replaced = Some(target_id);
+        self
+    }
+
+    /// Returns the canonical path.
+    ///
+    /// Returns `TargetPathError` if the path overlaps the data directory or a target,
+    /// or an error if the path does not exist or the targets cannot be loaded.
+    pub fn validate(&self, path: &Path) -> anyhow::Result<PathBuf> {
+        let path = std::fs::canonicalize(path)
+            .map_err(|_| anyhow::anyhow!("Path is invalid: '{}'", path.display()))?;
+
+        if let Some(data_dir) = self.repo.data_dir() {
+            let data_dir = canonical(data_dir);
+            if path.starts_with(&data_dir) || data_dir.starts_with(&path) {
+                return Err(TargetPathError::DataDir { path, data_dir }.into());
+            }
+        }
+
+        // The path cannot be checked against the targets which cannot be loaded.
+        let targets = self.repo.load_all()?;
+        for target in targets.iter() {
+            for target_path in self.target_paths(target) {
+                if let Some(e) = self.overlap(&path, target, &target_path) {
+                    return Err(e.into());
+                }
+            }
+        }
+
+        Ok(path)
+    }
+
+    /// Returns the canonical paths of the target and its sources.
+    fn target_paths(&self, target: &Target) -> Vec<PathBuf> {
+        let replaced = self.replaced == Some(target.id.as_str());
+        let path = (!replaced).then_some(target.path.as_path());
+        path.into_iter()
+            .chain(target.sources.iter().map(|s| s.path.as_path()))
+            .map(canonical)
+            .collect()
+    }
+
+    fn overlap(&self, path: &Path, target: &Target, target_path: &Path) -> Option<TargetPathError> {
+        let (path, id, name) = (path.to_path_buf(), target.id.clone(), target.name.clone());
+        let target_path = target_path.to_path_buf();
+
+        if path == target_path {
+            Some(TargetPathError::Duplicate { path, id, name })
+        } else if self.allow_overlap {
+            None
+        } else if path.starts_with(&target_path) {
+            Some(TargetPathError::InsideTarget {
+                path,
+                id,
+                name,
+                target_path,
+            })
+        } else if target_path.starts_with(&path) {
+            Some(TargetPathError::ContainsTarget {
+                path,
+                id,
+                name,
+                target_path,
+            })
+        } else {
+            None
+        }
+    }
+}
+
+/// Returns the canonical path, or the absolute path under the canonical existing ancestor
+/// if the path does not exist, e.g. a moved target or a data directory not made yet.
+fn canonical(path: &Path) -> PathBuf {
+    if let Ok(path) = std::fs::canonicalize(path) {
+        return path;
+    }
+
+    let path = std::path::absolute(path).unwrap_or_else(|_| path.to_path_buf());
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) => canonical(parent).join(name),
+        _ => path,
+    }
+}
@@ -0,0 +1,90 @@
+//!
+//! # Transfer targets usecase
+//!
+//! Copies all target informations from a repository to another one.
+//! e.g. migrate the FileStorage repository to the SQLite repository.
+//!
+//! Backup files are not copied, backup entries keep their paths.
+//!
+
+use crate::domain::repository::targets::TargetRepository;
+use crate::usecase::dto::Target;
+
+pub struct TransferTargetsUsecase<'a, S: TargetRepository, D: TargetRepository> {
+    src: &'a S,
+    dest: &'a mut D,
+}
+
+impl<'a, S: TargetRepository, D: TargetRepository> TransferTargetsUsecase<'a, S, D> {
+    pub fn new(src: &'a S, dest: &'a mut D) -> Self {
+        Self { src, dest }
+    }
+
+    /// Copies all targets.
+    ///
+    /// Targets that already exist in the destination are overwritten.
+    pub fn execute(&mut self) -> anyhow::Result<Vec<Target>> {
+        let mut transferred = Vec::new();
+
+        for target in self.src.load_all()? {
+            let target = if self.dest.load(&target.id).is_some() {
+                self.dest.update(&target)?
+            } else {
+                self.dest.import(&target)?
+            };
+            transferred.push(target.into());
+        }
+
+        Ok(transferred)
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use std::path::Path;
+
+    #[test]
+    fn it_works() {
+        let mut src = InMemoryTargetRepository::new();
+        for i in 1..=3 {
+            let name = format!("Test Target {i}");
+            let mut target = src.add(&name, Path::new(&name)).unwrap();
+            let entry = target.new_backup_entry(Path::new("backups"), "tar.gz");
+            let _ = target.register_backup_entry(entry);
+            let _ = src.update(&target);
+        }
+
+        let mut dest = InMemoryTargetRepository::new();
+        let mut usecase = TransferTargetsUsecase::new(&src, &mut dest);
+        let result = usecase.execute();
+        assert!(result.is_ok());
+        assert_eq!(result.unwrap().len(), 3);
+
+        assert_eq!(dest.load_all().unwrap(), src.load_all().unwrap());
+    }
+
+    #[test]
+    fn it_overwrites_existing_targets() {
+        let mut src = InMemoryTargetRepository::new();
+        let mut target = src.add("Test Target", Path::new("target")).unwrap();
+
+        let mut dest = InMemoryTargetRepository::new();
+        let _ = dest.import(&target);
+
+        target.name = String::from("Renamed");
+        let _ = src.update(&target);
+
+        let mut usecase = TransferTargetsUsecase::new(&src, &mut dest);
+        let result = usecase.execute();
+        assert!(result.is_ok());
+
+        let targets = dest.load_all().unwrap();
+        assert_eq!(targets.len(), 1);
+        assert_eq!(targets[0].name, "Renamed");
+    }
+}
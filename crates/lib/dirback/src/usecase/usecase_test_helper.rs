@@ -2,10 +2,23 @@
 //! # Usecase Test Helper
 //!
 
+use crate::domain::model::archived_file::ArchivedFile;
+use crate::domain::model::backup_stats::BackupStats;
+use crate::domain::model::file_pattern::FilePattern;
+use crate::domain::model::index_entry::IndexEntry;
+use crate::domain::model::source::Source;
+use crate::domain::repository::targets::TargetRepository;
 use crate::domain::service::backup_service::BackupService;
+use crate::domain::service::hook_service::HookService;
+use crate::infra::repository::file_storage::FileStorageTargetRepository;
+use crate::infra::service::archive_store::STORE_CONFIG_FILE_NAME;
+use crate::infra::service::targz_backup_service::TargzBackupService;
+use crate::usecase::backup::BackupUsecase;
 use std::cell::RefCell;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::rc::Rc;
+use std::sync::Mutex;
+use std::time::Duration;
 
 #[cfg(test)]
 pub struct TestBackupService {
@@ -29,14 +42,159 @@ impl TestBackupService {
     }
 }
 
-impl BackupService for TestBackupService {
-    fn backup(&self, _src: &Path, _dest: &Path) -> anyhow::Result<()> {
+impl TestBackupService {
+    fn backup_stats(&self) -> BackupStats {
         *self.backup_counter.borrow_mut() += 1;
-        Ok(())
+        BackupStats {
+            size: 10,
+            uncompressed_size: 40,
+            files: 2,
+            duration_ms: 0,
+        }
+    }
+}
+
+impl BackupService for TestBackupService {
+    fn backup(&self, _src: &Path, _dest: &Path) -> anyhow::Result<BackupStats> {
+        Ok(self.backup_stats())
+    }
+
+    fn backup_sources(&self, _sources: &[Source], _dest: &Path) -> anyhow::Result<BackupStats> {
+        Ok(self.backup_stats())
+    }
+
+    fn backup_file(&self, _src: &Path, _dest: &Path) -> anyhow::Result<BackupStats> {
+        Ok(self.backup_stats())
     }
 
     fn restore(&self, _src: &Path, _dest: &Path) -> anyhow::Result<()> {
         *self.restore_counter.borrow_mut() += 1;
         Ok(())
     }
+
+    fn restore_source(&self, _src: &Path, _name: &str, _dest: &Path) -> anyhow::Result<()> {
+        *self.restore_counter.borrow_mut() += 1;
+        Ok(())
+    }
+
+    fn restore_file(&self, _src: &Path, _dest: &Path) -> anyhow::Result<()> {
+        *self.restore_counter.borrow_mut() += 1;
+        Ok(())
+    }
+
+    fn list(&self, _src: &Path) -> anyhow::Result<Vec<PathBuf>> {
+        Ok(Vec::new())
+    }
+
+    fn find(&self, _src: &Path, _pattern: &FilePattern) -> anyhow::Result<Vec<ArchivedFile>> {
+        Ok(Vec::new())
+    }
+
+    fn index(&self, _src: &Path) -> anyhow::Result<Vec<IndexEntry>> {
+        Ok(Vec::new())
+    }
+
+    fn read_file(
+        &self,
+        _src: &Path,
+        _path: &Path,
+        _out: &mut dyn std::io::Write,
+    ) -> anyhow::Result<Option<u64>> {
+        Ok(None)
+    }
+}
+
+/// A hook command run by `TestHookService`.
+#[derive(Clone, Debug)]
+pub struct HookCall {
+    pub command: String,
+    pub env: Vec<(String, String)>,
+}
+
+impl HookCall {
+    pub fn env(&self, key: &str) -> Option<&str> {
+        self.env
+            .iter()
+            .find(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Records the hook commands instead of running them.
+///
+/// The commands starting with "fail" fail.
+#[derive(Default)]
+pub struct TestHookService {
+    calls: Mutex<Vec<HookCall>>,
+}
+
+impl TestHookService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn calls(&self) -> Vec<HookCall> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    /// Returns the `DIRBACK_HOOK` of each call.
+    pub fn events(&self) -> Vec<String> {
+        self.calls()
+            .iter()
+            .map(|c| c.env("DIRBACK_HOOK").unwrap_or_default().to_string())
+            .collect()
+    }
+}
+
+impl HookService for TestHookService {
+    fn run(
+        &self,
+        command: &str,
+        env: &[(String, String)],
+        _work_dir: &Path,
+        _timeout: Duration,
+    ) -> anyhow::Result<()> {
+        self.calls.lock().unwrap().push(HookCall {
+            command: command.to_string(),
+            env: env.to_vec(),
+        });
+
+        if command.starts_with("fail") {
+            anyhow::bail!("'{command}' failed.");
+        }
+        Ok(())
+    }
+}
+
+/// Registers a target of `{base_dir}/src` in the repository of `{base_dir}/data`,
+/// and takes real tar.gz backups of it.
+///
+/// Returns the repository and the target ID.
+pub fn prepare_target_with_backups(
+    base_dir: &Path,
+    backup_count: usize,
+) -> (FileStorageTargetRepository, String) {
+    let mut repo = FileStorageTargetRepository::new(&base_dir.join("data"));
+    let src = base_dir.join("src");
+    std::fs::create_dir_all(&src).unwrap();
+    std::fs::write(src.join("file.txt"), "hello").unwrap();
+
+    let target = repo.add("Test target", &src).unwrap();
+    let backup_service = TargzBackupService::new();
+    for _ in 0..backup_count {
+        BackupUsecase::new(&mut repo, &backup_service)
+            .execute(&target.id, "")
+            .unwrap();
+    }
+    (repo, target.id)
+}
+
+/// Registers a local directory archive store in the base_dir.
+///
+/// Returns the root directory of the store.
+pub fn register_local_store(base_dir: &Path, store_id: &str) -> PathBuf {
+    let root = base_dir.join(format!("store-{store_id}"));
+    let config = serde_json::json!({ store_id: { "type": "local", "root": root } });
+    jsonfile::write(&base_dir.join(STORE_CONFIG_FILE_NAME), &config).unwrap();
+    root
 }
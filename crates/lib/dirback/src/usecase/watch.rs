@@ -0,0 +1,369 @@
+//!
+//! # Watch usecase
+//!
+//! Takes a backup of the target shortly after its files change.
+//!
+//! A backup is taken once the target has been quiet for `quiet`
+//! (so a burst of changes makes one backup),
+//! and at least `min_interval` after the previous backup.
+//! The note of the backup lists the changed paths.
+//! Changes of the paths left out of the backups (`excludes`) are ignored.
+//!
+
+use crate::domain::model::change_set::ChangeSet;
+use crate::domain::model::file_pattern::FilePattern;
+use crate::domain::model::target_kind::TargetKind;
+use crate::domain::repository::targets::TargetRepository;
+use crate::domain::service::backup_service::BackupService;
+use crate::domain::service::hook_service::HookService;
+use crate::domain::service::watch_service::WatchService;
+use crate::usecase::backup::BackupUsecase;
+use crate::usecase::dto::BackupEntry;
+use crate::usecase::hook_runner::HookRunner;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+#[derive(Clone, Debug)]
+pub struct WatchOptions {
+    /// The time without changes before a backup.
+    pub quiet: Duration,
+
+    /// The minimum time between backups.
+    pub min_interval: Duration,
+
+    /// The patterns of the paths left out of the backups, matched as in the backup service.
+    pub excludes: Vec<FilePattern>,
+}
+
+impl Default for WatchOptions {
+    fn default() -> Self {
+        Self {
+            quiet: Duration::from_secs(10),
+            min_interval: Duration::from_secs(300),
+            excludes: Vec::new(),
+        }
+    }
+}
+
+pub struct WatchUsecase<'a, R: TargetRepository, B: BackupService, W: WatchService> {
+    repo: &'a mut R,
+    backup_service: &'a B,
+    watcher: &'a mut W,
+    hooks: HookRunner<'a>,
+}
+
+impl<'a, R: TargetRepository, B: BackupService, W: WatchService> WatchUsecase<'a, R, B, W> {
+    /// The watcher should watch the path and every source path of the target.
+    pub fn new(repo: &'a mut R, backup_service: &'a B, watcher: &'a mut W) -> Self {
+        Self {
+            repo,
+            backup_service,
+            watcher,
+            hooks: HookRunner::default(),
+        }
+    }
+
+    /// Runs the hooks of the target around each backup.
+    pub fn with_hooks(mut self, hook_service: &'a dyn HookService) -> Self {
+        self.hooks = HookRunner::new(Some(hook_service));
+        self
+    }
+
+    /// Watches the target until `on_backup` returns false, or the watcher fails.
+    ///
+    /// `on_backup` is called with the result of each backup.
+    /// The changes of a failed backup are kept, and retried after `min_interval`.
+    pub fn execute<F>(
+        &mut self,
+        target_id: &str,
+        options: &WatchOptions,
+        mut on_backup: F,
+    ) -> anyhow::Result<()>
+    where
+        F: FnMut(&anyhow::Result<BackupEntry>) -> bool,
+    {
+        let target = self
+            .repo
+            .load(target_id)
+            .ok_or_else(|| anyhow::anyhow!("Target({}) not found.", target_id))?;
+
+        // Backups written under the target must not trigger another backup.
+        let backup_dir = self.repo.make_backup_dir_path(&target);
+        let is_backup =
+            |p: &PathBuf| !backup_dir.as_os_str().is_empty() && p.starts_with(&backup_dir);
+
+        // Changes of a target with sources are listed under the source names, as in the archives.
+        let sources = target.archive_sources();
+
+        // A path under an excluded directory is excluded with it.
+        let is_excluded = |root: &Path, p: &Path| {
+            p.strip_prefix(root).is_ok_and(|rel| {
+                rel.ancestors()
+                    .filter(|a| !a.as_os_str().is_empty())
+                    .any(|a| options.excludes.iter().any(|e| e.matches(a)))
+            })
+        };
+
+        let mut changes = ChangeSet::new();
+        let mut last_change = Instant::now();
+        let mut last_backup: Option<Instant> = None;
+
+        loop {
+            // The time when the collected changes can be backed up.
+            let ready_at = (!changes.is_empty()).then(|| {
+                let quiet_until = last_change + options.quiet;
+                match last_backup {
+                    Some(t) => quiet_until.max(t + options.min_interval),
+                    None => quiet_until,
+                }
+            });
+
+            let timeout = ready_at.map(|t| t.saturating_duration_since(Instant::now()));
+            let paths = match timeout {
+                Some(Duration::ZERO) => Vec::new(),
+                _ => self.watcher.wait(timeout)?,
+            };
+
+            let now = Instant::now();
+            for path in paths.iter().filter(|p| !is_backup(p)) {
+                let changed = match target.kind {
+                    TargetKind::Directory if sources.is_empty() => {
+                        !is_excluded(&target.path, path) && changes.add(&target.path, path)
+                    }
+                    TargetKind::Directory => sources.iter().any(|s| {
+                        !is_excluded(&s.path, path) && changes.add_source(&s.name, &s.path, path)
+                    }),
+                    // The directory of a file target is watched, ignore the other files.
+                    TargetKind::File => {
+                        *path == target.path && changes.add(target.base_dir(), path)
+                    }
+                };
+                if changed {
+                    last_change = now;
+                }
+            }
+
+            // New changes postpone the backup.
+            let ready = ready_at.is_some_and(|t| now >= t) && now >= last_change + options.quiet;
+            if !ready {
+                continue;
+            }
+
+            let result = BackupUsecase::new(self.repo, self.backup_service)
+                .with_hook_runner(self.hooks)
+                .execute(target_id, &changes.note());
+            last_backup = Some(Instant::now());
+            if result.is_ok() {
+                changes.clear();
+            }
+
+            if !on_backup(&result) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+//-----------------------------------------------------------------------------
+// Tests
+//-----------------------------------------------------------------------------
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infra::repository::file_storage::FileStorageTargetRepository;
+    use crate::infra::repository::in_memory::InMemoryTargetRepository;
+    use crate::usecase::usecase_test_helper::*;
+    use std::collections::VecDeque;
+
+    /// Returns the scripted changes one by one, and fails when they run out.
+    struct TestWatchService {
+        script: VecDeque<Vec<PathBuf>>,
+    }
+
+    impl TestWatchService {
+        fn new(script: &[&[&str]]) -> Self {
+            Self {
+                script: script
+                    .iter()
+                    .map(|paths| paths.iter().map(PathBuf::from).collect())
+                    .collect(),
+            }
+        }
+    }
+
+    impl WatchService for TestWatchService {
+        fn wait(&mut self, timeout: Option<Duration>) -> anyhow::Result<Vec<PathBuf>> {
+            match self.script.pop_front() {
+                Some(paths) => Ok(paths),
+                None if timeout.is_some() => {
+                    std::thread::sleep(timeout.unwrap());
+                    Ok(Vec::new())
+                }
+                None => anyhow::bail!("no more changes"),
+            }
+        }
+    }
+
+    fn options(quiet_ms: u64, min_interval_ms: u64) -> WatchOptions {
+        WatchOptions {
+            quiet: Duration::from_millis(quiet_ms),
+            min_interval: Duration::from_millis(min_interval_ms),
+            excludes: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn it_backs_up_burst_of_changes_once() {
+        let mut repo = InMemoryTargetRepository::new();
+        let (backup_service, backup_counter, _) = TestBackupService::new();
+        let target = repo.add("notes", Path::new("/notes")).unwrap();
+        let mut watcher = TestWatchService::new(&[
+            &["/notes/b.txt"],
+            &["/notes/a.txt", "/notes/b.txt"],
+            &["/other/c.txt"],
+        ]);
+
+        let mut results = Vec::new();
+        let result = WatchUsecase::new(&mut repo, &backup_service, &mut watcher).execute(
+            &target.id,
+            &options(20, 0),
+            |r| {
+                results.push(r.as_ref().unwrap().clone());
+                true
+            },
+        );
+
+        // Stops when the test watcher runs out of changes.
+        assert!(result.is_err());
+        assert_eq!(*backup_counter.borrow(), 1);
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].note, "Changed (2): a.txt, b.txt");
+    }
+
+    #[test]
+    fn it_keeps_min_interval_between_backups() {
+        let mut repo = InMemoryTargetRepository::new();
+        let (backup_service, _, _) = TestBackupService::new();
+        let target = repo.add("notes", Path::new("/notes")).unwrap();
+        let mut watcher = TestWatchService::new(&[&["/notes/a.txt"], &["/notes/b.txt"]]);
+        let mut times = Vec::new();
+        let result = WatchUsecase::new(&mut repo, &backup_service, &mut watcher).execute(
+            &target.id,
+            &options(0, 100),
+            |_| {
+                times.push(Instant::now());
+                times.len() < 2
+            },
+        );
+        assert!(result.is_ok());
+        assert_eq!(times.len(), 2);
+        assert!(times[1] - times[0] >= Duration::from_millis(100));
+    }
+
+    #[test]
+    fn it_backs_up_file_target_on_its_changes_only() {
+        let mut repo = InMemoryTargetRepository::new();
+        let (backup_service, backup_counter, _) = TestBackupService::new();
+        let mut target = repo.add("db", Path::new("/srv/app.db")).unwrap();
+        target.kind = TargetKind::File;
+        repo.update(&target).unwrap();
+        let mut watcher = TestWatchService::new(&[&["/srv/app.db-journal"], &["/srv/app.db"]]);
+
+        let mut notes = Vec::new();
+        let result = WatchUsecase::new(&mut repo, &backup_service, &mut watcher).execute(
+            &target.id,
+            &options(0, 0),
+            |r| {
+                notes.push(r.as_ref().unwrap().note.clone());
+                true
+            },
+        );
+        assert!(result.is_err());
+        assert_eq!(*backup_counter.borrow(), 1);
+        assert_eq!(notes, vec!["Changed (1): app.db"]);
+    }
+
+    #[test]
+    fn it_backs_up_on_changes_of_sources() {
+        let mut repo = InMemoryTargetRepository::new();
+        let (backup_service, backup_counter, _) = TestBackupService::new();
+        let mut target = repo.add("app", Path::new("/srv/app")).unwrap();
+        target.add_source("config", Path::new("/etc/app")).unwrap();
+        repo.update(&target).unwrap();
+        let mut watcher = TestWatchService::new(&[&["/etc/app/app.conf", "/srv/app/data.txt"]]);
+
+        let mut notes = Vec::new();
+        let result = WatchUsecase::new(&mut repo, &backup_service, &mut watcher).execute(
+            &target.id,
+            &options(0, 0),
+            |r| {
+                notes.push(r.as_ref().unwrap().note.clone());
+                true
+            },
+        );
+        assert!(result.is_err());
+        assert_eq!(*backup_counter.borrow(), 1);
+        assert_eq!(notes, vec!["Changed (2): config/app.conf, main/data.txt"]);
+    }
+
+    #[test]
+    fn it_ignores_excluded_paths() {
+        let mut repo = InMemoryTargetRepository::new();
+        let (backup_service, backup_counter, _) = TestBackupService::new();
+        let target = repo.add("app", Path::new("/srv/app")).unwrap();
+        let mut watcher = TestWatchService::new(&[
+            &["/srv/app/node_modules/lib/index.js", "/srv/app/debug.log"],
+            &["/srv/app/src/main.rs", "/srv/app/node_modules"],
+        ]);
+
+        let mut options = options(0, 0);
+        options.excludes = ["node_modules", "*.log"]
+            .map(|p| p.parse().unwrap())
+            .to_vec();
+        let mut notes = Vec::new();
+        let result = WatchUsecase::new(&mut repo, &backup_service, &mut watcher).execute(
+            &target.id,
+            &options,
+            |r| {
+                notes.push(r.as_ref().unwrap().note.clone());
+                true
+            },
+        );
+        assert!(result.is_err());
+        assert_eq!(*backup_counter.borrow(), 1);
+        assert_eq!(notes, vec!["Changed (1): src/main.rs"]);
+    }
+
+    #[test]
+    fn it_ignores_backup_dir() {
+        let temp = mktemp::TempDir::new().unwrap();
+        let mut repo = FileStorageTargetRepository::new(&temp.path().join("data"));
+        let (backup_service, backup_counter, _) = TestBackupService::new();
+        let target = repo.add("home", &temp.path()).unwrap();
+
+        let backup_file = repo.make_backup_dir_path(&target).join("001.tar.gz");
+        let mut watcher = TestWatchService::new(&[&[backup_file.to_str().unwrap()]]);
+
+        let result = WatchUsecase::new(&mut repo, &backup_service, &mut watcher).execute(
+            &target.id,
+            &options(0, 0),
+            |_| true,
+        );
+        assert!(result.is_err());
+        assert_eq!(*backup_counter.borrow(), 0);
+    }
+
+    #[test]
+    fn it_returns_err_if_target_not_found() {
+        let mut repo = InMemoryTargetRepository::new();
+        let (backup_service, _, _) = TestBackupService::new();
+        let mut watcher = TestWatchService::new(&[]);
+
+        let result = WatchUsecase::new(&mut repo, &backup_service, &mut watcher).execute(
+            "xxx",
+            &WatchOptions::default(),
+            |_| true,
+        );
+        assert!(result.is_err());
+    }
+}
@@ -5,23 +5,150 @@
 //!
 
 use std::io::Write;
-use std::path::Path; // Required to flush tar data to disk.
+use std::path::{Component, Path, PathBuf}; // Required to flush tar data to disk.
+
+/// The default compression level, from 0 (no compression) to 9 (best).
+pub const DEFAULT_LEVEL: u32 = 6;
 
 /// Archive the specified directory as a tar.gz file.
 ///
 /// - src ... Path to the directory to be archived.
 /// - dest ... Output destination of archive file.
 pub fn archive(src: &Path, dest: &Path) -> anyhow::Result<()> {
+    archive_dirs(&[(".", src)], dest)
+}
+
+/// Archive the specified directories as a tar.gz file, each under its prefix.
+///
+/// - srcs ... The prefixes in the archive and the paths to the directories.
+/// - dest ... Output destination of archive file.
+pub fn archive_dirs(srcs: &[(&str, &Path)], dest: &Path) -> anyhow::Result<()> {
+    archive_dirs_with_level(srcs, dest, DEFAULT_LEVEL)
+}
+
+/// Same as `archive_dirs`, with the compression level (0 to 9).
+pub fn archive_dirs_with_level(
+    srcs: &[(&str, &Path)],
+    dest: &Path,
+    level: u32,
+) -> anyhow::Result<()> {
+    write_archive(dest, level, |ar| {
+        for (prefix, src) in srcs {
+            ar.append_dir_all(prefix, src)?;
+        }
+        Ok(())
+    })
+}
+
+/// Same as `archive_dirs_with_level`, without the entries for which `exclude` returns true.
+///
+/// `exclude` is called with the path relative to each directory,
+/// an excluded directory is skipped with all its contents.
+///
+/// Returns the stats of the archived files, counted while archiving them.
+pub fn archive_dirs_excluding<F>(
+    srcs: &[(&str, &Path)],
+    dest: &Path,
+    level: u32,
+    exclude: F,
+) -> anyhow::Result<ArchiveStats>
+where
+    F: Fn(&Path) -> bool,
+{
+    write_archive(dest, level, |ar| {
+        let mut stats = ArchiveStats::default();
+        for (prefix, src) in srcs {
+            append_dir_excluding(ar, Path::new(prefix), src, &exclude, &mut stats)?;
+        }
+        Ok(stats)
+    })
+}
+
+/// Appends the directory tree as `tar::Builder::append_dir_all` does, skipping the excluded paths.
+fn append_dir_excluding<W, F>(
+    ar: &mut tar::Builder<W>,
+    prefix: &Path,
+    src: &Path,
+    exclude: &F,
+    stats: &mut ArchiveStats,
+) -> anyhow::Result<()>
+where
+    W: Write,
+    F: Fn(&Path) -> bool,
+{
+    let mut stack = vec![src.to_path_buf()];
+    while let Some(path) = stack.pop() {
+        let dest = prefix.join(path.strip_prefix(src)?);
+
+        // Symlinks are followed, as by `append_dir_all`.
+        if path.is_dir() {
+            for entry in std::fs::read_dir(&path)? {
+                let entry = entry?.path();
+                if !exclude(entry.strip_prefix(src)?) {
+                    stack.push(entry);
+                }
+            }
+            if dest != Path::new("") {
+                ar.append_dir(&dest, &path)?;
+            }
+        } else {
+            ar.append_path_with_name(&path, &dest)?;
+            stats.add(&path)?;
+        }
+    }
+    Ok(())
+}
+
+/// Archive the specified file as a tar.gz file, under its file name.
+///
+/// - src ... Path to the file to be archived.
+/// - dest ... Output destination of archive file.
+pub fn archive_file(src: &Path, dest: &Path) -> anyhow::Result<ArchiveStats> {
+    archive_file_with_level(src, dest, DEFAULT_LEVEL)
+}
+
+/// Same as `archive_file`, with the compression level (0 to 9).
+pub fn archive_file_with_level(
+    src: &Path,
+    dest: &Path,
+    level: u32,
+) -> anyhow::Result<ArchiveStats> {
+    if !src.is_file() {
+        anyhow::bail!("'{}' is not a file.", src.display());
+    }
+    let name = src
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("'{}' has no file name.", src.display()))?;
+
+    write_archive(dest, level, |ar| {
+        ar.append_path_with_name(src, name)?;
+        let mut stats = ArchiveStats::default();
+        stats.add(src)?;
+        Ok(stats)
+    })
+}
+
+/// Makes the tar.gz file by `f`, and copies it to dest when completed.
+///
+/// Returns the value returned by `f`.
+fn write_archive<F, T>(dest: &Path, level: u32, f: F) -> anyhow::Result<T>
+where
+    F: FnOnce(&mut tar::Builder<flate2::write::GzEncoder<std::fs::File>>) -> anyhow::Result<T>,
+{
+    if level > 9 {
+        anyhow::bail!("Invalid compression level: {level} (0 to 9)");
+    }
+
     let temp = mktemp::TempDir::new()?;
     let temp_dest = temp.path().join("temp.tar.gz");
 
     // Make destination file (tar.gz).
     let targz = std::fs::File::create(&temp_dest)?;
-    let enc = flate2::write::GzEncoder::new(targz, flate2::Compression::default());
+    let enc = flate2::write::GzEncoder::new(targz, flate2::Compression::new(level));
     let mut ar = tar::Builder::new(enc);
 
-    // Add directory to archive.
-    ar.append_dir_all(".", src)?;
+    // Add entries to archive.
+    let value = f(&mut ar)?;
 
     // Flush data to disk.
     let mut enc = ar.into_inner()?;
@@ -31,7 +158,7 @@ pub fn archive(src: &Path, dest: &Path) -> anyhow::Result<()> {
     std::fs::copy(&temp_dest, dest)?;
     std::fs::remove_file(&temp_dest)?;
 
-    Ok(())
+    Ok(value)
 }
 
 /// Extracts the tar.gz file to the specified path.
@@ -49,12 +176,293 @@ pub fn extract(src: &Path, dest: &Path) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// Extracts the entries under the prefix of the tar.gz file to the specified path.
+///
+/// - src ... tar.gz file
+/// - prefix ... The top level directory in the archive.
+/// - dest ... Path of the destination directory, the prefix is stripped.
+pub fn extract_dir(src: &Path, prefix: &str, dest: &Path) -> anyhow::Result<()> {
+    let targz = std::fs::File::open(src)?;
+    let dec = flate2::read::GzDecoder::new(std::io::BufReader::new(targz));
+
+    let mut ar = tar::Archive::new(dec);
+    let mut found = false;
+    for entry in ar.entries()? {
+        let mut entry = entry?;
+        let path: PathBuf = entry
+            .path()?
+            .components()
+            .filter(|c| !matches!(c, Component::CurDir))
+            .collect();
+        let Ok(rel) = path.strip_prefix(prefix) else {
+            continue;
+        };
+        if !rel.components().all(|c| matches!(c, Component::Normal(_))) {
+            anyhow::bail!("Invalid entry in the archive: '{}'", path.display());
+        }
+        found = true;
+
+        let out = dest.join(rel);
+        if let Some(parent) = out.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        entry.unpack(&out)?;
+    }
+
+    if !found {
+        anyhow::bail!("'{prefix}' is not found in the archive.");
+    }
+
+    Ok(())
+}
+
+/// Extracts the file archived by `archive_file` to the specified path.
+///
+/// - src ... tar.gz file
+/// - dest ... Path of the destination file, replaced if it exists.
+///
+/// The file is extracted next to the destination first, and then renamed,
+/// so the destination is not left half-written.
+pub fn extract_file(src: &Path, dest: &Path) -> anyhow::Result<()> {
+    let targz = std::fs::File::open(src)?;
+    let dec = flate2::read::GzDecoder::new(std::io::BufReader::new(targz));
+
+    let mut ar = tar::Archive::new(dec);
+    for entry in ar.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let name = dest
+            .file_name()
+            .ok_or_else(|| anyhow::anyhow!("'{}' has no file name.", dest.display()))?;
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        let temp_dest = dest.with_file_name(format!(".{}.extracting", name.to_string_lossy()));
+        entry.unpack(&temp_dest)?;
+        if let Err(e) = std::fs::rename(&temp_dest, dest) {
+            let _ = std::fs::remove_file(&temp_dest);
+            return Err(e.into());
+        }
+
+        return Ok(());
+    }
+
+    anyhow::bail!("No file is found in the archive.")
+}
+
+/// List the entries in the tar.gz file.
+///
+/// Returns the relative paths of the entries, without the root (`.`) entry.
+pub fn list(src: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let targz = std::fs::File::open(src)?;
+    let dec = flate2::read::GzDecoder::new(std::io::BufReader::new(targz));
+
+    let mut ar = tar::Archive::new(dec);
+    let mut paths = Vec::new();
+    for entry in ar.entries()? {
+        let path = relative_path(&entry?.path()?);
+        if !path.as_os_str().is_empty() {
+            paths.push(path);
+        }
+    }
+
+    Ok(paths)
+}
+
+/// Returns the path of an entry without `.` components.
+fn relative_path(path: &Path) -> PathBuf {
+    path.components()
+        .filter(|c| !matches!(c, Component::CurDir))
+        .collect()
+}
+
+/// A regular file in a tar.gz file.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileEntry {
+    /// The relative path in the archive, as returned by `list`.
+    pub path: PathBuf,
+
+    /// The size of the file in bytes.
+    pub size: u64,
+
+    /// The modification time of the file, in seconds since the Unix epoch.
+    pub mtime: u64,
+}
+
+/// Calls `f` with each regular file in the tar.gz file and the reader of its content.
+///
+/// The archive is read once from the beginning,
+/// and the content of a file is decompressed only if `f` reads it.
+pub fn for_each_file<F>(src: &Path, mut f: F) -> anyhow::Result<()>
+where
+    F: FnMut(&FileEntry, &mut dyn std::io::Read) -> anyhow::Result<()>,
+{
+    let targz = std::fs::File::open(src)?;
+    let dec = flate2::read::GzDecoder::new(std::io::BufReader::new(targz));
+
+    let mut ar = tar::Archive::new(dec);
+    for entry in ar.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let file = FileEntry {
+            path: relative_path(&entry.path()?),
+            size: entry.size(),
+            mtime: entry.header().mtime()?,
+        };
+        f(&file, &mut entry)?;
+    }
+
+    Ok(())
+}
+
+/// The kind of an entry in a tar.gz file.
+#[derive(Clone, Debug, PartialEq)]
+pub enum EntryKind {
+    File,
+    Dir,
+
+    /// A symbolic link to the path.
+    Symlink(PathBuf),
+}
+
+/// An entry in a tar.gz file, read from its header.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ArchiveEntry {
+    /// The relative path in the archive, as returned by `list`.
+    pub path: PathBuf,
+    pub kind: EntryKind,
+
+    /// The size of the file in bytes, 0 for the others.
+    pub size: u64,
+
+    /// The modification time, in seconds since the Unix epoch.
+    pub mtime: u64,
+
+    /// The permission bits, e.g. `0o644`.
+    pub mode: u32,
+}
+
+/// Lists the files, directories and symbolic links in the tar.gz file,
+/// reading only the entry headers.
+///
+/// The root (`.`) entry and the other kinds of entries (e.g. devices) are skipped.
+pub fn entries(src: &Path) -> anyhow::Result<Vec<ArchiveEntry>> {
+    let targz = std::fs::File::open(src)?;
+    let dec = flate2::read::GzDecoder::new(std::io::BufReader::new(targz));
+
+    let mut ar = tar::Archive::new(dec);
+    let mut entries = Vec::new();
+    for entry in ar.entries()? {
+        let entry = entry?;
+        let path = relative_path(&entry.path()?);
+        if path.as_os_str().is_empty() {
+            continue;
+        }
+
+        let header = entry.header();
+        let entry_type = header.entry_type();
+        let kind = if entry_type.is_file() {
+            EntryKind::File
+        } else if entry_type.is_dir() {
+            EntryKind::Dir
+        } else if entry_type.is_symlink() {
+            let target = entry.link_name()?.unwrap_or_default().into_owned();
+            EntryKind::Symlink(target)
+        } else {
+            continue;
+        };
+
+        entries.push(ArchiveEntry {
+            size: if kind == EntryKind::File {
+                entry.size()
+            } else {
+                0
+            },
+            path,
+            kind,
+            mtime: header.mtime()?,
+            mode: header.mode()? & 0o7777,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Streams the content of the regular file at the relative path in the tar.gz file to `out`,
+/// without extracting anything to disk.
+///
+/// The archive is read only until the file is found.
+/// Returns the number of the written bytes, or `None` if the file is not in the archive.
+pub fn copy_file(src: &Path, path: &Path, out: &mut dyn Write) -> anyhow::Result<Option<u64>> {
+    let path: PathBuf = path
+        .components()
+        .filter(|c| matches!(c, Component::Normal(_)))
+        .collect();
+
+    let targz = std::fs::File::open(src)?;
+    let dec = flate2::read::GzDecoder::new(std::io::BufReader::new(targz));
+
+    let mut ar = tar::Archive::new(dec);
+    for entry in ar.entries()? {
+        let mut entry = entry?;
+        if entry.header().entry_type().is_file() && relative_path(&entry.path()?) == path {
+            return Ok(Some(std::io::copy(&mut entry, out)?));
+        }
+    }
+
+    Ok(None)
+}
+
+/// The number and the total size of the files in a tar.gz file.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ArchiveStats {
+    /// The number of the regular files, without directories.
+    pub files: u64,
+
+    /// The total size of the regular files in bytes, before compression.
+    pub size: u64,
+}
+
+impl ArchiveStats {
+    /// Counts the regular file archived from the path, following symlinks as the archivers do.
+    fn add(&mut self, path: &Path) -> std::io::Result<()> {
+        let metadata = std::fs::metadata(path)?;
+        if metadata.is_file() {
+            self.files += 1;
+            self.size += metadata.len();
+        }
+        Ok(())
+    }
+}
+
+/// Counts the files in the tar.gz file, reading only the entry headers.
+pub fn stats(src: &Path) -> anyhow::Result<ArchiveStats> {
+    let targz = std::fs::File::open(src)?;
+    let dec = flate2::read::GzDecoder::new(std::io::BufReader::new(targz));
+
+    let mut ar = tar::Archive::new(dec);
+    let mut stats = ArchiveStats::default();
+    for entry in ar.entries()? {
+        let entry = entry?;
+        if entry.header().entry_type().is_file() {
+            stats.files += 1;
+            stats.size += entry.size();
+        }
+    }
+
+    Ok(stats)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
-    use std::path::{Path, PathBuf};
-
     fn list_entry(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
         let mut files = Vec::new();
         for entry in std::fs::read_dir(dir)? {
@@ -140,6 +548,310 @@ mod tests {
             assert!(result.is_err());
             assert!(!targz.exists(), "test.tar.gz should not be created.");
         }
+
+        #[test]
+        fn it_compresses_with_level() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let sample = temp.path().join("sample");
+            std::fs::create_dir_all(&sample).unwrap();
+            std::fs::write(sample.join("text.txt"), "dirback ".repeat(10000)).unwrap();
+
+            let stored = temp.path().join("stored.tar.gz");
+            let best = temp.path().join("best.tar.gz");
+            archive_dirs_with_level(&[(".", &sample)], &stored, 0).unwrap();
+            archive_dirs_with_level(&[(".", &sample)], &best, 9).unwrap();
+
+            let size = |path: &Path| std::fs::metadata(path).unwrap().len();
+            assert!(size(&best) < size(&stored));
+
+            let extracted = temp.path().join("extracted");
+            extract(&stored, &extracted).unwrap();
+            assert!(extracted.join("text.txt").exists());
+
+            let invalid = temp.path().join("invalid.tar.gz");
+            assert!(archive_dirs_with_level(&[(".", &sample)], &invalid, 10).is_err());
+        }
+
+        #[test]
+        fn it_skips_excluded_paths() {
+            let temp = mktemp::TempDir::new().unwrap();
+            prepare_test_dir_and_files(&temp);
+            let sample = temp.path().join("sample");
+
+            std::fs::write(sample.join("foo/bar.txt"), "123").unwrap();
+
+            let targz = temp.path().join("test.tar.gz");
+            let exclude = |path: &Path| path == Path::new("foo/bar") || path.ends_with("foo.txt");
+            let archived =
+                archive_dirs_excluding(&[("main", &sample)], &targz, DEFAULT_LEVEL, exclude)
+                    .unwrap();
+            assert_eq!(archived, ArchiveStats { files: 2, size: 3 });
+            assert_eq!(stats(&targz).unwrap(), archived);
+
+            let mut paths = list(&targz).unwrap();
+            paths.sort();
+            let expected: Vec<PathBuf> = [
+                "main",
+                "main/foo",
+                "main/foo/.hiddenfile",
+                "main/foo/bar.txt",
+            ]
+            .iter()
+            .map(PathBuf::from)
+            .collect();
+            assert_eq!(paths, expected);
+
+            let missing = temp.path().join("missing");
+            assert!(
+                archive_dirs_excluding(&[(".", &missing)], &targz, DEFAULT_LEVEL, |_| false)
+                    .is_err()
+            );
+        }
+    }
+
+    mod list {
+        use super::*;
+
+        #[test]
+        fn it_returns_relative_paths() {
+            let temp = mktemp::TempDir::new().unwrap();
+            prepare_test_dir_and_files(&temp);
+
+            let targz = temp.path().join("test.tar.gz");
+            archive(&temp.path().join("sample"), &targz).unwrap();
+
+            let paths = list(&targz).unwrap();
+            assert!(paths.contains(&PathBuf::from("foo.txt")));
+            assert!(paths.contains(&PathBuf::from("foo/bar/baz.txt")));
+            assert!(!paths.iter().any(|p| p.as_os_str().is_empty()));
+        }
+    }
+
+    mod for_each_file {
+        use super::*;
+
+        #[test]
+        fn it_visits_files_with_content() {
+            let temp = mktemp::TempDir::new().unwrap();
+            prepare_test_dir_and_files(&temp);
+            let sample = temp.path().join("sample");
+            std::fs::write(sample.join("foo/bar.txt"), "bar").unwrap();
+
+            let targz = temp.path().join("test.tar.gz");
+            archive(&sample, &targz).unwrap();
+
+            let mut files = Vec::new();
+            for_each_file(&targz, |file, reader| {
+                let mut content = String::new();
+                if file.path == Path::new("foo/bar.txt") {
+                    reader.read_to_string(&mut content)?;
+                }
+                files.push((file.clone(), content));
+                Ok(())
+            })
+            .unwrap();
+
+            assert_eq!(files.len(), 4, "Only the regular files are visited.");
+            let (bar, content) = files
+                .iter()
+                .find(|(f, _)| f.path == Path::new("foo/bar.txt"))
+                .unwrap();
+            assert_eq!(bar.size, 3);
+            assert!(bar.mtime > 0);
+            assert_eq!(content, "bar");
+
+            let result = for_each_file(&targz, |_, _| anyhow::bail!("stop"));
+            assert!(result.is_err());
+        }
+    }
+
+    mod entries {
+        use super::*;
+
+        #[test]
+        fn it_reads_kinds_sizes_and_modes() {
+            let temp = mktemp::TempDir::new().unwrap();
+            prepare_test_dir_and_files(&temp);
+            let sample = temp.path().join("sample");
+            std::fs::write(sample.join("foo/bar.txt"), "bar").unwrap();
+
+            let targz = temp.path().join("test.tar.gz");
+            archive(&sample, &targz).unwrap();
+
+            let entries = entries(&targz).unwrap();
+            let paths: Vec<&Path> = entries.iter().map(|e| e.path.as_path()).collect();
+            assert_eq!(paths.len(), list(&targz).unwrap().len());
+
+            let bar = entries
+                .iter()
+                .find(|e| e.path == Path::new("foo/bar.txt"))
+                .unwrap();
+            assert_eq!(bar.kind, EntryKind::File);
+            assert_eq!(bar.size, 3);
+            assert!(bar.mtime > 0);
+            assert_ne!(bar.mode & 0o400, 0);
+
+            let foo = entries.iter().find(|e| e.path == Path::new("foo")).unwrap();
+            assert_eq!(foo.kind, EntryKind::Dir);
+            assert_eq!(foo.size, 0);
+        }
+    }
+
+    mod copy_file {
+        use super::*;
+
+        #[test]
+        fn it_streams_the_file() {
+            let temp = mktemp::TempDir::new().unwrap();
+            prepare_test_dir_and_files(&temp);
+            let sample = temp.path().join("sample");
+            std::fs::write(sample.join("foo/bar.txt"), "bar").unwrap();
+
+            let targz = temp.path().join("test.tar.gz");
+            archive(&sample, &targz).unwrap();
+
+            let mut out = Vec::new();
+            let size = copy_file(&targz, Path::new("foo/bar.txt"), &mut out).unwrap();
+            assert_eq!(size, Some(3));
+            assert_eq!(out, b"bar");
+
+            let mut out = Vec::new();
+            let size = copy_file(&targz, Path::new("./foo/bar.txt"), &mut out).unwrap();
+            assert_eq!(size, Some(3));
+
+            let mut out = Vec::new();
+            assert_eq!(copy_file(&targz, Path::new("foo"), &mut out).unwrap(), None);
+            assert_eq!(
+                copy_file(&targz, Path::new("nothing.txt"), &mut out).unwrap(),
+                None
+            );
+            assert!(out.is_empty());
+        }
+    }
+
+    mod stats {
+        use super::*;
+
+        #[test]
+        fn it_counts_files_and_sums_sizes() {
+            let temp = mktemp::TempDir::new().unwrap();
+            prepare_test_dir_and_files(&temp);
+            let sample = temp.path().join("sample");
+            std::fs::write(sample.join("foo.txt"), "12345").unwrap();
+            std::fs::write(sample.join("foo/bar.txt"), "123").unwrap();
+
+            let targz = temp.path().join("test.tar.gz");
+            archive(&sample, &targz).unwrap();
+
+            let stats = stats(&targz).unwrap();
+            assert_eq!(stats, ArchiveStats { files: 4, size: 8 });
+
+            assert!(super::stats(&temp.path().join("nonexistent.tar.gz")).is_err());
+        }
+    }
+
+    mod archive_dirs {
+        use super::*;
+
+        #[test]
+        fn it_extracts_each_dir_by_prefix() {
+            let temp = mktemp::TempDir::new().unwrap();
+            prepare_test_dir_and_files(&temp);
+            let sample = temp.path().join("sample");
+            let other = temp.path().join("other");
+            std::fs::create_dir_all(&other).unwrap();
+            std::fs::write(other.join("other.txt"), "other").unwrap();
+
+            let targz = temp.path().join("test.tar.gz");
+            archive_dirs(&[("main", &sample), ("other", &other)], &targz).unwrap();
+
+            let paths = list(&targz).unwrap();
+            assert!(paths.contains(&PathBuf::from("main/foo/bar/baz.txt")));
+            assert!(paths.contains(&PathBuf::from("other/other.txt")));
+
+            let out = temp.path().join("out");
+            extract_dir(&targz, "other", &out.join("other")).unwrap();
+            extract_dir(&targz, "main", &out.join("main")).unwrap();
+            assert_eq!(
+                std::fs::read_to_string(out.join("other/other.txt")).unwrap(),
+                "other"
+            );
+
+            let expected: Vec<PathBuf> = list_entry(&sample)
+                .unwrap()
+                .iter()
+                .map(|p| p.strip_prefix(&sample).unwrap().to_path_buf())
+                .collect();
+            let actual: Vec<PathBuf> = list_entry(&out.join("main"))
+                .unwrap()
+                .iter()
+                .map(|p| p.strip_prefix(out.join("main")).unwrap().to_path_buf())
+                .collect();
+            assert_eq!(actual, expected);
+        }
+
+        #[test]
+        fn it_returns_error_if_prefix_is_not_found() {
+            let temp = mktemp::TempDir::new().unwrap();
+            prepare_test_dir_and_files(&temp);
+
+            let targz = temp.path().join("test.tar.gz");
+            archive_dirs(&[("main", &temp.path().join("sample"))], &targz).unwrap();
+
+            let result = extract_dir(&targz, "config", &temp.path().join("out"));
+            assert!(result.is_err());
+        }
+    }
+
+    mod archive_file {
+        use super::*;
+
+        #[test]
+        fn it_extracts_file_to_dest() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let src = temp.path().join("app.db");
+            std::fs::write(&src, "version 1").unwrap();
+
+            let targz = temp.path().join("test.tar.gz");
+            let archived = archive_file(&src, &targz).unwrap();
+            assert_eq!(list(&targz).unwrap(), vec![PathBuf::from("app.db")]);
+            assert_eq!(archived, ArchiveStats { files: 1, size: 9 });
+
+            // Replaces the existing file.
+            std::fs::write(&src, "version 2").unwrap();
+            extract_file(&targz, &src).unwrap();
+            assert_eq!(std::fs::read_to_string(&src).unwrap(), "version 1");
+
+            // Restores under another name.
+            let dest = temp.path().join("out/restored.db");
+            extract_file(&targz, &dest).unwrap();
+            assert_eq!(std::fs::read_to_string(&dest).unwrap(), "version 1");
+            assert_eq!(
+                std::fs::read_dir(temp.path().join("out")).unwrap().count(),
+                1
+            );
+        }
+
+        #[test]
+        fn it_returns_error_if_src_is_not_file() {
+            let temp = mktemp::TempDir::new().unwrap();
+            prepare_test_dir_and_files(&temp);
+
+            let targz = temp.path().join("test.tar.gz");
+            assert!(archive_file(&temp.path().join("sample"), &targz).is_err());
+            assert!(!targz.exists());
+        }
+
+        #[test]
+        fn it_returns_error_if_no_file_in_archive() {
+            let temp = mktemp::TempDir::new().unwrap();
+            let empty = temp.path().join("empty");
+            std::fs::create_dir_all(&empty).unwrap();
+
+            let targz = temp.path().join("test.tar.gz");
+            archive(&empty, &targz).unwrap();
+            assert!(extract_file(&targz, &temp.path().join("out.db")).is_err());
+        }
     }
 
     mod extract {